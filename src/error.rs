@@ -1,9 +1,11 @@
 //! Generic error types used throughout the crate.
 
+use crate::local_socket::Name;
 use std::{
-	error::Error,
+	error::Error as StdError,
 	fmt::{self, Debug, Display, Formatter, Write},
 	io,
+	time::Duration,
 };
 
 /// General error type for fallible constructors.
@@ -108,7 +110,7 @@ impl<S, E: Display> ConversionError<S, E> {
 	/// Boxes the error into an `io::Error`.
 	pub fn to_io_error(&self) -> io::Error {
 		let msg = self.to_string();
-		io::Error::new(io::ErrorKind::Other, msg)
+		io::Error::other(msg)
 	}
 }
 /// Boxes the error into an `io::Error`, dropping the retained file descriptor in the process.
@@ -139,10 +141,10 @@ impl<S, E: Display> Display for ConversionError<S, E> {
 		Ok(())
 	}
 }
-impl<S: Debug, E: Error + 'static> Error for ConversionError<S, E> {
+impl<S: Debug, E: StdError + 'static> StdError for ConversionError<S, E> {
 	#[inline]
 	#[allow(clippy::as_conversions)]
-	fn source(&self) -> Option<&(dyn Error + 'static)> {
+	fn source(&self) -> Option<&(dyn StdError + 'static)> {
 		self.cause.as_ref().map(|r| r as &_)
 	}
 }
@@ -231,7 +233,195 @@ impl<R, S> Display for ReuniteError<R, S> {
 		f.write_str("attempt to reunite stream halves that come from different streams")
 	}
 }
-impl<R: Debug, S: Debug> Error for ReuniteError<R, S> {}
+impl<R: Debug, S: Debug> StdError for ReuniteError<R, S> {}
 
 /// Result type of `.reunite()` on splittable stream types.
 pub type ReuniteResult<T, R, S> = Result<T, ReuniteError<R, S>>;
+
+/// Error type of `connect_with_retries()` on local socket streams, describing the connection
+/// attempts that were made before giving up.
+#[derive(Debug)]
+pub struct ConnectRetryError {
+	/// The number of connection attempts that were made.
+	pub attempts: u32,
+	/// The time that passed between the start of the first attempt and the failure of the last one.
+	pub elapsed: Duration,
+	/// The error produced by the last attempt.
+	pub last_error: io::Error,
+}
+impl Display for ConnectRetryError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let s = if self.attempts == 1 { "" } else { "s" };
+		write!(
+			f,
+			"failed to connect after {} attempt{s} over {:?}: {}",
+			self.attempts, self.elapsed, self.last_error
+		)
+	}
+}
+impl StdError for ConnectRetryError {
+	#[inline]
+	fn source(&self) -> Option<&(dyn StdError + 'static)> {
+		Some(&self.last_error)
+	}
+}
+/// Boxes the error into an `io::Error` of the same kind as the last error.
+impl From<ConnectRetryError> for io::Error {
+	fn from(e: ConnectRetryError) -> Self {
+		io::Error::new(e.last_error.kind(), e)
+	}
+}
+
+/// Error produced by operations on an `IdleTimeout` wrapper once its connection has been shut
+/// down for being idle for too long.
+#[derive(Debug)]
+pub struct IdleTimeoutError {
+	/// The idle timeout which was exceeded.
+	pub timeout: Duration,
+}
+impl Display for IdleTimeoutError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"connection shut down after being idle for {:?}",
+			self.timeout
+		)
+	}
+}
+impl StdError for IdleTimeoutError {}
+/// Boxes the error into an `io::Error` of kind [`TimedOut`](io::ErrorKind::TimedOut).
+impl From<IdleTimeoutError> for io::Error {
+	fn from(e: IdleTimeoutError) -> Self {
+		io::Error::new(io::ErrorKind::TimedOut, e)
+	}
+}
+
+/// Structured cause of a failed local socket operation.
+///
+/// Local socket constructors return [`io::Error`]s, like the rest of the crate. For the failures
+/// which have a dedicated variant here, that `io::Error` wraps a value of this type, which the
+/// `From<io::Error>` implementation recovers; any other `io::Error` converts to [`Os`](Self::Os).
+/// Converting back yields an `io::Error` of the same [kind](Self::kind), so the two can be used
+/// interchangeably:
+/// ```
+/// use interprocess::{
+/// 	local_socket::{prelude::*, GenericFilePath, ListenerOptions},
+/// 	Error,
+/// };
+///
+/// let name = "/tmp/example.sock".to_fs_name::<GenericFilePath>()?;
+/// match ListenerOptions::new().name(name).create_sync().map_err(Error::from) {
+/// 	Ok(_listener) => {}
+/// 	Err(Error::AddrInUse { name, .. }) => eprintln!("{name:?} is taken"),
+/// 	Err(e) => return Err(e.into()),
+/// }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+	/// The name exceeds the length limit of the platform, which is measured in bytes on Unix and
+	/// in UTF-16 code units on Windows.
+	NameTooLong {
+		/// The length of the name.
+		len: usize,
+		/// The greatest length the platform allows.
+		max: usize,
+	},
+	/// The name is of a type which the platform does not support.
+	UnsupportedNameType {
+		/// Why the name isn't supported.
+		reason: &'static str,
+	},
+	/// Another listener is already bound to the name.
+	AddrInUse {
+		/// The name which is in use.
+		name: Name<'static>,
+		/// The error reported by the OS, which is also the [source](StdError::source) of this one.
+		source: io::Error,
+	},
+	/// There is a socket under the name, but no server accepted the connection.
+	ConnectionRefused {
+		/// The name which was connected to.
+		name: Name<'static>,
+		/// The error reported by the OS, which is also the [source](StdError::source) of this one.
+		source: io::Error,
+	},
+	/// Any other error, as reported by the OS.
+	Os(io::Error),
+}
+impl Error {
+	/// Returns the kind of the equivalent [`io::Error`].
+	pub fn kind(&self) -> io::ErrorKind {
+		match self {
+			Self::NameTooLong { .. } => io::ErrorKind::InvalidInput,
+			Self::UnsupportedNameType { .. } => io::ErrorKind::Unsupported,
+			Self::AddrInUse { .. } => io::ErrorKind::AddrInUse,
+			Self::ConnectionRefused { .. } => io::ErrorKind::ConnectionRefused,
+			Self::Os(e) => e.kind(),
+		}
+	}
+	/// Replaces errors from binding or connecting to `name` with the variant which carries it, if
+	/// there is one.
+	pub(crate) fn attach_name(e: io::Error, name: Name<'_>) -> io::Error {
+		if e.get_ref().is_some_and(|e| e.is::<Self>()) {
+			return e;
+		}
+		let name = name.into_owned();
+		match e.kind() {
+			io::ErrorKind::AddrInUse => Self::AddrInUse { name, source: e }.into(),
+			io::ErrorKind::ConnectionRefused => Self::ConnectionRefused { name, source: e }.into(),
+			_ => e,
+		}
+	}
+}
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::NameTooLong { len, max } => {
+				let unit = if cfg!(windows) { "characters" } else { "bytes" };
+				write!(
+					f,
+					"name is {len} {unit} long, exceeding the limit of {max} {unit}"
+				)
+			}
+			Self::UnsupportedNameType { reason } => f.write_str(reason),
+			Self::AddrInUse { name, .. } => write!(f, "address {name:?} is already in use"),
+			Self::ConnectionRefused { name, .. } => write!(f, "connection to {name:?} was refused"),
+			Self::Os(e) => Display::fmt(e, f),
+		}
+	}
+}
+impl StdError for Error {
+	#[inline]
+	fn source(&self) -> Option<&(dyn StdError + 'static)> {
+		match self {
+			Self::AddrInUse { source, .. } | Self::ConnectionRefused { source, .. } => Some(source),
+			Self::Os(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+/// Unwraps the error if the `io::Error` carries one, or wraps it into [`Os`](Error::Os)
+/// otherwise.
+impl From<io::Error> for Error {
+	fn from(e: io::Error) -> Self {
+		if !e.get_ref().is_some_and(|e| e.is::<Self>()) {
+			return Self::Os(e);
+		}
+		match e.into_inner().map(|e| e.downcast::<Self>()) {
+			Some(Ok(e)) => *e,
+			// Ruled out by the check above
+			_ => Self::Os(io::ErrorKind::Other.into()),
+		}
+	}
+}
+/// Boxes the error into an `io::Error` of the same [kind](Error::kind), or unwraps the OS error.
+impl From<Error> for io::Error {
+	fn from(e: Error) -> Self {
+		match e {
+			Error::Os(e) => e,
+			e => io::Error::new(e.kind(), e),
+		}
+	}
+}
@@ -0,0 +1,82 @@
+//! Lightweight notifications between processes.
+//!
+//! An event consists of a [`Notifier`] and a [`Waiter`], created together by [`pair()`]. Signalling
+//! the notifier wakes up the waiter, even if it lives in another process. Signals are remembered
+//! by the operating system, so a signal sent before the waiter starts waiting is not lost, but
+//! several signals sent before the waiter gets to them are coalesced into a single wakeup. This
+//! makes events a good fit for waking up a peer process once there is work for it to do, such as
+//! new data in [shared memory](crate::shmem), with the work itself being found out by looking at
+//! shared state.
+//!
+//! To use an event across processes, the handles of its halves have to be passed to the other
+//! process, typically by inheritance or, on Windows, via `ShareHandle`.
+//!
+//! ## Platform-specific behavior
+//! Events are implemented with an eventfd on Linux and Android, a pipe on other Unix-like systems,
+//! and an auto-reset event object on Windows. Both halves of an eventfd-based or Windows event
+//! refer to the same kernel object, so either half can be used to wait for signals and to send
+//! them; this is not the case for pipes.
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+impmod! {notify,
+	notify_pair,
+	Notifier as NotifierImpl,
+	Waiter as WaiterImpl,
+}
+use std::io;
+
+/// Creates a new event, returning its signalling and waiting halves.
+///
+/// See the [module-level documentation](self) for more.
+#[inline]
+pub fn pair() -> io::Result<(Notifier, Waiter)> {
+	let (notifier, waiter) = notify_pair()?;
+	Ok((Notifier(notifier), Waiter(waiter)))
+}
+
+/// The signalling half of an event.
+pub struct Notifier(NotifierImpl);
+impl Notifier {
+	/// Wakes up the waiting half of the event, or makes its next wait return immediately if it
+	/// isn't waiting. Never blocks.
+	#[inline]
+	pub fn signal(&self) -> io::Result<()> {
+		self.0.signal()
+	}
+}
+multimacro! {
+	Notifier,
+	forward_handle,
+	forward_try_clone,
+	forward_debug,
+	derive_raw,
+}
+
+/// The waiting half of an event.
+pub struct Waiter(pub(crate) WaiterImpl);
+impl Waiter {
+	/// Blocks until the event is signalled, consuming the signal.
+	#[inline]
+	pub fn wait(&self) -> io::Result<()> {
+		self.0.wait()
+	}
+	/// Consumes the pending signal without blocking, returning whether there was one.
+	///
+	/// On Unix-like systems other than Linux and Android, fails with an error of kind
+	/// [`BrokenPipe`](io::ErrorKind::BrokenPipe) if there is no pending signal and all notifiers
+	/// have been dropped.
+	#[inline]
+	pub fn try_wait(&self) -> io::Result<bool> {
+		self.0.try_wait()
+	}
+}
+multimacro! {
+	Waiter,
+	forward_handle,
+	forward_try_clone,
+	forward_debug,
+	derive_raw,
+}
@@ -0,0 +1,101 @@
+//! Asynchronous waiting for events with Tokio.
+//!
+//! Only the waiting half of an event has a Tokio counterpart, since [signalling](super::Notifier)
+//! never blocks.
+
+use super::{Waiter as SyncWaiter, WaiterImpl};
+use std::io;
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, BorrowedHandle};
+#[cfg(unix)]
+use {
+	std::os::unix::io::{AsFd, BorrowedFd},
+	tokio::io::unix::AsyncFd,
+};
+
+/// Tokio-based waiting half of an event.
+///
+/// See the [parent module](super) for more.
+#[derive(Debug)]
+pub struct Waiter {
+	#[cfg(unix)]
+	inner: AsyncFd<WaiterImpl>,
+	#[cfg(windows)]
+	inner: WaiterImpl,
+}
+impl Waiter {
+	/// Asynchronously waits until the event is signalled, consuming the signal.
+	///
+	/// ## Cancel safety
+	/// On Unix, this method is cancel-safe. On Windows, where the wait is performed on Tokio's
+	/// blocking thread pool, a signal which arrives after the future is dropped can still be
+	/// consumed by the abandoned wait.
+	pub async fn wait(&self) -> io::Result<()> {
+		#[cfg(unix)]
+		loop {
+			let mut guard = self.inner.readable().await?;
+			let rslt = guard.try_io(|w| match w.get_ref().try_wait() {
+				Ok(true) => Ok(()),
+				// Clears the readiness, so that the next iteration waits for the reactor.
+				Ok(false) => Err(io::ErrorKind::WouldBlock.into()),
+				Err(e) => Err(e),
+			});
+			if let Ok(rslt) = rslt {
+				return rslt;
+			}
+		}
+		#[cfg(windows)]
+		{
+			use crate::TryClone;
+			let waiter = self.inner.try_clone()?;
+			tokio::task::spawn_blocking(move || waiter.wait())
+				.await
+				.map_err(io::Error::other)?
+		}
+	}
+	/// Consumes the pending signal without waiting, returning whether there was one. See
+	/// [the synchronous version](SyncWaiter::try_wait) for more.
+	#[inline]
+	pub fn try_wait(&self) -> io::Result<bool> {
+		self.get_ref().try_wait()
+	}
+
+	#[inline]
+	fn get_ref(&self) -> &WaiterImpl {
+		#[cfg(unix)]
+		{
+			self.inner.get_ref()
+		}
+		#[cfg(windows)]
+		{
+			&self.inner
+		}
+	}
+}
+/// Registers the waiter with the Tokio reactor.
+impl TryFrom<SyncWaiter> for Waiter {
+	type Error = io::Error;
+	fn try_from(waiter: SyncWaiter) -> io::Result<Self> {
+		Ok(Self {
+			#[cfg(unix)]
+			inner: AsyncFd::new(waiter.0)?,
+			#[cfg(windows)]
+			inner: waiter.0,
+		})
+	}
+}
+#[cfg(unix)]
+impl AsFd for Waiter {
+	#[inline]
+	fn as_fd(&self) -> BorrowedFd<'_> {
+		self.get_ref().as_fd()
+	}
+}
+#[cfg(windows)]
+impl AsHandle for Waiter {
+	#[inline]
+	fn as_handle(&self) -> BorrowedHandle<'_> {
+		self.get_ref().as_handle()
+	}
+}
+derive_asraw!(Waiter);
@@ -0,0 +1,89 @@
+//! Migrating ownership of handles between cooperating processes over local sockets.
+//!
+//! Handles and file descriptors belong to specific processes, so sending the numeric value of one
+//! to another process does not let it use the object behind it. The [`HandleTransfer`] trait,
+//! implemented for local socket streams, sends a handle to the process on the other end of the
+//! stream in a way that makes it valid there, taking care of the platform-specific details.
+//! Tokio local socket streams have asynchronous `send_handle()` and `recv_handle()` methods of
+//! their own, which use the same wire format, so either end may be asynchronous.
+//!
+//! A transfer is a part of the byte stream, in the sense that the receiving side has to call
+//! [`recv_handle()`](HandleTransfer::recv_handle) at the exact point in the stream where the
+//! sending side called [`send_handle()`](HandleTransfer::send_handle), and that any buffered
+//! readers wrapping the stream must be empty at that point. Protocols typically announce
+//! transfers with a message sent beforehand.
+//!
+//! ## Platform-specific behavior
+//! ### Unix
+//! The file descriptor is sent as `SCM_RIGHTS` ancillary data, accompanied by a single byte of
+//! regular data. The kernel installs a duplicate of it into the receiving process.
+//!
+//! ### Windows
+//! The handle is duplicated into the process on the other end of the named pipe via
+//! `DuplicateHandle`, and its value in that process is sent as 8 bytes of regular data. The
+//! sending process must be permitted to open the receiving process with the `PROCESS_DUP_HANDLE`
+//! access right, which is normally the case for processes of the same user.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::{
+//! 	handle_transfer::HandleTransfer,
+//! 	local_socket::{prelude::*, GenericNamespaced, Stream},
+//! };
+//! use std::fs::File;
+//!
+//! let conn = Stream::connect("example.sock".to_ns_name::<GenericNamespaced>()?)?;
+//! let file = File::open("Cargo.toml")?;
+//! conn.send_handle(file.into())?;
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use crate::{
+	local_socket::Stream,
+	metrics::{self, Operation},
+	Sealed,
+};
+use std::io;
+
+/// The owned handle type of the platform: `OwnedFd` on Unix and `OwnedHandle` on Windows.
+#[cfg(unix)]
+pub type OwnedHandle = std::os::fd::OwnedFd;
+/// The owned handle type of the platform: `OwnedFd` on Unix and `OwnedHandle` on Windows.
+#[cfg(windows)]
+pub type OwnedHandle = std::os::windows::io::OwnedHandle;
+
+/// Streams which can transfer ownership of handles to the process on the other end.
+///
+/// See the [module-level documentation](self) for more.
+#[allow(private_bounds)]
+pub trait HandleTransfer: Sealed {
+	/// Sends the given handle to the process on the other end of the stream, closing it in this
+	/// process once it has been sent.
+	fn send_handle(&self, handle: OwnedHandle) -> io::Result<()>;
+	/// Receives a handle sent by the process on the other end of the stream. The resulting handle
+	/// is not inheritable.
+	fn recv_handle(&self) -> io::Result<OwnedHandle>;
+}
+
+impl HandleTransfer for Stream {
+	#[inline]
+	fn send_handle(&self, handle: OwnedHandle) -> io::Result<()> {
+		let rslt = match self {
+			#[cfg(windows)]
+			Self::NamedPipe(s) => s.send_handle(handle),
+			#[cfg(unix)]
+			Self::UdSocket(s) => s.send_handle(handle),
+		};
+		metrics::track(Operation::SendHandle, rslt)
+	}
+	#[inline]
+	fn recv_handle(&self) -> io::Result<OwnedHandle> {
+		let rslt = match self {
+			#[cfg(windows)]
+			Self::NamedPipe(s) => s.recv_handle(),
+			#[cfg(unix)]
+			Self::UdSocket(s) => s.recv_handle(),
+		};
+		metrics::track(Operation::RecvHandle, rslt)
+	}
+}
@@ -0,0 +1,226 @@
+//! Wrappers which shut connections down after a period of inactivity.
+//!
+//! Long-running servers usually want to get rid of connections whose peers have gone silent,
+//! whether because they crashed without the connection being torn down or because they are simply
+//! holding on to it. [`IdleTimeout`] does this for blocking streams by means of a watchdog thread,
+//! and its Tokio counterpart `idle_timeout::tokio::IdleTimeout` does the same using the Tokio
+//! timer when the `tokio` feature is enabled.
+//!
+//! Once a connection has been shut down for being idle, every operation on the wrapper fails with
+//! an error of kind [`TimedOut`](io::ErrorKind::TimedOut) which wraps an [`IdleTimeoutError`]. This
+//! can be told apart from other timeouts as follows:
+//! ```
+//! use interprocess::error::IdleTimeoutError;
+//! use std::io;
+//!
+//! fn is_idle_timeout(e: &io::Error) -> bool {
+//! 	e.get_ref().is_some_and(|e| e.is::<IdleTimeoutError>())
+//! }
+//! ```
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+use crate::{error::IdleTimeoutError, traits::Shutdown};
+use std::{
+	fmt::{self, Debug, Formatter},
+	io::{self, prelude::*, IoSlice, IoSliceMut},
+	net,
+	sync::{
+		atomic::{AtomicBool, AtomicU64, Ordering::SeqCst},
+		Arc, Weak,
+	},
+	thread::{self, Thread},
+	time::{Duration, Instant},
+};
+
+/// Wrapper around a blocking stream which shuts it down once no reads or writes have completed on
+/// it for a given period of time.
+///
+/// The timeout is enforced by a watchdog thread which is started for every wrapper and exits when
+/// the wrapper is dropped or the timeout expires. Upon expiry, the watchdog shuts down both halves
+/// of the stream, which makes operations that are blocked on it return, and the wrapper reports
+/// the [error](IdleTimeoutError) described in the [module-level documentation](self) from then on.
+///
+/// Only completed operations count as activity: a read which waits for data for longer than the
+/// timeout does not keep the connection alive.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::{idle_timeout::IdleTimeout, local_socket::{prelude::*, Stream}};
+/// use std::{io::prelude::*, time::Duration};
+///
+/// # fn handle(conn: Stream) -> std::io::Result<()> {
+/// let mut conn = IdleTimeout::new(conn, Duration::from_secs(60))?;
+/// let mut buf = [0; 64];
+/// while conn.read(&mut buf)? > 0 {
+/// 	// ...
+/// }
+/// # Ok(()) }
+/// ```
+pub struct IdleTimeout<S> {
+	shared: Arc<Shared<S>>,
+	watchdog: Thread,
+}
+
+struct Shared<S> {
+	stream: S,
+	timeout: Duration,
+	start: Instant,
+	/// Nanoseconds from `start` to the completion of the last operation.
+	last_active: AtomicU64,
+	expired: AtomicBool,
+	dropped: AtomicBool,
+}
+impl<S> Shared<S> {
+	fn touch(&self) {
+		let nanos = u64::try_from(self.start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+		self.last_active.store(nanos, SeqCst);
+	}
+	fn deadline(&self) -> Option<Instant> {
+		let last_active = Duration::from_nanos(self.last_active.load(SeqCst));
+		self.start
+			.checked_add(last_active)?
+			.checked_add(self.timeout)
+	}
+	fn error(&self) -> io::Error {
+		IdleTimeoutError {
+			timeout: self.timeout,
+		}
+		.into()
+	}
+	/// Performs an operation, counting it as activity if it succeeds and turning its outcome into
+	/// the idle timeout error if the watchdog interrupted it.
+	fn op<T>(&self, f: impl FnOnce(&S) -> io::Result<T>) -> io::Result<T> {
+		if self.expired.load(SeqCst) {
+			return Err(self.error());
+		}
+		let rslt = f(&self.stream);
+		if self.expired.load(SeqCst) {
+			return Err(self.error());
+		}
+		if rslt.is_ok() {
+			self.touch();
+		}
+		rslt
+	}
+}
+
+impl<S: Shutdown + Send + Sync + 'static> IdleTimeout<S> {
+	/// Wraps the given stream, starting a watchdog thread which shuts it down once it has been idle
+	/// for the duration of `timeout`.
+	///
+	/// The stream counts as active at the time of wrapping.
+	pub fn new(stream: S, timeout: Duration) -> io::Result<Self> {
+		let shared = Arc::new(Shared {
+			stream,
+			timeout,
+			start: Instant::now(),
+			last_active: AtomicU64::new(0),
+			expired: AtomicBool::new(false),
+			dropped: AtomicBool::new(false),
+		});
+		let weak = Arc::downgrade(&shared);
+		let watchdog = thread::Builder::new()
+			.name("interprocess idle timeout watchdog".to_owned())
+			.spawn(move || watch(&weak))?
+			.thread()
+			.clone();
+		Ok(Self { shared, watchdog })
+	}
+}
+
+/// Only holds on to the shared state while checking the deadline, so that dropping the wrapper
+/// closes the stream right away.
+fn watch<S: Shutdown>(shared: &Weak<Shared<S>>) {
+	loop {
+		let Some(shared) = shared.upgrade() else {
+			return;
+		};
+		if shared.dropped.load(SeqCst) {
+			return;
+		}
+		let now = Instant::now();
+		let wait = match shared.deadline() {
+			Some(deadline) => deadline.saturating_duration_since(now),
+			// A timeout so long that it overflows never expires
+			None => return,
+		};
+		if wait.is_zero() {
+			shared.expired.store(true, SeqCst);
+			let _ = shared.stream.shutdown(net::Shutdown::Both);
+			return;
+		}
+		drop(shared);
+		thread::park_timeout(wait);
+	}
+}
+
+impl<S> IdleTimeout<S> {
+	/// Borrows the wrapped stream.
+	///
+	/// Operations performed on the stream directly are not counted as activity.
+	#[inline]
+	pub fn get_ref(&self) -> &S {
+		&self.shared.stream
+	}
+	/// Returns the idle timeout.
+	#[inline]
+	pub fn timeout(&self) -> Duration {
+		self.shared.timeout
+	}
+	/// Returns `true` if the stream has been shut down for being idle.
+	#[inline]
+	pub fn is_expired(&self) -> bool {
+		self.shared.expired.load(SeqCst)
+	}
+}
+
+impl<S> Read for IdleTimeout<S>
+where
+	for<'a> &'a S: Read,
+{
+	#[inline]
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.shared.op(|mut s| s.read(buf))
+	}
+	#[inline]
+	fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+		self.shared.op(|mut s| s.read_vectored(bufs))
+	}
+}
+impl<S> Write for IdleTimeout<S>
+where
+	for<'a> &'a S: Write,
+{
+	#[inline]
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.shared.op(|mut s| s.write(buf))
+	}
+	#[inline]
+	fn flush(&mut self) -> io::Result<()> {
+		self.shared.op(|mut s| s.flush())
+	}
+	#[inline]
+	fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+		self.shared.op(|mut s| s.write_vectored(bufs))
+	}
+}
+
+impl<S> Drop for IdleTimeout<S> {
+	fn drop(&mut self) {
+		self.shared.dropped.store(true, SeqCst);
+		self.watchdog.unpark();
+	}
+}
+
+impl<S: Debug> Debug for IdleTimeout<S> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("IdleTimeout")
+			.field("stream", &self.shared.stream)
+			.field("timeout", &self.shared.timeout)
+			.field("expired", &self.is_expired())
+			.finish()
+	}
+}
@@ -0,0 +1,142 @@
+//! Tokio counterpart of [`IdleTimeout`](super::IdleTimeout).
+
+use crate::error::IdleTimeoutError;
+use std::{
+	future::Future,
+	io,
+	pin::Pin,
+	task::{Context, Poll},
+	time::Duration,
+};
+use tokio::{
+	io::{AsyncRead, AsyncWrite, ReadBuf},
+	time::{sleep, Instant, Sleep},
+};
+
+/// Wrapper around a Tokio stream which shuts it down once no reads or writes have completed on
+/// it for a given period of time.
+///
+/// Rather than using a watchdog, the wrapper arms a timer whenever an operation has to wait and
+/// checks it as part of polling that operation. When the timer expires, the wrapped stream is
+/// dropped, closing the connection, and the pending operation, along with every one after it,
+/// fails with the [error](IdleTimeoutError) described in the
+/// [module-level documentation](super). As a consequence, a connection on which no operation is
+/// in progress is only shut down once the next operation is attempted.
+///
+/// The timer uses the Tokio time driver, which has to be enabled in the runtime.
+#[derive(Debug)]
+pub struct IdleTimeout<S> {
+	inner: Option<S>,
+	timeout: Duration,
+	timer: Pin<Box<Sleep>>,
+}
+impl<S> IdleTimeout<S> {
+	/// Wraps the given stream, shutting it down once it has been idle for the duration of
+	/// `timeout`.
+	///
+	/// The stream counts as active at the time of wrapping. This function must be called within
+	/// the context of a Tokio runtime.
+	pub fn new(inner: S, timeout: Duration) -> Self {
+		Self {
+			inner: Some(inner),
+			timeout,
+			timer: Box::pin(sleep(timeout)),
+		}
+	}
+	/// Borrows the wrapped stream, or returns `None` if it has been shut down for being idle.
+	#[inline]
+	pub fn get_ref(&self) -> Option<&S> {
+		self.inner.as_ref()
+	}
+	/// Mutably borrows the wrapped stream, or returns `None` if it has been shut down for being
+	/// idle.
+	///
+	/// Operations performed on the stream directly are not counted as activity.
+	#[inline]
+	pub fn get_mut(&mut self) -> Option<&mut S> {
+		self.inner.as_mut()
+	}
+	/// Unwraps the stream, or returns `None` if it has been shut down for being idle.
+	#[inline]
+	pub fn into_inner(self) -> Option<S> {
+		self.inner
+	}
+	/// Returns the idle timeout.
+	#[inline]
+	pub fn timeout(&self) -> Duration {
+		self.timeout
+	}
+	/// Returns `true` if the stream has been shut down for being idle.
+	#[inline]
+	pub fn is_expired(&self) -> bool {
+		self.inner.is_none()
+	}
+
+	fn poll_op<T>(
+		&mut self,
+		cx: &mut Context<'_>,
+		f: impl FnOnce(Pin<&mut S>, &mut Context<'_>) -> Poll<io::Result<T>>,
+	) -> Poll<io::Result<T>>
+	where
+		S: Unpin,
+	{
+		let timeout = self.timeout;
+		let error = move || io::Error::from(IdleTimeoutError { timeout });
+		let Some(inner) = &mut self.inner else {
+			return Poll::Ready(Err(error()));
+		};
+		let rslt = f(Pin::new(inner), cx);
+		match rslt {
+			Poll::Ready(Ok(..)) => {
+				let deadline = Instant::now().checked_add(self.timeout);
+				// A timeout so long that it overflows never expires
+				if let Some(deadline) = deadline {
+					self.timer.as_mut().reset(deadline);
+				}
+			}
+			Poll::Pending if self.timer.as_mut().poll(cx).is_ready() => {
+				self.inner = None;
+				return Poll::Ready(Err(error()));
+			}
+			_ => {}
+		}
+		rslt
+	}
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for IdleTimeout<S> {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		self.get_mut().poll_op(cx, |s, cx| s.poll_read(cx, buf))
+	}
+}
+impl<S: AsyncWrite + Unpin> AsyncWrite for IdleTimeout<S> {
+	fn poll_write(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<io::Result<usize>> {
+		self.get_mut().poll_op(cx, |s, cx| s.poll_write(cx, buf))
+	}
+	fn poll_write_vectored(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		bufs: &[io::IoSlice<'_>],
+	) -> Poll<io::Result<usize>> {
+		self.get_mut()
+			.poll_op(cx, |s, cx| s.poll_write_vectored(cx, bufs))
+	}
+	#[inline]
+	fn is_write_vectored(&self) -> bool {
+		self.inner.as_ref().is_some_and(S::is_write_vectored)
+	}
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		self.get_mut().poll_op(cx, |s, cx| s.poll_flush(cx))
+	}
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		self.get_mut().poll_op(cx, |s, cx| s.poll_shutdown(cx))
+	}
+}
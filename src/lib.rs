@@ -9,7 +9,24 @@ mod macros;
 
 pub mod bound_util;
 pub mod error;
+pub mod event;
+pub mod handle_transfer;
+pub mod idle_timeout;
 pub mod local_socket;
+pub mod mem;
+pub mod metrics;
+#[cfg(feature = "registry")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "registry")))]
+pub mod registry;
+#[cfg(feature = "noise")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "noise")))]
+pub mod secure;
+pub mod shmem;
+pub mod single_instance;
+#[cfg(feature = "testing")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "testing")))]
+pub mod testing;
+pub mod traits;
 pub mod unnamed_pipe;
 
 /// Platform-specific functionality for various interprocess communication primitives.
@@ -30,6 +47,8 @@ pub mod os {
 	pub mod windows;
 }
 
+pub use error::Error;
+
 mod try_clone;
 pub use try_clone::*;
 
@@ -12,11 +12,18 @@
 //! for those traits. The implementation used, in cases where multiple options apply, is chosen at
 //! construction via the [name](Name) and [name type](NameType) infrastructure.
 //!
+//! Windows 10 and later also provide Unix domain sockets, but local sockets don't use them. Tokio
+//! has no support for them on Windows, and since both ends of a connection must agree on the
+//! implementation, choosing one at runtime would keep Tokio-based and blocking programs from
+//! connecting to each other. Names which work today would also stop referring to named pipes.
+//!
 //! ## Differences from regular sockets
 //! A few missing features, primarily on Windows, require local sockets to omit some important
 //! functionality, because code relying on it wouldn't be portable. Some notable differences are:
-//! -	No `.shutdown()` – your communication protocol must manually negotiate end of transmission.
-//! 	Notably, `.read_to_string()` and `.read_all()` will always block indefinitely at some point.
+//! -	No real `.shutdown()` on Windows – [`Stream::shutdown()`] is only emulated there, and the
+//! 	peer does not observe the end of transmission until the stream is dropped, so your
+//! 	communication protocol must manually negotiate it. Notably, `.read_to_string()` and
+//! 	`.read_all()` will always block indefinitely at some point.
 //! -	No datagram sockets – the difference in semantics between connectionless datagram Unix-domain
 //! 	sockets and connection-based named message pipes on Windows does not allow bridging those two
 //! 	into a common API. You can emulate datagrams on top of streams anyway, so no big deal, right?
@@ -24,7 +31,13 @@
 #[macro_use]
 mod enumdef;
 
+pub mod authenticated;
+mod batch;
+pub(crate) mod broker_util;
+pub mod hub;
 mod name;
+mod retry;
+pub mod server;
 mod stream {
 	pub(super) mod r#enum;
 	pub(super) mod r#trait;
@@ -32,6 +45,8 @@ mod stream {
 mod listener {
 	pub(super) mod r#enum;
 	pub(super) mod options;
+	#[cfg(any(unix, feature = "tokio"))]
+	pub(super) mod set;
 	pub(super) mod r#trait;
 }
 
@@ -53,8 +68,10 @@ pub mod traits {
 }
 
 pub use {
+	batch::{BatchStats, BatchWriter},
 	listener::{options::ListenerOptions, r#enum::*, r#trait::Incoming},
 	name::*,
+	retry::RetryConfig,
 	stream::r#enum::*,
 	traits::ListenerNonblockingMode,
 };
@@ -64,7 +81,7 @@ pub use {
 pub mod prelude {
 	pub use super::{
 		name::{NameType as _, ToFsName as _, ToNsName as _},
-		traits::{Listener as _, ListenerExt as _, Stream as _},
+		traits::{Listener as _, ListenerExt as _, Stream as _, StreamExt as _},
 		Listener as LocalSocketListener, Stream as LocalSocketStream,
 	};
 }
@@ -83,15 +100,22 @@ pub mod prelude {
 pub mod tokio {
 	pub(super) mod listener {
 		pub(in super::super) mod r#enum;
+		pub(in super::super) mod set;
 		pub(in super::super) mod r#trait;
 	}
 	pub(super) mod stream {
 		pub(in super::super) mod r#enum;
 		pub(in super::super) mod r#trait;
 	}
+	pub mod hub;
+	pub mod server;
+	mod splice;
+	mod timeout;
 	pub use {
-		listener::{r#enum::*, r#trait::Incoming},
+		listener::{r#enum::*, r#trait::Incoming, set::ListenerSet},
+		splice::splice,
 		stream::r#enum::*,
+		timeout::TimeoutStream,
 	};
 
 	/// Like the [sync local socket prelude](super::prelude), but for Tokio local sockets.
@@ -99,7 +123,7 @@ pub mod tokio {
 		pub use super::{
 			super::{
 				name::{NameType as _, ToFsName as _, ToNsName as _},
-				traits::tokio::{Listener as _, ListenerExt as _, Stream as _},
+				traits::tokio::{Listener as _, ListenerExt as _, Stream as _, StreamExt as _},
 			},
 			Listener as LocalSocketListener, Stream as LocalSocketStream,
 		};
@@ -107,4 +131,6 @@ pub mod tokio {
 }
 
 mod concurrency_detector;
-pub(crate) use concurrency_detector::*;
+#[cfg(any(unix, feature = "tokio"))]
+pub(crate) use listener::set::ListenerSetBase;
+pub(crate) use {concurrency_detector::*, retry::RetryState};
@@ -0,0 +1,244 @@
+//! Authentication of local socket clients by means of a handshake performed right after they
+//! connect.
+//!
+//! A server configured with a [`ServerBuilder`] only hands connections over to the application
+//! once the client has passed the checks it was set up with:
+//! -	A shared secret token, which the client has to present. This is useful for letting only the
+//! 	processes which the token was passed to, e.g. via an environment variable or a file with
+//! 	restrictive permissions, talk to the server.
+//! -	Allowlists of user IDs and process IDs, which are checked against the credentials of the
+//! 	client as reported by the OS. User IDs are only available on Unix.
+//!
+//! Clients [`connect()`] with the token, if any, and receive the verdict of the server. The
+//! handshake consists of the length of the token as a little-endian `u16` and the token itself,
+//! sent by the client, followed by a single byte sent by the server, which is 0 if the client was
+//! accepted. It is performed whether or not the server requires a token, so clients don't need to
+//! know in advance.
+//!
+//! The token is sent as is, relying on the local socket not being observable by third parties.
+//! Since a client cannot tell whether the server is the one it expects, it should only present
+//! the token to names which an impostor could not have taken, such as a path in a directory which
+//! only the server can write to.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::local_socket::{
+//! 	authenticated::{self, ServerBuilder},
+//! 	prelude::*,
+//! 	server::ServerConfig,
+//! 	GenericNamespaced,
+//! };
+//! use std::io::prelude::*;
+//!
+//! let name = "example-auth.sock".to_ns_name::<GenericNamespaced>()?;
+//! let _server = ServerBuilder::new().token("hunter2").serve(
+//! 	name.borrow(),
+//! 	|mut conn| {
+//! 		let _ = conn.write_all(b"Welcome!");
+//! 	},
+//! 	ServerConfig::new(),
+//! )?;
+//!
+//! let mut conn = authenticated::connect(name, b"hunter2")?;
+//! let mut buf = [0; 8];
+//! conn.read_exact(&mut buf)?;
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use super::{
+	prelude::*,
+	server::{self, serve, Server, ServerConfig},
+	Listener, Name, Stream,
+};
+use std::{
+	collections::BTreeSet,
+	io::{self, prelude::*},
+	sync::Arc,
+	time::Duration,
+};
+
+const ACCEPTED: u8 = 0;
+const REJECTED: u8 = 1;
+
+/// Builder for the checks which a server performs on newly connected clients.
+///
+/// A client is accepted if it passes all of the checks which are set up. With none set up, every
+/// client is accepted, although the handshake is still performed.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Clone, Debug, Default)]
+pub struct ServerBuilder {
+	token: Option<Vec<u8>>,
+	#[cfg(unix)]
+	uids: Option<BTreeSet<libc::uid_t>>,
+	pids: Option<BTreeSet<u32>>,
+	handshake_timeout: Option<Duration>,
+}
+impl ServerBuilder {
+	/// Creates a builder which accepts every client.
+	#[inline]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Requires clients to present the given token, which must be at most 65535 bytes long.
+	#[must_use = builder_must_use!()]
+	pub fn token(mut self, token: impl Into<Vec<u8>>) -> Self {
+		self.token = Some(token.into());
+		self
+	}
+	/// Adds a user ID to the allowlist of user IDs, which is checked against the effective user ID
+	/// of clients once it has been added to.
+	#[cfg(unix)]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(unix)))]
+	#[must_use = builder_must_use!()]
+	pub fn allow_uid(mut self, uid: libc::uid_t) -> Self {
+		self.uids.get_or_insert_with(BTreeSet::new).insert(uid);
+		self
+	}
+	/// Adds a process ID to the allowlist of process IDs, which is checked against the process ID
+	/// of clients once it has been added to.
+	///
+	/// ## Platform-specific behavior
+	/// On Unix, the process IDs of clients are only known on Linux and Android. Elsewhere, clients
+	/// are rejected if this allowlist is used.
+	#[must_use = builder_must_use!()]
+	pub fn allow_pid(mut self, pid: u32) -> Self {
+		self.pids.get_or_insert_with(BTreeSet::new).insert(pid);
+		self
+	}
+	/// Sets how long to wait for the client to complete the handshake, after which the connection
+	/// is dropped. There is no timeout by default.
+	///
+	/// ## Platform-specific behavior
+	/// Named pipes have no timeouts, so this is ignored on Windows, where a client which stays
+	/// silent holds up the handshake until it disconnects.
+	#[must_use = builder_must_use!()]
+	pub fn handshake_timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.handshake_timeout = timeout;
+		self
+	}
+
+	/// Performs the handshake on a newly accepted connection, returning it if the client passes
+	/// the checks.
+	///
+	/// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the client is rejected,
+	/// in which case it is notified of that before the connection is closed.
+	pub fn authenticate(&self, conn: Stream) -> io::Result<Stream> {
+		server::set_timeout(&conn, self.handshake_timeout)?;
+		let mut len = [0; 2];
+		(&conn).read_exact(&mut len)?;
+		let mut token = vec![0; usize::from(u16::from_le_bytes(len))];
+		(&conn).read_exact(&mut token)?;
+		let verdict = self.check(&conn, &token);
+		let reply = if verdict.is_ok() { ACCEPTED } else { REJECTED };
+		(&conn).write_all(&[reply])?;
+		verdict?;
+		server::set_timeout(&conn, None)?;
+		Ok(conn)
+	}
+	/// Accepts connections from the listener until one of them is from a client which passes the
+	/// checks, and returns that connection.
+	///
+	/// Connections which are rejected or fail to complete the handshake are dropped. Since the
+	/// handshake is performed on the calling thread, a client which connects and then stays silent
+	/// holds up accepting for up to the [handshake timeout](Self::handshake_timeout), or
+	/// indefinitely if there is none. [`.serve()`](Self::serve) does not have this problem.
+	pub fn accept(&self, listener: &Listener) -> io::Result<Stream> {
+		loop {
+			if let Ok(conn) = self.authenticate(listener.accept()?) {
+				return Ok(conn);
+			}
+		}
+	}
+	/// Starts a [thread-based server](server::serve) which performs the handshake on every
+	/// connection on one of its worker threads and calls `handler` with the connections from the
+	/// clients which pass the checks.
+	pub fn serve<H>(self, name: Name<'_>, handler: H, config: ServerConfig) -> io::Result<Server>
+	where
+		H: Fn(Stream) + Send + Sync + 'static,
+	{
+		let auth = Arc::new(self);
+		serve(
+			name,
+			move |conn| {
+				let Ok(conn) = auth.authenticate(conn) else {
+					return;
+				};
+				// The timeout of the handshake has replaced the one of the server
+				if server::set_timeout(&conn, config.timeout).is_ok() {
+					handler(conn);
+				}
+			},
+			config,
+		)
+	}
+
+	fn check(&self, conn: &Stream, token: &[u8]) -> io::Result<()> {
+		#[cfg(unix)]
+		if let Some(uids) = &self.uids {
+			let Stream::UdSocket(s) = conn;
+			if !uids.contains(&s.peer_credentials()?.uid) {
+				return Err(rejected("user ID of client not allowed"));
+			}
+		}
+		if let Some(pids) = &self.pids {
+			if !peer_pid(conn)?.is_some_and(|pid| pids.contains(&pid)) {
+				return Err(rejected("process ID of client not allowed"));
+			}
+		}
+		if let Some(expected) = &self.token {
+			if !constant_time_eq(expected, token) {
+				return Err(rejected("wrong token"));
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(unix)]
+fn peer_pid(conn: &Stream) -> io::Result<Option<u32>> {
+	let Stream::UdSocket(s) = conn;
+	Ok(s.peer_credentials()?
+		.pid
+		.and_then(|pid| u32::try_from(pid).ok()))
+}
+#[cfg(windows)]
+fn peer_pid(conn: &Stream) -> io::Result<Option<u32>> {
+	let Stream::NamedPipe(s) = conn;
+	s.peer_process_id().map(Some)
+}
+
+/// Compares the contents of two slices in an amount of time which only depends on their lengths.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	if a.len() != b.len() {
+		return false;
+	}
+	a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn rejected(msg: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::PermissionDenied, msg)
+}
+
+/// Connects to a server which authenticates its clients, presenting the given token, and returns
+/// the connection once the server has accepted the client. An empty token can be used for servers
+/// which don't require one.
+///
+/// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the server rejects the
+/// client, and with [`InvalidInput`](io::ErrorKind::InvalidInput) if the token is longer than
+/// 65535 bytes.
+pub fn connect(name: Name<'_>, token: &[u8]) -> io::Result<Stream> {
+	let len = u16::try_from(token.len())
+		.map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "token too long"))?;
+	let mut conn = Stream::connect(name)?;
+	let mut handshake = Vec::with_capacity(token.len().saturating_add(2));
+	handshake.extend(len.to_le_bytes());
+	handshake.extend(token);
+	conn.write_all(&handshake)?;
+	let mut reply = [0];
+	conn.read_exact(&mut reply)?;
+	match reply {
+		[ACCEPTED] => Ok(conn),
+		_ => Err(rejected("rejected by the server")),
+	}
+}
@@ -0,0 +1,174 @@
+use std::io::{self, prelude::*, IoSlice};
+
+/// A writer which coalesces many small writes into few system calls, sending them only once its
+/// buffer fills up or it is explicitly [flushed](Write::flush).
+///
+/// This is a stand-in for `TCP_CORK`, which has no analog for local sockets: chatty protocols
+/// which emit a message as several small writes (a header, a length, a payload) can batch them up
+/// and have the peer receive them with a single system call on each side. When a write doesn't
+/// fit in the remaining capacity, the buffered data and the new data are sent together with a
+/// single [vectored write](Write::write_vectored) rather than one after the other, and writes
+/// which are larger than the whole buffer go straight through without being copied.
+///
+/// Unlike [`BufWriter`](io::BufWriter), buffered data is **not** sent when the writer is dropped,
+/// since a failure to send it could not be reported. Call [`flush()`](Write::flush) or
+/// [`into_inner()`](Self::into_inner) once done, or recover the unsent data with
+/// [`into_parts()`](Self::into_parts).
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::{prelude::*, BatchWriter, GenericNamespaced, Stream};
+/// use std::io::prelude::*;
+///
+/// let conn = Stream::connect("example.sock".to_ns_name::<GenericNamespaced>()?)?;
+/// let mut writer = BatchWriter::new(&conn);
+/// for i in 0..100u32 {
+/// 	writer.write_all(&i.to_le_bytes())?;
+/// }
+/// // Everything goes out in one send
+/// writer.flush()?;
+/// assert_eq!(writer.stats().syscalls, 1);
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Debug)]
+pub struct BatchWriter<W: Write> {
+	inner: W,
+	buf: Vec<u8>,
+	capacity: usize,
+	stats: BatchStats,
+}
+
+/// Counters kept by a [`BatchWriter`], for telling how well batching works for a given workload.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BatchStats {
+	/// The number of successful calls to [`write()`](Write::write) made on the batch writer.
+	pub writes: u64,
+	/// The number of writes issued to the inner writer, each of which amounts to one system call
+	/// for local socket streams.
+	pub syscalls: u64,
+}
+
+impl<W: Write> BatchWriter<W> {
+	/// The capacity used by [`new()`](Self::new).
+	pub const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+	/// Creates a batch writer with the [default capacity](Self::DEFAULT_CAPACITY).
+	#[inline]
+	pub fn new(inner: W) -> Self {
+		Self::with_capacity(Self::DEFAULT_CAPACITY, inner)
+	}
+	/// Creates a batch writer which buffers up to `capacity` bytes before sending.
+	pub fn with_capacity(capacity: usize, inner: W) -> Self {
+		Self {
+			inner,
+			buf: Vec::with_capacity(capacity),
+			capacity,
+			stats: BatchStats::default(),
+		}
+	}
+
+	/// Returns a reference to the inner writer.
+	#[inline]
+	pub fn get_ref(&self) -> &W {
+		&self.inner
+	}
+	/// Returns a mutable reference to the inner writer.
+	///
+	/// Writing to the inner writer directly puts the data ahead of whatever is still buffered.
+	#[inline]
+	pub fn get_mut(&mut self) -> &mut W {
+		&mut self.inner
+	}
+	/// Returns the data which has been buffered but not yet sent.
+	#[inline]
+	pub fn buffer(&self) -> &[u8] {
+		&self.buf
+	}
+	/// Returns the number of bytes which can be buffered before a send is made.
+	#[inline]
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+	/// Returns the counters of the writer.
+	#[inline]
+	pub fn stats(&self) -> BatchStats {
+		self.stats
+	}
+
+	/// Sends the buffered data and returns the inner writer.
+	///
+	/// If sending fails, the data which could not be sent is lost together with the writer; use
+	/// [`flush()`](Write::flush) beforehand to be able to retry.
+	pub fn into_inner(mut self) -> io::Result<W> {
+		self.send_buffered()?;
+		Ok(self.inner)
+	}
+	/// Returns the inner writer and the buffered data which has not been sent, without sending it.
+	#[inline]
+	pub fn into_parts(self) -> (W, Vec<u8>) {
+		(self.inner, self.buf)
+	}
+
+	fn inner_write(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+		let written = match bufs {
+			[buf] => self.inner.write(buf),
+			bufs => self.inner.write_vectored(bufs),
+		}?;
+		self.stats.syscalls = self.stats.syscalls.saturating_add(1);
+		if written == 0 {
+			return Err(io::ErrorKind::WriteZero.into());
+		}
+		Ok(written)
+	}
+	fn send_buffered(&mut self) -> io::Result<()> {
+		while !self.buf.is_empty() {
+			let buf = std::mem::take(&mut self.buf);
+			let rslt = self.inner_write(&[IoSlice::new(&buf)]);
+			self.buf = buf;
+			match rslt {
+				Ok(written) => drop(self.buf.drain(..written.min(self.buf.len()))),
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(())
+	}
+	fn fits(&self, len: usize) -> bool {
+		self.buf
+			.len()
+			.checked_add(len)
+			.is_some_and(|total| total <= self.capacity)
+	}
+}
+
+impl<W: Write> Write for BatchWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let written = loop {
+			if self.fits(buf.len()) {
+				self.buf.extend_from_slice(buf);
+				break buf.len();
+			}
+			if self.buf.is_empty() {
+				break self.inner_write(&[IoSlice::new(buf)])?;
+			}
+			// Send what is buffered and as much of the new data as the inner writer takes at once
+			let pending = std::mem::take(&mut self.buf);
+			let rslt = self.inner_write(&[IoSlice::new(&pending), IoSlice::new(buf)]);
+			self.buf = pending;
+			let written = rslt?;
+			if written > self.buf.len() {
+				let from_buf = written.saturating_sub(self.buf.len());
+				self.buf.clear();
+				break from_buf;
+			}
+			drop(self.buf.drain(..written));
+		};
+		self.stats.writes = self.stats.writes.saturating_add(1);
+		Ok(written)
+	}
+	fn flush(&mut self) -> io::Result<()> {
+		self.send_buffered()?;
+		self.inner.flush()
+	}
+}
@@ -0,0 +1,113 @@
+//! Building blocks shared by the [hub](super::hub) and the registry, which
+//! both speak a protocol of frames consisting of a fixed-size header followed by a body whose
+//! length the header specifies.
+
+use std::io::{self, prelude::*};
+#[cfg(feature = "tokio")]
+use {
+	std::{
+		future::{poll_fn, Future},
+		pin::pin,
+		sync::{Mutex, MutexGuard, PoisonError},
+		task::Poll,
+	},
+	tokio::io::{AsyncRead, AsyncReadExt},
+};
+
+/// Defines a fieldless enum of frame kinds along with its conversions to and from the byte which
+/// represents it on the wire.
+macro_rules! frame_kind {
+	(
+		$(#[$attr:meta])*
+		$vis:vis enum $name:ident {
+			$($(#[$vattr:meta])* $variant:ident = $byte:literal),+ $(,)?
+		}
+	) => {
+		$(#[$attr])*
+		#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+		$vis enum $name {
+			$($(#[$vattr])* $variant),+
+		}
+		impl $name {
+			fn to_byte(self) -> u8 {
+				match self {
+					$(Self::$variant => $byte),+
+				}
+			}
+			fn from_byte(b: u8) -> Option<Self> {
+				Some(match b {
+					$($byte => Self::$variant,)+
+					_ => return None,
+				})
+			}
+		}
+	};
+}
+pub(crate) use frame_kind;
+
+/// The header of a frame, which determines the length of the body that follows it.
+pub(crate) trait FrameHeader: Sized {
+	/// The header as it is sent over the wire.
+	type Bytes: AsMut<[u8]> + Default;
+	/// The frame which the header and its body make up.
+	type Frame;
+	/// Parses and validates the header.
+	fn parse(bytes: Self::Bytes) -> io::Result<Self>;
+	/// The length of the rest of the frame.
+	fn body_len(&self) -> usize;
+	/// Turns the rest of the frame into a full frame.
+	fn finish(self, body: Vec<u8>) -> io::Result<Self::Frame>;
+}
+
+/// Reads a frame, returning `None` if the peer hangs up in between frames.
+pub(crate) fn read_frame<H: FrameHeader>(mut rdr: impl Read) -> io::Result<Option<H::Frame>> {
+	let mut header = H::Bytes::default();
+	match rdr.read_exact(header.as_mut()) {
+		Ok(()) => {}
+		Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+		Err(e) => return Err(e),
+	}
+	let header = H::parse(header)?;
+	let mut body = vec![0; header.body_len()];
+	rdr.read_exact(&mut body)?;
+	header.finish(body).map(Some)
+}
+
+/// Tokio counterpart of [`read_frame()`].
+#[cfg(feature = "tokio")]
+pub(crate) async fn read_frame_tokio<H: FrameHeader>(
+	rdr: &mut (impl AsyncRead + Unpin),
+) -> io::Result<Option<H::Frame>> {
+	let mut header = H::Bytes::default();
+	match rdr.read_exact(header.as_mut()).await {
+		Ok(..) => {}
+		Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+		Err(e) => return Err(e),
+	}
+	let header = H::parse(header)?;
+	let mut body = vec![0; header.body_len()];
+	rdr.read_exact(&mut body).await?;
+	header.finish(body).map(Some)
+}
+
+pub(crate) fn invalid(msg: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(feature = "tokio")]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+	mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Polls both futures until either of them completes, discarding the output.
+#[cfg(feature = "tokio")]
+pub(crate) async fn race(a: impl Future, b: impl Future) {
+	let (mut a, mut b) = (pin!(a), pin!(b));
+	poll_fn(|cx| {
+		if a.as_mut().poll(cx).is_ready() || b.as_mut().poll(cx).is_ready() {
+			return Poll::Ready(());
+		}
+		Poll::Pending
+	})
+	.await;
+}
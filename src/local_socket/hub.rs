@@ -0,0 +1,233 @@
+//! A publish-subscribe broker over local sockets.
+//!
+//! A hub accepts connections from any number of clients and relays the messages published by
+//! [`Publisher`]s to the [`Subscriber`]s which have subscribed to their topic, giving processes
+//! one-to-many messaging without having to know about each other. Topics are matched exactly.
+//!
+//! The hub itself runs on Tokio, and is available as `local_socket::tokio::hub::Hub` with the
+//! `tokio` feature, along with Tokio counterparts of the clients. The blocking clients in this
+//! module can connect to it from any process, whether that process uses Tokio or not.
+//!
+//! Messages are relayed in the order in which the hub receives them, and only to subscribers which
+//! are subscribed to the topic at that time. Nothing is stored for later: a message which no one is
+//! subscribed to is discarded. Topics are limited to 1 KiB and payloads to 16 MiB.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::local_socket::{
+//! 	hub::{Publisher, Subscriber},
+//! 	prelude::*,
+//! 	GenericNamespaced,
+//! };
+//!
+//! let name = "example-hub.sock".to_ns_name::<GenericNamespaced>()?;
+//!
+//! let mut subscriber = Subscriber::connect(name.borrow())?;
+//! subscriber.subscribe("weather")?;
+//!
+//! let mut publisher = Publisher::connect(name.borrow())?;
+//! publisher.publish("weather", b"sunny")?;
+//!
+//! let message = subscriber.recv()?.expect("the hub hung up");
+//! assert_eq!(message.payload, b"sunny");
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use super::{
+	broker_util::{frame_kind, invalid, read_frame, FrameHeader},
+	prelude::*,
+	Name, Stream,
+};
+use crate::SubUsizeExt;
+use std::{
+	collections::VecDeque,
+	io::{self, prelude::*},
+};
+
+const MAX_TOPIC_LEN: usize = 1 << 10;
+const MAX_PAYLOAD_LEN: usize = 1 << 24;
+const HEADER_LEN: usize = 9;
+
+/// A message relayed by the hub.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Message {
+	/// The topic the message was published under.
+	pub topic: String,
+	/// The payload of the message.
+	pub payload: Vec<u8>,
+}
+
+frame_kind! {
+	pub(super) enum Kind {
+		Subscribe = 1,
+		Unsubscribe = 2,
+		/// Sent by publishers, and relayed to subscribers as is.
+		Publish = 3,
+		/// Sent by the hub once a subscription has been updated.
+		Ack = 4,
+	}
+}
+
+/// A frame of the protocol spoken between the hub and its clients, which consists of the kind, the
+/// length of the topic and the length of the payload as little-endian `u32`s, the topic and the
+/// payload.
+#[derive(Debug)]
+pub(super) struct Frame {
+	pub(super) kind: Kind,
+	pub(super) topic: String,
+	pub(super) payload: Vec<u8>,
+}
+
+pub(super) fn encode(kind: Kind, topic: &str, payload: &[u8]) -> io::Result<Vec<u8>> {
+	if topic.len() > MAX_TOPIC_LEN {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"topic too long",
+		));
+	}
+	if payload.len() > MAX_PAYLOAD_LEN {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"payload too large",
+		));
+	}
+	// Both fit by the checks above
+	let topic_len = u32::try_from(topic.len()).unwrap_or(u32::MAX);
+	let payload_len = u32::try_from(payload.len()).unwrap_or(u32::MAX);
+
+	let mut buf = Vec::with_capacity(HEADER_LEN);
+	buf.push(kind.to_byte());
+	buf.extend(topic_len.to_le_bytes());
+	buf.extend(payload_len.to_le_bytes());
+	buf.extend(topic.as_bytes());
+	buf.extend(payload);
+	Ok(buf)
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(super) struct Header {
+	kind: Kind,
+	topic_len: usize,
+	payload_len: usize,
+}
+impl FrameHeader for Header {
+	type Bytes = [u8; HEADER_LEN];
+	type Frame = Frame;
+	fn parse(bytes: [u8; HEADER_LEN]) -> io::Result<Self> {
+		let [kind, t0, t1, t2, t3, p0, p1, p2, p3] = bytes;
+		let kind = Kind::from_byte(kind).ok_or_else(|| invalid("unknown frame kind"))?;
+		let topic_len = u32::from_le_bytes([t0, t1, t2, t3]).to_usize();
+		let payload_len = u32::from_le_bytes([p0, p1, p2, p3]).to_usize();
+		if topic_len > MAX_TOPIC_LEN || payload_len > MAX_PAYLOAD_LEN {
+			return Err(invalid("frame too large"));
+		}
+		Ok(Self {
+			kind,
+			topic_len,
+			payload_len,
+		})
+	}
+	fn body_len(&self) -> usize {
+		self.topic_len.saturating_add(self.payload_len)
+	}
+	fn finish(self, mut body: Vec<u8>) -> io::Result<Frame> {
+		if body.len() != self.body_len() {
+			return Err(invalid("frame truncated"));
+		}
+		let payload = body.split_off(self.topic_len);
+		let topic = String::from_utf8(body).map_err(|_| invalid("topic is not valid UTF-8"))?;
+		Ok(Frame {
+			kind: self.kind,
+			topic,
+			payload,
+		})
+	}
+}
+
+/// Client which publishes messages to a hub.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct Publisher(Stream);
+impl Publisher {
+	/// Connects to the hub with the given name.
+	#[inline]
+	pub fn connect(name: Name<'_>) -> io::Result<Self> {
+		Stream::connect(name).map(Self)
+	}
+	/// Publishes a message under the given topic.
+	///
+	/// This returns once the message has been sent to the hub, which may be before the subscribers
+	/// receive it. Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the topic or the
+	/// payload exceed the limits given in the [module-level documentation](self).
+	pub fn publish(&mut self, topic: &str, payload: &[u8]) -> io::Result<()> {
+		self.0.write_all(&encode(Kind::Publish, topic, payload)?)
+	}
+}
+
+/// Client which receives the messages published to a hub under the topics it subscribes to.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct Subscriber {
+	conn: Stream,
+	/// Messages which arrived while waiting for an acknowledgement.
+	pending: VecDeque<Message>,
+}
+impl Subscriber {
+	/// Connects to the hub with the given name.
+	pub fn connect(name: Name<'_>) -> io::Result<Self> {
+		Ok(Self {
+			conn: Stream::connect(name)?,
+			pending: VecDeque::new(),
+		})
+	}
+	/// Subscribes to the given topic, returning once the hub has registered the subscription. Every
+	/// message published under the topic after this returns is received by the subscriber.
+	///
+	/// Subscribing to a topic which the subscriber is already subscribed to has no effect.
+	pub fn subscribe(&mut self, topic: &str) -> io::Result<()> {
+		self.request(Kind::Subscribe, topic)
+	}
+	/// Unsubscribes from the given topic, returning once the hub has removed the subscription.
+	///
+	/// Messages published under the topic before that may still be received afterwards.
+	pub fn unsubscribe(&mut self, topic: &str) -> io::Result<()> {
+		self.request(Kind::Unsubscribe, topic)
+	}
+	/// Receives the next message, blocking until there is one. Returns `None` if the hub has hung
+	/// up.
+	pub fn recv(&mut self) -> io::Result<Option<Message>> {
+		if let Some(message) = self.pending.pop_front() {
+			return Ok(Some(message));
+		}
+		match read_frame::<Header>(&mut self.conn)? {
+			Some(Frame {
+				kind: Kind::Publish,
+				topic,
+				payload,
+			}) => Ok(Some(Message { topic, payload })),
+			// Acknowledgements are only waited for by requests
+			Some(..) => Err(invalid("unexpected frame from hub")),
+			None => Ok(None),
+		}
+	}
+
+	fn request(&mut self, kind: Kind, topic: &str) -> io::Result<()> {
+		self.conn.write_all(&encode(kind, topic, &[])?)?;
+		loop {
+			match read_frame::<Header>(&mut self.conn)? {
+				Some(Frame {
+					kind: Kind::Ack, ..
+				}) => return Ok(()),
+				Some(Frame {
+					kind: Kind::Publish,
+					topic,
+					payload,
+				}) => self.pending.push_back(Message { topic, payload }),
+				Some(..) => return Err(invalid("unexpected frame from hub")),
+				None => return Err(io::ErrorKind::UnexpectedEof.into()),
+			}
+		}
+	}
+}
@@ -1,9 +1,13 @@
 use super::{options::ListenerOptions, r#trait};
-use crate::local_socket::{ListenerNonblockingMode, Stream};
 #[cfg(unix)]
 use crate::os::unix::uds_local_socket as uds_impl;
 #[cfg(windows)]
 use crate::os::windows::named_pipe::local_socket as np_impl;
+use crate::{
+	local_socket::{ListenerNonblockingMode, Stream},
+	metrics::{self, Operation},
+	TryClone,
+};
 use std::io;
 
 impmod! {local_socket::dispatch_sync as dispatch}
@@ -34,10 +38,10 @@ mkenum!(
 /// Note that the socket file can be unlinked by other programs at any time, retaining the inode the
 /// listener is bound to but making it inaccessible to peers if it was at its last hardlink. If that
 /// happens and another listener takes the same path before the first one performs name reclamation,
-/// the socket file deletion wouldn't correspond to the listener being closed, instead deleting the
-/// socket file of the second listener. If the second listener also performs name reclamation, the
-/// ensuing deletion will silently fail. Due to the awful design of Unix, this issue cannot be
-/// mitigated.
+/// the path no longer refers to the socket file of the first listener, which is detected by
+/// comparing device and inode numbers, and the file is left alone. The comparison and the deletion
+/// are not atomic, however, so a replacement which happens right in between the two is still
+/// deleted. See `os::unix::local_socket::OwnedSocketFile`, which implements this, for more.
 ///
 /// [`create_sync()`]: super::options::ListenerOptions::create_sync
 ///
@@ -135,6 +139,22 @@ mkenum!(
 /// # io::Result::<()>::Ok(())
 /// ```
 Listener);
+impl Listener {
+	/// Retrieves and clears the pending error of the listener, or returns `None` if there is none.
+	///
+	/// ## Platform-specific behavior
+	/// On Unix, this reads and clears the `SO_ERROR` socket option. Named pipes have no such
+	/// notion, so `None` is always returned on Windows.
+	#[inline]
+	pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+		match self {
+			#[cfg(windows)]
+			Self::NamedPipe(..) => Ok(None),
+			#[cfg(unix)]
+			Self::UdSocket(s) => s.take_error(),
+		}
+	}
+}
 
 impl r#trait::Listener for Listener {
 	type Stream = Stream;
@@ -145,7 +165,9 @@ impl r#trait::Listener for Listener {
 	}
 	#[inline]
 	fn accept(&self) -> io::Result<Stream> {
-		dispatch!(Self: x in self => x.accept()).map(Stream::from)
+		metrics::track_blocking(Operation::Accept, || {
+			dispatch!(Self: x in self => x.accept()).map(Stream::from)
+		})
 	}
 	#[inline]
 	fn set_nonblocking(&self, nonblocking: ListenerNonblockingMode) -> io::Result<()> {
@@ -156,3 +178,11 @@ impl r#trait::Listener for Listener {
 		dispatch!(Self: x in self => x.do_not_reclaim_name_on_drop())
 	}
 }
+/// Clones of a listener accept connections independently of each other; see the documentation of
+/// the backend-specific implementations for details. Only the original listener performs
+/// [name reclamation](#name-reclamation).
+impl TryClone for Listener {
+	fn try_clone(&self) -> io::Result<Self> {
+		dispatch!(Self: x in self => x.try_clone()).map(From::from)
+	}
+}
@@ -1,8 +1,11 @@
 #[cfg(feature = "tokio")]
 use crate::local_socket::tokio::Listener as TokioListener;
+#[cfg(unix)]
+use crate::os::unix::local_socket::SocketHook;
 #[cfg(windows)]
-use crate::os::windows::security_descriptor::SecurityDescriptor;
+use crate::os::windows::{named_pipe::WaitTimeout, security_descriptor::SecurityDescriptor};
 use crate::{
+	error::Error,
 	local_socket::{traits, Listener, ListenerNonblockingMode, Name},
 	Sealed, TryClone,
 };
@@ -16,8 +19,22 @@ pub struct ListenerOptions<'n> {
 	pub(crate) reclaim_name: bool,
 	#[cfg(unix)]
 	pub(crate) mode: libc::mode_t,
+	#[cfg(unix)]
+	pub(crate) replace_dead_socket: bool,
+	#[cfg(unix)]
+	pub(crate) backlog: Option<libc::c_int>,
+	#[cfg(unix)]
+	pub(crate) configure_socket: Option<SocketHook>,
 	#[cfg(windows)]
 	pub(crate) security_descriptor: Option<SecurityDescriptor>,
+	#[cfg(windows)]
+	pub(crate) accept_remote: bool,
+	#[cfg(windows)]
+	pub(crate) input_buffer_size_hint: u32,
+	#[cfg(windows)]
+	pub(crate) output_buffer_size_hint: u32,
+	#[cfg(windows)]
+	pub(crate) wait_timeout: WaitTimeout,
 }
 impl Sealed for ListenerOptions<'_> {}
 
@@ -29,12 +46,26 @@ impl TryClone for ListenerOptions<'_> {
 			reclaim_name: self.reclaim_name,
 			#[cfg(unix)]
 			mode: self.mode,
+			#[cfg(unix)]
+			replace_dead_socket: self.replace_dead_socket,
+			#[cfg(unix)]
+			backlog: self.backlog,
+			#[cfg(unix)]
+			configure_socket: self.configure_socket.clone(),
 			#[cfg(windows)]
 			security_descriptor: self
 				.security_descriptor
 				.as_ref()
 				.map(TryClone::try_clone)
 				.transpose()?,
+			#[cfg(windows)]
+			accept_remote: self.accept_remote,
+			#[cfg(windows)]
+			input_buffer_size_hint: self.input_buffer_size_hint,
+			#[cfg(windows)]
+			output_buffer_size_hint: self.output_buffer_size_hint,
+			#[cfg(windows)]
+			wait_timeout: self.wait_timeout,
 		})
 	}
 }
@@ -50,8 +81,22 @@ impl<'n> ListenerOptions<'n> {
 			reclaim_name: true,
 			#[cfg(unix)]
 			mode: 0o666, // oremoR nhoJ, em llik tsum uoy etarc eht hsinif ot
+			#[cfg(unix)]
+			replace_dead_socket: false,
+			#[cfg(unix)]
+			backlog: None,
+			#[cfg(unix)]
+			configure_socket: None,
 			#[cfg(windows)]
 			security_descriptor: None,
+			#[cfg(windows)]
+			accept_remote: false,
+			#[cfg(windows)]
+			input_buffer_size_hint: 512,
+			#[cfg(windows)]
+			output_buffer_size_hint: 512,
+			#[cfg(windows)]
+			wait_timeout: WaitTimeout::DEFAULT,
 		}
 	}
 }
@@ -86,7 +131,8 @@ impl ListenerOptions<'_> {
 	/// socket name.
 	#[inline]
 	pub fn create_sync_as<L: traits::Listener>(self) -> io::Result<L> {
-		L::from_options(self)
+		let name = self.name.clone();
+		L::from_options(self).map_err(|e| Error::attach_name(e, name))
 	}
 	/// Creates a [`Listener`](TokioListener), binding it to the specified local socket name.
 	///
@@ -102,7 +148,8 @@ impl ListenerOptions<'_> {
 	#[inline]
 	#[cfg(feature = "tokio")]
 	pub fn create_tokio_as<L: traits::tokio::Listener>(self) -> io::Result<L> {
-		L::from_options(self)
+		let name = self.name.clone();
+		L::from_options(self).map_err(|e| Error::attach_name(e, name))
 	}
 }
 
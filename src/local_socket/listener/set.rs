@@ -0,0 +1,81 @@
+use std::{
+	io,
+	iter::Chain,
+	ops::Range,
+	sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+/// The bookkeeping shared by the blocking and Tokio listener sets, which only differ in how they
+/// wait for a listener to become ready.
+#[derive(Debug)]
+pub(crate) struct ListenerSetBase<L> {
+	listeners: Vec<L>,
+	next: AtomicUsize,
+}
+impl<L> ListenerSetBase<L> {
+	pub fn push(&mut self, listener: L) -> usize {
+		self.listeners.push(listener);
+		self.listeners.len().saturating_sub(1)
+	}
+	#[inline]
+	pub fn get(&self, index: usize) -> Option<&L> {
+		self.listeners.get(index)
+	}
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.listeners.len()
+	}
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.listeners.is_empty()
+	}
+	#[cfg(unix)]
+	#[inline]
+	pub fn iter(&self) -> std::slice::Iter<'_, L> {
+		self.listeners.iter()
+	}
+	#[inline]
+	pub fn into_inner(self) -> Vec<L> {
+		self.listeners
+	}
+	/// Fails if the set is empty, which would make accepting from it wait forever.
+	pub fn check_nonempty(&self) -> io::Result<()> {
+		if self.listeners.is_empty() {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"cannot accept from an empty listener set",
+			));
+		}
+		Ok(())
+	}
+	/// Returns the indices of all listeners in the order in which they should be checked for
+	/// readiness, starting one further each time so that a busy listener cannot starve the others.
+	///
+	/// Fails if the set is empty.
+	pub fn round_robin(&self) -> io::Result<Chain<Range<usize>, Range<usize>>> {
+		self.check_nonempty()?;
+		let len = self.listeners.len();
+		let start = self
+			.next
+			.fetch_add(1, Relaxed)
+			.checked_rem(len)
+			.unwrap_or(0);
+		Ok((start..len).chain(0..start))
+	}
+}
+
+impl<L> Default for ListenerSetBase<L> {
+	#[inline]
+	fn default() -> Self {
+		Vec::new().into()
+	}
+}
+impl<L> From<Vec<L>> for ListenerSetBase<L> {
+	#[inline]
+	fn from(listeners: Vec<L>) -> Self {
+		Self {
+			listeners,
+			next: AtomicUsize::new(0),
+		}
+	}
+}
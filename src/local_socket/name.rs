@@ -1,10 +1,20 @@
+mod builder;
 mod inner;
+mod resolver;
 pub(super) mod to_name;
 pub(super) mod r#type;
 
 pub(crate) use self::inner::*;
 
-pub use {r#type::*, to_name::*};
+pub use {builder::*, r#type::*, resolver::*, to_name::*};
+
+use std::{io, str::FromStr};
+
+impmod! {local_socket::dispatch_sync}
+impmod! {local_socket::name_type,
+	user_scoped as user_scoped_impl,
+	validate,
+}
 
 /// Name for a local socket.
 ///
@@ -15,7 +25,10 @@ pub use {r#type::*, to_name::*};
 ///
 /// # Creation
 /// Two traits are used to create names from basic strings: [`ToFsName`](super::ToFsName) and
-/// [`ToNsName`](super::ToNsName).
+/// [`ToNsName`](super::ToNsName). Alternatively, [`Name::interpret()`] and the [`FromStr`]
+/// implementation pick the type of the name based on a prefix, which is handy for accepting names
+/// from users. [`Name::builder()`] additionally checks names against the limits of the platform, and
+/// [`Name::service()`] looks names up by service with an application-defined [`NameResolver`].
 ///
 /// # Validity
 /// As mentioned in the [module-level documentation](super), not all platforms support all types of
@@ -63,7 +76,120 @@ impl Name<'_> {
 		Name(self.0.into_owned())
 	}
 
+	/// Checks whether a server is currently listening on the name.
+	///
+	/// This is useful for service discovery and single-instance logic, but is inherently racy: the
+	/// server may appear or go away right after the check.
+	///
+	/// ## Platform-specific behavior
+	/// ### Unix
+	/// The check starts a nonblocking connection to the socket and closes it right away, so the
+	/// server will accept a connection which receives end of file immediately. A server whose
+	/// queue of pending connections is full is still reported as listening, without waiting for
+	/// room in the queue. A socket file which exists but refuses connections is reported as
+	/// [`Stale`](Liveness::Stale), while a file which isn't a socket makes the check fail with
+	/// [`AlreadyExists`](io::ErrorKind::AlreadyExists).
+	///
+	/// ### Windows
+	/// The check waits for an instance of the named pipe to become available for as short a time
+	/// as possible, without connecting to it. Named pipes disappear along with their servers, and
+	/// thus are never reported as [`Stale`](Liveness::Stale).
+	pub fn probe(&self) -> io::Result<Liveness> {
+		dispatch_sync::probe(self.borrow())
+	}
+
 	pub(crate) fn invalid() -> Self {
 		Self(NameInner::default())
 	}
 }
+impl<'s> Name<'s> {
+	/// Creates a [builder](NameBuilder) which checks the name against the limits of the platform.
+	#[inline]
+	pub fn builder() -> NameBuilder<'s> {
+		NameBuilder::new()
+	}
+	/// Interprets a string as a name of either type, depending on its prefix:
+	/// -	Strings starting with `@` are namespaced names of the [`GenericNamespaced`] type, with the
+	/// 	`@` removed. On Linux, this makes them abstract socket names.
+	/// -	Everything else is a filesystem path of the [`GenericFilePath`] type. Paths which start
+	/// 	with `@` can be written with a leading `./`.
+	///
+	/// This allows programs to accept a local socket name as a single argument or configuration
+	/// value without having to pick a name type themselves. Names of pipes on other machines are
+	/// rejected on Windows, as described in the documentation of [`GenericFilePath`].
+	///
+	/// ```
+	/// use interprocess::local_socket::Name;
+	/// let name = Name::interpret("@example.sock")?;
+	/// assert!(name.is_namespaced());
+	/// # std::io::Result::<()>::Ok(())
+	/// ```
+	pub fn interpret(s: &'s str) -> io::Result<Self> {
+		match s.strip_prefix('@') {
+			Some(ns) => ns.to_ns_name::<GenericNamespaced>(),
+			None => s.to_fs_name::<GenericFilePath>(),
+		}
+	}
+}
+
+impl Name<'static> {
+	/// Creates a name for a socket of the current user, in a place that is chosen according to the
+	/// conventions of the platform:
+	/// -	On Unix, the socket file is placed in `$XDG_RUNTIME_DIR`. If that isn't set, a private
+	/// 	`interprocess-<uid>` directory is created in the temporary directory (`$TMPDIR` or `/tmp`)
+	/// 	with permissions that only let the current user in. If the directory already exists and
+	/// 	isn't private, an error of kind [`PermissionDenied`](io::ErrorKind::PermissionDenied) is
+	/// 	returned. Either way, only the current user (and the superuser) can reach the socket.
+	/// -	On Windows, the pipe name is prefixed with the ID of the session of the current process,
+	/// 	so that sockets of different sessions on the same system don't collide. This does
+	/// 	**not** restrict access: the pipe namespace is shared by the whole system, so other users
+	/// 	can still connect to the pipe, or create one with the same name first, as far as the
+	/// 	security descriptor of the pipe allows. The default one lets everyone connect for
+	/// 	reading. To keep other users out, give the listener a security descriptor which only
+	/// 	grants access to the current user, via the `security_descriptor()` method of the
+	/// 	Windows-specific `ListenerOptionsExt` trait.
+	///
+	/// `file` must be a single path component, i.e. non-empty, not `.` or `..`, and free of
+	/// slashes and backslashes. Otherwise, an error of kind
+	/// [`InvalidInput`](io::ErrorKind::InvalidInput) is returned. The result is checked against the
+	/// limits of the platform as [`NameBuilder`] does.
+	///
+	/// ```no_run
+	/// use interprocess::local_socket::{ListenerOptions, Name};
+	/// let listener = ListenerOptions::new().name(Name::user_scoped("myapp.sock")?).create_sync()?;
+	/// # std::io::Result::<()>::Ok(())
+	/// ```
+	pub fn user_scoped(file: &str) -> io::Result<Self> {
+		if matches!(file, "" | "." | "..") || file.contains(['/', '\\']) {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"user-scoped name must be a single path component",
+			));
+		}
+		let name = user_scoped_impl(file)?;
+		validate(&name)?;
+		Ok(name)
+	}
+}
+
+/// Parses a name with the prefix syntax of [`Name::interpret()`].
+impl FromStr for Name<'static> {
+	type Err = io::Error;
+	#[inline]
+	fn from_str(s: &str) -> io::Result<Self> {
+		Name::interpret(s).map(Name::into_owned)
+	}
+}
+
+/// Result of [probing](Name::probe) a name.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Liveness {
+	/// A server is listening on the name.
+	Listening,
+	/// The name refers to a socket file, but no server is listening on it, most likely because the
+	/// server which created it has exited without removing it. A new server can only be created
+	/// on the name after the file is removed.
+	Stale,
+	/// Nothing exists under the name.
+	Absent,
+}
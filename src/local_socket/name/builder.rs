@@ -0,0 +1,138 @@
+#[cfg(unix)]
+use super::{GenericFilePath, ToFsName};
+use super::{GenericNamespaced, Name, ToNsName};
+use crate::{error::Error, Sealed};
+use std::{ffi::OsStr, io, path::Path};
+
+impmod! {local_socket::name_type,
+	validate,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Kind {
+	Namespaced,
+	Path,
+	Auto,
+}
+
+/// A builder for [local socket names](Name) which checks the name against the limits of the
+/// platform upfront.
+///
+/// Names produced by [`ToFsName`](super::ToFsName) and [`ToNsName`] are only checked for a few
+/// basic properties, while things like length limits are only enforced by the OS when the name is
+/// used to create a listener or to connect, and reported with rather unhelpful error codes.
+/// [`.build()`](Self::build) performs those checks itself, failing with an error of kind
+/// [`InvalidInput`](io::ErrorKind::InvalidInput) which explains what's wrong with the name.
+///
+/// The following is checked:
+/// -	The name must not be empty.
+/// -	On Unix, the name must fit into the `sun_path` field of `sockaddr_un`, which is 108 bytes
+/// 	long on Linux and 104 bytes long on most other systems, including the nul terminator.
+/// -	On Windows, the full path of the pipe must not exceed 256 characters.
+/// -	On Windows, the name must not refer to a pipe on another machine, unless allowed with
+/// 	`NameBuilderExt::allow_remote()` from `os::windows::local_socket`.
+///
+/// ```
+/// use interprocess::local_socket::Name;
+/// let name = Name::builder().auto("example.sock").build()?;
+/// let long = "a".repeat(1000);
+/// assert!(Name::builder().path(&long).build().is_err());
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NameBuilder<'s> {
+	name: Option<(Kind, &'s OsStr)>,
+	#[cfg(windows)]
+	pub(crate) host: Option<&'s OsStr>,
+	#[cfg(windows)]
+	pub(crate) allow_remote: bool,
+}
+impl Sealed for NameBuilder<'_> {}
+impl<'s> NameBuilder<'s> {
+	/// Creates a builder with no name specified.
+	#[inline]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Specifies a name in the dedicated local socket namespace of the platform, which is the
+	/// abstract namespace on Linux and Android and the named pipe filesystem on Windows.
+	///
+	/// Building fails with an error of kind [`Unsupported`](io::ErrorKind::Unsupported) on
+	/// platforms which don't have such a namespace. Use [`.auto()`](Self::auto) to fall back to a
+	/// path in that case.
+	#[inline]
+	pub fn namespaced(mut self, name: &'s (impl AsRef<OsStr> + ?Sized)) -> Self {
+		self.name = Some((Kind::Namespaced, name.as_ref()));
+		self
+	}
+	/// Specifies a filesystem path, with the semantics of [`GenericFilePath`](super::GenericFilePath).
+	#[inline]
+	pub fn path(mut self, path: &'s (impl AsRef<Path> + ?Sized)) -> Self {
+		self.name = Some((Kind::Path, path.as_ref().as_os_str()));
+		self
+	}
+	/// Specifies a name which is put in the dedicated local socket namespace if the platform has
+	/// one, and in a special directory otherwise, with the semantics of [`GenericNamespaced`].
+	#[inline]
+	pub fn auto(mut self, name: &'s (impl AsRef<OsStr> + ?Sized)) -> Self {
+		self.name = Some((Kind::Auto, name.as_ref()));
+		self
+	}
+
+	/// Produces the name, checking it against the limits of the platform.
+	///
+	/// Fails with an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput) if no name was
+	/// specified or if the name is invalid.
+	pub fn build(self) -> io::Result<Name<'s>> {
+		let Some((kind, name)) = self.name else {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"no local socket name was specified",
+			));
+		};
+		#[cfg(windows)]
+		if let Some(host) = self.host {
+			if let Kind::Path = kind {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidInput,
+					"a host can only be specified along with a namespaced name",
+				));
+			}
+			let name = crate::os::windows::local_socket::name_type::on_host(host, name)?;
+			return self.finish(name);
+		}
+		let name = match kind {
+			Kind::Namespaced => {
+				let name = name.to_ns_name::<GenericNamespaced>()?;
+				if !name.is_namespaced() {
+					return Err(Error::UnsupportedNameType {
+						reason: "this platform has no dedicated local socket namespace",
+					}
+					.into());
+				}
+				name
+			}
+			// Remote paths are only rejected in `finish()`, where `allow_remote` is considered
+			#[cfg(windows)]
+			Kind::Path => crate::os::windows::local_socket::name_type::map_path(name.into())?,
+			#[cfg(unix)]
+			Kind::Path => Path::new(name).to_fs_name::<GenericFilePath>()?,
+			Kind::Auto => name.to_ns_name::<GenericNamespaced>()?,
+		};
+		self.finish(name)
+	}
+	fn finish<'n>(&self, name: Name<'n>) -> io::Result<Name<'n>> {
+		validate(&name)?;
+		#[cfg(windows)]
+		let name = {
+			use crate::os::windows::local_socket::name_type::{allow_remote, reject_remote};
+			if self.allow_remote {
+				allow_remote(name)
+			} else {
+				reject_remote(&name)?;
+				name
+			}
+		};
+		Ok(name)
+	}
+}
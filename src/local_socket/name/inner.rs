@@ -9,6 +9,9 @@ use widestring::U16CStr;
 pub(crate) enum NameInner<'s> {
 	#[cfg(windows)]
 	NamedPipe(Cow<'s, U16CStr>),
+	/// A named pipe path which is allowed to refer to a pipe on another machine.
+	#[cfg(windows)]
+	RemoteNamedPipe(Cow<'s, U16CStr>),
 	#[cfg(unix)]
 	UdSocketPath(Cow<'s, OsStr>),
 	#[cfg(unix)]
@@ -35,6 +38,8 @@ macro_rules! map_cow {
 		match $var {
 			#[cfg(windows)]
 			NameInner::NamedPipe($nm) => NameInner::NamedPipe($e),
+			#[cfg(windows)]
+			NameInner::RemoteNamedPipe($nm) => NameInner::RemoteNamedPipe($e),
 			#[cfg(unix)]
 			NameInner::UdSocketPath($nm) => NameInner::UdSocketPath($e),
 			#[cfg(unix)]
@@ -49,7 +54,7 @@ impl<'s> NameInner<'s> {
 	pub const fn is_namespaced(&self) -> bool {
 		match self {
 			#[cfg(windows)]
-			Self::NamedPipe(..) => true,
+			Self::NamedPipe(..) | Self::RemoteNamedPipe(..) => true,
 			#[cfg(unix)]
 			Self::UdSocketPath(..) => false,
 			#[cfg(unix)]
@@ -61,7 +66,7 @@ impl<'s> NameInner<'s> {
 	pub const fn is_path(&self) -> bool {
 		match self {
 			#[cfg(windows)]
-			Self::NamedPipe(..) => true,
+			Self::NamedPipe(..) | Self::RemoteNamedPipe(..) => true,
 			#[cfg(unix)]
 			Self::UdSocketPath(..) => true,
 			#[cfg(unix)]
@@ -0,0 +1,78 @@
+use super::{GenericNamespaced, Name, ToNsName};
+use std::{io, sync::OnceLock};
+
+impmod! {local_socket::name_type,
+	validate,
+}
+
+/// Maps logical service names to concrete [local socket names](Name).
+///
+/// Applications refer to their sockets by service name via [`Name::service()`], and the
+/// process-wide resolver, installed once at startup with [`set_name_resolver()`], decides where the
+/// sockets of those services actually live. This keeps knowledge of the deployment layout, such as
+/// a per-user runtime directory or a pipe name prefix, in one place rather than at every call site.
+///
+/// Closures of the right signature implement this trait.
+///
+/// # Example
+/// ```
+/// use interprocess::local_socket::{set_name_resolver, GenericFilePath, Name, ToFsName};
+/// use std::env;
+///
+/// # #[cfg(unix)] {
+/// let installed = set_name_resolver(|service: &str| {
+/// 	let dir = env::var_os("XDG_RUNTIME_DIR").unwrap_or_else(|| "/tmp".into());
+/// 	let path = std::path::Path::new(&dir).join(format!("{service}.sock"));
+/// 	path.to_fs_name::<GenericFilePath>().map(Name::into_owned)
+/// });
+/// assert!(installed.is_ok());
+/// let name = Name::service("example")?;
+/// assert!(name.is_path());
+/// # }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+pub trait NameResolver: Send + Sync + 'static {
+	/// Resolves the given service name.
+	fn resolve(&self, service: &str) -> io::Result<Name<'static>>;
+}
+impl<F: Fn(&str) -> io::Result<Name<'static>> + Send + Sync + 'static> NameResolver for F {
+	#[inline]
+	fn resolve(&self, service: &str) -> io::Result<Name<'static>> {
+		self(service)
+	}
+}
+
+static RESOLVER: OnceLock<Box<dyn NameResolver>> = OnceLock::new();
+
+/// Installs the process-wide [name resolver](NameResolver).
+///
+/// The resolver can only be installed once, and cannot be removed afterwards. If a resolver has
+/// already been installed, the given one is returned back.
+pub fn set_name_resolver<R: NameResolver>(resolver: R) -> Result<(), R> {
+	let mut resolver = Some(resolver);
+	RESOLVER.get_or_init(|| match resolver.take() {
+		Some(resolver) => Box::new(resolver),
+		None => unreachable!(),
+	});
+	match resolver {
+		Some(resolver) => Err(resolver),
+		None => Ok(()),
+	}
+}
+
+impl Name<'static> {
+	/// Resolves a logical service name to a name with the process-wide
+	/// [name resolver](NameResolver), checking the result against the limits of the platform as
+	/// [`NameBuilder`](super::NameBuilder) does.
+	///
+	/// If no resolver has been [installed](set_name_resolver), the service name is used as a
+	/// [`GenericNamespaced`] name.
+	pub fn service(service: &str) -> io::Result<Self> {
+		let name = match RESOLVER.get() {
+			Some(resolver) => resolver.resolve(service)?,
+			None => service.to_ns_name::<GenericNamespaced>()?.into_owned(),
+		};
+		validate(&name)?;
+		Ok(name)
+	}
+}
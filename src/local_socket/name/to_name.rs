@@ -2,6 +2,7 @@ use super::{
 	r#type::{NamespacedNameType, PathNameType},
 	Name,
 };
+use crate::error::Error;
 use std::{
 	borrow::Cow,
 	ffi::{CStr, CString, OsStr, OsString},
@@ -43,7 +44,7 @@ pub trait ToNsName<'s, S: ToOwned + ?Sized> {
 
 #[allow(dead_code)]
 fn err(s: &'static str) -> io::Error {
-	io::Error::new(io::ErrorKind::Unsupported, s)
+	Error::UnsupportedNameType { reason: s }.into()
 }
 
 impl<'s> ToFsName<'s, OsStr> for &'s Path {
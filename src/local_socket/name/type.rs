@@ -65,6 +65,11 @@ tag_enum!(
 /// transformations. Attempting to map any other type of path, including a normalization-bypassing
 /// path (`\\?\`) currently returns an error.
 ///
+/// Paths of pipes on other machines, which start with `\\HOSTNAME\pipe\`, are rejected with an
+/// error of kind [`PermissionDenied`](io::ErrorKind::PermissionDenied), since connecting to them
+/// authenticates the current user to that machine. [`NameBuilder`](super::NameBuilder) can be
+/// told to allow them.
+///
 /// ### Unix
 /// Resolves to filesystem paths to Unix domain sockets without performing any transformations.
 GenericFilePath);
@@ -0,0 +1,135 @@
+use crate::error::ConnectRetryError;
+use std::{
+	io,
+	num::NonZeroU32,
+	time::{Duration, Instant},
+};
+
+/// Configuration of the retry loop performed by
+/// [`connect_with_retries()`](super::traits::StreamExt::connect_with_retries).
+///
+/// Connection attempts are retried only if they fail in a way that suggests that the server might
+/// not have started listening yet: on [`NotFound`](io::ErrorKind::NotFound),
+/// [`ConnectionRefused`](io::ErrorKind::ConnectionRefused) and
+/// [`WouldBlock`](io::ErrorKind::WouldBlock) (which is what busy named pipes report). Any other
+/// error is returned immediately.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RetryConfig {
+	/// The maximum number of connection attempts to make, including the first one. `None` means
+	/// that there is no limit, in which case [`deadline`](#structfield.deadline) should be set.
+	///
+	/// The default value is 10.
+	pub max_attempts: Option<NonZeroU32>,
+	/// How long to wait after the first failed attempt.
+	///
+	/// The default value is 10 milliseconds.
+	pub initial_delay: Duration,
+	/// The factor by which the delay is multiplied after each failed attempt.
+	///
+	/// The default value is 2.
+	pub backoff_factor: u32,
+	/// The upper bound on the delay between two attempts.
+	///
+	/// The default value is 1 second.
+	pub max_delay: Duration,
+	/// The total amount of time after which no further attempts are made, counted from the start
+	/// of the first attempt.
+	///
+	/// There is no deadline by default.
+	pub deadline: Option<Duration>,
+}
+impl RetryConfig {
+	/// Creates a configuration with default values. Identical to `Default::default()`.
+	pub const fn new() -> Self {
+		Self {
+			max_attempts: NonZeroU32::new(10),
+			initial_delay: Duration::from_millis(10),
+			backoff_factor: 2,
+			max_delay: Duration::from_secs(1),
+			deadline: None,
+		}
+	}
+	builder_setters! {
+		/// Sets the maximum number of connection attempts.
+		///
+		/// See the [associated field](#structfield.max_attempts) for more.
+		max_attempts: Option<NonZeroU32>,
+		/// Sets the delay after the first failed attempt.
+		///
+		/// See the [associated field](#structfield.initial_delay) for more.
+		initial_delay: Duration,
+		/// Sets the factor by which the delay grows after each failed attempt.
+		///
+		/// See the [associated field](#structfield.backoff_factor) for more.
+		backoff_factor: u32,
+		/// Sets the upper bound on the delay.
+		///
+		/// See the [associated field](#structfield.max_delay) for more.
+		max_delay: Duration,
+		/// Sets the total time budget for all attempts.
+		///
+		/// See the [associated field](#structfield.deadline) for more.
+		deadline: Option<Duration>,
+	}
+}
+impl Default for RetryConfig {
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+fn is_retryable(e: &io::Error) -> bool {
+	use io::ErrorKind::*;
+	matches!(e.kind(), NotFound | ConnectionRefused | WouldBlock)
+}
+
+/// Bookkeeping shared by the sync and async retry loops.
+pub(crate) struct RetryState {
+	config: RetryConfig,
+	start: Instant,
+	attempts: u32,
+	delay: Duration,
+}
+impl RetryState {
+	pub fn new(config: RetryConfig) -> Self {
+		Self {
+			config,
+			start: Instant::now(),
+			attempts: 0,
+			delay: config.initial_delay,
+		}
+	}
+	/// Records a failed attempt, returning either the amount of time to wait before the next one or
+	/// the final error if no more attempts are to be made.
+	pub fn fail(&mut self, error: io::Error) -> Result<Duration, ConnectRetryError> {
+		self.attempts = self.attempts.saturating_add(1);
+		let elapsed = self.start.elapsed();
+		let give_up = |error| ConnectRetryError {
+			attempts: self.attempts,
+			elapsed,
+			last_error: error,
+		};
+
+		if !is_retryable(&error) {
+			return Err(give_up(error));
+		}
+		if matches!(self.config.max_attempts, Some(max) if self.attempts >= max.get()) {
+			return Err(give_up(error));
+		}
+		let mut delay = self.delay;
+		if let Some(deadline) = self.config.deadline {
+			let remaining = deadline.saturating_sub(elapsed);
+			if remaining.is_zero() {
+				return Err(give_up(error));
+			}
+			delay = delay.min(remaining);
+		}
+		self.delay = self
+			.delay
+			.saturating_mul(self.config.backoff_factor)
+			.min(self.config.max_delay);
+		Ok(delay)
+	}
+}
@@ -0,0 +1,372 @@
+//! Ready-made accept loops for local socket servers.
+//!
+//! Most servers accept connections in a loop and hand each one off to be handled concurrently with
+//! the others. [`serve()`] implements this pattern on top of a bounded pool of threads, taking care
+//! of per-connection timeouts and of winding the server down without cutting off clients which are
+//! being served. A Tokio counterpart which spawns tasks instead of using threads is available as
+//! `local_socket::tokio::server::serve()` with the `tokio` feature.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::local_socket::{
+//! 	prelude::*,
+//! 	server::{serve, ServerConfig},
+//! 	GenericNamespaced,
+//! };
+//! use std::io::{prelude::*, BufReader};
+//!
+//! let name = "example.sock".to_ns_name::<GenericNamespaced>()?;
+//! let server = serve(
+//! 	name,
+//! 	|conn| {
+//! 		let mut conn = BufReader::new(conn);
+//! 		let mut line = String::new();
+//! 		if conn.read_line(&mut line).is_ok() {
+//! 			let _ = conn.get_mut().write_all(line.as_bytes());
+//! 		}
+//! 	},
+//! 	ServerConfig::new(),
+//! )?;
+//! // ...
+//! server.shutdown()?;
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use super::{prelude::*, ListenerOptions, Name, Stream};
+use std::{
+	io,
+	num::{NonZeroU32, NonZeroUsize},
+	panic::{self, AssertUnwindSafe},
+	sync::{
+		atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst},
+		mpsc::{self, Receiver},
+		Arc, Mutex,
+	},
+	thread::{self, JoinHandle},
+	time::{Duration, Instant},
+};
+
+/// Configuration of the servers run by [`serve()`] and its Tokio counterpart.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ServerConfig {
+	/// The maximum number of connections handled at once. For the thread-based server, this is the
+	/// number of worker threads.
+	///
+	/// What happens to clients which connect while this many connections are being handled is
+	/// determined by the [overload policy](#structfield.overload).
+	///
+	/// The default value is 16.
+	pub max_connections: NonZeroUsize,
+	/// The timeout applied to every receive and send operation on accepted connections. `None`
+	/// lets them wait indefinitely.
+	///
+	/// Operations which time out fail with an error of kind
+	/// [`TimedOut`](io::ErrorKind::TimedOut) or [`WouldBlock`](io::ErrorKind::WouldBlock),
+	/// depending on the platform.
+	///
+	/// There is no timeout by default.
+	///
+	/// ## Platform-specific behavior
+	/// The thread-based server can only apply timeouts on Unix, where it uses the `SO_RCVTIMEO` and
+	/// `SO_SNDTIMEO` socket options. Named pipes have no timeouts, so this is ignored by the
+	/// thread-based server on Windows. The Tokio server applies timeouts on all platforms.
+	pub timeout: Option<Duration>,
+	/// The maximum rate at which connections are accepted, or `None` to accept them as fast as
+	/// they come in.
+	///
+	/// Together with [`max_connections`](#structfield.max_connections), this keeps a client which
+	/// opens connections in a loop from exhausting the file descriptors or handles of the server.
+	///
+	/// There is no rate limit by default.
+	pub accept_rate: Option<RateLimit>,
+	/// What to do with connections which exceed the limits set by
+	/// [`max_connections`](#structfield.max_connections) and
+	/// [`accept_rate`](#structfield.accept_rate).
+	///
+	/// The default is [`OverloadPolicy::Delay`].
+	pub overload: OverloadPolicy,
+}
+impl ServerConfig {
+	/// Creates a configuration with default values. Identical to `Default::default()`.
+	pub const fn new() -> Self {
+		Self {
+			max_connections: match NonZeroUsize::new(16) {
+				Some(n) => n,
+				None => unreachable!(),
+			},
+			timeout: None,
+			accept_rate: None,
+			overload: OverloadPolicy::Delay,
+		}
+	}
+	builder_setters! {
+		/// Sets the maximum number of connections handled at once.
+		///
+		/// See the [associated field](#structfield.max_connections) for more.
+		max_connections: NonZeroUsize,
+		/// Sets the timeout for operations on accepted connections.
+		///
+		/// See the [associated field](#structfield.timeout) for more.
+		timeout: Option<Duration>,
+		/// Sets the maximum rate at which connections are accepted.
+		///
+		/// See the [associated field](#structfield.accept_rate) for more.
+		accept_rate: Option<RateLimit>,
+		/// Sets what to do with connections which exceed the limits.
+		///
+		/// See the [associated field](#structfield.overload) for more.
+		overload: OverloadPolicy,
+	}
+}
+impl Default for ServerConfig {
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// A limit on the rate of accepted connections, allowing a given number of them per period of
+/// time.
+///
+/// Connections may arrive in bursts of up to the full number at once, after which they are spaced
+/// out evenly over the period.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RateLimit {
+	connections: NonZeroU32,
+	period: Duration,
+}
+impl RateLimit {
+	/// Creates a limit of the given number of connections per period.
+	#[inline]
+	pub const fn new(connections: NonZeroU32, period: Duration) -> Self {
+		Self {
+			connections,
+			period,
+		}
+	}
+	/// Returns the number of connections which may be accepted per period.
+	#[inline]
+	pub const fn connections(&self) -> NonZeroU32 {
+		self.connections
+	}
+	/// Returns the period.
+	#[inline]
+	pub const fn period(&self) -> Duration {
+		self.period
+	}
+}
+
+/// What a server does with connections which exceed its limits.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OverloadPolicy {
+	/// Stop accepting until the limits allow for another connection. Clients which connect in the
+	/// meantime wait in the listen queue of the OS, which may refuse them once full.
+	#[default]
+	Delay,
+	/// Keep accepting, but close connections which exceed the limits right away, before they are
+	/// handed to the handler. The clients observe this as the other end hanging up.
+	Refuse,
+}
+
+/// Enforces a [`RateLimit`] using the generic cell rate algorithm.
+#[derive(Debug)]
+pub(super) struct RateLimiter {
+	interval: Duration,
+	tolerance: Duration,
+	/// When the next connection would be accepted if connections were spaced out perfectly.
+	next: Option<Instant>,
+}
+impl RateLimiter {
+	pub(super) fn new(limit: RateLimit) -> Self {
+		// Cannot fail, the divisor is nonzero
+		let interval = limit
+			.period
+			.checked_div(limit.connections.get())
+			.unwrap_or_default();
+		Self {
+			interval,
+			tolerance: limit.period.saturating_sub(interval),
+			next: None,
+		}
+	}
+	/// Returns how long to wait before a connection can be accepted, which is zero if it can be
+	/// accepted right away.
+	pub(super) fn delay(&self, now: Instant) -> Duration {
+		let next = self.next.map_or(now, |next| next.max(now));
+		next.saturating_duration_since(now)
+			.saturating_sub(self.tolerance)
+	}
+	/// Accounts for a connection accepted at the given point in time.
+	pub(super) fn record(&mut self, now: Instant) {
+		let next = self.next.map_or(now, |next| next.max(now));
+		self.next = Some(next.checked_add(self.interval).unwrap_or(next));
+	}
+}
+
+/// Creates a listener on the given name and starts serving connections to it on a pool of
+/// threads, calling `handler` with every accepted connection.
+///
+/// The accept loop runs on a thread of its own, and this function returns as soon as the listener
+/// is created. See [`ServerConfig`] for the available knobs, including limits on the number of
+/// connections and on the rate at which they are accepted. A handler which panics only terminates
+/// the connection it was handling.
+///
+/// If accepting a connection fails, the server stops accepting and winds down as if shut down,
+/// and the error is returned by [`Server::wait()`] or [`Server::shutdown()`].
+pub fn serve<H>(name: Name<'_>, handler: H, config: ServerConfig) -> io::Result<Server>
+where
+	H: Fn(Stream) + Send + Sync + 'static,
+{
+	let name = name.into_owned();
+	let listener = ListenerOptions::new().name(name.borrow()).create_sync()?;
+	let stopping = Arc::new(AtomicBool::new(false));
+	let handler = Arc::new(handler);
+	let busy = Arc::new(AtomicUsize::new(0));
+
+	// A rendezvous channel makes the accept loop wait for a free worker before accepting more.
+	let (tx, rx) = mpsc::sync_channel::<Stream>(0);
+	let rx = Arc::new(Mutex::new(rx));
+	let workers = (0..config.max_connections.get())
+		.map(|_| {
+			let (rx, handler, busy) = (Arc::clone(&rx), Arc::clone(&handler), Arc::clone(&busy));
+			thread::spawn(move || work(&rx, &*handler, &busy, config.timeout))
+		})
+		.collect();
+
+	let acceptor = {
+		let stopping = Arc::clone(&stopping);
+		let mut limiter = config.accept_rate.map(RateLimiter::new);
+		let refuse = config.overload == OverloadPolicy::Refuse;
+		thread::spawn(move || -> io::Result<()> {
+			loop {
+				if let (Some(limiter), false) = (&limiter, refuse) {
+					// Parked rather than asleep so that shutting down can cut the wait short
+					loop {
+						let delay = limiter.delay(Instant::now());
+						if delay.is_zero() || stopping.load(SeqCst) {
+							break;
+						}
+						thread::park_timeout(delay);
+					}
+				}
+				let conn = listener.accept();
+				if stopping.load(SeqCst) {
+					return Ok(());
+				}
+				let conn = conn?;
+				if let Some(limiter) = &mut limiter {
+					let now = Instant::now();
+					if refuse && !limiter.delay(now).is_zero() {
+						continue;
+					}
+					limiter.record(now);
+				}
+				if refuse && busy.load(SeqCst) >= config.max_connections.get() {
+					continue;
+				}
+				busy.fetch_add(1, SeqCst);
+				if tx.send(conn).is_err() {
+					return Ok(());
+				}
+			}
+		})
+	};
+
+	Ok(Server {
+		name,
+		stopping,
+		acceptor: Some(acceptor),
+		workers,
+	})
+}
+
+fn work(
+	rx: &Mutex<Receiver<Stream>>,
+	handler: &(dyn Fn(Stream) + Sync),
+	busy: &AtomicUsize,
+	timeout: Option<Duration>,
+) {
+	loop {
+		// The lock is released before the handler is called
+		let conn = match rx.lock() {
+			Ok(rx) => rx.recv(),
+			Err(..) => return,
+		};
+		let Ok(conn) = conn else {
+			return;
+		};
+		if set_timeout(&conn, timeout).is_ok() {
+			let _ = panic::catch_unwind(AssertUnwindSafe(|| handler(conn)));
+		}
+		busy.fetch_sub(1, SeqCst);
+	}
+}
+
+pub(super) fn set_timeout(conn: &Stream, timeout: Option<Duration>) -> io::Result<()> {
+	match conn {
+		#[cfg(windows)]
+		Stream::NamedPipe(..) => {
+			let _ = timeout;
+			Ok(())
+		}
+		#[cfg(unix)]
+		Stream::UdSocket(s) => {
+			s.set_read_timeout(timeout)?;
+			s.set_write_timeout(timeout)
+		}
+	}
+}
+
+/// Handle to a server started by [`serve()`].
+///
+/// Dropping the handle shuts the server down in the same way as [`.shutdown()`](Self::shutdown)
+/// does, discarding the error it would return.
+#[derive(Debug)]
+pub struct Server {
+	name: Name<'static>,
+	stopping: Arc<AtomicBool>,
+	acceptor: Option<JoinHandle<io::Result<()>>>,
+	workers: Vec<JoinHandle<()>>,
+}
+impl Server {
+	/// Stops accepting new connections and waits for the connections which are being handled to
+	/// be finished with.
+	///
+	/// Returns the error which made the server stop beforehand, if any.
+	pub fn shutdown(mut self) -> io::Result<()> {
+		self.stop()
+	}
+	/// Waits for the server to stop on its own, which only happens if accepting a connection fails,
+	/// and returns the error which caused it.
+	pub fn wait(mut self) -> io::Result<()> {
+		self.join()
+	}
+
+	fn stop(&mut self) -> io::Result<()> {
+		if let (Some(acceptor), false) = (&self.acceptor, self.stopping.swap(true, SeqCst)) {
+			acceptor.thread().unpark();
+			// Wake the accept loop up with a connection of our own. If this fails, the accept loop
+			// has already stopped.
+			let _ = Stream::connect(self.name.borrow());
+		}
+		self.join()
+	}
+	fn join(&mut self) -> io::Result<()> {
+		let rslt = match self.acceptor.take().map(JoinHandle::join) {
+			Some(Ok(rslt)) => rslt,
+			Some(Err(..)) => Err(io::Error::other("the accept loop panicked")),
+			None => Ok(()),
+		};
+		for worker in self.workers.drain(..) {
+			let _ = worker.join();
+		}
+		rslt
+	}
+}
+impl Drop for Server {
+	fn drop(&mut self) {
+		let _ = self.stop();
+	}
+}
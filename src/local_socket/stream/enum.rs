@@ -3,8 +3,17 @@ use super::r#trait;
 use crate::os::unix::uds_local_socket as uds_impl;
 #[cfg(windows)]
 use crate::os::windows::named_pipe::local_socket as np_impl;
-use crate::{local_socket::Name, TryClone};
-use std::io::{self, prelude::*, IoSlice, IoSliceMut};
+use crate::{
+	error::Error,
+	local_socket::Name,
+	metrics::{self, Operation},
+	TryClone,
+};
+use std::{
+	io::{self, prelude::*, IoSlice, IoSliceMut},
+	mem::MaybeUninit,
+	net::Shutdown,
+};
 
 impmod! {local_socket::dispatch_sync}
 
@@ -12,11 +21,12 @@ macro_rules! dispatch_read {
 	(@iw $ty:ident) => {
 		#[inline]
 		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-			dispatch!($ty: x in self => x.read(buf))
+			metrics::track_bytes(Operation::Receive, dispatch!($ty: x in self => x.read(buf)))
 		}
 		#[inline]
 		fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-			dispatch!($ty: x in self => x.read_vectored(bufs))
+			let rslt = dispatch!($ty: x in self => x.read_vectored(bufs));
+			metrics::track_bytes(Operation::Receive, rslt)
 		}
 	};
 	($ty:ident) => {
@@ -32,7 +42,7 @@ macro_rules! dispatch_write {
 	(@iw $ty:ident) => {
 		#[inline]
 		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-			dispatch!($ty: x in self => x.write(buf))
+			metrics::track_bytes(Operation::Send, dispatch!($ty: x in self => x.write(buf)))
 		}
 		#[inline]
 		fn flush(&mut self) -> io::Result<()> {
@@ -40,7 +50,8 @@ macro_rules! dispatch_write {
 		}
 		#[inline]
 		fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-			dispatch!($ty: x in self => x.write_vectored(bufs))
+			let rslt = dispatch!($ty: x in self => x.write_vectored(bufs));
+			metrics::track_bytes(Operation::Send, rslt)
 		}
 	};
 	($ty:ident) => {
@@ -103,13 +114,96 @@ mkenum!(
 /// # std::io::Result::<()>::Ok(())
 /// ```
 Stream);
+impl Stream {
+	/// Creates a pair of connected streams without giving them a name that other processes could
+	/// use to connect.
+	///
+	/// This is useful for communication with child processes that inherit one of the ends, or for
+	/// tests that don't need to touch the filesystem.
+	///
+	/// ## Platform-specific behavior
+	/// ### Unix
+	/// Uses `socketpair(2)`, producing a truly unnamed pair of Unix domain sockets.
+	///
+	/// ### Windows
+	/// Named pipes cannot exist without a name. A named pipe with a unique name that's derived from
+	/// the process ID is created and immediately connected to, and the first end of the pair is
+	/// the server. If a different process manages to connect to the pipe first, its only instance
+	/// is busy and an error of kind [`WouldBlock`](io::ErrorKind::WouldBlock) is returned.
+	#[inline]
+	pub fn pair() -> io::Result<(Self, Self)> {
+		dispatch_sync::pair()
+	}
+	/// Retrieves and clears the pending error of the stream, or returns `None` if there is none.
+	///
+	/// ## Platform-specific behavior
+	/// On Unix, this reads and clears the `SO_ERROR` socket option. Named pipes have no such
+	/// notion, so `None` is always returned on Windows.
+	#[inline]
+	pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+		match self {
+			#[cfg(windows)]
+			Self::NamedPipe(..) => Ok(None),
+			#[cfg(unix)]
+			Self::UdSocket(s) => s.take_error(),
+		}
+	}
+	/// Receives data from the stream without removing it, returning how many bytes were received.
+	///
+	/// Successive calls return the same data, and subsequent reads will receive it again. This
+	/// allows for inspecting the beginning of the data, such as a protocol signature, before
+	/// deciding how to handle the connection.
+	///
+	/// ## Platform-specific behavior
+	/// ### Unix
+	/// Uses `recv(2)` with `MSG_PEEK`, blocking until data arrives unless the stream is in
+	/// nonblocking mode.
+	///
+	/// ### Windows
+	/// Uses `PeekNamedPipe`, which never blocks: if no data is available, an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) is returned regardless of the nonblocking mode.
+	#[inline]
+	pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+		dispatch!(Self: x in self => x.peek(buf))
+	}
+	/// Same as `.read()` from the [`Read`] trait, but accepts an uninitialized buffer, sparing the
+	/// cost of zeroing large buffers before receiving into them.
+	///
+	/// Upon success, the first as many bytes of `buf` as are returned are initialized.
+	#[inline]
+	pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+		let rslt = dispatch!(Self: x in self => x.read_to_uninit(buf));
+		metrics::track_bytes(Operation::Receive, rslt)
+	}
+	/// Shuts down the receive half, the send half, or both halves of the stream.
+	///
+	/// After the send half is shut down, sending fails; after the receive half is shut down,
+	/// receiving reports end of file.
+	///
+	/// ## Platform-specific behavior
+	/// ### Unix
+	/// Uses `shutdown(2)`. Shutting down the send half makes the peer receive end of file once it
+	/// has received everything sent before that, which can be used to mark the end of a request.
+	///
+	/// ### Windows
+	/// Named pipes cannot be half-closed, so this is emulated on the local end only. Shutting down
+	/// the send half flushes the stream, blocking until the peer has received everything sent so
+	/// far, but the peer only observes end of file once the stream is dropped. Protocols which need
+	/// to be portable should thus not rely on half-closing to delimit messages.
+	#[inline]
+	pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+		dispatch!(Self: x in self => x.shutdown(how))
+	}
+}
+
 impl r#trait::Stream for Stream {
 	type RecvHalf = RecvHalf;
 	type SendHalf = SendHalf;
 
 	#[inline]
 	fn connect(name: Name<'_>) -> io::Result<Self> {
-		dispatch_sync::connect(name)
+		metrics::track_blocking(Operation::Connect, || dispatch_sync::connect(name.borrow()))
+			.map_err(|e| Error::attach_name(e, name))
 	}
 	#[inline]
 	fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
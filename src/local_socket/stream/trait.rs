@@ -2,10 +2,14 @@
 
 use crate::{
 	bound_util::{RefRead, RefWrite},
-	local_socket::Name,
+	error::ConnectRetryError,
+	local_socket::{Name, RetryConfig, RetryState},
 	Sealed,
 };
-use std::io::{self, prelude::*};
+use std::{
+	io::{self, prelude::*},
+	thread,
+};
 
 /// Local socket stream implementations.
 ///
@@ -46,6 +50,28 @@ pub trait Stream: Sized + Read + RefRead + Write + RefWrite + Sealed {
 	// created for features like impersonation (ones that are instantaneous in nature).
 }
 
+/// Methods derived from the interface of [`Stream`].
+pub trait StreamExt: Stream {
+	/// Connects to a remote local socket server, retrying with exponential backoff if the server is
+	/// not available yet.
+	///
+	/// This is useful for clients that may be started at the same time as the server. See
+	/// [`RetryConfig`] for which errors are retried and how the delays are computed.
+	fn connect_with_retries(
+		name: Name<'_>,
+		config: RetryConfig,
+	) -> Result<Self, ConnectRetryError> {
+		let mut state = RetryState::new(config);
+		loop {
+			match Self::connect(name.borrow()) {
+				Ok(stream) => return Ok(stream),
+				Err(e) => thread::sleep(state.fail(e)?),
+			}
+		}
+	}
+}
+impl<T: Stream> StreamExt for T {}
+
 /// Receive halves of [`Stream`]s, obtained through [`.split()`](Stream::split).
 ///
 /// Types on which this trait is implemented are variants of the
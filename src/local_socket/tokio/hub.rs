@@ -0,0 +1,282 @@
+//! Tokio-based [hub](crate::local_socket::hub) and Tokio counterparts of the hub clients.
+//!
+//! The clients speak the same protocol as the blocking ones, and either kind can connect to
+//! [`Hub`].
+//!
+//! # Example
+//! ```no_run
+//! # #[tokio::main(flavor = "current_thread")] async fn main() -> std::io::Result<()> {
+//! use interprocess::local_socket::{
+//! 	server::ServerConfig,
+//! 	tokio::{
+//! 		hub::{Hub, Publisher, Subscriber},
+//! 		prelude::*,
+//! 	},
+//! 	GenericNamespaced,
+//! };
+//!
+//! let name = "example-hub.sock".to_ns_name::<GenericNamespaced>()?;
+//! let hub = Hub::serve(name.borrow(), ServerConfig::new())?;
+//!
+//! let mut subscriber = Subscriber::connect(name.borrow()).await?;
+//! subscriber.subscribe("weather").await?;
+//!
+//! let mut publisher = Publisher::connect(name.borrow()).await?;
+//! publisher.publish("weather", b"sunny").await?;
+//!
+//! let message = subscriber.recv().await?.expect("the hub hung up");
+//! assert_eq!(message.payload, b"sunny");
+//! hub.shutdown().await?;
+//! # Ok(()) }
+//! ```
+
+use super::{
+	prelude::*,
+	server::{serve, Server},
+	Stream, TimeoutStream,
+};
+use crate::local_socket::{
+	broker_util::{invalid, lock, race, read_frame_tokio},
+	hub::{encode, Frame, Header, Kind, Message},
+	server::ServerConfig,
+	Name,
+};
+use std::{
+	collections::{BTreeMap, BTreeSet, VecDeque},
+	io,
+	sync::{
+		atomic::{AtomicU64, Ordering::SeqCst},
+		Arc, Mutex,
+	},
+};
+use tokio::{
+	io::{split, AsyncRead, AsyncWrite, AsyncWriteExt, ReadHalf},
+	sync::{
+		mpsc::{self, error::TrySendError},
+		watch,
+	},
+};
+
+/// How many frames may be queued up for a client before it is disconnected for falling behind.
+const QUEUE_LEN: usize = 256;
+
+/// A broker which relays messages from publishers to subscribers, running on the
+/// [Tokio server](super::server).
+///
+/// Every client occupies one of the [`max_connections`](ServerConfig::max_connections) of the
+/// configuration for as long as it is connected. The [`timeout`](ServerConfig::timeout) of the
+/// configuration bounds how long a client may go without sending anything, which includes
+/// subscribers waiting for messages, and should thus usually be left unset.
+///
+/// Each client has a queue of messages waiting to be sent to it. A subscriber which falls so far
+/// behind on receiving that its queue fills up is disconnected after the messages in the queue
+/// have been sent, so that it cannot hold up publishers or other subscribers.
+///
+/// Dropping the hub disconnects all clients and stops accepting new ones, without waiting for the
+/// tasks which handle the clients to finish.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct Hub {
+	stop: watch::Sender<bool>,
+	server: Server,
+}
+impl Hub {
+	/// Creates a listener on the given name and starts relaying messages between the clients which
+	/// connect to it.
+	///
+	/// This function must be called within the context of a Tokio runtime.
+	pub fn serve(name: Name<'_>, config: ServerConfig) -> io::Result<Self> {
+		let state = Arc::new(State::default());
+		let (stop, stop_rx) = watch::channel(false);
+		let server = serve(
+			name,
+			move |conn| Arc::clone(&state).handle(conn, stop_rx.clone()),
+			config,
+		)?;
+		Ok(Self { stop, server })
+	}
+	/// Disconnects all clients, stops accepting new ones and waits for the tasks which handle the
+	/// clients to finish.
+	///
+	/// Returns the error which made the underlying server stop beforehand, if any.
+	pub async fn shutdown(self) -> io::Result<()> {
+		let Self { stop, server } = self;
+		// Dropping the sender counts as a stop request as well
+		let _ = stop.send(true);
+		server.shutdown().await
+	}
+}
+
+#[derive(Debug, Default)]
+struct State {
+	next_id: AtomicU64,
+	/// The queues of the clients which are connected.
+	clients: Mutex<BTreeMap<u64, mpsc::Sender<Arc<[u8]>>>>,
+	topics: Mutex<BTreeMap<String, BTreeSet<u64>>>,
+}
+
+impl State {
+	async fn handle(self: Arc<Self>, conn: TimeoutStream<Stream>, mut stop: watch::Receiver<bool>) {
+		let id = self.next_id.fetch_add(1, SeqCst);
+		let (tx, rx) = mpsc::channel(QUEUE_LEN);
+		lock(&self.clients).insert(id, tx);
+		let (rd, wr) = split(conn);
+		// The writer finishes early if the client is disconnected for falling behind
+		let serve = race(write_frames(rx, wr), self.serve_client(id, rd));
+		race(stop.wait_for(|&stop| stop), serve).await;
+		self.remove(id);
+	}
+	async fn serve_client<R: AsyncRead>(&self, id: u64, mut rd: ReadHalf<R>) -> io::Result<()> {
+		while let Some(frame) = read_frame_tokio::<Header>(&mut rd).await? {
+			match frame.kind {
+				Kind::Subscribe => {
+					lock(&self.topics)
+						.entry(frame.topic)
+						.or_default()
+						.insert(id);
+					self.send(id, encode(Kind::Ack, "", &[])?.into());
+				}
+				Kind::Unsubscribe => {
+					let mut topics = lock(&self.topics);
+					if let Some(subscribers) = topics.get_mut(&frame.topic) {
+						subscribers.remove(&id);
+						if subscribers.is_empty() {
+							topics.remove(&frame.topic);
+						}
+					}
+					drop(topics);
+					self.send(id, encode(Kind::Ack, "", &[])?.into());
+				}
+				Kind::Publish => {
+					let subscribers = lock(&self.topics)
+						.get(&frame.topic)
+						.map(|ids| ids.iter().copied().collect::<Vec<_>>())
+						.unwrap_or_default();
+					let message: Arc<[u8]> =
+						encode(Kind::Publish, &frame.topic, &frame.payload)?.into();
+					for subscriber in subscribers {
+						self.send(subscriber, Arc::clone(&message));
+					}
+				}
+				Kind::Ack => return Err(invalid("unexpected acknowledgement from client")),
+			}
+		}
+		Ok(())
+	}
+	/// Queues a frame to be sent to a client, disconnecting the client if its queue is full.
+	fn send(&self, id: u64, frame: Arc<[u8]>) {
+		let mut clients = lock(&self.clients);
+		let Some(tx) = clients.get(&id) else {
+			return;
+		};
+		if let Err(TrySendError::Full(..)) = tx.try_send(frame) {
+			// Its writer stops once it has sent out what is already queued
+			clients.remove(&id);
+		}
+	}
+	fn remove(&self, id: u64) {
+		lock(&self.clients).remove(&id);
+		lock(&self.topics).retain(|_, subscribers| {
+			subscribers.remove(&id);
+			!subscribers.is_empty()
+		});
+	}
+}
+
+async fn write_frames(
+	mut rx: mpsc::Receiver<Arc<[u8]>>,
+	mut wr: impl AsyncWrite + Unpin,
+) -> io::Result<()> {
+	while let Some(frame) = rx.recv().await {
+		wr.write_all(&frame).await?;
+	}
+	Ok(())
+}
+
+/// Tokio counterpart of the blocking [`Publisher`](crate::local_socket::hub::Publisher).
+#[derive(Debug)]
+pub struct Publisher(Stream);
+impl Publisher {
+	/// Connects to the hub with the given name.
+	#[inline]
+	pub async fn connect(name: Name<'_>) -> io::Result<Self> {
+		Stream::connect(name).await.map(Self)
+	}
+	/// Publishes a message under the given topic.
+	///
+	/// See the [blocking counterpart](crate::local_socket::hub::Publisher::publish) for more.
+	pub async fn publish(&mut self, topic: &str, payload: &[u8]) -> io::Result<()> {
+		self.0
+			.write_all(&encode(Kind::Publish, topic, payload)?)
+			.await
+	}
+}
+
+/// Tokio counterpart of the blocking [`Subscriber`](crate::local_socket::hub::Subscriber).
+///
+/// Cancelling a pending [`.subscribe()`](Self::subscribe) or [`.unsubscribe()`](Self::unsubscribe)
+/// leaves the subscriber in an unspecified state, in which it should be dropped. The same goes for
+/// [`.recv()`](Self::recv) if it is cancelled after part of a message has arrived, so rather than
+/// calling it in a `tokio::select!` loop, move the subscriber into a task of its own and forward
+/// the messages over a channel.
+#[derive(Debug)]
+pub struct Subscriber {
+	conn: Stream,
+	/// Messages which arrived while waiting for an acknowledgement.
+	pending: VecDeque<Message>,
+}
+impl Subscriber {
+	/// Connects to the hub with the given name.
+	pub async fn connect(name: Name<'_>) -> io::Result<Self> {
+		Ok(Self {
+			conn: Stream::connect(name).await?,
+			pending: VecDeque::new(),
+		})
+	}
+	/// Subscribes to the given topic, returning once the hub has registered the subscription.
+	///
+	/// See the [blocking counterpart](crate::local_socket::hub::Subscriber::subscribe) for more.
+	pub async fn subscribe(&mut self, topic: &str) -> io::Result<()> {
+		self.request(Kind::Subscribe, topic).await
+	}
+	/// Unsubscribes from the given topic, returning once the hub has removed the subscription.
+	///
+	/// See the [blocking counterpart](crate::local_socket::hub::Subscriber::unsubscribe) for more.
+	pub async fn unsubscribe(&mut self, topic: &str) -> io::Result<()> {
+		self.request(Kind::Unsubscribe, topic).await
+	}
+	/// Receives the next message. Returns `None` if the hub has hung up.
+	pub async fn recv(&mut self) -> io::Result<Option<Message>> {
+		if let Some(message) = self.pending.pop_front() {
+			return Ok(Some(message));
+		}
+		match read_frame_tokio::<Header>(&mut self.conn).await? {
+			Some(Frame {
+				kind: Kind::Publish,
+				topic,
+				payload,
+			}) => Ok(Some(Message { topic, payload })),
+			Some(..) => Err(invalid("unexpected frame from hub")),
+			None => Ok(None),
+		}
+	}
+
+	async fn request(&mut self, kind: Kind, topic: &str) -> io::Result<()> {
+		self.conn.write_all(&encode(kind, topic, &[])?).await?;
+		loop {
+			match read_frame_tokio::<Header>(&mut self.conn).await? {
+				Some(Frame {
+					kind: Kind::Ack, ..
+				}) => return Ok(()),
+				Some(Frame {
+					kind: Kind::Publish,
+					topic,
+					payload,
+				}) => self.pending.push_back(Message { topic, payload }),
+				Some(..) => return Err(invalid("unexpected frame from hub")),
+				None => return Err(io::ErrorKind::UnexpectedEof.into()),
+			}
+		}
+	}
+}
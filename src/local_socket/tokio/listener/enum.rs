@@ -1,9 +1,12 @@
 use super::r#trait;
-use crate::local_socket::{tokio::Stream, ListenerOptions};
 #[cfg(unix)]
 use crate::os::unix::uds_local_socket::tokio as uds_impl;
 #[cfg(windows)]
 use crate::os::windows::named_pipe::local_socket::tokio as np_impl;
+use crate::{
+	local_socket::{tokio::Stream, ListenerOptions},
+	metrics::{self, Operation},
+};
 use std::io;
 
 impmod! {local_socket::dispatch_tokio as dispatch}
@@ -113,6 +116,22 @@ mkenum!(
 /// # Ok(()) }
 /// ```
 Listener);
+impl Listener {
+	/// Retrieves and clears the pending error of the listener, or returns `None` if there is none.
+	///
+	/// ## Platform-specific behavior
+	/// On Unix, this reads and clears the `SO_ERROR` socket option. Named pipes have no such
+	/// notion, so `None` is always returned on Windows.
+	#[inline]
+	pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+		match self {
+			#[cfg(windows)]
+			Self::NamedPipe(..) => Ok(None),
+			#[cfg(unix)]
+			Self::UdSocket(s) => s.take_error(),
+		}
+	}
+}
 
 impl r#trait::Listener for Listener {
 	type Stream = Stream;
@@ -123,9 +142,12 @@ impl r#trait::Listener for Listener {
 	}
 	#[inline]
 	async fn accept(&self) -> io::Result<Stream> {
-		dispatch!(Self: x in self => x.accept())
-			.await
-			.map(Stream::from)
+		let accept = async {
+			dispatch!(Self: x in self => x.accept())
+				.await
+				.map(Stream::from)
+		};
+		metrics::track_async(Operation::Accept, accept).await
 	}
 	#[inline]
 	fn do_not_reclaim_name_on_drop(&mut self) {
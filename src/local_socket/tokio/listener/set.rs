@@ -0,0 +1,90 @@
+use crate::local_socket::{
+	tokio::{Listener, Stream},
+	traits::tokio::Listener as _,
+	ListenerSetBase,
+};
+use std::{
+	future::{poll_fn, Future},
+	io,
+	task::Poll,
+};
+
+/// A set of [Tokio local socket listeners](Listener) which can be accepted from all at once.
+///
+/// This is useful for servers that expose multiple endpoints, such as a public socket and an
+/// administrative one, without spawning a task for each of them.
+///
+/// Listeners are identified by their index in the set, which is the order in which they were
+/// added.
+#[derive(Debug, Default)]
+pub struct ListenerSet(ListenerSetBase<Listener>);
+impl ListenerSet {
+	/// Creates an empty set.
+	#[inline]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Adds a listener to the set, returning its index.
+	#[inline]
+	pub fn push(&mut self, listener: Listener) -> usize {
+		self.0.push(listener)
+	}
+	/// Returns the listener with the given index, if there is one.
+	#[inline]
+	pub fn get(&self, index: usize) -> Option<&Listener> {
+		self.0.get(index)
+	}
+	/// Returns the number of listeners in the set.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+	/// Returns `true` if the set has no listeners.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+	/// Disassembles the set into its listeners.
+	#[inline]
+	pub fn into_inner(self) -> Vec<Listener> {
+		self.0.into_inner()
+	}
+
+	/// Asynchronously waits until a client connects to any of the listeners in the set, returning
+	/// the index of that listener together with the stream.
+	///
+	/// If several listeners have clients waiting, the one which gets to accept is picked in a
+	/// round-robin fashion, so that a busy endpoint cannot starve the others.
+	///
+	/// Fails with an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput) if the set is
+	/// empty.
+	pub async fn accept(&self) -> io::Result<(usize, Stream)> {
+		let mut futs = self
+			.0
+			.round_robin()?
+			.filter_map(|i| Some((i, Box::pin(self.0.get(i)?.accept()))))
+			.collect::<Vec<_>>();
+		poll_fn(|cx| {
+			for (i, fut) in &mut futs {
+				if let Poll::Ready(rslt) = fut.as_mut().poll(cx) {
+					return Poll::Ready(rslt.map(|s| (*i, s)));
+				}
+			}
+			Poll::Pending
+		})
+		.await
+	}
+}
+
+impl From<Vec<Listener>> for ListenerSet {
+	#[inline]
+	fn from(listeners: Vec<Listener>) -> Self {
+		Self(listeners.into())
+	}
+}
+impl FromIterator<Listener> for ListenerSet {
+	#[inline]
+	fn from_iter<I: IntoIterator<Item = Listener>>(iter: I) -> Self {
+		Vec::from_iter(iter).into()
+	}
+}
@@ -0,0 +1,154 @@
+//! Tokio counterpart of the [thread-based server](crate::local_socket::server).
+
+use super::{prelude::*, Stream, TimeoutStream};
+use crate::{
+	local_socket::{
+		server::{OverloadPolicy, RateLimiter, ServerConfig},
+		ListenerOptions, Name,
+	},
+	SubUsizeExt,
+};
+use std::{
+	future::{poll_fn, Future},
+	io,
+	pin::{pin, Pin},
+	sync::Arc,
+	task::Poll,
+	time::Instant,
+};
+use tokio::{
+	sync::{oneshot, Semaphore},
+	task::JoinHandle,
+	time::sleep,
+};
+
+/// Creates a listener on the given name and starts serving connections to it, spawning a task
+/// which runs the future returned by `handler` for every accepted connection.
+///
+/// The connections are wrapped in [`TimeoutStream`]s with both timeouts set to the
+/// [`timeout`](ServerConfig::timeout) of the configuration. At most
+/// [`max_connections`](ServerConfig::max_connections) tasks exist at once, and connections which
+/// exceed this or the [`accept_rate`](ServerConfig::accept_rate) are dealt with according to the
+/// [overload policy](ServerConfig::overload).
+///
+/// The accept loop runs in a task of its own, and this function returns as soon as the listener
+/// is created. It must be called within the context of a Tokio runtime, which needs to have the
+/// time driver enabled if timeouts or a rate limit are used.
+///
+/// If accepting a connection fails, the server stops accepting and winds down as if shut down,
+/// and the error is returned by [`Server::wait()`] or [`Server::shutdown()`].
+pub fn serve<H, F>(name: Name<'_>, handler: H, config: ServerConfig) -> io::Result<Server>
+where
+	H: Fn(TimeoutStream<Stream>) -> F + Send + Sync + 'static,
+	F: Future<Output = ()> + Send + 'static,
+{
+	let listener = ListenerOptions::new().name(name).create_tokio()?;
+	let permits = u32::try_from(config.max_connections.get()).unwrap_or(u32::MAX);
+	let semaphore = Arc::new(Semaphore::new(permits.to_usize()));
+	let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+
+	let refuse = config.overload == OverloadPolicy::Refuse;
+	let mut limiter = config.accept_rate.map(RateLimiter::new);
+
+	let acceptor = tokio::spawn(async move {
+		let rslt = loop {
+			let mut permit = None;
+			if !refuse {
+				let Some(p) =
+					until_stop(Arc::clone(&semaphore).acquire_owned(), &mut stop_rx).await
+				else {
+					break Ok(());
+				};
+				permit = Some(p.expect("the semaphore was closed"));
+				if let Some(limiter) = &limiter {
+					let delay = sleep(limiter.delay(Instant::now()));
+					if until_stop(delay, &mut stop_rx).await.is_none() {
+						break Ok(());
+					}
+				}
+			}
+			let conn = match until_stop(listener.accept(), &mut stop_rx).await {
+				Some(Ok(conn)) => conn,
+				Some(Err(e)) => break Err(e),
+				None => break Ok(()),
+			};
+			if let Some(limiter) = &mut limiter {
+				let now = Instant::now();
+				if refuse && !limiter.delay(now).is_zero() {
+					continue;
+				}
+				limiter.record(now);
+			}
+			let permit = match permit {
+				Some(permit) => permit,
+				None => match Arc::clone(&semaphore).try_acquire_owned() {
+					Ok(permit) => permit,
+					Err(..) => continue,
+				},
+			};
+			let mut conn = TimeoutStream::new(conn);
+			conn.set_read_timeout(config.timeout);
+			conn.set_write_timeout(config.timeout);
+			let task = handler(conn);
+			tokio::spawn(async move {
+				task.await;
+				drop(permit);
+			});
+		};
+		drop(listener);
+		// Drain by waiting for every task to give back its permit
+		let _ = semaphore.acquire_many(permits).await;
+		rslt
+	});
+
+	Ok(Server {
+		stop: Some(stop_tx),
+		acceptor,
+	})
+}
+
+/// Runs `fut` to completion unless a stop is requested first, in which case `None` is returned.
+async fn until_stop<T>(
+	fut: impl Future<Output = T>,
+	stop: &mut oneshot::Receiver<()>,
+) -> Option<T> {
+	let mut fut = pin!(fut);
+	poll_fn(|cx| {
+		// Dropping the sender also counts as a stop request
+		if Pin::new(&mut *stop).poll(cx).is_ready() {
+			return Poll::Ready(None);
+		}
+		fut.as_mut().poll(cx).map(Some)
+	})
+	.await
+}
+
+/// Handle to a server started by [`serve()`].
+///
+/// Dropping the handle stops the server from accepting new connections, but does not cancel the
+/// tasks which are handling existing connections.
+#[derive(Debug)]
+pub struct Server {
+	stop: Option<oneshot::Sender<()>>,
+	acceptor: JoinHandle<io::Result<()>>,
+}
+impl Server {
+	/// Stops accepting new connections and waits for the tasks which are handling connections to
+	/// finish.
+	///
+	/// Returns the error which made the server stop beforehand, if any.
+	pub async fn shutdown(mut self) -> io::Result<()> {
+		if let Some(stop) = self.stop.take() {
+			let _ = stop.send(());
+		}
+		self.wait().await
+	}
+	/// Waits for the server to stop on its own, which only happens if accepting a connection fails,
+	/// and returns the error which caused it.
+	pub async fn wait(self) -> io::Result<()> {
+		match self.acceptor.await {
+			Ok(rslt) => rslt,
+			Err(e) => Err(io::Error::other(e)),
+		}
+	}
+}
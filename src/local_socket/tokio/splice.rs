@@ -0,0 +1,20 @@
+use super::super::traits::tokio::Stream;
+use std::io;
+
+/// Asynchronously shuttles bytes between two Tokio local socket streams in both directions until
+/// both of them reach end of file, returning the amount of bytes copied from `a` to `b` and from
+/// `b` to `a` respectively.
+///
+/// This makes it trivial to build simple local socket proxies. When one direction reaches end of
+/// file, the stream being sent to is shut down, forwarding the end of file where the platform
+/// allows it.
+///
+/// There is no synchronous counterpart to this function, since sync local sockets do not permit
+/// receiving and sending concurrently.
+pub async fn splice<A, B>(mut a: A, mut b: B) -> io::Result<(u64, u64)>
+where
+	A: Stream + Unpin,
+	B: Stream + Unpin,
+{
+	tokio::io::copy_bidirectional(&mut a, &mut b).await
+}
@@ -1,11 +1,18 @@
 use super::r#trait;
-use crate::local_socket::Name;
 #[cfg(unix)]
 use crate::os::unix::uds_local_socket::tokio as uds_impl;
 #[cfg(windows)]
 use crate::os::windows::named_pipe::local_socket::tokio as np_impl;
+use crate::{
+	error::Error,
+	handle_transfer::OwnedHandle,
+	local_socket::Name,
+	metrics::{self, Operation},
+};
 use std::{
+	future::poll_fn,
 	io,
+	mem::MaybeUninit,
 	pin::Pin,
 	task::{Context, Poll},
 };
@@ -17,7 +24,10 @@ macro_rules! dispatch_read {
 	(@iw $ty:ident) => {
 		#[inline]
 		fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
-			dispatch!($ty: x in self.get_mut() => Pin::new(x).poll_read(cx, buf))
+			let before = buf.filled().len();
+			dispatch!($ty: x in self.get_mut() => Pin::new(x).poll_read(cx, buf)).map(|rslt| {
+				metrics::track_with(Operation::Receive, rslt, |()| buf.filled().len().saturating_sub(before))
+			})
 		}
 	};
 	($ty:ident) => {
@@ -34,6 +44,7 @@ macro_rules! dispatch_write {
 		#[inline]
 		fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
 			dispatch!($ty: x in self.get_mut() => Pin::new(x).poll_write(cx, buf))
+				.map(|rslt| metrics::track_bytes(Operation::Send, rslt))
 		}
 		#[inline]
 		fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
@@ -107,6 +118,102 @@ mkenum!(
 /// # Ok(()) }
 /// ```
 Stream);
+impl Stream {
+	/// Connects to the given name, letting `configure` set up the socket before the connection is
+	/// initiated.
+	///
+	/// Some socket options, such as `SO_PASSCRED` and the buffer sizes, have to be set before
+	/// connecting to take full effect, which is too late with the stream returned by
+	/// [`connect()`](crate::local_socket::traits::tokio::Stream::connect). The socket handed to
+	/// `configure` is already in nonblocking mode, which it must be left in. An error returned by
+	/// `configure` aborts the connection attempt and is returned as is.
+	///
+	/// # Example
+	/// ```no_run
+	/// use interprocess::local_socket::{
+	/// 	tokio::{prelude::*, Stream},
+	/// 	GenericNamespaced,
+	/// };
+	///
+	/// # async fn example() -> std::io::Result<()> {
+	/// let name = "example.sock".to_ns_name::<GenericNamespaced>()?;
+	/// let conn = Stream::connect_with(name, |fd| {
+	/// 	// e.g. setsockopt() on fd
+	/// 	Ok(())
+	/// })
+	/// .await?;
+	/// # Ok(()) }
+	/// ```
+	#[cfg(unix)]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(unix)))]
+	pub async fn connect_with<F>(name: Name<'_>, configure: F) -> io::Result<Self>
+	where
+		F: FnOnce(std::os::fd::BorrowedFd<'_>) -> io::Result<()>,
+	{
+		let connect = uds_impl::Stream::connect_with(name.borrow(), configure);
+		metrics::track_async(Operation::Connect, connect)
+			.await
+			.map(Self::UdSocket)
+			.map_err(|e| Error::attach_name(e, name))
+	}
+	/// Retrieves and clears the pending error of the stream, or returns `None` if there is none.
+	///
+	/// ## Platform-specific behavior
+	/// On Unix, this reads and clears the `SO_ERROR` socket option. Named pipes have no such
+	/// notion, so `None` is always returned on Windows.
+	#[inline]
+	pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+		match self {
+			#[cfg(windows)]
+			Self::NamedPipe(..) => Ok(None),
+			#[cfg(unix)]
+			Self::UdSocket(s) => s.take_error(),
+		}
+	}
+	/// Sends the given handle to the process on the other end of the stream, closing it in this
+	/// process once it has been sent.
+	///
+	/// This is the asynchronous counterpart of
+	/// [`HandleTransfer::send_handle()`](crate::handle_transfer::HandleTransfer::send_handle), and
+	/// the two are interoperable: a handle sent by one can be received by the other. See the
+	/// [`handle_transfer`](crate::handle_transfer) module for how transfers fit into the stream.
+	pub async fn send_handle(&self, handle: OwnedHandle) -> io::Result<()> {
+		let send = async {
+			match self {
+				#[cfg(windows)]
+				Self::NamedPipe(s) => s.send_handle(handle).await,
+				#[cfg(unix)]
+				Self::UdSocket(s) => s.send_handle(handle).await,
+			}
+		};
+		metrics::track_async(Operation::SendHandle, send).await
+	}
+	/// Receives a handle sent by the process on the other end of the stream. The resulting handle
+	/// is not inheritable.
+	///
+	/// This is the asynchronous counterpart of
+	/// [`HandleTransfer::recv_handle()`](crate::handle_transfer::HandleTransfer::recv_handle).
+	pub async fn recv_handle(&self) -> io::Result<OwnedHandle> {
+		let recv = async {
+			match self {
+				#[cfg(windows)]
+				Self::NamedPipe(s) => s.recv_handle().await,
+				#[cfg(unix)]
+				Self::UdSocket(s) => s.recv_handle().await,
+			}
+		};
+		metrics::track_async(Operation::RecvHandle, recv).await
+	}
+	/// Same as [`.read()`](tokio::io::AsyncReadExt::read), but accepts an uninitialized buffer,
+	/// sparing the cost of zeroing large buffers before receiving into them.
+	///
+	/// Upon success, the first as many bytes of `buf` as are returned are initialized.
+	pub async fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+		let mut buf = ReadBuf::uninit(buf);
+		poll_fn(|cx| Pin::new(&mut &*self).poll_read(cx, &mut buf)).await?;
+		Ok(buf.filled().len())
+	}
+}
 
 impl r#trait::Stream for Stream {
 	type RecvHalf = RecvHalf;
@@ -114,7 +221,9 @@ impl r#trait::Stream for Stream {
 
 	#[inline]
 	async fn connect(name: Name<'_>) -> io::Result<Self> {
-		dispatch::connect(name).await
+		metrics::track_async(Operation::Connect, dispatch::connect(name.borrow()))
+			.await
+			.map_err(|e| Error::attach_name(e, name))
 	}
 	fn split(self) -> (RecvHalf, SendHalf) {
 		match self {
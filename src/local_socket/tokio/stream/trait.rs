@@ -2,11 +2,15 @@
 
 use crate::{
 	bound_util::{RefTokioAsyncRead, RefTokioAsyncWrite},
-	local_socket::Name,
+	error::ConnectRetryError,
+	local_socket::{Name, RetryConfig, RetryState},
 	Sealed,
 };
 use std::{future::Future, io};
-use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::{
+	io::{AsyncRead, AsyncWrite},
+	time::sleep,
+};
 
 /// Tokio local socket stream implementations.
 ///
@@ -35,6 +39,31 @@ pub trait Stream:
 	fn reunite(rh: Self::RecvHalf, sh: Self::SendHalf) -> ReuniteResult<Self>;
 }
 
+/// Methods derived from the interface of Tokio [`Stream`]s.
+pub trait StreamExt: Stream {
+	/// Asynchronously connects to a remote local socket server, retrying with exponential backoff if
+	/// the server is not available yet.
+	///
+	/// This is useful for clients that may be started at the same time as the server. See
+	/// [`RetryConfig`] for which errors are retried and how the delays are computed.
+	fn connect_with_retries(
+		name: Name<'_>,
+		config: RetryConfig,
+	) -> impl Future<Output = Result<Self, ConnectRetryError>> + Send {
+		async move {
+			let mut state = RetryState::new(config);
+			loop {
+				let e = match Self::connect(name.borrow()).await {
+					Ok(stream) => return Ok(stream),
+					Err(e) => e,
+				};
+				sleep(state.fail(e)?).await;
+			}
+		}
+	}
+}
+impl<T: Stream> StreamExt for T {}
+
 /// Receive halves of Tokio [`Stream`]s, obtained through [`.split()`](Stream::split).
 ///
 /// Types on which this trait is implemented are variants of the
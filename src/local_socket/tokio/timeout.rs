@@ -0,0 +1,180 @@
+use std::{
+	future::Future,
+	io,
+	pin::Pin,
+	task::{Context, Poll},
+	time::Duration,
+};
+use tokio::{
+	io::{AsyncRead, AsyncWrite, ReadBuf},
+	time::{sleep, Sleep},
+};
+
+/// Wrapper around a Tokio local socket stream or one of its halves which fails reads and writes
+/// that take too long with an error of kind [`TimedOut`](io::ErrorKind::TimedOut).
+///
+/// A timeout limits the time that a single read or write may spend waiting for the stream to
+/// become ready, and is restarted whenever an operation completes. Unlike wrapping a whole
+/// `read_exact()` or `write_all()` in [`tokio::time::timeout()`], this never cancels an operation
+/// midway: everything received or sent before the timeout expired is accounted for in the
+/// buffers and return values of the operations which completed, so the stream can still be used
+/// after a timeout. Flushing and shutting down are subject to the write timeout.
+///
+/// Since the timeouts are configured per wrapper, each half of a split stream can be given its own
+/// timeout by wrapping it separately.
+///
+/// The timers use the Tokio time driver, which has to be enabled in the runtime.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::{
+/// 	tokio::{prelude::*, Stream, TimeoutStream},
+/// 	GenericNamespaced,
+/// };
+/// use std::time::Duration;
+/// use tokio::io::AsyncReadExt;
+///
+/// # async fn example() -> std::io::Result<()> {
+/// let conn = Stream::connect("example.sock".to_ns_name::<GenericNamespaced>()?).await?;
+/// let mut conn = TimeoutStream::new(conn);
+/// conn.set_read_timeout(Some(Duration::from_secs(5)));
+/// let mut buf = [0; 64];
+/// let received = conn.read(&mut buf).await?;
+/// # let _ = received; Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct TimeoutStream<S> {
+	inner: S,
+	read_timeout: Option<Duration>,
+	write_timeout: Option<Duration>,
+	read_timer: Option<Pin<Box<Sleep>>>,
+	write_timer: Option<Pin<Box<Sleep>>>,
+}
+impl<S> TimeoutStream<S> {
+	/// Wraps the given stream without setting any timeouts.
+	#[inline]
+	pub fn new(inner: S) -> Self {
+		Self {
+			inner,
+			read_timeout: None,
+			write_timeout: None,
+			read_timer: None,
+			write_timer: None,
+		}
+	}
+	/// Returns the read timeout, or `None` if reads may wait indefinitely.
+	#[inline]
+	pub fn read_timeout(&self) -> Option<Duration> {
+		self.read_timeout
+	}
+	/// Sets the read timeout. `None` lets reads wait indefinitely, which is the default.
+	///
+	/// A read that is already waiting starts over with the new timeout.
+	#[inline]
+	pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+		self.read_timeout = timeout;
+		self.read_timer = None;
+	}
+	/// Returns the write timeout, or `None` if writes may wait indefinitely.
+	#[inline]
+	pub fn write_timeout(&self) -> Option<Duration> {
+		self.write_timeout
+	}
+	/// Sets the write timeout. `None` lets writes wait indefinitely, which is the default.
+	///
+	/// A write that is already waiting starts over with the new timeout.
+	#[inline]
+	pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+		self.write_timeout = timeout;
+		self.write_timer = None;
+	}
+	/// Borrows the wrapped stream.
+	#[inline]
+	pub fn get_ref(&self) -> &S {
+		&self.inner
+	}
+	/// Mutably borrows the wrapped stream.
+	#[inline]
+	pub fn get_mut(&mut self) -> &mut S {
+		&mut self.inner
+	}
+	/// Unwraps the stream, discarding the timeouts.
+	#[inline]
+	pub fn into_inner(self) -> S {
+		self.inner
+	}
+}
+
+/// Arms the timer when an operation starts waiting, disarms it when the operation completes and
+/// turns its expiry into an error.
+fn apply_timeout<T>(
+	timeout: Option<Duration>,
+	timer: &mut Option<Pin<Box<Sleep>>>,
+	cx: &mut Context<'_>,
+	rslt: Poll<io::Result<T>>,
+) -> Poll<io::Result<T>> {
+	if rslt.is_ready() {
+		*timer = None;
+		return rslt;
+	}
+	let Some(timeout) = timeout else {
+		return Poll::Pending;
+	};
+	let expired = timer
+		.get_or_insert_with(|| Box::pin(sleep(timeout)))
+		.as_mut()
+		.poll(cx)
+		.is_ready();
+	if expired {
+		*timer = None;
+		Poll::Ready(Err(io::ErrorKind::TimedOut.into()))
+	} else {
+		Poll::Pending
+	}
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for TimeoutStream<S> {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		let slf = self.get_mut();
+		let rslt = Pin::new(&mut slf.inner).poll_read(cx, buf);
+		apply_timeout(slf.read_timeout, &mut slf.read_timer, cx, rslt)
+	}
+}
+impl<S: AsyncWrite + Unpin> AsyncWrite for TimeoutStream<S> {
+	fn poll_write(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<io::Result<usize>> {
+		let slf = self.get_mut();
+		let rslt = Pin::new(&mut slf.inner).poll_write(cx, buf);
+		apply_timeout(slf.write_timeout, &mut slf.write_timer, cx, rslt)
+	}
+	fn poll_write_vectored(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		bufs: &[io::IoSlice<'_>],
+	) -> Poll<io::Result<usize>> {
+		let slf = self.get_mut();
+		let rslt = Pin::new(&mut slf.inner).poll_write_vectored(cx, bufs);
+		apply_timeout(slf.write_timeout, &mut slf.write_timer, cx, rslt)
+	}
+	#[inline]
+	fn is_write_vectored(&self) -> bool {
+		self.inner.is_write_vectored()
+	}
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		let slf = self.get_mut();
+		let rslt = Pin::new(&mut slf.inner).poll_flush(cx);
+		apply_timeout(slf.write_timeout, &mut slf.write_timer, cx, rslt)
+	}
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		let slf = self.get_mut();
+		let rslt = Pin::new(&mut slf.inner).poll_shutdown(cx);
+		apply_timeout(slf.write_timeout, &mut slf.write_timer, cx, rslt)
+	}
+}
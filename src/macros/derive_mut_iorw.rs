@@ -43,8 +43,8 @@ macro_rules! derive_sync_mut_write {
 
 macro_rules! derive_sync_mut_rw {
 	($({$($lt:tt)*})? $ty:ty) => {
-		forward_sync_read!($({$($lt)*})? $ty);
-		forward_sync_write!($({$($lt)*})? $ty);
+		derive_sync_mut_read!($({$($lt)*})? $ty);
+		derive_sync_mut_write!($({$($lt)*})? $ty);
 	};
 }
 
@@ -0,0 +1,275 @@
+//! In-process transport which implements the [generic traits](crate::traits) without involving the
+//! OS.
+//!
+//! [`MemoryListener`]s are registered under string names in a table which is global to the process,
+//! and [`MemoryStream`]s connect to them by those names. The data sent over a connection is kept in
+//! an unbounded buffer, so sending never blocks, and receiving blocks until the data is there or
+//! the peer has hung up. This makes the transport fully deterministic and available on all
+//! platforms, which is useful for unit tests and fuzzers which exercise protocol code written
+//! against the [`Stream`](crate::traits::Stream) and [`Listener`](crate::traits::Listener) traits.
+//!
+//! # Example
+//! ```
+//! use interprocess::{
+//! 	mem::{MemoryListener, MemoryStream},
+//! 	traits::{Listener, Stream},
+//! };
+//! use std::io::prelude::*;
+//!
+//! let listener = MemoryListener::bind("example")?;
+//! let mut client = MemoryStream::connect("example")?;
+//! let mut server = listener.accept()?;
+//!
+//! client.write_all(b"Hello!")?;
+//! let mut buf = [0; 6];
+//! server.read_exact(&mut buf)?;
+//! assert_eq!(&buf, b"Hello!");
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use crate::traits;
+use std::{
+	collections::{BTreeMap, VecDeque},
+	fmt::{self, Debug, Formatter},
+	io::{self, prelude::*},
+	net::Shutdown,
+	sync::{
+		mpsc::{self, Receiver, Sender},
+		Arc, Condvar, Mutex, MutexGuard, PoisonError,
+	},
+};
+
+static REGISTRY: Mutex<BTreeMap<String, Sender<MemoryStream>>> = Mutex::new(BTreeMap::new());
+
+fn registry() -> MutexGuard<'static, BTreeMap<String, Sender<MemoryStream>>> {
+	REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// One direction of a connection.
+#[derive(Debug, Default)]
+struct Pipe {
+	state: Mutex<PipeState>,
+	cond: Condvar,
+}
+#[derive(Debug, Default)]
+struct PipeState {
+	buf: VecDeque<u8>,
+	/// The sending end is gone or was shut down.
+	send_closed: bool,
+	/// The receiving end is gone or was shut down.
+	recv_closed: bool,
+}
+impl Pipe {
+	fn lock(&self) -> MutexGuard<'_, PipeState> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+	fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+		let mut state = self.lock();
+		loop {
+			if state.recv_closed || buf.is_empty() {
+				return Ok(0);
+			}
+			if !state.buf.is_empty() {
+				return state.buf.read(buf);
+			}
+			if state.send_closed {
+				return Ok(0);
+			}
+			state = self
+				.cond
+				.wait(state)
+				.unwrap_or_else(PoisonError::into_inner);
+		}
+	}
+	fn send(&self, buf: &[u8]) -> io::Result<usize> {
+		let mut state = self.lock();
+		if state.send_closed || state.recv_closed {
+			return Err(io::ErrorKind::BrokenPipe.into());
+		}
+		state.buf.extend(buf);
+		self.cond.notify_all();
+		Ok(buf.len())
+	}
+	fn close_send(&self) {
+		self.lock().send_closed = true;
+		self.cond.notify_all();
+	}
+	fn close_recv(&self) {
+		let mut state = self.lock();
+		state.recv_closed = true;
+		state.buf.clear();
+	}
+}
+
+/// In-memory byte stream, obtained either from [`MemoryListener`] or by connecting to one.
+///
+/// Dropping the stream hangs up: the peer receives end of file once it has received everything
+/// sent before that, and its sends fail with [`BrokenPipe`](io::ErrorKind::BrokenPipe).
+///
+/// See the [module-level documentation](self) for more.
+pub struct MemoryStream {
+	rx: Arc<Pipe>,
+	tx: Arc<Pipe>,
+}
+impl MemoryStream {
+	/// Creates a pair of connected streams without going through a listener.
+	pub fn pair() -> (Self, Self) {
+		let (a, b) = (Arc::<Pipe>::default(), Arc::<Pipe>::default());
+		(
+			Self {
+				rx: Arc::clone(&a),
+				tx: Arc::clone(&b),
+			},
+			Self { rx: b, tx: a },
+		)
+	}
+	/// Connects to the listener with the given name.
+	///
+	/// Fails with [`NotFound`](io::ErrorKind::NotFound) if there is no such listener. Connecting
+	/// never blocks: the connection is queued until the listener accepts it.
+	pub fn connect(name: &str) -> io::Result<Self> {
+		let registry = registry();
+		let Some(listener) = registry.get(name) else {
+			return Err(io::ErrorKind::NotFound.into());
+		};
+		let (client, server) = Self::pair();
+		listener
+			.send(server)
+			.map_err(|_| io::Error::from(io::ErrorKind::ConnectionRefused))?;
+		Ok(client)
+	}
+	/// Shuts down the receive half, the send half, or both halves of the stream.
+	///
+	/// After the send half is shut down, sends fail and the peer receives end of file once it has
+	/// received everything sent before that. After the receive half is shut down, receives report
+	/// end of file, data which has not been received yet is discarded and the sends of the peer
+	/// fail.
+	pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+		if matches!(how, Shutdown::Read | Shutdown::Both) {
+			self.rx.close_recv();
+		}
+		if matches!(how, Shutdown::Write | Shutdown::Both) {
+			self.tx.close_send();
+		}
+		Ok(())
+	}
+}
+impl Read for &MemoryStream {
+	#[inline]
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.rx.recv(buf)
+	}
+}
+impl Read for MemoryStream {
+	#[inline]
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		(&*self).read(buf)
+	}
+}
+/// Flushing is an always successful no-op.
+impl Write for &MemoryStream {
+	#[inline]
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.tx.send(buf)
+	}
+	#[inline]
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+/// Flushing is an always successful no-op.
+impl Write for MemoryStream {
+	#[inline]
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		(&*self).write(buf)
+	}
+	#[inline]
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+impl Drop for MemoryStream {
+	fn drop(&mut self) {
+		self.rx.close_recv();
+		self.tx.close_send();
+	}
+}
+impl Debug for MemoryStream {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("MemoryStream").finish_non_exhaustive()
+	}
+}
+impl traits::Stream for MemoryStream {
+	type Name<'n> = &'n str;
+	#[inline]
+	fn connect(name: Self::Name<'_>) -> io::Result<Self> {
+		MemoryStream::connect(name)
+	}
+}
+impl traits::Shutdown for MemoryStream {
+	#[inline]
+	fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+		MemoryStream::shutdown(self, how)
+	}
+}
+
+/// In-memory listener, registered under a name for [`MemoryStream`]s to connect to.
+///
+/// The name is unregistered when the listener is dropped, and connections which have not been
+/// accepted by then are hung up on.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct MemoryListener {
+	name: String,
+	incoming: Mutex<Receiver<MemoryStream>>,
+}
+impl MemoryListener {
+	/// Registers a listener under the given name.
+	///
+	/// Fails with [`AddrInUse`](io::ErrorKind::AddrInUse) if another listener is registered under
+	/// the same name.
+	pub fn bind(name: &str) -> io::Result<Self> {
+		let mut registry = registry();
+		if registry.contains_key(name) {
+			return Err(io::ErrorKind::AddrInUse.into());
+		}
+		let (tx, rx) = mpsc::channel();
+		registry.insert(name.to_owned(), tx);
+		Ok(Self {
+			name: name.to_owned(),
+			incoming: Mutex::new(rx),
+		})
+	}
+	/// Returns the name which the listener is registered under.
+	#[inline]
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+	/// Blocks until a client connects, returning the server end of the connection.
+	pub fn accept(&self) -> io::Result<MemoryStream> {
+		let incoming = self.incoming.lock().unwrap_or_else(PoisonError::into_inner);
+		// The sender stays in the registry for as long as the listener exists
+		incoming
+			.recv()
+			.map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+	}
+	/// Returns a connection if a client is waiting to be accepted, or `None` if there is none,
+	/// without blocking.
+	pub fn try_accept(&self) -> Option<MemoryStream> {
+		let incoming = self.incoming.lock().unwrap_or_else(PoisonError::into_inner);
+		incoming.try_recv().ok()
+	}
+}
+impl Drop for MemoryListener {
+	fn drop(&mut self) {
+		registry().remove(&self.name);
+	}
+}
+impl traits::Listener for MemoryListener {
+	type Stream = MemoryStream;
+	#[inline]
+	fn accept(&self) -> io::Result<MemoryStream> {
+		MemoryListener::accept(self)
+	}
+}
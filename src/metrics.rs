@@ -0,0 +1,188 @@
+//! Instrumentation hooks for local socket operations.
+//!
+//! Connecting, accepting, receiving, sending and transferring handles over
+//! [local sockets](crate::local_socket), both blocking and Tokio-based, is reported to two
+//! optional observers:
+//! -	A process-wide [`MetricsSink`], installed with [`set_sink()`], which can be used to maintain
+//! 	counters and export them to a monitoring system such as Prometheus;
+//! -	The [`tracing`](https://docs.rs/tracing) subscriber, if the `tracing` feature is enabled.
+//! 	Connecting and accepting are wrapped in spans named `interprocess` at the `DEBUG` level, and
+//! 	every operation emits an event with the amount of data transferred or the error it failed
+//! 	with: at the `TRACE` level for receiving and sending, and at the `DEBUG` level otherwise.
+//!
+//! Errors of kind [`WouldBlock`](io::ErrorKind::WouldBlock) and
+//! [`Interrupted`](io::ErrorKind::Interrupted) are expected in the normal course of operation and
+//! are not reported.
+//!
+//! Operations performed through the backend-specific types in the [`os`](crate::os) module are not
+//! instrumented.
+//!
+//! # Example
+//! ```
+//! use interprocess::metrics::{self, Event, MetricsSink, Operation};
+//! use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+//!
+//! #[derive(Default)]
+//! struct Counters {
+//! 	received: AtomicUsize,
+//! 	sent: AtomicUsize,
+//! 	errors: AtomicUsize,
+//! }
+//! impl MetricsSink for Counters {
+//! 	fn record(&self, event: &Event<'_>) {
+//! 		match *event {
+//! 			Event::Completed { op: Operation::Receive, bytes } => {
+//! 				self.received.fetch_add(bytes, Relaxed);
+//! 			}
+//! 			Event::Completed { op: Operation::Send, bytes } => {
+//! 				self.sent.fetch_add(bytes, Relaxed);
+//! 			}
+//! 			Event::Failed { .. } => {
+//! 				self.errors.fetch_add(1, Relaxed);
+//! 			}
+//! 			_ => {}
+//! 		}
+//! 	}
+//! }
+//!
+//! assert!(metrics::set_sink(Counters::default()).is_ok());
+//! ```
+
+#[cfg(feature = "tokio")]
+use std::future::Future;
+use std::{io, sync::OnceLock};
+
+/// Receiver of the [events](Event) produced by local socket operations.
+///
+/// See the [module-level documentation](self) for more.
+pub trait MetricsSink: Send + Sync + 'static {
+	/// Records an event. This is called on the thread which performed the operation, right after
+	/// it completed, and should thus return quickly.
+	fn record(&self, event: &Event<'_>);
+}
+
+/// An operation on a local socket.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+	/// Connecting to a server.
+	Connect,
+	/// Accepting a connection.
+	Accept,
+	/// Receiving data.
+	Receive,
+	/// Sending data.
+	Send,
+	/// Sending a handle or file descriptor with
+	/// [`send_handle()`](crate::handle_transfer::HandleTransfer::send_handle).
+	SendHandle,
+	/// Receiving a handle or file descriptor with
+	/// [`recv_handle()`](crate::handle_transfer::HandleTransfer::recv_handle).
+	RecvHandle,
+}
+
+/// The outcome of an [operation](Operation), as passed to [`MetricsSink::record()`].
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug)]
+pub enum Event<'a> {
+	/// The operation succeeded.
+	Completed {
+		/// The operation.
+		op: Operation,
+		/// The number of bytes received or sent, or 0 for operations which don't transfer data.
+		bytes: usize,
+	},
+	/// The operation failed.
+	Failed {
+		/// The operation.
+		op: Operation,
+		/// The error it failed with.
+		error: &'a io::Error,
+	},
+}
+
+static SINK: OnceLock<Box<dyn MetricsSink>> = OnceLock::new();
+
+/// Installs the process-wide metrics sink.
+///
+/// The sink can only be installed once, and cannot be removed afterwards. If a sink has already
+/// been installed, the given one is returned back.
+pub fn set_sink<S: MetricsSink>(sink: S) -> Result<(), S> {
+	let mut sink = Some(sink);
+	SINK.get_or_init(|| match sink.take() {
+		Some(sink) => Box::new(sink),
+		None => unreachable!(),
+	});
+	match sink {
+		Some(sink) => Err(sink),
+		None => Ok(()),
+	}
+}
+
+fn report(event: Event<'_>) {
+	#[cfg(feature = "tracing")]
+	trace_event(&event);
+	if let Some(sink) = SINK.get() {
+		sink.record(&event);
+	}
+}
+
+#[cfg(feature = "tracing")]
+fn trace_event(event: &Event<'_>) {
+	match *event {
+		Event::Completed {
+			op: op @ (Operation::Receive | Operation::Send),
+			bytes,
+		} => tracing::trace!(?op, bytes, "operation completed"),
+		Event::Completed { op, .. } => tracing::debug!(?op, "operation completed"),
+		Event::Failed { op, error } => tracing::debug!(?op, %error, "operation failed"),
+	}
+}
+
+/// Reports the outcome of an operation which doesn't transfer data.
+pub(crate) fn track<T>(op: Operation, rslt: io::Result<T>) -> io::Result<T> {
+	track_with(op, rslt, |_| 0)
+}
+
+/// Reports the outcome of an operation which transfers the returned number of bytes.
+pub(crate) fn track_bytes(op: Operation, rslt: io::Result<usize>) -> io::Result<usize> {
+	track_with(op, rslt, |&bytes| bytes)
+}
+
+pub(crate) fn track_with<T>(
+	op: Operation,
+	rslt: io::Result<T>,
+	bytes: impl FnOnce(&T) -> usize,
+) -> io::Result<T> {
+	match &rslt {
+		Ok(val) => report(Event::Completed {
+			op,
+			bytes: bytes(val),
+		}),
+		Err(e)
+			if matches!(
+				e.kind(),
+				io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+			) => {}
+		Err(error) => report(Event::Failed { op, error }),
+	}
+	rslt
+}
+
+/// Runs a blocking operation inside of a span and reports its outcome.
+pub(crate) fn track_blocking<T>(op: Operation, f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+	#[cfg(feature = "tracing")]
+	let _span = tracing::debug_span!("interprocess", ?op).entered();
+	track(op, f())
+}
+
+/// Runs a future inside of a span for the given operation and reports its outcome.
+#[cfg(feature = "tokio")]
+pub(crate) async fn track_async<T>(
+	op: Operation,
+	fut: impl Future<Output = io::Result<T>>,
+) -> io::Result<T> {
+	#[cfg(feature = "tracing")]
+	let fut = tracing::Instrument::instrument(fut, tracing::debug_span!("interprocess", ?op));
+	track(op, fut.await)
+}
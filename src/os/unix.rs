@@ -8,6 +8,10 @@
 //! need to be spawned by another).
 //!
 //! FIFO files are available on all supported systems.
+//!
+//! ## POSIX message queues
+//! Named queues of discrete, prioritized messages which persist independently of the processes
+//! using them. Only available on Linux.
 
 pub(crate) mod imports;
 
@@ -18,10 +22,97 @@ use fdops::*;
 
 pub mod fifo_file;
 pub mod local_socket;
+#[cfg(target_os = "linux")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(target_os = "linux")))]
+pub mod posix_mqueue;
+pub mod process;
+#[cfg(feature = "systemd")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "systemd")))]
+pub mod sd_listen_fds;
 pub mod uds_local_socket;
 
+pub(crate) mod notify;
+pub(crate) mod shmem;
 pub(crate) mod unnamed_pipe;
 
+/// Controls whether writes to local sockets suppress `SIGPIPE`, which is the default.
+///
+/// Writing to a socket whose peer has gone away normally raises `SIGPIPE`, which terminates the
+/// process unless the signal is ignored or handled. Rust programs ignore it by default, but code
+/// embedded into other programs or built with `-Zon-broken-pipe` can't rely on that. With
+/// suppression enabled, such writes fail with [`BrokenPipe`](std::io::ErrorKind::BrokenPipe)
+/// instead. Programs which handle `SIGPIPE` themselves and want it to be delivered can disable it.
+///
+/// On Linux, Android and the BSDs, this is implemented via `MSG_NOSIGNAL` and takes effect
+/// immediately for all sockets. On Apple platforms, which lack `MSG_NOSIGNAL`, the `SO_NOSIGPIPE`
+/// socket option is set when streams are connected or accepted, and so only streams created after
+/// the change are affected. `sendfile(2)`, which the `send_file()` methods of Unix domain socket
+/// streams use on Linux, Android and FreeBSD, has no such flag, and so `SIGPIPE` is instead
+/// blocked in the calling thread for the duration of the call, discarding the signal if the call
+/// raises it. Pipes and FIFO files cannot suppress the signal this way and are unaffected.
+#[inline]
+pub fn set_sigpipe_suppression(suppress: bool) {
+	c_wrappers::SUPPRESS_SIGPIPE.store(suppress, std::sync::atomic::Ordering::Relaxed);
+}
+/// Returns whether writes to local sockets suppress `SIGPIPE`. See
+/// [`set_sigpipe_suppression()`] for more.
+#[inline]
+pub fn sigpipe_suppression() -> bool {
+	c_wrappers::SUPPRESS_SIGPIPE.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// How calls interrupted by a signal (`EINTR`) are handled by the operations of the crate which
+/// retry them, as set with [`set_eintr_policy()`].
+///
+/// This governs the system calls which the crate makes on its own account, such as waiting for
+/// connections of a [shared listener](local_socket::SharedListener), opening
+/// [FIFO files](fifo_file), exchanging POSIX messages, transferring
+/// [handles](crate::handle_transfer) and sending or receiving data along with file descriptors or
+/// credentials. Implementations of [`Read`](std::io::Read) and [`Write`](std::io::Write) are
+/// unaffected: as is customary for those traits, they surface
+/// [`Interrupted`](std::io::ErrorKind::Interrupted) to the caller, and helpers such as
+/// [`read_exact()`](std::io::Read::read_exact) retry.
+///
+/// Programs which use signals to interrupt blocking calls, for instance to check a shutdown flag
+/// from a signal handler installed without `SA_RESTART`, can use [`Surface`](Self::Surface) or a
+/// bounded number of retries to regain control.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EintrPolicy {
+	/// Retry interrupted calls until they complete. This is the default.
+	#[default]
+	RetryForever,
+	/// Retry interrupted calls up to the given number of times before returning an error of kind
+	/// [`Interrupted`](std::io::ErrorKind::Interrupted). `Retry(u32::MAX)` is equivalent to
+	/// `RetryForever`.
+	Retry(u32),
+	/// Return an error of kind [`Interrupted`](std::io::ErrorKind::Interrupted) right away.
+	/// Equivalent to `Retry(0)`.
+	Surface,
+}
+
+/// Sets the process-wide [policy for handling `EINTR`](EintrPolicy).
+#[inline]
+pub fn set_eintr_policy(policy: EintrPolicy) {
+	let retries = match policy {
+		EintrPolicy::RetryForever => u32::MAX,
+		EintrPolicy::Retry(n) => n,
+		EintrPolicy::Surface => 0,
+	};
+	c_wrappers::EINTR_RETRIES.store(retries, std::sync::atomic::Ordering::Relaxed);
+}
+/// Returns the process-wide [policy for handling `EINTR`](EintrPolicy). `Retry(0)` is reported as
+/// [`Surface`](EintrPolicy::Surface), and `Retry(u32::MAX)` as
+/// [`RetryForever`](EintrPolicy::RetryForever).
+#[inline]
+pub fn eintr_policy() -> EintrPolicy {
+	match c_wrappers::EINTR_RETRIES.load(std::sync::atomic::Ordering::Relaxed) {
+		u32::MAX => EintrPolicy::RetryForever,
+		0 => EintrPolicy::Surface,
+		n => EintrPolicy::Retry(n),
+	}
+}
+
 mod unixprelude {
 	#[allow(unused_imports)]
 	pub use libc::{c_char, c_int, c_short, gid_t, mode_t, pid_t, size_t, uid_t};
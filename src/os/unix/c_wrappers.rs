@@ -1,5 +1,5 @@
 use super::unixprelude::*;
-use crate::AsPtr;
+use crate::{AsMutPtr, AsPtr};
 #[allow(unused_imports)]
 use crate::{FdOrErrno, OrErrno};
 use libc::{sockaddr_un, AF_UNIX};
@@ -8,12 +8,33 @@ use std::net::Shutdown;
 #[cfg(any(target_os = "linux", target_os = "android"))]
 use std::os::linux::net::SocketAddrExt;
 use std::{
-	io,
-	mem::{transmute, zeroed},
+	io::{self, IoSlice},
+	mem::{self, transmute, zeroed},
 	os::unix::net::SocketAddr,
-	sync::atomic::{AtomicBool, Ordering::Relaxed},
+	sync::atomic::{AtomicBool, AtomicU32, Ordering::Relaxed},
+	time::Duration,
 };
 
+/// How many times calls interrupted by a signal are retried, with `u32::MAX` meaning indefinitely.
+pub(super) static EINTR_RETRIES: AtomicU32 = AtomicU32::new(u32::MAX);
+
+/// Performs a call, retrying it if it fails with `EINTR` as allowed by the configured policy.
+pub(super) fn retry_eintr<T>(mut f: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+	let limit = EINTR_RETRIES.load(Relaxed);
+	let mut retries = 0_u32;
+	loop {
+		match f() {
+			Err(e)
+				if e.kind() == io::ErrorKind::Interrupted
+					&& (limit == u32::MAX || retries < limit) =>
+			{
+				retries = retries.saturating_add(1);
+			}
+			els => return els,
+		}
+	}
+}
+
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub(super) unsafe fn fcntl_int(fd: BorrowedFd<'_>, cmd: c_int, val: c_int) -> io::Result<c_int> {
 	let val = unsafe { libc::fcntl(fd.as_raw_fd(), cmd, val) };
@@ -38,12 +59,10 @@ pub(super) fn duplicate_fd(fd: BorrowedFd<'_>) -> io::Result<OwnedFd> {
 	}
 }
 
-#[cfg(not(any(target_os = "linux", target_os = "android")))]
 fn get_fdflags(fd: BorrowedFd<'_>) -> io::Result<c_int> {
 	let val = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD, 0) };
 	(val != -1).true_val_or_errno(val)
 }
-#[cfg(not(any(target_os = "linux", target_os = "android")))]
 fn set_fdflags(fd: BorrowedFd<'_>, flags: c_int) -> io::Result<()> {
 	unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, flags) != -1 }.true_val_or_errno(())
 }
@@ -52,6 +71,65 @@ fn set_cloexec(fd: BorrowedFd<'_>) -> io::Result<()> {
 	set_fdflags(fd, get_fdflags(fd)? | libc::FD_CLOEXEC)?;
 	Ok(())
 }
+/// Sets or clears `FD_CLOEXEC`, controlling whether the descriptor survives `exec`.
+pub(super) fn set_inheritable(fd: BorrowedFd<'_>, inheritable: bool) -> io::Result<()> {
+	let flags = get_fdflags(fd)?;
+	let new_flags = if inheritable {
+		flags & !libc::FD_CLOEXEC
+	} else {
+		flags | libc::FD_CLOEXEC
+	};
+	if new_flags != flags {
+		set_fdflags(fd, new_flags)?;
+	}
+	Ok(())
+}
+
+fn get_status_flags(fd: BorrowedFd<'_>) -> io::Result<c_int> {
+	let val = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL, 0) };
+	(val != -1).true_val_or_errno(val)
+}
+fn set_status_flags(fd: BorrowedFd<'_>, flags: c_int) -> io::Result<()> {
+	unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags) != -1 }.true_val_or_errno(())
+}
+pub(super) fn set_nonblocking(fd: BorrowedFd<'_>, nonblocking: bool) -> io::Result<()> {
+	let flags = get_status_flags(fd)?;
+	let new_flags = if nonblocking {
+		flags | libc::O_NONBLOCK
+	} else {
+		flags & !libc::O_NONBLOCK
+	};
+	if new_flags != flags {
+		set_status_flags(fd, new_flags)?;
+	}
+	Ok(())
+}
+
+/// Creates an unnamed pipe, returning its receiving and sending ends in that order. If `cloexec`
+/// is `true`, both ends are made non-inheritable – atomically so on Linux and Android.
+pub(super) fn pipe(cloexec: bool) -> io::Result<(OwnedFd, OwnedFd)> {
+	let mut fds: [c_int; 2] = [0; 2];
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	let success = {
+		let flags = if cloexec { libc::O_CLOEXEC } else { 0 };
+		unsafe { libc::pipe2(fds.as_mut_ptr().cast(), flags) == 0 }
+	};
+	#[cfg(not(any(target_os = "linux", target_os = "android")))]
+	let success = unsafe { libc::pipe(fds.as_mut_ptr().cast()) == 0 };
+	success.true_val_or_errno(())?;
+	let [r, w] = fds;
+	let (r, w) = unsafe {
+		// SAFETY: we just created both of those file descriptors, which means that neither of
+		// them can be in use elsewhere.
+		(OwnedFd::from_raw_fd(r), OwnedFd::from_raw_fd(w))
+	};
+	#[cfg(not(any(target_os = "linux", target_os = "android")))]
+	if cloexec {
+		set_cloexec(r.as_fd())?;
+		set_cloexec(w.as_fd())?;
+	}
+	Ok((r, w))
+}
 
 pub(super) fn set_mode(fd: BorrowedFd<'_>, mode: mode_t) -> io::Result<()> {
 	unsafe { libc::fchmod(fd.as_raw_fd(), mode) != -1 }.true_val_or_errno(())
@@ -71,15 +149,12 @@ pub(super) fn set_socket_mode(fd: BorrowedFd<'_>, mode: mode_t) -> io::Result<()
 	rslt
 }
 
-/// Creates a Unix domain socket of the given type. If on Linux or Android and `nonblocking` is
-/// `true`, also makes it nonblocking.
-pub(super) const CAN_CREATE_NONBLOCKING: bool =
-	cfg!(any(target_os = "linux", target_os = "android"));
-fn create_socket(ty: c_int, nonblocking: bool) -> io::Result<OwnedFd> {
+/// Creates a Unix domain socket of the given type, making it nonblocking if `nonblocking` is
+/// `true`. This is done atomically on Linux and Android, and with a separate call elsewhere.
+pub(super) fn create_socket(ty: c_int, nonblocking: bool) -> io::Result<OwnedFd> {
 	let flags = {
 		#[cfg(not(any(target_os = "linux", target_os = "android")))]
 		{
-			let _ = nonblocking;
 			0
 		}
 		#[cfg(any(target_os = "linux", target_os = "android"))]
@@ -99,6 +174,9 @@ fn create_socket(ty: c_int, nonblocking: bool) -> io::Result<OwnedFd> {
 	#[cfg(not(any(target_os = "linux", target_os = "android")))]
 	{
 		set_cloexec(fd.as_fd())?;
+		if nonblocking {
+			set_nonblocking(fd.as_fd(), true)?;
+		}
 	}
 	Ok(fd)
 }
@@ -129,7 +207,7 @@ const SUN_PATH_OFFSET: usize = unsafe {
 	clippy::arithmetic_side_effects,
 	clippy::as_conversions
 )]
-fn bind(fd: BorrowedFd<'_>, addr: &SocketAddr) -> io::Result<()> {
+fn to_sockaddr(addr: &SocketAddr) -> (sockaddr_un, libc::socklen_t) {
 	let (path, extra) = addr_to_slice(addr);
 	let path = unsafe { transmute::<&[u8], &[i8]>(path) };
 
@@ -138,20 +216,34 @@ fn bind(fd: BorrowedFd<'_>, addr: &SocketAddr) -> io::Result<()> {
 	addr.sun_path[extra..(extra + path.len())].copy_from_slice(path);
 
 	let len = path.len() + extra + SUN_PATH_OFFSET;
+	// It's impossible for this to exceed socklen_t::MAX, since it came from a valid SocketAddr
+	(addr, len as _)
+}
 
-	unsafe {
-		libc::bind(
-			fd.as_raw_fd(),
-			addr.as_ptr().cast(),
-			// It's impossible for this to exceed socklen_t::MAX, since it came from a valid
-			// SocketAddr
-			len as _,
-		) != -1
+fn bind(fd: BorrowedFd<'_>, addr: &SocketAddr) -> io::Result<()> {
+	let (addr, len) = to_sockaddr(addr);
+	unsafe { libc::bind(fd.as_raw_fd(), addr.as_ptr().cast(), len) != -1 }.true_val_or_errno(())
+}
+
+/// Initiates a connection on a nonblocking socket, returning `false` if it has yet to complete.
+pub(super) fn connect(fd: BorrowedFd<'_>, addr: &SocketAddr) -> io::Result<bool> {
+	let (addr, len) = to_sockaddr(addr);
+	match unsafe { libc::connect(fd.as_raw_fd(), addr.as_ptr().cast(), len) != -1 }
+		.true_val_or_errno(())
+	{
+		Ok(()) => Ok(true),
+		// An interrupted connection attempt carries on in the background
+		Err(e)
+			if e.raw_os_error()
+				.is_some_and(|c| c == libc::EINPROGRESS || c == libc::EINTR) =>
+		{
+			Ok(false)
+		}
+		Err(e) => Err(e),
 	}
-	.true_val_or_errno(())
 }
 
-fn listen(fd: BorrowedFd<'_>) -> io::Result<()> {
+fn listen(fd: BorrowedFd<'_>, backlog: Option<c_int>) -> io::Result<()> {
 	// The standard library does this
 	#[cfg(any(
 		target_os = "windows",
@@ -178,7 +270,8 @@ fn listen(fd: BorrowedFd<'_>) -> io::Result<()> {
 		target_os = "horizon"
 	)))]
 	const BACKLOG: libc::c_int = libc::SOMAXCONN;
-	unsafe { libc::listen(fd.as_raw_fd(), BACKLOG) != -1 }.true_val_or_errno(())
+	let backlog = backlog.unwrap_or(BACKLOG);
+	unsafe { libc::listen(fd.as_raw_fd(), backlog) != -1 }.true_val_or_errno(())
 }
 
 struct WithUmask {
@@ -208,6 +301,8 @@ pub(super) fn bind_and_listen_with_mode(
 	addr: &SocketAddr,
 	nonblocking: bool,
 	mode: mode_t,
+	backlog: Option<c_int>,
+	configure: impl Fn(BorrowedFd<'_>) -> io::Result<()>,
 ) -> io::Result<OwnedFd> {
 	if mode & 0o111 != 0 {
 		return Err(io::Error::new(
@@ -220,8 +315,9 @@ pub(super) fn bind_and_listen_with_mode(
 		let sock = create_socket(ty, nonblocking)?;
 		match set_socket_mode(sock.as_fd(), mode) {
 			Ok(()) => {
+				configure(sock.as_fd())?;
 				bind(sock.as_fd(), addr)?;
-				listen(sock.as_fd())?;
+				listen(sock.as_fd(), backlog)?;
 				return Ok(sock);
 			}
 			Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
@@ -236,11 +332,29 @@ pub(super) fn bind_and_listen_with_mode(
 	});
 	// We race in this muthafucka, better get yo secure code ass back to Linux
 	let sock = create_socket(ty, nonblocking)?;
+	configure(sock.as_fd())?;
 	bind(sock.as_fd(), addr)?;
-	listen(sock.as_fd())?;
+	listen(sock.as_fd(), backlog)?;
 	Ok(sock)
 }
 
+/// Waits for events on the given descriptors, retrying on `EINTR` as configured. A negative
+/// timeout waits indefinitely.
+pub(super) fn poll(fds: &mut [libc::pollfd], timeout: c_int) -> io::Result<c_int> {
+	let nfds = libc::nfds_t::try_from(fds.len())
+		.map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "too many descriptors to poll"))?;
+	retry_eintr(|| {
+		let val = unsafe { libc::poll(fds.as_mut_ptr().cast(), nfds, timeout) };
+		(val != -1).true_val_or_errno(val)
+	})
+}
+
+/// Returns the maximum length of the queue of pending connections of a listening socket.
+#[cfg(target_os = "freebsd")]
+pub(super) fn listen_queue_limit(fd: BorrowedFd<'_>) -> io::Result<c_int> {
+	unsafe { getsockopt::<c_int>(fd, libc::SOL_SOCKET, libc::SO_LISTENQLIMIT) }
+}
+
 #[cfg(feature = "tokio")]
 pub(super) fn shutdown(fd: BorrowedFd<'_>, how: Shutdown) -> io::Result<()> {
 	let how = match how {
@@ -250,3 +364,884 @@ pub(super) fn shutdown(fd: BorrowedFd<'_>, how: Shutdown) -> io::Result<()> {
 	};
 	unsafe { libc::shutdown(fd.as_raw_fd(), how) != -1 }.true_val_or_errno(())
 }
+
+#[allow(clippy::as_conversions)]
+unsafe fn getsockopt<T>(fd: BorrowedFd<'_>, level: c_int, name: c_int) -> io::Result<T> {
+	let mut val = unsafe { zeroed::<T>() };
+	let mut len = mem::size_of::<T>() as libc::socklen_t;
+	unsafe {
+		libc::getsockopt(
+			fd.as_raw_fd(),
+			level,
+			name,
+			val.as_mut_ptr().cast(),
+			&mut len,
+		) != -1
+	}
+	.true_val_or_errno(val)
+}
+#[allow(clippy::as_conversions)]
+unsafe fn setsockopt<T>(fd: BorrowedFd<'_>, level: c_int, name: c_int, val: T) -> io::Result<()> {
+	let len = mem::size_of::<T>() as libc::socklen_t;
+	unsafe { libc::setsockopt(fd.as_raw_fd(), level, name, val.as_ptr().cast(), len) != -1 }
+		.true_val_or_errno(())
+}
+
+/// Checks whether the given descriptor is a Unix domain socket of the given type.
+#[allow(clippy::as_conversions)]
+pub(super) fn is_uds_of_type(fd: BorrowedFd<'_>, ty: c_int) -> io::Result<bool> {
+	match unsafe { getsockopt::<c_int>(fd, libc::SOL_SOCKET, libc::SO_TYPE) } {
+		Ok(actual) if actual == ty => {}
+		Ok(..) => return Ok(false),
+		Err(e) if e.raw_os_error() == Some(libc::ENOTSOCK) => return Ok(false),
+		Err(e) => return Err(e),
+	}
+	let mut addr = unsafe { zeroed::<libc::sockaddr_storage>() };
+	let mut len = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+	unsafe { libc::getsockname(fd.as_raw_fd(), addr.as_mut_ptr().cast(), &mut len) != -1 }
+		.true_val_or_errno(())?;
+	Ok(c_int::from(addr.ss_family) == AF_UNIX)
+}
+
+pub(super) fn is_listening(fd: BorrowedFd<'_>) -> io::Result<bool> {
+	unsafe { getsockopt::<c_int>(fd, libc::SOL_SOCKET, libc::SO_ACCEPTCONN) }.map(|v| v != 0)
+}
+
+/// Makes `fd` available as `target` and clears `FD_CLOEXEC` on it. Only performs
+/// async-signal-safe calls, and is thus suitable for use between `fork` and `exec`.
+pub(super) fn dup2_inheritable(fd: BorrowedFd<'_>, target: c_int) -> io::Result<()> {
+	if fd.as_raw_fd() == target {
+		set_inheritable(fd, true)
+	} else {
+		// dup2 never sets FD_CLOEXEC on the new descriptor
+		unsafe { libc::dup2(fd.as_raw_fd(), target) != -1 }.true_val_or_errno(())
+	}
+}
+
+fn size_to_c_int(size: usize) -> c_int {
+	c_int::try_from(size).unwrap_or(c_int::MAX)
+}
+fn c_int_to_size(val: c_int) -> usize {
+	usize::try_from(val).unwrap_or(0)
+}
+
+pub(super) fn recv_buffer_size(fd: BorrowedFd<'_>) -> io::Result<usize> {
+	unsafe { getsockopt::<c_int>(fd, libc::SOL_SOCKET, libc::SO_RCVBUF) }.map(c_int_to_size)
+}
+pub(super) fn set_recv_buffer_size(fd: BorrowedFd<'_>, size: usize) -> io::Result<()> {
+	unsafe { setsockopt(fd, libc::SOL_SOCKET, libc::SO_RCVBUF, size_to_c_int(size)) }
+}
+pub(super) fn send_buffer_size(fd: BorrowedFd<'_>) -> io::Result<usize> {
+	unsafe { getsockopt::<c_int>(fd, libc::SOL_SOCKET, libc::SO_SNDBUF) }.map(c_int_to_size)
+}
+pub(super) fn set_send_buffer_size(fd: BorrowedFd<'_>, size: usize) -> io::Result<()> {
+	unsafe { setsockopt(fd, libc::SOL_SOCKET, libc::SO_SNDBUF, size_to_c_int(size)) }
+}
+
+pub(super) fn take_error(fd: BorrowedFd<'_>) -> io::Result<Option<io::Error>> {
+	let errno = unsafe { getsockopt::<c_int>(fd, libc::SOL_SOCKET, libc::SO_ERROR)? };
+	Ok((errno != 0).then(|| io::Error::from_raw_os_error(errno)))
+}
+
+pub(super) fn linger(fd: BorrowedFd<'_>) -> io::Result<Option<Duration>> {
+	let linger = unsafe { getsockopt::<libc::linger>(fd, libc::SOL_SOCKET, libc::SO_LINGER)? };
+	Ok((linger.l_onoff != 0)
+		.then(|| Duration::from_secs(u64::try_from(linger.l_linger).unwrap_or(0))))
+}
+pub(super) fn set_linger(fd: BorrowedFd<'_>, linger: Option<Duration>) -> io::Result<()> {
+	let linger = libc::linger {
+		l_onoff: linger.is_some().into(),
+		l_linger: linger
+			.map(|dur| c_int::try_from(dur.as_secs()).unwrap_or(c_int::MAX))
+			.unwrap_or(0),
+	};
+	unsafe { setsockopt(fd, libc::SOL_SOCKET, libc::SO_LINGER, linger) }
+}
+
+fn get_timeout(fd: BorrowedFd<'_>, name: c_int) -> io::Result<Option<Duration>> {
+	let tv = unsafe { getsockopt::<libc::timeval>(fd, libc::SOL_SOCKET, name)? };
+	let secs = u64::try_from(tv.tv_sec).unwrap_or(0);
+	let nanos = u32::try_from(tv.tv_usec).unwrap_or(0).saturating_mul(1000);
+	let dur = Duration::new(secs, nanos);
+	Ok((!dur.is_zero()).then_some(dur))
+}
+/// Sets `SO_RCVTIMEO` or `SO_SNDTIMEO`, rejecting a zero duration like the standard library does,
+/// since the OS would interpret it as no timeout.
+fn set_timeout(fd: BorrowedFd<'_>, name: c_int, timeout: Option<Duration>) -> io::Result<()> {
+	let tv = match timeout {
+		Some(dur) if dur.is_zero() => {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"cannot set a 0 duration timeout",
+			));
+		}
+		Some(dur) => {
+			let mut tv = libc::timeval {
+				tv_sec: libc::time_t::try_from(dur.as_secs()).unwrap_or(libc::time_t::MAX),
+				// Always below a million, which fits into suseconds_t everywhere
+				tv_usec: i32::try_from(dur.subsec_micros()).unwrap_or(0).into(),
+			};
+			// Durations below a microsecond would otherwise round down to no timeout at all
+			if tv.tv_sec == 0 && tv.tv_usec == 0 {
+				tv.tv_usec = 1;
+			}
+			tv
+		}
+		None => libc::timeval {
+			tv_sec: 0,
+			tv_usec: 0,
+		},
+	};
+	unsafe { setsockopt(fd, libc::SOL_SOCKET, name, tv) }
+}
+pub(super) fn recv_timeout(fd: BorrowedFd<'_>) -> io::Result<Option<Duration>> {
+	get_timeout(fd, libc::SO_RCVTIMEO)
+}
+pub(super) fn set_recv_timeout(fd: BorrowedFd<'_>, timeout: Option<Duration>) -> io::Result<()> {
+	set_timeout(fd, libc::SO_RCVTIMEO, timeout)
+}
+pub(super) fn send_timeout(fd: BorrowedFd<'_>) -> io::Result<Option<Duration>> {
+	get_timeout(fd, libc::SO_SNDTIMEO)
+}
+pub(super) fn set_send_timeout(fd: BorrowedFd<'_>, timeout: Option<Duration>) -> io::Result<()> {
+	set_timeout(fd, libc::SO_SNDTIMEO, timeout)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn set_passcred(fd: BorrowedFd<'_>, passcred: bool) -> io::Result<()> {
+	unsafe {
+		setsockopt(
+			fd,
+			libc::SOL_SOCKET,
+			libc::SO_PASSCRED,
+			c_int::from(passcred),
+		)
+	}
+}
+
+/// Returns the process ID (where available), user ID and group ID of the peer of a connected Unix
+/// domain socket, as they were when the connection was established.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn peer_credentials(fd: BorrowedFd<'_>) -> io::Result<(Option<pid_t>, uid_t, gid_t)> {
+	let cred = unsafe { getsockopt::<libc::ucred>(fd, libc::SOL_SOCKET, libc::SO_PEERCRED)? };
+	Ok((Some(cred.pid), cred.uid, cred.gid))
+}
+/// Returns the process ID (where available), user ID and group ID of the peer of a connected Unix
+/// domain socket, as they were when the connection was established.
+#[cfg(any(
+	target_os = "macos",
+	target_os = "ios",
+	target_os = "tvos",
+	target_os = "watchos",
+	target_os = "freebsd",
+	target_os = "dragonfly",
+	target_os = "openbsd",
+	target_os = "netbsd",
+))]
+pub(super) fn peer_credentials(fd: BorrowedFd<'_>) -> io::Result<(Option<pid_t>, uid_t, gid_t)> {
+	let (mut uid, mut gid) = (0, 0);
+	unsafe { libc::getpeereid(fd.as_raw_fd(), &mut uid, &mut gid) != -1 }.true_val_or_errno(())?;
+	Ok((None, uid, gid))
+}
+/// Returns the process ID (where available), user ID and group ID of the peer of a connected Unix
+/// domain socket, as they were when the connection was established.
+#[cfg(not(any(
+	target_os = "linux",
+	target_os = "android",
+	target_os = "macos",
+	target_os = "ios",
+	target_os = "tvos",
+	target_os = "watchos",
+	target_os = "freebsd",
+	target_os = "dragonfly",
+	target_os = "openbsd",
+	target_os = "netbsd",
+)))]
+pub(super) fn peer_credentials(fd: BorrowedFd<'_>) -> io::Result<(Option<pid_t>, uid_t, gid_t)> {
+	let _ = fd;
+	Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Returns the supplementary groups of the peer of a connected Unix domain socket, as they were
+/// when the connection was established.
+#[cfg(target_os = "linux")]
+#[allow(clippy::as_conversions, clippy::arithmetic_side_effects)]
+pub(super) fn peer_groups(fd: BorrowedFd<'_>) -> io::Result<Vec<gid_t>> {
+	const GID_SIZE: usize = mem::size_of::<gid_t>();
+	let mut groups: Vec<gid_t> = vec![0; 16];
+	loop {
+		let mut len = (groups.len() * GID_SIZE) as libc::socklen_t;
+		let success = unsafe {
+			libc::getsockopt(
+				fd.as_raw_fd(),
+				libc::SOL_SOCKET,
+				libc::SO_PEERGROUPS,
+				groups.as_mut_ptr().cast(),
+				&mut len,
+			) != -1
+		};
+		// SO_PEERGROUPS reports the required length via ERANGE if the buffer is too small
+		match success.true_val_or_errno(()) {
+			Ok(()) => {
+				groups.truncate(len as usize / GID_SIZE);
+				return Ok(groups);
+			}
+			Err(e) if e.raw_os_error() == Some(libc::ERANGE) => {
+				groups.resize((len as usize / GID_SIZE).max(groups.len() * 2), 0);
+			}
+			Err(e) => return Err(e),
+		}
+	}
+}
+/// Returns the supplementary groups of the peer of a connected Unix domain socket, as they were
+/// when the connection was established.
+#[cfg(any(
+	target_os = "macos",
+	target_os = "ios",
+	target_os = "tvos",
+	target_os = "watchos",
+	target_os = "freebsd",
+	target_os = "dragonfly",
+))]
+pub(super) fn peer_groups(fd: BorrowedFd<'_>) -> io::Result<Vec<gid_t>> {
+	// SOL_LOCAL is 0 on all of these platforms, but libc only defines it for some of them
+	let cred = unsafe { getsockopt::<libc::xucred>(fd, 0, libc::LOCAL_PEERCRED)? };
+	let ngroups = usize::try_from(cred.cr_ngroups).unwrap_or(0);
+	// The first entry is the effective group ID
+	Ok(cred
+		.cr_groups
+		.iter()
+		.take(ngroups)
+		.skip(1)
+		.copied()
+		.collect())
+}
+/// Returns the supplementary groups of the peer of a connected Unix domain socket, as they were
+/// when the connection was established.
+#[cfg(not(any(
+	target_os = "linux",
+	target_os = "macos",
+	target_os = "ios",
+	target_os = "tvos",
+	target_os = "watchos",
+	target_os = "freebsd",
+	target_os = "dragonfly",
+)))]
+pub(super) fn peer_groups(fd: BorrowedFd<'_>) -> io::Result<Vec<gid_t>> {
+	let _ = fd;
+	Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Returns the security label of the peer of a connected Unix domain socket.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[allow(clippy::as_conversions)]
+pub(super) fn peer_security_context(fd: BorrowedFd<'_>) -> io::Result<Vec<u8>> {
+	let mut label = vec![0_u8; 256];
+	loop {
+		let mut len = label.len() as libc::socklen_t;
+		let success = unsafe {
+			libc::getsockopt(
+				fd.as_raw_fd(),
+				libc::SOL_SOCKET,
+				libc::SO_PEERSEC,
+				label.as_mut_ptr().cast(),
+				&mut len,
+			) != -1
+		};
+		// SO_PEERSEC reports the required length via ERANGE if the buffer is too small
+		match success.true_val_or_errno(()) {
+			Ok(()) => {
+				label.truncate(len as usize);
+				return Ok(label);
+			}
+			Err(e) if e.raw_os_error() == Some(libc::ERANGE) => {
+				label.resize((len as usize).max(label.len().saturating_mul(2)), 0);
+			}
+			Err(e) => return Err(e),
+		}
+	}
+}
+
+/// Receives data from the socket into a buffer which need not be initialized.
+pub(super) fn recv_uninit(
+	fd: BorrowedFd<'_>,
+	buf: &mut [mem::MaybeUninit<u8>],
+) -> io::Result<usize> {
+	let received = unsafe { libc::recv(fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), 0) };
+	(received != -1).true_val_or_errno(ssize_to_usize(received))
+}
+
+/// Receives data from the socket without removing it from the receive queue.
+pub(super) fn peek(fd: BorrowedFd<'_>, buf: &mut [u8]) -> io::Result<usize> {
+	let received = unsafe {
+		libc::recv(
+			fd.as_raw_fd(),
+			buf.as_mut_ptr().cast(),
+			buf.len(),
+			libc::MSG_PEEK,
+		)
+	};
+	(received != -1).true_val_or_errno(ssize_to_usize(received))
+}
+
+/// Whether sends on sockets created or written to by the crate suppress `SIGPIPE`.
+pub(super) static SUPPRESS_SIGPIPE: AtomicBool = AtomicBool::new(true);
+
+#[cfg(any(
+	target_os = "linux",
+	target_os = "android",
+	target_os = "freebsd",
+	target_os = "dragonfly",
+	target_os = "openbsd",
+	target_os = "netbsd",
+))]
+fn send_flags() -> c_int {
+	if SUPPRESS_SIGPIPE.load(Relaxed) {
+		libc::MSG_NOSIGNAL
+	} else {
+		0
+	}
+}
+#[cfg(not(any(
+	target_os = "linux",
+	target_os = "android",
+	target_os = "freebsd",
+	target_os = "dragonfly",
+	target_os = "openbsd",
+	target_os = "netbsd",
+)))]
+fn send_flags() -> c_int {
+	0
+}
+
+/// Applies per-socket `SIGPIPE` suppression to a freshly created or accepted stream socket on
+/// platforms which lack `MSG_NOSIGNAL` but have `SO_NOSIGPIPE`. A no-op elsewhere.
+pub(super) fn prepare_stream(fd: BorrowedFd<'_>) -> io::Result<()> {
+	#[cfg(any(
+		target_os = "macos",
+		target_os = "ios",
+		target_os = "tvos",
+		target_os = "watchos",
+	))]
+	if SUPPRESS_SIGPIPE.load(Relaxed) {
+		unsafe { setsockopt(fd, libc::SOL_SOCKET, libc::SO_NOSIGPIPE, c_int::from(true))? };
+	}
+	let _ = fd;
+	Ok(())
+}
+
+/// Sends data on a connected socket, suppressing `SIGPIPE` if so configured.
+pub(super) fn send(fd: BorrowedFd<'_>, buf: &[u8]) -> io::Result<usize> {
+	let sent = unsafe { libc::send(fd.as_raw_fd(), buf.as_ptr().cast(), buf.len(), send_flags()) };
+	(sent != -1).true_val_or_errno(ssize_to_usize(sent))
+}
+
+/// The largest number of buffers passed to a single `sendmsg` call. Matches `IOV_MAX` on Linux
+/// and the BSDs; excess buffers are left for subsequent writes to deal with.
+const MAX_IOVECS: usize = 1024;
+
+/// Sends data from multiple buffers on a connected socket, suppressing `SIGPIPE` if so
+/// configured.
+#[allow(clippy::as_conversions)]
+pub(super) fn send_vectored(fd: BorrowedFd<'_>, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+	let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+	hdr.msg_iov = bufs.as_ptr().cast_mut().cast();
+	// Both size_t and c_int can hold MAX_IOVECS
+	hdr.msg_iovlen = bufs.len().min(MAX_IOVECS) as _;
+	let sent = unsafe { libc::sendmsg(fd.as_raw_fd(), &hdr, send_flags()) };
+	(sent != -1).true_val_or_errno(ssize_to_usize(sent))
+}
+
+/// Buffer for a control message carrying a single file descriptor or a set of credentials,
+/// aligned for `cmsghdr`.
+#[repr(C)]
+union CmsgBuf {
+	_align: libc::cmsghdr,
+	buf: [u8; 64],
+}
+
+/// Sends a duplicate of `fd` over `sock` as `SCM_RIGHTS` ancillary data. Since stream sockets
+/// cannot carry ancillary data on its own, a single zero byte is sent along with it.
+#[allow(clippy::as_conversions)]
+pub(super) fn send_fd(sock: BorrowedFd<'_>, fd: BorrowedFd<'_>) -> io::Result<()> {
+	let mut byte = [0_u8];
+	let mut iov = libc::iovec {
+		iov_base: byte.as_mut_ptr().cast(),
+		iov_len: byte.len(),
+	};
+	let mut cmsg_buf = CmsgBuf { buf: [0; 64] };
+	let fd_size = mem::size_of::<c_int>() as u32;
+
+	let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+	hdr.msg_iov = &mut iov;
+	hdr.msg_iovlen = 1;
+	hdr.msg_control = unsafe { cmsg_buf.buf.as_mut_ptr().cast() };
+	hdr.msg_controllen = unsafe { libc::CMSG_SPACE(fd_size) } as _;
+	unsafe {
+		// SAFETY: the control buffer is aligned and fits a control message with one descriptor
+		let cmsg = libc::CMSG_FIRSTHDR(&hdr);
+		(*cmsg).cmsg_level = libc::SOL_SOCKET;
+		(*cmsg).cmsg_type = libc::SCM_RIGHTS;
+		(*cmsg).cmsg_len = libc::CMSG_LEN(fd_size) as _;
+		std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<c_int>(), fd.as_raw_fd());
+	}
+	retry_eintr(|| {
+		let sent = unsafe { libc::sendmsg(sock.as_raw_fd(), &hdr, send_flags()) };
+		(sent != -1).true_val_or_errno(())
+	})
+}
+
+/// Receives a file descriptor sent with [`send_fd()`] from `sock`. The resulting descriptor is
+/// not inheritable.
+///
+/// The control buffer has room for more descriptors than the one expected, so that a peer which
+/// sends several of them doesn't get the excess ones silently installed into this process and
+/// leaked. All descriptors that arrive are taken ownership of, and if there is more than one, they
+/// are all closed and an error is returned.
+#[allow(clippy::as_conversions, clippy::arithmetic_side_effects)]
+pub(super) fn recv_fd(sock: BorrowedFd<'_>) -> io::Result<OwnedFd> {
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	const FLAGS: c_int = libc::MSG_CMSG_CLOEXEC;
+	#[cfg(not(any(target_os = "linux", target_os = "android")))]
+	const FLAGS: c_int = 0;
+
+	let mut byte = [0_u8];
+	let mut iov = libc::iovec {
+		iov_base: byte.as_mut_ptr().cast(),
+		iov_len: byte.len(),
+	};
+	let mut cmsg_buf = CmsgBuf { buf: [0; 64] };
+	let fd_size = mem::size_of::<c_int>();
+
+	let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+	hdr.msg_iov = &mut iov;
+	hdr.msg_iovlen = 1;
+	hdr.msg_control = unsafe { cmsg_buf.buf.as_mut_ptr().cast() };
+	hdr.msg_controllen = unsafe { cmsg_buf.buf.len() } as _;
+	let received = retry_eintr(|| {
+		let received = unsafe { libc::recvmsg(sock.as_raw_fd(), &mut hdr, FLAGS) };
+		(received != -1).true_val_or_errno(received)
+	})?;
+
+	// Take ownership of everything the kernel has installed before looking at anything else, so
+	// that every early return below closes the descriptors.
+	let mut fds = Vec::with_capacity(1);
+	unsafe {
+		// SAFETY: the kernel has filled in a valid control message buffer, if any
+		let data_offset = libc::CMSG_LEN(0) as usize;
+		let mut cmsg = libc::CMSG_FIRSTHDR(&hdr);
+		while !cmsg.is_null() {
+			if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+				let count = ((*cmsg).cmsg_len as usize).saturating_sub(data_offset) / fd_size;
+				let data = libc::CMSG_DATA(cmsg).cast::<c_int>();
+				for i in 0..count {
+					let raw = std::ptr::read_unaligned(data.add(i));
+					// SAFETY: the kernel has just installed this descriptor for us
+					fds.push(OwnedFd::from_raw_fd(raw));
+				}
+			}
+			cmsg = libc::CMSG_NXTHDR(&hdr, cmsg);
+		}
+	}
+
+	if received == 0 {
+		return Err(io::ErrorKind::UnexpectedEof.into());
+	}
+	if hdr.msg_flags & libc::MSG_CTRUNC != 0 || fds.len() > 1 {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"more file descriptors were received than expected",
+		));
+	}
+	let fd = fds.pop().ok_or_else(|| {
+		io::Error::new(
+			io::ErrorKind::InvalidData,
+			"no file descriptor was received",
+		)
+	})?;
+	#[cfg(not(any(target_os = "linux", target_os = "android")))]
+	set_cloexec(fd.as_fd())?;
+	Ok(fd)
+}
+
+/// Sends data along with `cred` as `SCM_CREDENTIALS` ancillary data. The kernel checks the
+/// credentials against the privileges of the calling process.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[allow(clippy::as_conversions)]
+pub(super) fn send_with_credentials(
+	sock: BorrowedFd<'_>,
+	buf: &[u8],
+	cred: libc::ucred,
+) -> io::Result<usize> {
+	let mut iov = libc::iovec {
+		iov_base: buf.as_ptr().cast_mut().cast(),
+		iov_len: buf.len(),
+	};
+	let mut cmsg_buf = CmsgBuf { buf: [0; 64] };
+	let cred_size = mem::size_of::<libc::ucred>() as u32;
+
+	let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+	hdr.msg_iov = &mut iov;
+	hdr.msg_iovlen = 1;
+	hdr.msg_control = unsafe { cmsg_buf.buf.as_mut_ptr().cast() };
+	hdr.msg_controllen = unsafe { libc::CMSG_SPACE(cred_size) } as _;
+	unsafe {
+		// SAFETY: the control buffer is aligned and fits a control message with one ucred
+		let cmsg = libc::CMSG_FIRSTHDR(&hdr);
+		(*cmsg).cmsg_level = libc::SOL_SOCKET;
+		(*cmsg).cmsg_type = libc::SCM_CREDENTIALS;
+		(*cmsg).cmsg_len = libc::CMSG_LEN(cred_size) as _;
+		std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<libc::ucred>(), cred);
+	}
+	retry_eintr(|| {
+		let sent = unsafe { libc::sendmsg(sock.as_raw_fd(), &hdr, send_flags()) };
+		(sent != -1).true_val_or_errno(ssize_to_usize(sent))
+	})
+}
+
+/// Receives data along with the `SCM_CREDENTIALS` ancillary data attached to it, which is only
+/// there if `SO_PASSCRED` is enabled on `sock`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[allow(clippy::as_conversions, clippy::arithmetic_side_effects)]
+pub(super) fn recv_with_credentials(
+	sock: BorrowedFd<'_>,
+	buf: &mut [u8],
+) -> io::Result<(usize, Option<libc::ucred>)> {
+	let mut iov = libc::iovec {
+		iov_base: buf.as_mut_ptr().cast(),
+		iov_len: buf.len(),
+	};
+	let mut cmsg_buf = CmsgBuf { buf: [0; 64] };
+
+	let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+	hdr.msg_iov = &mut iov;
+	hdr.msg_iovlen = 1;
+	hdr.msg_control = unsafe { cmsg_buf.buf.as_mut_ptr().cast() };
+	hdr.msg_controllen = unsafe { cmsg_buf.buf.len() } as _;
+	// Descriptors are never expected, but are closed rather than leaked if they arrive anyway
+	let received = retry_eintr(|| {
+		let received = unsafe { libc::recvmsg(sock.as_raw_fd(), &mut hdr, libc::MSG_CMSG_CLOEXEC) };
+		(received != -1).true_val_or_errno(ssize_to_usize(received))
+	})?;
+
+	let mut cred = None;
+	unsafe {
+		// SAFETY: the kernel has filled in a valid control message buffer, if any
+		let mut cmsg = libc::CMSG_FIRSTHDR(&hdr);
+		while !cmsg.is_null() {
+			match ((*cmsg).cmsg_level, (*cmsg).cmsg_type) {
+				(libc::SOL_SOCKET, libc::SCM_CREDENTIALS) => {
+					cred = Some(std::ptr::read_unaligned(
+						libc::CMSG_DATA(cmsg).cast::<libc::ucred>(),
+					));
+				}
+				(libc::SOL_SOCKET, libc::SCM_RIGHTS) => {
+					let data_offset = libc::CMSG_LEN(0) as usize;
+					let len = ((*cmsg).cmsg_len as usize).saturating_sub(data_offset);
+					let data = libc::CMSG_DATA(cmsg).cast::<c_int>();
+					for i in 0..len / mem::size_of::<c_int>() {
+						// SAFETY: the kernel has just installed this descriptor for us
+						drop(OwnedFd::from_raw_fd(std::ptr::read_unaligned(data.add(i))));
+					}
+				}
+				_ => {}
+			}
+			cmsg = libc::CMSG_NXTHDR(&hdr, cmsg);
+		}
+	}
+	// Descriptors are what normally overflows the buffer, and could have displaced the credentials
+	if hdr.msg_flags & libc::MSG_CTRUNC != 0 {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"ancillary data was truncated",
+		));
+	}
+	Ok((received, cred))
+}
+
+/// Sends data along with any number of file descriptors as `SCM_RIGHTS` ancillary data, which are
+/// attached to the first byte sent. With no descriptors, this is a plain send.
+#[allow(clippy::as_conversions)]
+pub(super) fn send_with_fds(
+	sock: BorrowedFd<'_>,
+	buf: &[u8],
+	fds: &[BorrowedFd<'_>],
+) -> io::Result<usize> {
+	if fds.is_empty() {
+		return retry_eintr(|| send(sock, buf));
+	}
+	let fds_size = u32::try_from(mem::size_of_val(fds))
+		.map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "too many file descriptors"))?;
+	let mut iov = libc::iovec {
+		iov_base: buf.as_ptr().cast_mut().cast(),
+		iov_len: buf.len(),
+	};
+	let mut cmsg_buf = cmsg_buffer(unsafe { libc::CMSG_SPACE(fds_size) } as usize);
+
+	let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+	hdr.msg_iov = &mut iov;
+	hdr.msg_iovlen = 1;
+	hdr.msg_control = cmsg_buf.as_mut_ptr().cast();
+	hdr.msg_controllen = unsafe { libc::CMSG_SPACE(fds_size) } as _;
+	unsafe {
+		// SAFETY: the control buffer is aligned and fits a control message with all descriptors
+		let cmsg = libc::CMSG_FIRSTHDR(&hdr);
+		(*cmsg).cmsg_level = libc::SOL_SOCKET;
+		(*cmsg).cmsg_type = libc::SCM_RIGHTS;
+		(*cmsg).cmsg_len = libc::CMSG_LEN(fds_size) as _;
+		let data = libc::CMSG_DATA(cmsg).cast::<c_int>();
+		for (i, fd) in fds.iter().enumerate() {
+			std::ptr::write_unaligned(data.add(i), fd.as_raw_fd());
+		}
+	}
+	retry_eintr(|| {
+		let sent = unsafe { libc::sendmsg(sock.as_raw_fd(), &hdr, send_flags()) };
+		(sent != -1).true_val_or_errno(ssize_to_usize(sent))
+	})
+}
+
+/// Receives data along with up to `max_fds` file descriptors sent as `SCM_RIGHTS` ancillary data.
+/// The resulting descriptors are not inheritable.
+///
+/// Fails with an error of kind [`InvalidData`](io::ErrorKind::InvalidData) if more descriptors
+/// arrive than there is room for, in which case the kernel has already discarded the excess ones.
+pub(super) fn recv_with_fds(
+	sock: BorrowedFd<'_>,
+	buf: &mut [u8],
+	max_fds: usize,
+) -> io::Result<(usize, Vec<OwnedFd>)> {
+	let recv = recv_ancillary(sock, buf, max_fds, false)?;
+	if recv.ancillary_truncated {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"more file descriptors were received than allowed",
+		));
+	}
+	Ok((recv.received, recv.fds))
+}
+
+/// Credentials received as `SCM_CREDENTIALS` ancillary data, where supported.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) type RecvCred = Option<libc::ucred>;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub(super) type RecvCred = ();
+
+/// The result of [`recv_ancillary()`].
+pub(super) struct AncillaryRecv {
+	pub received: usize,
+	pub fds: Vec<OwnedFd>,
+	#[cfg_attr(not(any(target_os = "linux", target_os = "android")), allow(dead_code))]
+	pub cred: RecvCred,
+	/// Whether some of the ancillary data was lost, either because the kernel truncated it
+	/// (`MSG_CTRUNC`) or because more than `max_fds` descriptors arrived, the excess ones having
+	/// been closed.
+	pub ancillary_truncated: bool,
+}
+
+/// Receives data along with at most `max_fds` file descriptors and, where supported, the
+/// credentials attached to it, optionally leaving the data in the receive queue (`MSG_PEEK`).
+/// Peeking installs a fresh set of descriptors every time.
+#[allow(clippy::as_conversions, clippy::arithmetic_side_effects)]
+pub(super) fn recv_ancillary(
+	sock: BorrowedFd<'_>,
+	buf: &mut [u8],
+	max_fds: usize,
+	peek: bool,
+) -> io::Result<AncillaryRecv> {
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	const FLAGS: c_int = libc::MSG_CMSG_CLOEXEC;
+	#[cfg(not(any(target_os = "linux", target_os = "android")))]
+	const FLAGS: c_int = 0;
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	let cred_space = unsafe { libc::CMSG_SPACE(mem::size_of::<libc::ucred>() as u32) } as usize;
+	#[cfg(not(any(target_os = "linux", target_os = "android")))]
+	let cred_space = 0;
+
+	let fd_size = mem::size_of::<c_int>();
+	let fds_size = u32::try_from(max_fds.saturating_mul(fd_size)).unwrap_or(u32::MAX);
+	let space = (unsafe { libc::CMSG_SPACE(fds_size) } as usize).saturating_add(cred_space);
+	let mut iov = libc::iovec {
+		iov_base: buf.as_mut_ptr().cast(),
+		iov_len: buf.len(),
+	};
+	let mut cmsg_buf = cmsg_buffer(space);
+
+	let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+	hdr.msg_iov = &mut iov;
+	hdr.msg_iovlen = 1;
+	hdr.msg_control = cmsg_buf.as_mut_ptr().cast();
+	hdr.msg_controllen = space as _;
+	let flags = if peek { FLAGS | libc::MSG_PEEK } else { FLAGS };
+	let received = retry_eintr(|| {
+		let received = unsafe { libc::recvmsg(sock.as_raw_fd(), &mut hdr, flags) };
+		(received != -1).true_val_or_errno(ssize_to_usize(received))
+	})?;
+
+	let mut fds = Vec::new();
+	#[allow(clippy::let_unit_value, unused_mut)]
+	let mut cred = RecvCred::default();
+	unsafe {
+		// SAFETY: the kernel has filled in a valid control message buffer, if any
+		let data_offset = libc::CMSG_LEN(0) as usize;
+		let mut cmsg = libc::CMSG_FIRSTHDR(&hdr);
+		while !cmsg.is_null() {
+			match ((*cmsg).cmsg_level, (*cmsg).cmsg_type) {
+				(libc::SOL_SOCKET, libc::SCM_RIGHTS) => {
+					let count = ((*cmsg).cmsg_len as usize).saturating_sub(data_offset) / fd_size;
+					let data = libc::CMSG_DATA(cmsg).cast::<c_int>();
+					for i in 0..count {
+						let raw = std::ptr::read_unaligned(data.add(i));
+						// SAFETY: the kernel has just installed this descriptor for us
+						fds.push(OwnedFd::from_raw_fd(raw));
+					}
+				}
+				#[cfg(any(target_os = "linux", target_os = "android"))]
+				(libc::SOL_SOCKET, libc::SCM_CREDENTIALS) => {
+					cred = Some(std::ptr::read_unaligned(
+						libc::CMSG_DATA(cmsg).cast::<libc::ucred>(),
+					));
+				}
+				_ => {}
+			}
+			cmsg = libc::CMSG_NXTHDR(&hdr, cmsg);
+		}
+	}
+	// The room left for credentials may fit a few more descriptors than were asked for
+	let ancillary_truncated = hdr.msg_flags & libc::MSG_CTRUNC != 0 || fds.len() > max_fds;
+	fds.truncate(max_fds);
+	#[cfg(not(any(target_os = "linux", target_os = "android")))]
+	for fd in &fds {
+		set_cloexec(fd.as_fd())?;
+	}
+	Ok(AncillaryRecv {
+		received,
+		fds,
+		cred,
+		ancillary_truncated,
+	})
+}
+
+/// Allocates a zeroed control message buffer of at least `len` bytes, aligned for `cmsghdr`.
+#[allow(clippy::arithmetic_side_effects)] // size_of is never 0
+fn cmsg_buffer(len: usize) -> Vec<libc::cmsghdr> {
+	let elem = mem::size_of::<libc::cmsghdr>();
+	vec![unsafe { zeroed() }; len.div_ceil(elem)]
+}
+
+#[allow(clippy::as_conversions)]
+fn ssize_to_usize(ssz: isize) -> usize {
+	ssz as usize
+}
+
+fn offset_to_off_t(offset: u64) -> io::Result<libc::off_t> {
+	libc::off_t::try_from(offset).map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"file offset does not fit into off_t",
+		)
+	})
+}
+
+/// Runs `f`, which makes a system call that has no equivalent of `MSG_NOSIGNAL`, with `SIGPIPE`
+/// blocked in the calling thread if suppression is enabled. If `f` fails with `EPIPE`, the
+/// `SIGPIPE` which it raised is taken off the pending signals before the signal mask is restored.
+///
+/// A `SIGPIPE` which was already pending beforehand is left alone. One which is sent to the
+/// process by someone else while `f` runs can still get discarded along with ours, since the two
+/// are indistinguishable.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+fn without_sigpipe<T>(f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+	if !SUPPRESS_SIGPIPE.load(Relaxed) {
+		return f();
+	}
+	let mut set = unsafe { zeroed::<libc::sigset_t>() };
+	let mut pending = unsafe { zeroed::<libc::sigset_t>() };
+	let mut old = unsafe { zeroed::<libc::sigset_t>() };
+	let was_pending = unsafe {
+		libc::sigemptyset(&mut set);
+		libc::sigaddset(&mut set, libc::SIGPIPE);
+		libc::sigpending(&mut pending) == 0 && libc::sigismember(&pending, libc::SIGPIPE) == 1
+	};
+	let e = unsafe { libc::pthread_sigmask(libc::SIG_BLOCK, &set, &mut old) };
+	if e != 0 {
+		return Err(io::Error::from_raw_os_error(e));
+	}
+	let rslt = f();
+	if !was_pending
+		&& rslt
+			.as_ref()
+			.is_err_and(|e| e.raw_os_error() == Some(libc::EPIPE))
+	{
+		let timeout = unsafe { zeroed::<libc::timespec>() };
+		while unsafe { libc::sigtimedwait(&set, std::ptr::null_mut(), &timeout) } == -1
+			&& io::Error::last_os_error().kind() == io::ErrorKind::Interrupted
+		{}
+	}
+	unsafe { libc::pthread_sigmask(libc::SIG_SETMASK, &old, std::ptr::null_mut()) };
+	rslt
+}
+
+/// Sends up to `len` bytes of `file`, starting at `offset`, to `sock` without going through a
+/// userspace buffer where the OS allows. The file position of `file` is not used or modified.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn sendfile(
+	sock: BorrowedFd<'_>,
+	file: BorrowedFd<'_>,
+	offset: u64,
+	len: usize,
+) -> io::Result<usize> {
+	let mut offset = offset_to_off_t(offset)?;
+	without_sigpipe(|| {
+		let sent = unsafe { libc::sendfile(sock.as_raw_fd(), file.as_raw_fd(), &mut offset, len) };
+		(sent != -1).true_val_or_errno(ssize_to_usize(sent))
+	})
+}
+
+/// Sends up to `len` bytes of `file`, starting at `offset`, to `sock` without going through a
+/// userspace buffer where the OS allows. The file position of `file` is not used or modified.
+#[cfg(target_os = "freebsd")]
+pub(super) fn sendfile(
+	sock: BorrowedFd<'_>,
+	file: BorrowedFd<'_>,
+	offset: u64,
+	len: usize,
+) -> io::Result<usize> {
+	let offset = offset_to_off_t(offset)?;
+	without_sigpipe(|| {
+		let mut sbytes: libc::off_t = 0;
+		let success = unsafe {
+			libc::sendfile(
+				file.as_raw_fd(),
+				sock.as_raw_fd(),
+				offset,
+				len,
+				std::ptr::null_mut(),
+				&mut sbytes,
+				0,
+			) != -1
+		};
+		// On nonblocking sockets, FreeBSD reports partial transfers as EAGAIN
+		if success || sbytes > 0 {
+			Ok(usize::try_from(sbytes).unwrap_or(len))
+		} else {
+			Err(io::Error::last_os_error())
+		}
+	})
+}
+
+/// Sends up to `len` bytes of `file`, starting at `offset`, to `sock` without going through a
+/// userspace buffer where the OS allows. The file position of `file` is not used or modified.
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+pub(super) fn sendfile(
+	sock: BorrowedFd<'_>,
+	file: BorrowedFd<'_>,
+	offset: u64,
+	len: usize,
+) -> io::Result<usize> {
+	let offset = offset_to_off_t(offset)?;
+	let mut buf = [0_u8; 8192];
+	let to_read = len.min(buf.len());
+	let read = unsafe { libc::pread(file.as_raw_fd(), buf.as_mut_ptr().cast(), to_read, offset) };
+	let read = (read != -1).true_val_or_errno(ssize_to_usize(read))?;
+	send(sock, buf.get(..read).unwrap_or_default())
+}
@@ -15,14 +15,36 @@
 //! known path which works like a pipe and nothing else.
 //!
 //! ## Usage
-//! The [`create_fifo()`] function serves for a FIFO file creation. Opening FIFO files works via the
-//! standard [`File`](std::fs::File)s, opened either only for sending or only for receiving.
-//! Deletion works the same way as with any regular file, via
-//! [`remove_file()`](std::fs::remove_file).
+//! The [`create_fifo()`] function serves for a FIFO file creation, and [`Fifo::create()`] does the
+//! same while also remembering the path so that the ends of the FIFO can be opened through it.
+//! Opening FIFO files works either via the standard [`File`](std::fs::File)s, opened either only
+//! for sending or only for receiving, or via [`Recver`] and [`Sender`], which take care of the
+//! quirks of opening FIFOs in nonblocking mode. Deletion works the same way as with any regular
+//! file, via [`remove_file()`](std::fs::remove_file).
+//!
+//! Opening the receiving end of a FIFO normally blocks until a sender appears and vice versa. In
+//! nonblocking mode, the receiving end opens immediately, but the sending end fails with `ENXIO`
+//! if nobody has the receiving end open. [`Fifo::open_sender_nonblocking()`] turns that into an
+//! error of kind [`WouldBlock`](io::ErrorKind::WouldBlock), and [`Fifo::open_sender_timeout()`]
+//! retries until a receiver appears or the timeout runs out.
+//!
+//! With the `tokio` feature, the `tokio` submodule provides asynchronous counterparts of
+//! [`Recver`] and [`Sender`].
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
 
-use super::unixprelude::*;
-use crate::OrErrno;
-use std::{ffi::CString, io, path::Path};
+use super::{c_wrappers, unixprelude::*, FdOps};
+use crate::{FdOrErrno, OrErrno};
+use std::{
+	ffi::CString,
+	fmt::{self, Debug, Formatter},
+	io,
+	path::{Path, PathBuf},
+	thread,
+	time::{Duration, Instant},
+};
 
 /// Creates a FIFO file at the specified path with the specified permissions.
 ///
@@ -42,3 +64,183 @@ fn _create_fifo(path: &Path, mode: mode_t) -> io::Result<()> {
 	unsafe { libc::mkfifo(path.as_bytes_with_nul().as_ptr().cast(), mode) != -1 }
 		.true_val_or_errno(())
 }
+
+/// How often [`Fifo::open_sender_timeout()`] checks whether a receiver has appeared.
+const SENDER_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+fn open_fifo(path: &Path, flags: c_int) -> io::Result<OwnedFd> {
+	let path = CString::new(path.as_os_str().as_bytes())?;
+	let fd = c_wrappers::retry_eintr(|| {
+		unsafe { libc::open(path.as_ptr(), flags | libc::O_CLOEXEC) }.fd_or_errno()
+	})?;
+	// SAFETY: we just opened this descriptor
+	Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+/// Opens the sending end without blocking, turning the `ENXIO` which signifies the absence of a
+/// receiver into `WouldBlock`.
+fn open_sender_nonblocking(path: &Path) -> io::Result<OwnedFd> {
+	open_fifo(path, libc::O_WRONLY | libc::O_NONBLOCK).map_err(|e| {
+		if e.raw_os_error() == Some(libc::ENXIO) {
+			io::ErrorKind::WouldBlock.into()
+		} else {
+			e
+		}
+	})
+}
+
+/// The path of a FIFO file, used to open its ends.
+///
+/// Dropping this does not delete the FIFO file; use [`remove()`](Self::remove) for that.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fifo(PathBuf);
+impl Fifo {
+	/// Creates a FIFO file at the specified path with the specified permissions. See
+	/// [`create_fifo()`] for more.
+	pub fn create(path: impl Into<PathBuf>, mode: mode_t) -> io::Result<Self> {
+		let path = path.into();
+		_create_fifo(&path, mode)?;
+		Ok(Self(path))
+	}
+	/// Refers to an existing FIFO file at the specified path. Whether the file exists and is a FIFO
+	/// is not checked until one of its ends is opened.
+	#[inline]
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self(path.into())
+	}
+	/// Returns the path of the FIFO file.
+	#[inline]
+	pub fn path(&self) -> &Path {
+		&self.0
+	}
+	/// Deletes the FIFO file. Ends of it which are already open remain usable.
+	///
+	/// ## System calls
+	/// - `unlink`
+	#[inline]
+	pub fn remove(self) -> io::Result<()> {
+		std::fs::remove_file(&self.0)
+	}
+
+	/// Opens the receiving end of the FIFO, blocking until a sender opens it too.
+	///
+	/// ## System calls
+	/// - `open`
+	pub fn open_recver(&self) -> io::Result<Recver> {
+		open_fifo(&self.0, libc::O_RDONLY).map(|fd| Recver(FdOps(fd)))
+	}
+	/// Opens the receiving end of the FIFO without waiting for a sender. The resulting receiver is
+	/// in nonblocking mode.
+	///
+	/// Until a sender opens the FIFO, reading from the receiver reports end of file.
+	///
+	/// ## System calls
+	/// - `open`
+	pub fn open_recver_nonblocking(&self) -> io::Result<Recver> {
+		open_fifo(&self.0, libc::O_RDONLY | libc::O_NONBLOCK).map(|fd| Recver(FdOps(fd)))
+	}
+	/// Opens the sending end of the FIFO, blocking until a receiver opens it too.
+	///
+	/// ## System calls
+	/// - `open`
+	pub fn open_sender(&self) -> io::Result<Sender> {
+		open_fifo(&self.0, libc::O_WRONLY).map(|fd| Sender(FdOps(fd)))
+	}
+	/// Opens the sending end of the FIFO if a receiver has it open, failing with an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) otherwise. The resulting sender is in nonblocking
+	/// mode.
+	///
+	/// ## System calls
+	/// - `open`
+	pub fn open_sender_nonblocking(&self) -> io::Result<Sender> {
+		open_sender_nonblocking(&self.0).map(|fd| Sender(FdOps(fd)))
+	}
+	/// Opens the sending end of the FIFO, waiting for at most `timeout` for a receiver to open it
+	/// and failing with an error of kind [`TimedOut`](io::ErrorKind::TimedOut) if none does. The
+	/// resulting sender is in blocking mode.
+	///
+	/// Unlike [`open_sender()`](Self::open_sender), this polls for the receiver rather than waiting
+	/// for it in the kernel.
+	///
+	/// ## System calls
+	/// - `open`
+	/// - `fcntl`
+	pub fn open_sender_timeout(&self, timeout: Duration) -> io::Result<Sender> {
+		let deadline = Instant::now().checked_add(timeout);
+		loop {
+			match open_sender_nonblocking(&self.0) {
+				Ok(fd) => {
+					c_wrappers::set_nonblocking(fd.as_fd(), false)?;
+					return Ok(Sender(FdOps(fd)));
+				}
+				Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+				Err(e) => return Err(e),
+			}
+			let remaining = match deadline {
+				Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+				None => SENDER_RETRY_INTERVAL,
+			};
+			if remaining.is_zero() {
+				return Err(io::ErrorKind::TimedOut.into());
+			}
+			thread::sleep(remaining.min(SENDER_RETRY_INTERVAL));
+		}
+	}
+}
+impl From<PathBuf> for Fifo {
+	#[inline]
+	fn from(path: PathBuf) -> Self {
+		Self(path)
+	}
+}
+
+/// The receiving end of a FIFO file, opened via [`Fifo`].
+pub struct Recver(FdOps);
+impl Recver {
+	/// Enables or disables nonblocking mode.
+	#[inline]
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		c_wrappers::set_nonblocking(self.0 .0.as_fd(), nonblocking)
+	}
+}
+impl Debug for Recver {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Recver")
+			.field("fd", &self.0 .0.as_raw_fd())
+			.finish()
+	}
+}
+multimacro! {
+	Recver,
+	forward_rbv(FdOps, &),
+	forward_sync_ref_read,
+	forward_try_clone,
+	forward_handle,
+	derive_sync_mut_read,
+	derive_raw,
+}
+
+/// The sending end of a FIFO file, opened via [`Fifo`].
+pub struct Sender(FdOps);
+impl Sender {
+	/// Enables or disables nonblocking mode.
+	#[inline]
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		c_wrappers::set_nonblocking(self.0 .0.as_fd(), nonblocking)
+	}
+}
+impl Debug for Sender {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Sender")
+			.field("fd", &self.0 .0.as_raw_fd())
+			.finish()
+	}
+}
+multimacro! {
+	Sender,
+	forward_rbv(FdOps, &),
+	forward_sync_ref_write,
+	forward_try_clone,
+	forward_handle,
+	derive_sync_mut_write,
+	derive_raw,
+}
@@ -0,0 +1,82 @@
+//! Tokio-based asynchronous FIFO file ends.
+
+use super::{
+	open_fifo, open_sender_nonblocking, Fifo, Recver as SyncRecver, Sender as SyncSender,
+	SENDER_RETRY_INTERVAL,
+};
+use std::io;
+use tokio::{
+	net::unix::pipe::{Receiver as TokioRecver, Sender as TokioSender},
+	time::sleep,
+};
+
+/// Tokio-based asynchronous receiving end of a FIFO file.
+pub struct Recver(TokioRecver);
+impl Recver {
+	/// Opens the receiving end of the FIFO without waiting for a sender.
+	///
+	/// Until a sender opens the FIFO, reading reports end of file.
+	pub fn open(fifo: &Fifo) -> io::Result<Self> {
+		let fd = open_fifo(fifo.path(), libc::O_RDONLY | libc::O_NONBLOCK)?;
+		TokioRecver::from_owned_fd(fd).map(Self)
+	}
+}
+/// Puts the receiver in nonblocking mode and registers it with the Tokio reactor.
+impl TryFrom<SyncRecver> for Recver {
+	type Error = io::Error;
+	#[inline]
+	fn try_from(rx: SyncRecver) -> io::Result<Self> {
+		TokioRecver::from_owned_fd(rx.0 .0).map(Self)
+	}
+}
+multimacro! {
+	Recver,
+	pinproj_for_unpin(TokioRecver),
+	forward_tokio_read,
+	forward_as_handle(unix),
+	forward_debug("Recver"),
+	derive_asraw(unix),
+}
+
+/// Tokio-based asynchronous sending end of a FIFO file.
+pub struct Sender(TokioSender);
+impl Sender {
+	/// Opens the sending end of the FIFO, asynchronously waiting for a receiver to open it.
+	///
+	/// The FIFO is polled for a receiver periodically, since the operating system provides no way
+	/// of waiting for one without blocking. This requires the time driver of the Tokio runtime to be
+	/// enabled.
+	pub async fn open(fifo: &Fifo) -> io::Result<Self> {
+		loop {
+			match Self::try_open(fifo) {
+				Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+					sleep(SENDER_RETRY_INTERVAL).await
+				}
+				els => return els,
+			}
+		}
+	}
+	/// Opens the sending end of the FIFO if a receiver has it open, failing with an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) otherwise.
+	pub fn try_open(fifo: &Fifo) -> io::Result<Self> {
+		let fd = open_sender_nonblocking(fifo.path())?;
+		TokioSender::from_owned_fd(fd).map(Self)
+	}
+}
+/// Puts the sender in nonblocking mode and registers it with the Tokio reactor.
+impl TryFrom<SyncSender> for Sender {
+	type Error = io::Error;
+	#[inline]
+	fn try_from(tx: SyncSender) -> io::Result<Self> {
+		TokioSender::from_owned_fd(tx.0 .0).map(Self)
+	}
+}
+multimacro! {
+	Sender,
+	pinproj_for_unpin(TokioSender),
+	forward_rbv(TokioSender, &),
+	forward_tokio_write,
+	forward_as_handle(unix),
+	forward_debug("Sender"),
+	derive_asraw(unix),
+}
@@ -1,23 +1,103 @@
 //! Unix-specific local socket features.
 
+mod conv;
+mod copy;
 pub(crate) mod dispatch_sync;
 #[cfg(feature = "tokio")]
 pub(crate) mod dispatch_tokio;
+mod handoff;
+mod listener_set;
 pub(crate) mod name_type;
+mod owned_socket_file;
+mod shared_listener;
 
-pub use name_type::*;
+pub use {
+	copy::*, handoff::*, listener_set::*, name_type::*, owned_socket_file::*, shared_listener::*,
+};
 
 use crate::{local_socket::ListenerOptions, Sealed};
+use std::{
+	fmt::{self, Debug, Formatter},
+	io,
+	os::fd::BorrowedFd,
+	sync::Arc,
+};
 
 /// Unix-specific [listener options](ListenerOptions).
 #[allow(private_bounds)]
 pub trait ListenerOptionsExt: Sized + Sealed {
 	/// Sets the file mode (Unix permissions) to be applied to the socket file.
 	///
-	/// Note that this *may* or *may not* obey `umask`. It is recommended to set `umask` to 666₈
-	/// just before `.create()`.
+	/// The mode is applied before the socket starts listening, so there is no window during which
+	/// the socket can be connected to with less restrictive permissions. Where the platform
+	/// supports it, this is done with `fchmod` on the socket before binding; elsewhere, the
+	/// process-wide `umask` is temporarily changed for the duration of the bind, which may race
+	/// with other threads creating files at the same time.
+	///
+	/// Socket files cannot be marked executable – setting any of the executable bits will result
+	/// in an error of kind [`InvalidInput`](std::io::ErrorKind::InvalidInput) upon creation.
+	///
+	/// The default value is 666₈.
 	#[must_use = builder_must_use!()]
 	fn mode(self, mode: libc::mode_t) -> Self;
+
+	/// Sets whether a leftover socket file which no server is listening on should be replaced.
+	///
+	/// If binding fails because the path is already occupied, the listener will attempt to connect
+	/// to the existing socket. If that connection is refused, the socket is considered dead – its
+	/// file is unlinked and the bind is retried once. Files that aren't sockets and sockets that
+	/// accept the probe connection are never touched, and the original "address in use" error is
+	/// returned instead.
+	///
+	/// This has no effect on names that don't resolve to a filesystem path. Note that the probe
+	/// and the removal are not atomic with respect to other processes attempting the same thing.
+	///
+	/// This is disabled by default.
+	#[must_use = builder_must_use!()]
+	fn replace_dead_socket(self, replace: bool) -> Self;
+
+	/// Sets the maximum length of the queue of pending connections, i.e. the `backlog` argument
+	/// of `listen(2)`.
+	///
+	/// The OS silently clamps the value to a system-wide limit (`somaxconn` on Linux, for
+	/// instance), and may round it or add a small amount of slack. Nonpositive values are
+	/// interpreted differently by different systems.
+	///
+	/// By default, the same value as in the standard library is used, which is the maximum
+	/// permitted by the system on most platforms.
+	#[must_use = builder_must_use!()]
+	fn backlog(self, backlog: libc::c_int) -> Self;
+
+	/// Sets a function which is called with the socket after it is created and before it is bound
+	/// and starts listening, for setting socket options which the other options don't cover.
+	///
+	/// An error returned by the function aborts the creation of the listener and is returned as
+	/// is. The function may be called more than once if
+	/// [dead socket replacement](Self::replace_dead_socket) kicks in, each time with a new socket.
+	/// The socket is already in the right nonblocking mode and has had its [mode](Self::mode)
+	/// applied where `fchmod` is supported.
+	///
+	/// # Example
+	/// ```no_run
+	/// use interprocess::{
+	/// 	local_socket::{prelude::*, GenericFilePath, ListenerOptions},
+	/// 	os::unix::local_socket::ListenerOptionsExt,
+	/// };
+	///
+	/// let listener = ListenerOptions::new()
+	/// 	.name("/tmp/example.sock".to_fs_name::<GenericFilePath>()?)
+	/// 	.mode(0o600)
+	/// 	.configure_socket(|fd| {
+	/// 		// e.g. setsockopt() on fd
+	/// 		Ok(())
+	/// 	})
+	/// 	.create_sync()?;
+	/// # std::io::Result::<()>::Ok(())
+	/// ```
+	#[must_use = builder_must_use!()]
+	fn configure_socket<F>(self, configure: F) -> Self
+	where
+		F: Fn(BorrowedFd<'_>) -> io::Result<()> + Send + Sync + 'static;
 }
 
 impl ListenerOptionsExt for ListenerOptions<'_> {
@@ -26,4 +106,33 @@ impl ListenerOptionsExt for ListenerOptions<'_> {
 		self.mode = mode;
 		self
 	}
+	#[inline(always)]
+	fn replace_dead_socket(mut self, replace: bool) -> Self {
+		self.replace_dead_socket = replace;
+		self
+	}
+	#[inline(always)]
+	fn backlog(mut self, backlog: libc::c_int) -> Self {
+		self.backlog = Some(backlog);
+		self
+	}
+	#[inline]
+	fn configure_socket<F>(mut self, configure: F) -> Self
+	where
+		F: Fn(BorrowedFd<'_>) -> io::Result<()> + Send + Sync + 'static,
+	{
+		self.configure_socket = Some(SocketHook(Arc::new(configure)));
+		self
+	}
+}
+
+/// Function set with [`ListenerOptionsExt::configure_socket()`].
+#[derive(Clone)]
+pub(crate) struct SocketHook(
+	pub(crate) Arc<dyn Fn(BorrowedFd<'_>) -> io::Result<()> + Send + Sync>,
+);
+impl Debug for SocketHook {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str("SocketHook")
+	}
 }
@@ -0,0 +1,105 @@
+//! Conversions between the local socket enums and the Unix domain socket types of the standard
+//! library and Tokio.
+//!
+//! These go through the conversions of the
+//! [Unix domain socket backend](crate::os::unix::uds_local_socket) and uphold the same invariants:
+//! listeners created by other means don't reclaim their name, and sockets handed to Tokio are put
+//! in nonblocking mode first.
+
+use crate::local_socket::{Listener, Stream};
+use std::os::unix::net::{UnixListener, UnixStream};
+
+impl From<UnixStream> for Stream {
+	#[inline]
+	fn from(s: UnixStream) -> Self {
+		Self::UdSocket(s.into())
+	}
+}
+impl From<Stream> for UnixStream {
+	#[inline]
+	fn from(s: Stream) -> Self {
+		let Stream::UdSocket(s) = s;
+		s.into()
+	}
+}
+impl From<UnixListener> for Listener {
+	#[inline]
+	fn from(l: UnixListener) -> Self {
+		Self::UdSocket(l.into())
+	}
+}
+impl From<Listener> for UnixListener {
+	#[inline]
+	fn from(l: Listener) -> Self {
+		let Listener::UdSocket(l) = l;
+		l.into()
+	}
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+	use crate::local_socket::tokio::{Listener, Stream};
+	use std::{
+		io,
+		os::unix::net::{UnixListener as SyncUnixListener, UnixStream as SyncUnixStream},
+	};
+	use tokio::net::{UnixListener, UnixStream};
+
+	impl From<UnixStream> for Stream {
+		#[inline]
+		fn from(s: UnixStream) -> Self {
+			Self::UdSocket(s.into())
+		}
+	}
+	impl From<Stream> for UnixStream {
+		#[inline]
+		fn from(s: Stream) -> Self {
+			let Stream::UdSocket(s) = s;
+			s.into()
+		}
+	}
+	impl TryFrom<SyncUnixStream> for Stream {
+		type Error = io::Error;
+		#[inline]
+		fn try_from(s: SyncUnixStream) -> io::Result<Self> {
+			s.try_into().map(Self::UdSocket)
+		}
+	}
+	impl TryFrom<Stream> for SyncUnixStream {
+		type Error = io::Error;
+		#[inline]
+		fn try_from(s: Stream) -> io::Result<Self> {
+			let Stream::UdSocket(s) = s;
+			s.try_into()
+		}
+	}
+
+	impl From<UnixListener> for Listener {
+		#[inline]
+		fn from(l: UnixListener) -> Self {
+			Self::UdSocket(l.into())
+		}
+	}
+	impl From<Listener> for UnixListener {
+		#[inline]
+		fn from(l: Listener) -> Self {
+			let Listener::UdSocket(l) = l;
+			l.into()
+		}
+	}
+	impl TryFrom<SyncUnixListener> for Listener {
+		type Error = io::Error;
+		#[inline]
+		fn try_from(l: SyncUnixListener) -> io::Result<Self> {
+			l.try_into().map(Self::UdSocket)
+		}
+	}
+	impl TryFrom<Listener> for SyncUnixListener {
+		type Error = io::Error;
+		#[inline]
+		fn try_from(l: Listener) -> io::Result<Self> {
+			let Listener::UdSocket(l) = l;
+			l.try_into()
+		}
+	}
+}
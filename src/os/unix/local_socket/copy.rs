@@ -0,0 +1,106 @@
+use crate::{local_socket::Stream, os::unix::c_wrappers};
+use std::{
+	io,
+	os::fd::{AsFd, BorrowedFd},
+};
+
+/// Limits on what [`copy_ancillary()`] relays in one go.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CopyLimits {
+	/// The size of the buffer that data is relayed through, which bounds how many bytes are moved
+	/// per receive.
+	///
+	/// The default value is 65536.
+	pub buffer_size: usize,
+	/// The maximum number of file descriptors which may arrive with a single receive. If the
+	/// reader is sent more than that at once, the copy fails with an error of kind
+	/// [`InvalidData`](io::ErrorKind::InvalidData), as the kernel discards the excess descriptors.
+	///
+	/// The default value is 253, which is the most Linux allows to be sent in one message.
+	pub max_fds: usize,
+}
+impl CopyLimits {
+	/// Creates limits with default values. Identical to `Default::default()`.
+	pub const fn new() -> Self {
+		Self {
+			buffer_size: 1 << 16,
+			max_fds: 253,
+		}
+	}
+	builder_setters! {
+		/// Sets the size of the buffer that data is relayed through.
+		///
+		/// See the [associated field](#structfield.buffer_size) for more.
+		buffer_size: usize,
+		/// Sets the maximum number of file descriptors per receive.
+		///
+		/// See the [associated field](#structfield.max_fds) for more.
+		max_fds: usize,
+	}
+}
+impl Default for CopyLimits {
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Amounts relayed by [`copy_ancillary()`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Copied {
+	/// The number of bytes relayed.
+	pub bytes: u64,
+	/// The number of file descriptors relayed.
+	pub fds: u64,
+}
+
+/// Relays data from `reader` to `writer` until `reader` reaches end of file, passing on the file
+/// descriptors sent to `reader` along with it.
+///
+/// This is [`io::copy()`] for proxies which sit between processes that send each other file
+/// descriptors, such as with [`HandleTransfer`](crate::handle_transfer::HandleTransfer):
+/// descriptors are received as `SCM_RIGHTS` ancillary data and sent on attached to the last byte
+/// of the data they arrived with, so that the receiving end gets them once it reads up to the end
+/// of what the sending end sent them with, just as it would without the proxy in between. The
+/// proxy's copies of the descriptors are closed once they have been sent.
+///
+/// Both streams must be in blocking mode. `writer` is not shut down once `reader` reaches end of
+/// file; to relay in both directions, run a copy for each direction on threads of their own and
+/// [shut down](Stream::shutdown) the writing side of each stream when its copy returns.
+pub fn copy_ancillary(reader: &Stream, writer: &Stream, limits: CopyLimits) -> io::Result<Copied> {
+	let (Stream::UdSocket(reader), Stream::UdSocket(writer)) = (reader, writer);
+	let (reader, writer) = (reader.as_fd(), writer.as_fd());
+	let mut buf = vec![0; limits.buffer_size.max(1)];
+	let mut copied = Copied::default();
+	loop {
+		let (received, fds) = c_wrappers::recv_with_fds(reader, &mut buf, limits.max_fds)?;
+		if received == 0 {
+			return Ok(copied);
+		}
+		let data = buf.get(..received).unwrap_or_default();
+		// The kernel ends a receive with the data that the descriptors were sent with
+		let (body, last) = data.split_at(received.saturating_sub(1));
+		send_all(writer, body)?;
+		let fds_borrowed = fds.iter().map(AsFd::as_fd).collect::<Vec<BorrowedFd<'_>>>();
+		if c_wrappers::send_with_fds(writer, last, &fds_borrowed)? == 0 {
+			return Err(io::ErrorKind::WriteZero.into());
+		}
+		copied.bytes = copied
+			.bytes
+			.saturating_add(u64::try_from(received).unwrap_or(u64::MAX));
+		copied.fds = copied
+			.fds
+			.saturating_add(u64::try_from(fds.len()).unwrap_or(u64::MAX));
+	}
+}
+
+fn send_all(writer: BorrowedFd<'_>, mut data: &[u8]) -> io::Result<()> {
+	while !data.is_empty() {
+		match c_wrappers::retry_eintr(|| c_wrappers::send(writer, data))? {
+			0 => return Err(io::ErrorKind::WriteZero.into()),
+			n => data = data.get(n..).unwrap_or_default(),
+		}
+	}
+	Ok(())
+}
@@ -1,5 +1,5 @@
 use super::super::uds_local_socket as uds_impl;
-use crate::local_socket::{prelude::*, Listener, ListenerOptions, Name, Stream};
+use crate::local_socket::{prelude::*, Listener, ListenerOptions, Liveness, Name, Stream};
 use std::io;
 
 #[inline]
@@ -13,3 +13,14 @@ pub fn from_options(options: ListenerOptions<'_>) -> io::Result<Listener> {
 pub fn connect(name: Name<'_>) -> io::Result<Stream> {
 	uds_impl::Stream::connect(name).map(Stream::from)
 }
+
+#[inline]
+pub fn pair() -> io::Result<(Stream, Stream)> {
+	let (a, b) = uds_impl::Stream::pair()?;
+	Ok((Stream::from(a), Stream::from(b)))
+}
+
+#[inline]
+pub fn probe(name: Name<'_>) -> io::Result<Liveness> {
+	uds_impl::probe(name)
+}
@@ -0,0 +1,194 @@
+use super::OwnedSocketFile;
+use crate::{
+	handle_transfer::HandleTransfer,
+	local_socket::{traits::Listener as _, Listener, Stream},
+	os::unix::{c_wrappers, uds_local_socket},
+};
+use std::{
+	ffi::OsString,
+	io::{self, prelude::*},
+	os::{
+		fd::AsFd,
+		unix::{
+			ffi::{OsStrExt, OsStringExt},
+			net::UnixListener,
+		},
+	},
+	path::PathBuf,
+};
+
+const VERSION: u8 = 1;
+const NONBLOCKING_STREAMS: u8 = 1 << 0;
+const RECLAIM: u8 = 1 << 1;
+
+/// A [local socket listener](Listener) which can be handed over to another process, such as a
+/// freshly started version of a server that is being upgraded in place.
+///
+/// The listening socket is sent over a local socket stream with
+/// [`HandleTransfer`], together with the state that doesn't live in the socket itself: the name
+/// of the socket file which is to be [reclaimed](Listener#name-reclamation) when the listener is
+/// dropped, and the nonblocking mode of accepted streams. Since the receiving process gets a
+/// duplicate of the very same socket, connections which arrive during the handoff wait in its
+/// queue instead of being refused, and both processes can keep accepting until the old one is done.
+///
+/// Upon a successful [`send()`](Self::send), name reclamation becomes the responsibility of the
+/// receiving side, and the token can be dropped or turned back into a listener that keeps
+/// accepting until the old process shuts down.
+///
+/// As with other [handle transfers](crate::handle_transfer), the receiving side has to call
+/// [`recv()`](Self::recv) at the exact point in the stream where the sending side called
+/// [`send()`](Self::send).
+///
+/// # Example
+/// ```no_run
+/// use interprocess::{
+/// 	local_socket::{prelude::*, GenericFilePath, ListenerOptions, Stream},
+/// 	os::unix::local_socket::ListenerToken,
+/// };
+///
+/// // In the old process
+/// let listener = ListenerOptions::new()
+/// 	.name("/tmp/example.sock".to_fs_name::<GenericFilePath>()?)
+/// 	.create_sync()?;
+/// let worker = Stream::connect("/tmp/example-handoff.sock".to_fs_name::<GenericFilePath>()?)?;
+/// let mut token = ListenerToken::from(listener);
+/// token.send(&worker)?;
+/// drop(token);
+///
+/// // In the new process, on the other end of `worker`
+/// # let conn: Stream = unimplemented!();
+/// let listener = ListenerToken::recv(&conn)?.into_listener();
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Debug)]
+pub struct ListenerToken {
+	listener: Listener,
+}
+impl ListenerToken {
+	/// Sends the listener to the process on the other end of `conn`, keeping this process's copy
+	/// of it open.
+	///
+	/// Upon success, this copy no longer reclaims the name of the listener when dropped. Upon
+	/// failure, the token is left untouched, but the receiving side may have received a part of the
+	/// transfer and cannot be expected to make sense of what follows.
+	pub fn send(&mut self, conn: &Stream) -> io::Result<()> {
+		let Listener::UdSocket(listener) = &self.listener;
+		send_listener(listener, conn)?;
+		self.listener.do_not_reclaim_name_on_drop();
+		Ok(())
+	}
+	/// Receives a listener sent by the process on the other end of `conn`.
+	///
+	/// Fails with an error of kind [`InvalidData`](io::ErrorKind::InvalidData) if what is received
+	/// is not a listener token, including if the received descriptor is not a listening Unix
+	/// domain stream socket.
+	pub fn recv(conn: &Stream) -> io::Result<Self> {
+		Ok(Self {
+			listener: recv_listener(conn)?.into(),
+		})
+	}
+
+	/// Returns a reference to the listener, which can be used to keep accepting connections while
+	/// the handoff is in progress.
+	#[inline]
+	pub fn listener(&self) -> &Listener {
+		&self.listener
+	}
+	/// Turns the token into the listener.
+	#[inline]
+	pub fn into_listener(self) -> Listener {
+		self.listener
+	}
+}
+
+impl From<Listener> for ListenerToken {
+	#[inline]
+	fn from(listener: Listener) -> Self {
+		Self { listener }
+	}
+}
+impl From<ListenerToken> for Listener {
+	#[inline]
+	fn from(token: ListenerToken) -> Self {
+		token.listener
+	}
+}
+
+/// Sends the listening socket and the state of the listener, without changing the listener.
+pub(super) fn send_listener(
+	listener: &uds_local_socket::Listener,
+	conn: &Stream,
+) -> io::Result<()> {
+	let (reclaim, nonblocking_streams) = listener.handoff_state();
+	let path = reclaim.map(|path| path.as_os_str());
+	let mut flags = 0;
+	if nonblocking_streams {
+		flags |= NONBLOCKING_STREAMS;
+	}
+	if path.is_some() {
+		flags |= RECLAIM;
+	}
+	let mut header = vec![VERSION, flags];
+	if let Some(path) = path {
+		let len = u16::try_from(path.len()).map_err(|_| {
+			io::Error::new(io::ErrorKind::InvalidInput, "socket file path too long")
+		})?;
+		header.extend_from_slice(&len.to_le_bytes());
+		header.extend_from_slice(path.as_bytes());
+	}
+
+	let fd = c_wrappers::duplicate_fd(listener.as_fd())?;
+	let mut writer = conn;
+	writer.write_all(&header)?;
+	conn.send_handle(fd)
+}
+
+pub(super) fn recv_listener(conn: &Stream) -> io::Result<uds_local_socket::Listener> {
+	let mut reader = conn;
+	let mut fixed = [0; 2];
+	reader.read_exact(&mut fixed)?;
+	let [VERSION, flags] = fixed else {
+		return Err(invalid_data("unsupported listener token version"));
+	};
+	let path = if flags & RECLAIM != 0 {
+		let mut len = [0; 2];
+		reader.read_exact(&mut len)?;
+		let mut path = vec![0; usize::from(u16::from_le_bytes(len))];
+		reader.read_exact(&mut path)?;
+		Some(PathBuf::from(OsString::from_vec(path)))
+	} else {
+		None
+	};
+
+	let fd = conn.recv_handle()?;
+	if !c_wrappers::is_uds_of_type(fd.as_fd(), libc::SOCK_STREAM)?
+		|| !c_wrappers::is_listening(fd.as_fd())?
+	{
+		return Err(invalid_data(
+			"received descriptor is not a listening Unix domain stream socket",
+		));
+	}
+	let listener = UnixListener::from(fd);
+	// The path comes from the peer, and is only trusted to the extent that the socket really is
+	// bound to it, lest dropping the listener delete an arbitrary file
+	let reclaim = match path {
+		Some(path) if listener.local_addr()?.as_pathname() == Some(&path) => {
+			OwnedSocketFile::new(path).ok()
+		}
+		Some(..) => {
+			return Err(invalid_data(
+				"socket file path does not match the address of the received socket",
+			))
+		}
+		None => None,
+	};
+	Ok(uds_local_socket::Listener::from_handoff(
+		listener.into(),
+		reclaim,
+		flags & NONBLOCKING_STREAMS != 0,
+	))
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,106 @@
+use crate::{
+	local_socket::{traits::Listener as _, Listener, ListenerSetBase, Stream},
+	os::unix::c_wrappers,
+};
+use std::{
+	io,
+	os::fd::{AsFd, AsRawFd},
+};
+
+/// A set of [local socket listeners](Listener) which can be accepted from all at once.
+///
+/// This is useful for servers that expose multiple endpoints, such as a public socket and an
+/// administrative one, without dedicating a thread to each of them. Readiness is detected with
+/// `poll(2)`, after which the connection is accepted from the listener that became ready.
+///
+/// Listeners are identified by their index in the set, which is the order in which they were
+/// added.
+#[derive(Debug, Default)]
+pub struct ListenerSet(ListenerSetBase<Listener>);
+impl ListenerSet {
+	/// Creates an empty set.
+	#[inline]
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Adds a listener to the set, returning its index.
+	#[inline]
+	pub fn push(&mut self, listener: Listener) -> usize {
+		self.0.push(listener)
+	}
+	/// Returns the listener with the given index, if there is one.
+	#[inline]
+	pub fn get(&self, index: usize) -> Option<&Listener> {
+		self.0.get(index)
+	}
+	/// Returns the number of listeners in the set.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+	/// Returns `true` if the set has no listeners.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+	/// Disassembles the set into its listeners.
+	#[inline]
+	pub fn into_inner(self) -> Vec<Listener> {
+		self.0.into_inner()
+	}
+
+	/// Blocks until a client connects to any of the listeners in the set, returning the index of
+	/// that listener together with the stream.
+	///
+	/// If several listeners have clients waiting, the one which gets to accept is picked in a
+	/// round-robin fashion, so that a busy endpoint cannot starve the others. This blocks even if
+	/// the listeners are in nonblocking mode.
+	///
+	/// Fails with an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput) if the set is
+	/// empty.
+	pub fn accept(&self) -> io::Result<(usize, Stream)> {
+		self.0.check_nonempty()?;
+		let mut fds = self
+			.0
+			.iter()
+			.map(|l| {
+				let Listener::UdSocket(l) = l;
+				libc::pollfd {
+					fd: l.as_fd().as_raw_fd(),
+					events: libc::POLLIN,
+					revents: 0,
+				}
+			})
+			.collect::<Vec<_>>();
+		loop {
+			fds.iter_mut().for_each(|fd| fd.revents = 0);
+			c_wrappers::poll(&mut fds, -1)?;
+
+			let ready = self
+				.0
+				.round_robin()?
+				.find(|&i| fds.get(i).is_some_and(|fd| fd.revents != 0));
+			let Some((idx, listener)) = ready.and_then(|i| Some((i, self.0.get(i)?))) else {
+				continue;
+			};
+			match listener.accept() {
+				// Another thread got to it first
+				Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+				rslt => return rslt.map(|s| (idx, s)),
+			}
+		}
+	}
+}
+
+impl From<Vec<Listener>> for ListenerSet {
+	#[inline]
+	fn from(listeners: Vec<Listener>) -> Self {
+		Self(listeners.into())
+	}
+}
+impl FromIterator<Listener> for ListenerSet {
+	#[inline]
+	fn from_iter<I: IntoIterator<Item = Listener>>(iter: I) -> Self {
+		Vec::from_iter(iter).into()
+	}
+}
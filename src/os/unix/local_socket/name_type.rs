@@ -1,9 +1,16 @@
-use crate::local_socket::{Name, NameInner, NameType, NamespacedNameType, PathNameType};
+use crate::{
+	error::Error,
+	local_socket::{Name, NameInner, NameType, NamespacedNameType, PathNameType},
+	os::unix::uds_local_socket::{NMCAP, SUN_LEN},
+};
 use std::{
 	borrow::Cow,
+	env,
 	ffi::{CStr, OsStr, OsString},
+	fs::{self, DirBuilder},
 	io,
-	os::unix::prelude::*,
+	os::unix::{fs::DirBuilderExt, prelude::*},
+	path::PathBuf,
 };
 
 fn c2os(ccow: Cow<'_, CStr>) -> Cow<'_, OsStr> {
@@ -83,7 +90,7 @@ impl NamespacedNameType<CStr> for SpecialDirUdSocket {
 tag_enum!(
 /// [Mapping](NameType) that produces local socket names referring to Unix domain sockets bound to
 /// the Linux abstract namespace.
-#[cfg_attr(feature = "cfg_doc", doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(target_os = "linux", target_os = "android"))))]
 AbstractNsUdSocket);
 #[cfg(any(target_os = "linux", target_os = "android"))]
 impl NameType for AbstractNsUdSocket {
@@ -140,3 +147,62 @@ map_generic! {
 	namespaced	map_generic_namespaced_osstr	for OsStr
 	namespaced	map_generic_namespaced_cstr		for CStr
 }
+
+/// Puts the socket file in `$XDG_RUNTIME_DIR`, which is private to the user, or in a private
+/// subdirectory of the temporary directory if that isn't set.
+pub(crate) fn user_scoped(file: &str) -> io::Result<Name<'static>> {
+	let dir = match env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from) {
+		Some(dir) if dir.is_absolute() => dir,
+		_ => private_temp_dir()?,
+	};
+	let path = dir.join(file).into_os_string();
+	Ok(Name(NameInner::UdSocketPath(Cow::Owned(path))))
+}
+
+/// Creates `$TMPDIR/interprocess-<ruid>` with permissions that only let the current user in, or
+/// checks that it already exists with such permissions.
+fn private_temp_dir() -> io::Result<PathBuf> {
+	let uid = unsafe { libc::getuid() };
+	let dir = env::temp_dir().join(format!("interprocess-{uid}"));
+	match DirBuilder::new().mode(0o700).create(&dir) {
+		Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+		rslt => rslt?,
+	}
+	// Anyone can create directories in the temporary directory, so it could have been planted by
+	// someone else. Symlinks are not followed for the same reason.
+	let meta = fs::symlink_metadata(&dir)?;
+	if !meta.is_dir() || meta.uid() != uid || meta.mode() & 0o077 != 0 {
+		return Err(io::Error::new(
+			io::ErrorKind::PermissionDenied,
+			"socket directory in the temporary directory is not private to the current user",
+		));
+	}
+	Ok(dir)
+}
+
+fn check_len(kind: &str, len: usize, max: usize) -> io::Result<()> {
+	if len == 0 {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("{kind} is empty"),
+		));
+	}
+	if len > max {
+		return Err(Error::NameTooLong { len, max }.into());
+	}
+	Ok(())
+}
+
+/// Checks the name against the limits imposed by `sockaddr_un`, so that overly long names are
+/// reported before they get to `bind` or `connect`.
+#[allow(clippy::arithmetic_side_effects)] // SUN_LEN is never 0
+pub(crate) fn validate(name: &Name<'_>) -> io::Result<()> {
+	match &name.0 {
+		// One byte is taken by the nul terminator.
+		NameInner::UdSocketPath(path) => check_len("socket path", path.len(), SUN_LEN - 1),
+		NameInner::UdSocketPseudoNs(name) => check_len("namespaced socket name", name.len(), NMCAP),
+		// One byte is taken by the leading nul which marks the name as abstract.
+		#[cfg(any(target_os = "linux", target_os = "android"))]
+		NameInner::UdSocketNs(name) => check_len("abstract socket name", name.len(), SUN_LEN - 1),
+	}
+}
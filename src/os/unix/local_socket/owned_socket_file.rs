@@ -0,0 +1,77 @@
+use std::{
+	fs, io, mem,
+	os::unix::fs::{FileTypeExt, MetadataExt},
+	path::{Path, PathBuf},
+};
+
+/// Ownership of a socket file, which is unlinked when the guard is dropped.
+///
+/// The device and inode numbers and the status change time of the socket file are recorded when
+/// the guard is created, and the file is only removed if the path still refers to that same socket
+/// by the time the guard is dropped. This keeps a guard that outlives its listener from deleting a
+/// socket file which another server has since created in its place. The check is best-effort:
+/// -	The check and the removal are two separate system calls, so a replacement which happens in
+/// 	between the two is still deleted.
+/// -	Inode numbers are reused once a file is deleted, and a replacement created right after the
+/// 	deletion may also share its status change time if the filesystem timestamps are coarse.
+/// -	Conversely, changing the permissions or the owner of the socket file updates its status
+/// 	change time, after which the guard leaves the file in place.
+///
+/// Listeners that [reclaim their name](crate::local_socket::Listener#name-reclamation) hold one of
+/// these internally, which can be detached from the listener with
+/// [`take_socket_file()`](crate::os::unix::uds_local_socket::Listener::take_socket_file). Socket
+/// files created by other means can be wrapped with [`new()`](Self::new).
+#[derive(Debug)]
+pub struct OwnedSocketFile {
+	path: PathBuf,
+	dev: u64,
+	ino: u64,
+	ctime: (i64, i64),
+}
+impl OwnedSocketFile {
+	/// Takes ownership of the socket file at the given path.
+	///
+	/// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the file is not a socket.
+	pub fn new(path: impl Into<PathBuf>) -> io::Result<Self> {
+		let path = path.into();
+		let meta = fs::symlink_metadata(&path)?;
+		if !meta.file_type().is_socket() {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"file is not a socket",
+			));
+		}
+		Ok(Self {
+			path,
+			dev: meta.dev(),
+			ino: meta.ino(),
+			ctime: (meta.ctime(), meta.ctime_nsec()),
+		})
+	}
+	/// Returns the path of the socket file.
+	#[inline]
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+	/// Gives up ownership of the socket file without removing it, returning its path.
+	pub fn release(mut self) -> PathBuf {
+		let path = mem::take(&mut self.path);
+		mem::forget(self);
+		path
+	}
+	fn is_same_file(&self) -> bool {
+		fs::symlink_metadata(&self.path).is_ok_and(|meta| {
+			meta.file_type().is_socket()
+				&& meta.dev() == self.dev
+				&& meta.ino() == self.ino
+				&& (meta.ctime(), meta.ctime_nsec()) == self.ctime
+		})
+	}
+}
+impl Drop for OwnedSocketFile {
+	fn drop(&mut self) {
+		if self.is_same_file() {
+			let _ = fs::remove_file(&self.path);
+		}
+	}
+}
@@ -0,0 +1,211 @@
+use super::handoff;
+use crate::{
+	local_socket::{traits::Listener as _, Listener, ListenerNonblockingMode, Stream},
+	os::unix::c_wrappers,
+};
+use std::{
+	fs::File,
+	io::{self, prelude::*},
+	os::fd::{AsFd, AsRawFd, OwnedFd},
+	sync::atomic::{AtomicBool, Ordering::SeqCst},
+};
+
+/// Sent by the successor once it is ready to accept connections.
+const ACK: u8 = 0x06;
+
+/// A [local socket listener](Listener) shared between the processes of a daemon that upgrades in
+/// place, with the old process handing the listening socket over to its successor and draining
+/// once the successor has taken over.
+///
+/// Both processes accept from the same socket, so there is no moment at which the name is not
+/// bound or at which connections are refused: clients that connect during the upgrade wait in the
+/// queue of the socket and are accepted by whichever process gets to them first. Once the
+/// successor acknowledges the takeover, [`accept()`](Self::accept) in the old process starts
+/// returning `None`, waking up any threads blocked in it, and the old process can finish serving
+/// the connections it already has and exit.
+///
+/// The handover happens over a local socket stream between the two processes. Typically, the old
+/// process creates a [socket pair](std::os::unix::net::UnixStream::pair) and passes one end to the
+/// successor with [`CommandExt::inherit_fd()`](crate::os::unix::process::CommandExt::inherit_fd),
+/// which the successor then picks up with [`adopt_stream()`](crate::os::unix::process::adopt_stream).
+/// The listening socket itself is sent as a [`ListenerToken`](super::ListenerToken) would be, and
+/// the successor takes over [name reclamation](Listener#name-reclamation).
+///
+/// The listener is put in nonblocking mode for accepting, which is shared with the successor, so
+/// that a process that loses the race for a connection doesn't block in `accept()`. Accepted
+/// streams keep the nonblocking mode that the listener was created with.
+///
+/// Unix domain sockets have no `SO_REUSEPORT`-style load balancing, which is why the socket is
+/// shared rather than bound anew by each process.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::{
+/// 	local_socket::{prelude::*, GenericFilePath, ListenerOptions, Stream},
+/// 	os::unix::{
+/// 		local_socket::SharedListener,
+/// 		process::{adopt_stream, CommandExt},
+/// 	},
+/// };
+/// use std::{os::unix::net::UnixStream, process::Command};
+///
+/// const HANDOVER_FD: i32 = 3;
+///
+/// # fn old() -> std::io::Result<()> {
+/// // In the old process
+/// let listener = SharedListener::new(
+/// 	ListenerOptions::new()
+/// 		.name("/tmp/example.sock".to_fs_name::<GenericFilePath>()?)
+/// 		.create_sync()?,
+/// )?;
+/// // ...upon being asked to upgrade:
+/// let (ours, theirs) = UnixStream::pair()?;
+/// Command::new("/usr/local/bin/example-daemon").inherit_fd(theirs, HANDOVER_FD).spawn()?;
+/// listener.hand_over(&Stream::from(ours))?;
+/// // ...meanwhile, on the accepting thread:
+/// while let Some(conn) = listener.accept()? {
+/// 	// Serve the connection
+/// }
+/// // Wait for the connections that are being served to finish, and exit
+/// # Ok(()) }
+///
+/// # fn new() -> std::io::Result<()> {
+/// // In the new process
+/// // SAFETY: the descriptor was set up by the old process and isn't owned by anything else
+/// let conn = unsafe { adopt_stream(HANDOVER_FD)? };
+/// let listener = SharedListener::take_over(&conn)?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct SharedListener {
+	listener: Listener,
+	draining: AtomicBool,
+	handed_over: AtomicBool,
+	wake_recver: OwnedFd,
+	wake_sender: File,
+}
+impl SharedListener {
+	/// Prepares the given listener for sharing.
+	pub fn new(listener: Listener) -> io::Result<Self> {
+		let wake = c_wrappers::pipe(true)?;
+		Self::assemble(listener, wake, false)
+	}
+	/// Receives the listener from the old process on the other end of `conn` and acknowledges the
+	/// takeover, after which the old process starts draining.
+	///
+	/// If anything fails after the listener is received, it is closed without reclaiming its name,
+	/// since the old process is still serving it.
+	pub fn take_over(conn: &Stream) -> io::Result<Self> {
+		let wake = c_wrappers::pipe(true)?;
+		let listener = handoff::recv_listener(conn)?.into();
+		let slf = Self::assemble(listener, wake, true)?;
+		let mut writer = conn;
+		writer.write_all(&[ACK])?;
+		slf.handed_over.store(false, SeqCst);
+		Ok(slf)
+	}
+	fn assemble(
+		listener: Listener,
+		(wake_recver, wake_sender): (OwnedFd, OwnedFd),
+		handed_over: bool,
+	) -> io::Result<Self> {
+		let slf = Self {
+			listener,
+			draining: AtomicBool::new(false),
+			handed_over: AtomicBool::new(handed_over),
+			wake_recver,
+			wake_sender: wake_sender.into(),
+		};
+		let Listener::UdSocket(inner) = &slf.listener;
+		let (_, nonblocking_streams) = inner.handoff_state();
+		slf.listener.set_nonblocking(match nonblocking_streams {
+			true => ListenerNonblockingMode::Both,
+			false => ListenerNonblockingMode::Accept,
+		})?;
+		Ok(slf)
+	}
+
+	/// Hands the listener over to the successor process on the other end of `conn`, waiting for
+	/// it to [take over](Self::take_over) and then [draining](Self::drain).
+	///
+	/// If the successor hangs up without acknowledging the takeover, an error of kind
+	/// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) is returned, and the listener is left
+	/// serving connections as before. Fails with an error of kind
+	/// [`InvalidInput`](io::ErrorKind::InvalidInput) if the listener is already draining.
+	pub fn hand_over(&self, conn: &Stream) -> io::Result<()> {
+		if self.is_draining() {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"listener is already draining",
+			));
+		}
+		let Listener::UdSocket(inner) = &self.listener;
+		handoff::send_listener(inner, conn)?;
+		let mut ack = [0];
+		let mut reader = conn;
+		reader.read_exact(&mut ack)?;
+		if ack != [ACK] {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"successor sent an invalid acknowledgement",
+			));
+		}
+		self.handed_over.store(true, SeqCst);
+		self.drain()
+	}
+
+	/// Blocks until a client connects, returning `None` once the listener is draining.
+	///
+	/// Threads blocked in this method when [`drain()`](Self::drain) is called return `None`
+	/// immediately.
+	pub fn accept(&self) -> io::Result<Option<Stream>> {
+		let Listener::UdSocket(inner) = &self.listener;
+		let mut fds = [inner.as_fd(), self.wake_recver.as_fd()].map(|fd| libc::pollfd {
+			fd: fd.as_raw_fd(),
+			events: libc::POLLIN,
+			revents: 0,
+		});
+		loop {
+			if self.is_draining() {
+				return Ok(None);
+			}
+			fds.iter_mut().for_each(|fd| fd.revents = 0);
+			c_wrappers::poll(&mut fds, -1)?;
+			match self.listener.accept() {
+				// Another thread or process got to it first, or we were woken up to drain
+				Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+				rslt => return rslt.map(Some),
+			}
+		}
+	}
+	/// Stops accepting connections, waking up all threads blocked in [`accept()`](Self::accept).
+	///
+	/// This is done automatically by [`hand_over()`](Self::hand_over), but can also be used on its
+	/// own to shut down gracefully. The listening socket stays open until the `SharedListener` is
+	/// dropped.
+	pub fn drain(&self) -> io::Result<()> {
+		if self.draining.swap(true, SeqCst) {
+			return Ok(());
+		}
+		// Never read from, so that the pipe stays readable for everyone who polls it
+		(&self.wake_sender).write_all(&[0])
+	}
+	/// Returns `true` if the listener is [draining](Self::drain).
+	#[inline]
+	pub fn is_draining(&self) -> bool {
+		self.draining.load(SeqCst)
+	}
+	/// Returns a reference to the listener.
+	#[inline]
+	pub fn listener(&self) -> &Listener {
+		&self.listener
+	}
+}
+impl Drop for SharedListener {
+	fn drop(&mut self) {
+		// The successor is responsible for the name now
+		if *self.handed_over.get_mut() {
+			self.listener.do_not_reclaim_name_on_drop();
+		}
+	}
+}
@@ -0,0 +1,88 @@
+//! Wakeup notifications between processes, split into a signalling half and a waiting half.
+//!
+//! An eventfd is used for both halves on Linux and Android; a nonblocking pipe is used elsewhere.
+//! In both cases, signals are counted in the kernel, so a signal sent before the waiter starts
+//! waiting is not lost, and several signals may be coalesced into a single wakeup.
+
+use super::{c_wrappers, unixprelude::*, FdOps};
+use std::io::{self, prelude::*};
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) fn notify_pair() -> io::Result<(Notifier, Waiter)> {
+	use crate::FdOrErrno;
+	let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) }.fd_or_errno()?;
+	let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+	let dup = c_wrappers::duplicate_fd(fd.as_fd())?;
+	Ok((Notifier(FdOps(dup)), Waiter(FdOps(fd))))
+}
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub(crate) fn notify_pair() -> io::Result<(Notifier, Waiter)> {
+	let (r, w) = c_wrappers::pipe(true)?;
+	c_wrappers::set_nonblocking(r.as_fd(), true)?;
+	c_wrappers::set_nonblocking(w.as_fd(), true)?;
+	Ok((Notifier(FdOps(w)), Waiter(FdOps(r))))
+}
+
+pub(crate) struct Notifier(FdOps);
+impl Notifier {
+	pub fn signal(&self) -> io::Result<()> {
+		#[cfg(any(target_os = "linux", target_os = "android"))]
+		let buf = 1_u64.to_ne_bytes();
+		#[cfg(not(any(target_os = "linux", target_os = "android")))]
+		let buf = [1_u8];
+		match (&self.0).write(&buf) {
+			// The counter is saturated or the pipe is full, either of which means that there is a
+			// signal pending already.
+			Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(()),
+			rslt => rslt.map(drop),
+		}
+	}
+}
+multimacro! {
+	Notifier,
+	forward_handle(unix),
+	forward_try_clone,
+	forward_debug("Notifier"),
+}
+
+pub(crate) struct Waiter(FdOps);
+impl Waiter {
+	/// Consumes all pending signals, returning whether there were any. Fails with `BrokenPipe` if
+	/// there are none and the pipe has no signalling half left.
+	pub fn try_wait(&self) -> io::Result<bool> {
+		let mut buf = [0_u8; 8];
+		let mut signalled = false;
+		loop {
+			match c_wrappers::retry_eintr(|| (&self.0).read(&mut buf)) {
+				// An eventfd is drained in one read.
+				Ok(..) if cfg!(any(target_os = "linux", target_os = "android")) => return Ok(true),
+				Ok(0) if signalled => return Ok(true),
+				Ok(0) => return Err(io::ErrorKind::BrokenPipe.into()),
+				Ok(..) => signalled = true,
+				Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(signalled),
+				Err(e) => return Err(e),
+			}
+		}
+	}
+	/// Blocks until a signal arrives, consuming it along with any other pending ones.
+	pub fn wait(&self) -> io::Result<()> {
+		loop {
+			if self.try_wait()? {
+				return Ok(());
+			}
+			let mut fds = [libc::pollfd {
+				fd: self.0 .0.as_raw_fd(),
+				events: libc::POLLIN,
+				revents: 0,
+			}];
+			c_wrappers::poll(&mut fds, -1)?;
+		}
+	}
+}
+multimacro! {
+	Waiter,
+	forward_handle(unix),
+	forward_try_clone,
+	forward_debug("Waiter"),
+	derive_asraw(unix),
+}
@@ -0,0 +1,218 @@
+//! POSIX message queues.
+//!
+//! A message queue is a named kernel object which stores discrete messages, each with a priority.
+//! Receivers always get the oldest message of the highest priority first. Unlike with sockets and
+//! pipes, messages stay in the queue when nobody has it open, until it's
+//! [unlinked](MessageQueue::unlink) or the system is rebooted.
+//!
+//! Message queues are only supported on Linux, where they are file descriptors which can be polled
+//! like any other, which is what the Tokio adapter relies on.
+//!
+//! ## Usage
+//! [`MessageQueue::create()`] creates a new queue with the given capacity, which other processes
+//! can then [open](MessageQueue::open) by name. Names must start with a slash and contain no other
+//! slashes; a slash is prepended automatically if the name doesn't start with one. The queue is
+//! closed when the [`MessageQueue`] is dropped.
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+use super::{c_wrappers, unixprelude::*};
+use crate::{FdOrErrno, OrErrno};
+use std::{
+	ffi::CString,
+	fmt::{self, Debug, Formatter},
+	io,
+	mem::zeroed,
+};
+
+extern "C" {
+	// Not exposed by the libc crate on Linux.
+	fn mq_notify(mqdes: libc::mqd_t, sevp: *const libc::sigevent) -> c_int;
+}
+
+fn invalid_input(msg: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+fn queue_name(name: &str) -> io::Result<CString> {
+	let name = if name.starts_with('/') {
+		name.to_owned()
+	} else {
+		format!("/{name}")
+	};
+	CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+// The field types of `mq_attr` vary with the ABI.
+fn to_attr_field<T: TryInto<U>, U>(val: T) -> io::Result<U> {
+	val.try_into()
+		.map_err(|_| invalid_input("message queue attribute out of range"))
+}
+fn from_attr_field<T: TryInto<usize>>(val: T) -> usize {
+	val.try_into().unwrap_or(0)
+}
+
+/// The attributes of a message queue, as returned by [`MessageQueue::attributes()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Attributes {
+	/// The maximum number of messages which can be in the queue at once.
+	pub max_messages: usize,
+	/// The maximum size of a message in bytes. Buffers passed to
+	/// [`recv()`](MessageQueue::recv) must be at least this large.
+	pub max_message_size: usize,
+	/// The number of messages currently in the queue.
+	pub current_messages: usize,
+	/// Whether the queue descriptor is in nonblocking mode.
+	pub nonblocking: bool,
+}
+
+/// A handle to a POSIX message queue, which is closed on drop.
+///
+/// See the [module-level documentation](self) for more.
+pub struct MessageQueue(OwnedFd);
+impl MessageQueue {
+	/// Creates a new message queue for sending and receiving, failing with an error of kind
+	/// [`AlreadyExists`](io::ErrorKind::AlreadyExists) if one with the given name already exists.
+	///
+	/// The queue is created with permissions `0o600`. The maximum values of `max_messages` and
+	/// `max_message_size` for unprivileged processes are controlled by `/proc/sys/fs/mqueue`.
+	///
+	/// ## System calls
+	/// - `mq_open`
+	pub fn create(name: &str, max_messages: usize, max_message_size: usize) -> io::Result<Self> {
+		let mut attr = unsafe { zeroed::<libc::mq_attr>() };
+		attr.mq_maxmsg = to_attr_field(max_messages)?;
+		attr.mq_msgsize = to_attr_field(max_message_size)?;
+		let name = queue_name(name)?;
+		let mode: mode_t = 0o600;
+		let attr: *const libc::mq_attr = &attr;
+		let fd = unsafe {
+			libc::mq_open(
+				name.as_ptr(),
+				libc::O_RDWR | libc::O_CREAT | libc::O_EXCL | libc::O_CLOEXEC,
+				mode,
+				attr,
+			)
+		}
+		.fd_or_errno()?;
+		Ok(Self(unsafe { OwnedFd::from_raw_fd(fd) }))
+	}
+	/// Opens an existing message queue for sending and receiving.
+	///
+	/// ## System calls
+	/// - `mq_open`
+	pub fn open(name: &str) -> io::Result<Self> {
+		let name = queue_name(name)?;
+		let fd = unsafe { libc::mq_open(name.as_ptr(), libc::O_RDWR | libc::O_CLOEXEC) }
+			.fd_or_errno()?;
+		Ok(Self(unsafe { OwnedFd::from_raw_fd(fd) }))
+	}
+	/// Removes the name of a message queue, so that it's destroyed once all processes close it.
+	///
+	/// ## System calls
+	/// - `mq_unlink`
+	pub fn unlink(name: &str) -> io::Result<()> {
+		let name = queue_name(name)?;
+		unsafe { libc::mq_unlink(name.as_ptr()) != -1 }.true_val_or_errno(())
+	}
+
+	/// Adds a message to the queue, blocking while the queue is full unless in nonblocking mode.
+	///
+	/// Messages with larger values of `priority` are received first.
+	///
+	/// ## System calls
+	/// - `mq_send`
+	pub fn send(&self, msg: &[u8], priority: u32) -> io::Result<()> {
+		c_wrappers::retry_eintr(|| {
+			let rslt = unsafe {
+				libc::mq_send(self.0.as_raw_fd(), msg.as_ptr().cast(), msg.len(), priority)
+			} != -1;
+			rslt.true_val_or_errno(())
+		})
+	}
+	/// Removes the oldest message of the highest priority from the queue, writing it into `buf`
+	/// and returning its size and priority. Blocks while the queue is empty unless in nonblocking
+	/// mode.
+	///
+	/// Fails if `buf` is smaller than the [maximum message size](Attributes::max_message_size),
+	/// even if the message would fit.
+	///
+	/// ## System calls
+	/// - `mq_receive`
+	pub fn recv(&self, buf: &mut [u8]) -> io::Result<(usize, u32)> {
+		let mut priority = 0;
+		let len = c_wrappers::retry_eintr(|| {
+			let rslt = unsafe {
+				libc::mq_receive(
+					self.0.as_raw_fd(),
+					buf.as_mut_ptr().cast(),
+					buf.len(),
+					&mut priority,
+				)
+			};
+			usize::try_from(rslt).map_err(|_| io::Error::last_os_error())
+		})?;
+		Ok((len, priority))
+	}
+
+	/// Retrieves the attributes of the queue.
+	///
+	/// ## System calls
+	/// - `mq_getattr`
+	pub fn attributes(&self) -> io::Result<Attributes> {
+		let mut attr = unsafe { zeroed::<libc::mq_attr>() };
+		unsafe { libc::mq_getattr(self.0.as_raw_fd(), &mut attr) != -1 }.true_val_or_errno(())?;
+		Ok(Attributes {
+			max_messages: from_attr_field(attr.mq_maxmsg),
+			max_message_size: from_attr_field(attr.mq_msgsize),
+			current_messages: from_attr_field(attr.mq_curmsgs),
+			nonblocking: from_attr_field(attr.mq_flags) & from_attr_field(libc::O_NONBLOCK) != 0,
+		})
+	}
+	/// Enables or disables nonblocking mode, in which sending to a full queue or receiving from an
+	/// empty one fails with an error of kind [`WouldBlock`](io::ErrorKind::WouldBlock).
+	///
+	/// ## System calls
+	/// - `mq_setattr`
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		let mut attr = unsafe { zeroed::<libc::mq_attr>() };
+		if nonblocking {
+			attr.mq_flags = to_attr_field(libc::O_NONBLOCK)?;
+		}
+		unsafe { libc::mq_setattr(self.0.as_raw_fd(), &attr, std::ptr::null_mut()) != -1 }
+			.true_val_or_errno(())
+	}
+	/// Registers the process to receive the signal `signal` when a message arrives in the queue
+	/// while it's empty, or removes the registration if `None` is passed.
+	///
+	/// Only one process can be registered at a time, and the registration is removed once the
+	/// signal is sent. Fails with `EBUSY` if a different process is registered.
+	///
+	/// ## System calls
+	/// - `mq_notify`
+	pub fn notify(&self, signal: Option<c_int>) -> io::Result<()> {
+		let sev = signal.map(|signo| {
+			let mut sev = unsafe { zeroed::<libc::sigevent>() };
+			sev.sigev_notify = libc::SIGEV_SIGNAL;
+			sev.sigev_signo = signo;
+			sev
+		});
+		let sevp: *const libc::sigevent = match &sev {
+			Some(sev) => sev,
+			None => std::ptr::null(),
+		};
+		unsafe { mq_notify(self.0.as_raw_fd(), sevp) != -1 }.true_val_or_errno(())
+	}
+}
+impl Debug for MessageQueue {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("MessageQueue")
+			.field(&self.0.as_raw_fd())
+			.finish()
+	}
+}
+multimacro! {
+	MessageQueue,
+	forward_handle(unix),
+	derive_raw(unix),
+}
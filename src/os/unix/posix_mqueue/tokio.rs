@@ -0,0 +1,77 @@
+//! Tokio-based asynchronous POSIX message queues.
+
+use super::{Attributes, MessageQueue as SyncMessageQueue};
+use crate::os::unix::unixprelude::*;
+use std::io;
+use tokio::io::unix::AsyncFd;
+
+/// Tokio-based asynchronous handle to a POSIX message queue.
+///
+/// The queue is put in nonblocking mode and registered with the Tokio reactor, so that sending and
+/// receiving wait for the queue to become writable or readable instead of blocking the thread.
+#[derive(Debug)]
+pub struct MessageQueue(AsyncFd<SyncMessageQueue>);
+impl MessageQueue {
+	/// Creates a new message queue. See [the synchronous version](SyncMessageQueue::create) for
+	/// more.
+	#[inline]
+	pub fn create(name: &str, max_messages: usize, max_message_size: usize) -> io::Result<Self> {
+		SyncMessageQueue::create(name, max_messages, max_message_size)?.try_into()
+	}
+	/// Opens an existing message queue. See [the synchronous version](SyncMessageQueue::open) for
+	/// more.
+	#[inline]
+	pub fn open(name: &str) -> io::Result<Self> {
+		SyncMessageQueue::open(name)?.try_into()
+	}
+
+	/// Asynchronously adds a message to the queue, waiting while the queue is full.
+	pub async fn send(&self, msg: &[u8], priority: u32) -> io::Result<()> {
+		loop {
+			let mut guard = self.0.writable().await?;
+			match guard.try_io(|mq| mq.get_ref().send(msg, priority)) {
+				Ok(rslt) => return rslt,
+				Err(..) => continue,
+			}
+		}
+	}
+	/// Asynchronously receives a message, waiting while the queue is empty. See
+	/// [the synchronous version](SyncMessageQueue::recv) for more.
+	pub async fn recv(&self, buf: &mut [u8]) -> io::Result<(usize, u32)> {
+		loop {
+			let mut guard = self.0.readable().await?;
+			match guard.try_io(|mq| mq.get_ref().recv(buf)) {
+				Ok(rslt) => return rslt,
+				Err(..) => continue,
+			}
+		}
+	}
+
+	/// Retrieves the attributes of the queue.
+	#[inline]
+	pub fn attributes(&self) -> io::Result<Attributes> {
+		self.0.get_ref().attributes()
+	}
+
+	/// Deregisters the queue from the Tokio reactor and returns the synchronous handle to it, which
+	/// remains in nonblocking mode.
+	#[inline]
+	pub fn into_sync(self) -> SyncMessageQueue {
+		self.0.into_inner()
+	}
+}
+/// Puts the queue in nonblocking mode and registers it with the Tokio reactor.
+impl TryFrom<SyncMessageQueue> for MessageQueue {
+	type Error = io::Error;
+	fn try_from(mq: SyncMessageQueue) -> io::Result<Self> {
+		mq.set_nonblocking(true)?;
+		AsyncFd::new(mq).map(Self)
+	}
+}
+impl AsFd for MessageQueue {
+	#[inline]
+	fn as_fd(&self) -> BorrowedFd<'_> {
+		self.0.get_ref().as_fd()
+	}
+}
+derive_asraw!(MessageQueue, unix);
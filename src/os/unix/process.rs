@@ -0,0 +1,77 @@
+//! Passing local sockets to child processes.
+//!
+//! On the parent side, [`CommandExt::inherit_fd()`] arranges for a socket to appear under a
+//! chosen descriptor number in a child spawned via [`Command`]. On the child side,
+//! [`adopt_stream()`] turns such a descriptor back into a [local socket
+//! stream](crate::local_socket::Stream), after checking that it really is one.
+//!
+//! How the child learns which descriptor to adopt is up to the application – a fixed number
+//! known to both sides, a command-line argument and an environment variable are all common
+//! choices.
+
+use super::{c_wrappers, uds_local_socket};
+use crate::{local_socket::Stream, Sealed};
+use std::{
+	io,
+	os::unix::{
+		io::{AsFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+		process::CommandExt as _,
+	},
+	process::Command,
+};
+
+/// Extension trait for [`Command`] that allows passing file descriptors, such as those of local
+/// sockets, to the child process.
+#[allow(private_bounds)]
+pub trait CommandExt: Sealed {
+	/// Makes `fd` available in the child process as descriptor number `child_fd`.
+	///
+	/// This takes ownership of `fd`, which is kept open in the parent process for as long as the
+	/// `Command` exists, so that it can be passed to every child spawned from it. The descriptor
+	/// is duplicated onto `child_fd` with `dup2` after `fork` and before `exec`, and the copy
+	/// does not have the `FD_CLOEXEC` flag set. No other descriptors are made inheritable.
+	///
+	/// Passing 0, 1 or 2 as `child_fd` replaces the corresponding standard I/O stream of the child,
+	/// overriding whatever was configured via [`Command::stdin()`] and friends.
+	///
+	/// If this is called multiple times, the descriptors are duplicated in the order in which the
+	/// calls were made. Be careful not to pick a `child_fd` which is the parent-side descriptor
+	/// of a subsequent call, since that one would then be overwritten before being duplicated.
+	fn inherit_fd(&mut self, fd: impl Into<OwnedFd>, child_fd: RawFd) -> &mut Self;
+}
+
+impl Sealed for Command {}
+impl CommandExt for Command {
+	fn inherit_fd(&mut self, fd: impl Into<OwnedFd>, child_fd: RawFd) -> &mut Self {
+		let fd = fd.into();
+		let hook = move || c_wrappers::dup2_inheritable(fd.as_fd(), child_fd);
+		// SAFETY: the hook only calls dup2 and fcntl, which are async-signal-safe, and does not
+		// allocate.
+		unsafe { self.pre_exec(hook) }
+	}
+}
+
+/// Takes ownership of a local socket stream inherited from the parent process.
+///
+/// Before taking ownership, the descriptor is checked to actually be a Unix domain socket of type
+/// `SOCK_STREAM`, failing with an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput)
+/// if it isn't. In that case, the descriptor is left open. Upon success, the `FD_CLOEXEC` flag
+/// is set on the descriptor so that it doesn't leak further into grandchildren.
+///
+/// # Safety
+/// `fd` must be an open file descriptor which isn't owned by anything else in the process, as per
+/// [`FromRawFd::from_raw_fd()`].
+pub unsafe fn adopt_stream(fd: RawFd) -> io::Result<Stream> {
+	// SAFETY: as per safety contract, the descriptor is open
+	let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+	if !c_wrappers::is_uds_of_type(borrowed, libc::SOCK_STREAM)? {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"inherited file descriptor is not a Unix domain stream socket",
+		));
+	}
+	c_wrappers::set_inheritable(borrowed, false)?;
+	// SAFETY: as per safety contract, nothing else owns the descriptor
+	let owned = unsafe { OwnedFd::from_raw_fd(fd) };
+	Ok(uds_local_socket::Stream::from(owned).into())
+}
@@ -0,0 +1,203 @@
+//! Socket activation in the style of systemd's `sd_listen_fds()`.
+//!
+//! A service manager which supports socket activation creates the listening sockets of a service
+//! itself, then spawns the service with those sockets inherited as descriptors 3 and onwards,
+//! passing their number in the `LISTEN_FDS` environment variable and, optionally, their names in
+//! `LISTEN_FDNAMES`. The `LISTEN_PID` variable specifies the process ID for which those variables
+//! are intended, so that they are not accidentally picked up by child processes.
+//!
+//! [`sd_listen_fds()`] implements the receiving side of that protocol, and
+//! [`ListenFd::into_listener()`] turns the received descriptors into [local socket
+//! listeners](crate::local_socket::Listener), verifying that they are listening Unix domain stream
+//! sockets in the process.
+
+use super::{
+	c_wrappers,
+	uds_local_socket::{name_to_addr, Listener as UdsListener},
+};
+use crate::local_socket::{Listener, Name};
+use std::{
+	env,
+	ffi::OsString,
+	io,
+	os::{
+		fd::{AsFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+		unix::net::{SocketAddr, UnixListener},
+	},
+	process,
+};
+
+/// The first descriptor passed by the service manager.
+pub const SD_LISTEN_FDS_START: RawFd = 3;
+
+/// A descriptor passed by the service manager, along with its name.
+#[derive(Debug)]
+pub struct ListenFd {
+	fd: OwnedFd,
+	name: Option<String>,
+}
+impl ListenFd {
+	/// Returns the name assigned to the descriptor via `FileDescriptorName=` in the socket unit, or
+	/// `None` if `LISTEN_FDNAMES` was not set.
+	#[inline]
+	pub fn name(&self) -> Option<&str> {
+		self.name.as_deref()
+	}
+
+	/// Turns the descriptor into a local socket listener.
+	///
+	/// Fails with an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput) if the
+	/// descriptor is not a Unix domain socket of type `SOCK_STREAM` that is in the listening
+	/// state. The socket file is never unlinked by the resulting listener, since it's owned by
+	/// the service manager.
+	pub fn into_listener(self) -> io::Result<Listener> {
+		self.validate()?;
+		Ok(UdsListener::from(self.fd).into())
+	}
+
+	/// Like [`into_listener()`](Self::into_listener), but additionally checks that the socket is
+	/// bound to the given name, failing with an error of kind
+	/// [`InvalidInput`](io::ErrorKind::InvalidInput) if it is not.
+	pub fn into_listener_at(self, name: Name<'_>) -> io::Result<Listener> {
+		self.validate()?;
+		let expected = name_to_addr(name, false)?;
+		let actual = UnixListener::from(self.fd.try_clone()?).local_addr()?;
+		if !addr_eq(&expected, &actual) {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"passed socket is bound to a different name",
+			));
+		}
+		Ok(UdsListener::from(self.fd).into())
+	}
+
+	fn validate(&self) -> io::Result<()> {
+		let fd = self.fd.as_fd();
+		if !c_wrappers::is_uds_of_type(fd, libc::SOCK_STREAM)? || !c_wrappers::is_listening(fd)? {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"passed descriptor is not a listening Unix domain stream socket",
+			));
+		}
+		Ok(())
+	}
+}
+impl AsFd for ListenFd {
+	#[inline]
+	fn as_fd(&self) -> BorrowedFd<'_> {
+		self.fd.as_fd()
+	}
+}
+impl From<ListenFd> for OwnedFd {
+	#[inline]
+	fn from(fd: ListenFd) -> Self {
+		fd.fd
+	}
+}
+
+fn addr_eq(a: &SocketAddr, b: &SocketAddr) -> bool {
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	{
+		use std::os::linux::net::SocketAddrExt;
+		if a.as_abstract_name().is_some() || b.as_abstract_name().is_some() {
+			return a.as_abstract_name() == b.as_abstract_name();
+		}
+	}
+	a.as_pathname().is_some() && a.as_pathname() == b.as_pathname()
+}
+
+fn invalid_env(msg: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn var(key: &str, lookup: &mut impl FnMut(&str) -> Option<OsString>) -> io::Result<Option<String>> {
+	match lookup(key).map(OsString::into_string) {
+		None => Ok(None),
+		Some(Ok(s)) => Ok(Some(s)),
+		Some(Err(..)) => Err(invalid_env("socket activation variable is not valid UTF-8")),
+	}
+}
+
+/// Takes ownership of the descriptors passed by the service manager.
+///
+/// Returns an empty list if `LISTEN_PID` is not set or does not match the current process, i.e.
+/// if the process was not socket-activated. Fails with an error of kind
+/// [`InvalidData`](io::ErrorKind::InvalidData) if the variables are malformed or the number of
+/// names in `LISTEN_FDNAMES` doesn't match `LISTEN_FDS`.
+///
+/// Every returned descriptor has `FD_CLOEXEC` set on it. If `unset_env` is `true`, the
+/// `LISTEN_PID`, `LISTEN_FDS` and `LISTEN_FDNAMES` environment variables are removed from the
+/// environment of the process, which is subject to the same caveats as [`env::remove_var()`].
+///
+/// # Safety
+/// The descriptors starting at [`SD_LISTEN_FDS_START`] must not be owned by anything else in the
+/// process. In particular, this function must not be called more than once, unless it returned
+/// an empty list.
+pub unsafe fn sd_listen_fds(unset_env: bool) -> io::Result<Vec<ListenFd>> {
+	let rslt = parse_env(process::id(), |key| env::var_os(key));
+	if unset_env {
+		env::remove_var("LISTEN_PID");
+		env::remove_var("LISTEN_FDS");
+		env::remove_var("LISTEN_FDNAMES");
+	}
+	let (count, names) = match rslt? {
+		Some(x) => x,
+		None => return Ok(Vec::new()),
+	};
+
+	let mut fds = Vec::with_capacity(count);
+	let mut names = names.map(Vec::into_iter);
+	for fd in (SD_LISTEN_FDS_START..).take(count) {
+		// SAFETY: as per safety contract, nothing else owns the descriptor
+		let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+		c_wrappers::set_inheritable(fd.as_fd(), false)?;
+		let name = names.as_mut().and_then(Iterator::next);
+		fds.push(ListenFd { fd, name });
+	}
+	Ok(fds)
+}
+
+/// Parses the socket activation variables, as retrieved by `lookup`, for the process with the given
+/// ID, returning the number of descriptors and their names.
+#[allow(clippy::type_complexity)]
+pub(crate) fn parse_env(
+	own_pid: u32,
+	mut lookup: impl FnMut(&str) -> Option<OsString>,
+) -> io::Result<Option<(usize, Option<Vec<String>>)>> {
+	let Some(pid) = var("LISTEN_PID", &mut lookup)? else {
+		return Ok(None);
+	};
+	let pid = pid
+		.parse::<u32>()
+		.map_err(|_| invalid_env("LISTEN_PID is not a valid process ID"))?;
+	if pid != own_pid {
+		return Ok(None);
+	}
+
+	let count = var("LISTEN_FDS", &mut lookup)?
+		.ok_or_else(|| invalid_env("LISTEN_PID is set, but LISTEN_FDS is not"))?
+		.parse::<usize>()
+		.map_err(|_| invalid_env("LISTEN_FDS is not a valid number"))?;
+	// Guards against overflowing the descriptor range
+	if RawFd::try_from(count).map_or(true, |c| c.checked_add(SD_LISTEN_FDS_START).is_none()) {
+		return Err(invalid_env("LISTEN_FDS is out of range"));
+	}
+
+	let names = match var("LISTEN_FDNAMES", &mut lookup)? {
+		Some(names) => {
+			let names = if names.is_empty() {
+				Vec::new()
+			} else {
+				names.split(':').map(str::to_owned).collect::<Vec<_>>()
+			};
+			if names.len() != count {
+				return Err(invalid_env(
+					"number of names in LISTEN_FDNAMES does not match LISTEN_FDS",
+				));
+			}
+			Some(names)
+		}
+		None => None,
+	};
+	Ok(Some((count, names)))
+}
@@ -0,0 +1,219 @@
+use super::{c_wrappers, unixprelude::*};
+use crate::{error::FromFdError, FdOrErrno, OrErrno, TryClone};
+use std::{
+	ffi::{CStr, CString},
+	fmt::{self, Debug, Formatter},
+	io,
+	ptr::{self, NonNull},
+	slice,
+};
+
+fn invalid_input(msg: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+fn check_size(size: usize) -> io::Result<libc::off_t> {
+	if size == 0 {
+		return Err(invalid_input("shared memory object cannot be empty"));
+	}
+	libc::off_t::try_from(size).map_err(|_| invalid_input("shared memory size out of range"))
+}
+
+/// POSIX only guarantees portable behavior for names with exactly one leading slash.
+fn shm_name(name: &str) -> io::Result<CString> {
+	let name = if name.starts_with('/') {
+		name.to_owned()
+	} else {
+		format!("/{name}")
+	};
+	CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+fn shm_open(name: &CStr, flags: c_int) -> io::Result<OwnedFd> {
+	// shm_open() always sets FD_CLOEXEC on the resulting descriptor.
+	let fd = unsafe { libc::shm_open(name.as_ptr(), flags | libc::O_RDWR, 0o600) }.fd_or_errno()?;
+	Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+fn shm_unlink(name: &CStr) -> io::Result<()> {
+	unsafe { libc::shm_unlink(name.as_ptr()) != -1 }.true_val_or_errno(())
+}
+
+fn ftruncate(fd: BorrowedFd<'_>, size: libc::off_t) -> io::Result<()> {
+	unsafe { libc::ftruncate(fd.as_raw_fd(), size) != -1 }.true_val_or_errno(())
+}
+fn fd_size(fd: BorrowedFd<'_>) -> io::Result<usize> {
+	let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+	unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) != -1 }.true_val_or_errno(())?;
+	usize::try_from(stat.st_size).map_err(|_| invalid_input("shared memory size out of range"))
+}
+
+pub(crate) struct SharedMemObject {
+	fd: OwnedFd,
+	size: usize,
+}
+impl SharedMemObject {
+	pub fn create(name: &str, size: usize) -> io::Result<Self> {
+		let off = check_size(size)?;
+		let name = shm_name(name)?;
+		let fd = shm_open(&name, libc::O_CREAT | libc::O_EXCL)?;
+		if let Err(e) = ftruncate(fd.as_fd(), off) {
+			let _ = shm_unlink(&name);
+			return Err(e);
+		}
+		Ok(Self { fd, size })
+	}
+	pub fn open(name: &str, size: usize) -> io::Result<Self> {
+		check_size(size)?;
+		let fd = shm_open(&shm_name(name)?, 0)?;
+		if fd_size(fd.as_fd())? < size {
+			return Err(invalid_input(
+				"shared memory object is smaller than the requested size",
+			));
+		}
+		Ok(Self { fd, size })
+	}
+	#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+	pub fn anonymous(size: usize) -> io::Result<Self> {
+		let off = check_size(size)?;
+		let fd = unsafe {
+			libc::memfd_create(b"interprocess-shmem\0".as_ptr().cast(), libc::MFD_CLOEXEC)
+		}
+		.fd_or_errno()?;
+		let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+		ftruncate(fd.as_fd(), off)?;
+		Ok(Self { fd, size })
+	}
+	#[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+	pub fn anonymous(size: usize) -> io::Result<Self> {
+		use std::{
+			process,
+			sync::atomic::{AtomicU32, Ordering::Relaxed},
+		};
+		static COUNTER: AtomicU32 = AtomicU32::new(0);
+		let off = check_size(size)?;
+		let fd = loop {
+			let name = format!(
+				"/interprocess-anon-{:08x}-{:08x}",
+				process::id(),
+				COUNTER.fetch_add(1, Relaxed)
+			);
+			let name = shm_name(&name)?;
+			match shm_open(&name, libc::O_CREAT | libc::O_EXCL) {
+				Ok(fd) => {
+					shm_unlink(&name)?;
+					break fd;
+				}
+				Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+				Err(e) => return Err(e),
+			}
+		};
+		ftruncate(fd.as_fd(), off)?;
+		Ok(Self { fd, size })
+	}
+	pub fn unlink(name: &str) -> io::Result<()> {
+		shm_unlink(&shm_name(name)?)
+	}
+
+	#[inline]
+	pub fn size(&self) -> usize {
+		self.size
+	}
+	pub unsafe fn map(&self) -> io::Result<Mapping> {
+		let ptr = unsafe {
+			libc::mmap(
+				ptr::null_mut(),
+				self.size,
+				libc::PROT_READ | libc::PROT_WRITE,
+				libc::MAP_SHARED,
+				self.fd.as_raw_fd(),
+				0,
+			)
+		};
+		(ptr != libc::MAP_FAILED).true_or_errno(|| Mapping {
+			// mmap() never returns null on success for a null address hint
+			ptr: NonNull::new(ptr.cast()).unwrap_or(NonNull::dangling()),
+			len: self.size,
+		})
+	}
+}
+impl Debug for SharedMemObject {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SharedMemObject")
+			.field("fd", &self.fd.as_raw_fd())
+			.field("size", &self.size)
+			.finish()
+	}
+}
+impl TryClone for SharedMemObject {
+	fn try_clone(&self) -> io::Result<Self> {
+		Ok(Self {
+			fd: c_wrappers::duplicate_fd(self.fd.as_fd())?,
+			size: self.size,
+		})
+	}
+}
+impl AsFd for SharedMemObject {
+	#[inline]
+	fn as_fd(&self) -> BorrowedFd<'_> {
+		self.fd.as_fd()
+	}
+}
+impl From<SharedMemObject> for OwnedFd {
+	#[inline]
+	fn from(obj: SharedMemObject) -> Self {
+		obj.fd
+	}
+}
+impl TryFrom<OwnedFd> for SharedMemObject {
+	type Error = FromFdError;
+	fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+		match fd_size(fd.as_fd()) {
+			Ok(0) => Err(FromFdError {
+				details: Default::default(),
+				cause: Some(invalid_input("shared memory object cannot be empty")),
+				source: Some(fd),
+			}),
+			Ok(size) => Ok(Self { fd, size }),
+			Err(e) => Err(FromFdError::from_source_and_cause(fd, e)),
+		}
+	}
+}
+
+pub(crate) struct Mapping {
+	ptr: NonNull<u8>,
+	len: usize,
+}
+impl Mapping {
+	#[inline]
+	pub fn as_ptr(&self) -> *mut u8 {
+		self.ptr.as_ptr()
+	}
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.len
+	}
+	#[inline]
+	pub fn as_slice(&self) -> &[u8] {
+		unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+	}
+	#[inline]
+	pub fn as_mut_slice(&mut self) -> &mut [u8] {
+		unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+	}
+}
+impl Drop for Mapping {
+	fn drop(&mut self) {
+		unsafe { libc::munmap(self.ptr.as_ptr().cast(), self.len) };
+	}
+}
+impl Debug for Mapping {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Mapping")
+			.field("ptr", &self.ptr)
+			.field("len", &self.len)
+			.finish()
+	}
+}
+// SAFETY: the mapping is just memory; synchronizing access to it is up to the user, as promised
+// when mapping it.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
@@ -1,8 +1,262 @@
 //! Local sockets implemented using Unix domain sockets.
 
+/// Generates socket option accessors for a socket type that implements `AsFd`.
+macro_rules! sockopt_methods {
+	($ty:ident) => {
+		/// Socket options.
+		impl $ty {
+			/// Returns the size of the receive buffer of the socket, as reported by the OS.
+			///
+			/// Note that Linux reports double the size that was set, to account for bookkeeping
+			/// overhead.
+			#[inline]
+			pub fn recv_buffer_size(&self) -> ::std::io::Result<usize> {
+				$crate::os::unix::c_wrappers::recv_buffer_size(::std::os::fd::AsFd::as_fd(self))
+			}
+			/// Sets the size of the receive buffer of the socket. The OS may round the value or
+			/// clamp it to a system-wide limit.
+			#[inline]
+			pub fn set_recv_buffer_size(&self, size: usize) -> ::std::io::Result<()> {
+				$crate::os::unix::c_wrappers::set_recv_buffer_size(
+					::std::os::fd::AsFd::as_fd(self),
+					size,
+				)
+			}
+			/// Returns the size of the send buffer of the socket, as reported by the OS.
+			///
+			/// Note that Linux reports double the size that was set, to account for bookkeeping
+			/// overhead.
+			#[inline]
+			pub fn send_buffer_size(&self) -> ::std::io::Result<usize> {
+				$crate::os::unix::c_wrappers::send_buffer_size(::std::os::fd::AsFd::as_fd(self))
+			}
+			/// Sets the size of the send buffer of the socket. The OS may round the value or clamp
+			/// it to a system-wide limit.
+			#[inline]
+			pub fn set_send_buffer_size(&self, size: usize) -> ::std::io::Result<()> {
+				$crate::os::unix::c_wrappers::set_send_buffer_size(
+					::std::os::fd::AsFd::as_fd(self),
+					size,
+				)
+			}
+			/// Returns the linger timeout of the socket (`SO_LINGER`), or `None` if lingering is
+			/// disabled.
+			#[inline]
+			pub fn linger(&self) -> ::std::io::Result<Option<::std::time::Duration>> {
+				$crate::os::unix::c_wrappers::linger(::std::os::fd::AsFd::as_fd(self))
+			}
+			/// Sets the linger timeout of the socket (`SO_LINGER`), which controls how long closing
+			/// the socket may block to wait for unsent data to be delivered. `None` disables
+			/// lingering. The timeout has a granularity of one second.
+			#[inline]
+			pub fn set_linger(
+				&self,
+				linger: Option<::std::time::Duration>,
+			) -> ::std::io::Result<()> {
+				$crate::os::unix::c_wrappers::set_linger(::std::os::fd::AsFd::as_fd(self), linger)
+			}
+			/// Returns the receive timeout of the socket (`SO_RCVTIMEO`), or `None` if receive
+			/// operations wait indefinitely.
+			#[inline]
+			pub fn recv_timeout(&self) -> ::std::io::Result<Option<::std::time::Duration>> {
+				$crate::os::unix::c_wrappers::recv_timeout(::std::os::fd::AsFd::as_fd(self))
+			}
+			/// Sets the receive timeout of the socket (`SO_RCVTIMEO`), after which blocking receive
+			/// operations fail with an error of kind
+			/// [`WouldBlock`](::std::io::ErrorKind::WouldBlock). On listeners, this applies to
+			/// accepting on most platforms. `None` lets them wait indefinitely, which is the
+			/// default.
+			///
+			/// A zero duration is rejected with an error of kind
+			/// [`InvalidInput`](::std::io::ErrorKind::InvalidInput).
+			#[inline]
+			pub fn set_recv_timeout(
+				&self,
+				timeout: Option<::std::time::Duration>,
+			) -> ::std::io::Result<()> {
+				$crate::os::unix::c_wrappers::set_recv_timeout(
+					::std::os::fd::AsFd::as_fd(self),
+					timeout,
+				)
+			}
+			/// Returns the send timeout of the socket (`SO_SNDTIMEO`), or `None` if send
+			/// operations wait indefinitely.
+			#[inline]
+			pub fn send_timeout(&self) -> ::std::io::Result<Option<::std::time::Duration>> {
+				$crate::os::unix::c_wrappers::send_timeout(::std::os::fd::AsFd::as_fd(self))
+			}
+			/// Sets the send timeout of the socket (`SO_SNDTIMEO`), after which blocking send
+			/// operations fail with an error of kind
+			/// [`WouldBlock`](::std::io::ErrorKind::WouldBlock). `None` lets them wait
+			/// indefinitely, which is the default.
+			///
+			/// A zero duration is rejected with an error of kind
+			/// [`InvalidInput`](::std::io::ErrorKind::InvalidInput).
+			#[inline]
+			pub fn set_send_timeout(
+				&self,
+				timeout: Option<::std::time::Duration>,
+			) -> ::std::io::Result<()> {
+				$crate::os::unix::c_wrappers::set_send_timeout(
+					::std::os::fd::AsFd::as_fd(self),
+					timeout,
+				)
+			}
+			/// Retrieves and clears the pending error of the socket (`SO_ERROR`), or returns `None`
+			/// if there is none.
+			///
+			/// This is useful for finding out why a nonblocking operation failed when the error is
+			/// only reported asynchronously.
+			#[inline]
+			pub fn take_error(&self) -> ::std::io::Result<Option<::std::io::Error>> {
+				$crate::os::unix::c_wrappers::take_error(::std::os::fd::AsFd::as_fd(self))
+			}
+			/// Enables or disables the reception of the peer's credentials (`SO_PASSCRED`).
+			#[cfg(any(target_os = "linux", target_os = "android"))]
+			#[cfg_attr(
+				feature = "doc_cfg",
+				doc(cfg(any(target_os = "linux", target_os = "android")))
+			)]
+			#[inline]
+			pub fn set_passcred(&self, passcred: bool) -> ::std::io::Result<()> {
+				$crate::os::unix::c_wrappers::set_passcred(
+					::std::os::fd::AsFd::as_fd(self),
+					passcred,
+				)
+			}
+		}
+	};
+}
+
+/// Generates accessors for information about the peer for a stream type that implements `AsFd`.
+macro_rules! peer_methods {
+	($ty:ident) => {
+		/// Peer information.
+		impl $ty {
+			/// Returns the credentials of the process on the other end of the connection, as they
+			/// were when the connection was established.
+			///
+			/// Fails with [`Unsupported`](::std::io::ErrorKind::Unsupported) on platforms which
+			/// provide no way of retrieving them.
+			#[inline]
+			pub fn peer_credentials(
+				&self,
+			) -> ::std::io::Result<$crate::os::unix::uds_local_socket::PeerCredentials> {
+				let (pid, uid, gid) = $crate::os::unix::c_wrappers::peer_credentials(
+					::std::os::fd::AsFd::as_fd(self),
+				)?;
+				Ok($crate::os::unix::uds_local_socket::PeerCredentials { pid, uid, gid })
+			}
+			/// Returns the supplementary groups of the process on the other end of the
+			/// connection, as they were when the connection was established.
+			///
+			/// ## Platform-specific behavior
+			/// On Linux, this uses `SO_PEERGROUPS`, which requires Linux 4.13 or later. On Apple
+			/// platforms, FreeBSD and DragonFly BSD, `LOCAL_PEERCRED` is used, which reports at
+			/// most 15 supplementary groups. Elsewhere, this fails with
+			/// [`Unsupported`](::std::io::ErrorKind::Unsupported).
+			#[inline]
+			pub fn peer_groups(&self) -> ::std::io::Result<Vec<::libc::gid_t>> {
+				$crate::os::unix::c_wrappers::peer_groups(::std::os::fd::AsFd::as_fd(self))
+			}
+			/// Returns the security label of the process on the other end of the connection
+			/// (`SO_PEERSEC`), as assigned by the Linux security module in use, such as SELinux or
+			/// AppArmor.
+			///
+			/// Fails with an error of raw OS error `ENOPROTOOPT` if no security module which
+			/// labels sockets is active.
+			#[cfg(any(target_os = "linux", target_os = "android"))]
+			#[cfg_attr(
+				feature = "doc_cfg",
+				doc(cfg(any(target_os = "linux", target_os = "android")))
+			)]
+			#[inline]
+			pub fn peer_security_context(
+				&self,
+			) -> ::std::io::Result<$crate::os::unix::uds_local_socket::SecurityContext> {
+				$crate::os::unix::c_wrappers::peer_security_context(::std::os::fd::AsFd::as_fd(
+					self,
+				))
+				.map($crate::os::unix::uds_local_socket::SecurityContext::from)
+			}
+		}
+	};
+}
+
 mod listener;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod security_context;
 mod stream;
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[cfg_attr(
+	feature = "doc_cfg",
+	doc(cfg(any(target_os = "linux", target_os = "android")))
+)]
+pub use security_context::*;
+
+/// Credentials of the process on the other end of a Unix domain socket connection, as returned by
+/// the `peer_credentials()` method of [`Stream`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PeerCredentials {
+	/// The process ID of the peer. Only available on Linux and Android.
+	pub pid: Option<libc::pid_t>,
+	/// The effective user ID of the peer.
+	pub uid: libc::uid_t,
+	/// The effective group ID of the peer.
+	pub gid: libc::gid_t,
+}
+
+/// Credentials sent along with data as `SCM_CREDENTIALS` ancillary data, as accepted by the
+/// `write_with_credentials()` method of [`Stream`] and returned by its `read_with_credentials()`
+/// method.
+///
+/// The kernel only lets a process send credentials which it could assume itself: its own process
+/// ID and its real, effective or saved user and group IDs, unless it has the `CAP_SYS_ADMIN`,
+/// `CAP_SETUID` or `CAP_SETGID` capability, respectively.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[cfg_attr(
+	feature = "doc_cfg",
+	doc(cfg(any(target_os = "linux", target_os = "android")))
+)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Credentials {
+	/// The process ID.
+	pub pid: libc::pid_t,
+	/// The user ID.
+	pub uid: libc::uid_t,
+	/// The group ID.
+	pub gid: libc::gid_t,
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl Credentials {
+	/// Returns the process ID, effective user ID and effective group ID of the current process,
+	/// which can always be sent.
+	pub fn current() -> Self {
+		unsafe {
+			Self {
+				pid: libc::getpid(),
+				uid: libc::geteuid(),
+				gid: libc::getegid(),
+			}
+		}
+	}
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl From<Credentials> for libc::ucred {
+	#[inline]
+	fn from(Credentials { pid, uid, gid }: Credentials) -> Self {
+		Self { pid, uid, gid }
+	}
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl From<libc::ucred> for Credentials {
+	#[inline]
+	fn from(libc::ucred { pid, uid, gid }: libc::ucred) -> Self {
+		Self { pid, uid, gid }
+	}
+}
+
 pub use {listener::*, stream::*};
 
 #[cfg(feature = "tokio")]
@@ -13,37 +267,43 @@ pub(crate) mod tokio {
 }
 
 use crate::{
-	local_socket::{Name, NameInner},
-	os::unix::unixprelude::*,
+	local_socket::{Liveness, Name, NameInner},
+	os::unix::{c_wrappers, local_socket::OwnedSocketFile, unixprelude::*},
 };
 #[cfg(any(target_os = "linux", target_os = "android"))]
 use std::os::linux::net::SocketAddrExt;
-use std::{borrow::Cow, ffi::OsStr, fs, io, mem, os::unix::net::SocketAddr, path::Path};
+use std::{
+	borrow::Cow,
+	ffi::OsStr,
+	fs, io, mem,
+	os::unix::{fs::FileTypeExt, net::SocketAddr},
+	path::Path,
+};
 
-#[derive(Clone, Debug, Default)]
-struct ReclaimGuard(Option<Name<'static>>);
+#[derive(Debug, Default)]
+struct ReclaimGuard(Option<OwnedSocketFile>);
 impl ReclaimGuard {
-	fn new(name: Name<'static>) -> Self {
-		Self(if name.is_path() { Some(name) } else { None })
+	/// Takes ownership of the socket file that the name refers to, if any. If the file can't be
+	/// inspected right after binding, there's nothing which could safely be reclaimed.
+	fn new(name: &Name<'_>) -> Self {
+		Self(match &name.0 {
+			NameInner::UdSocketPath(path) => OwnedSocketFile::new(Path::new(&**path)).ok(),
+			_ => None,
+		})
 	}
 	#[cfg_attr(not(feature = "tokio"), allow(dead_code))]
 	fn take(&mut self) -> Self {
 		Self(self.0.take())
 	}
 	fn forget(&mut self) {
-		self.0 = None;
-	}
-}
-impl Drop for ReclaimGuard {
-	fn drop(&mut self) {
-		if let Self(Some(Name(NameInner::UdSocketPath(path)))) = self {
-			let _ = std::fs::remove_file(path);
+		if let Some(file) = self.0.take() {
+			file.release();
 		}
 	}
 }
 
 #[allow(clippy::indexing_slicing)]
-fn name_to_addr(name: Name<'_>, create_dirs: bool) -> io::Result<SocketAddr> {
+pub(super) fn name_to_addr(name: Name<'_>, create_dirs: bool) -> io::Result<SocketAddr> {
 	match name.0 {
 		NameInner::UdSocketPath(path) => SocketAddr::from_pathname(path),
 		NameInner::UdSocketPseudoNs(name) => construct_and_prepare_pseudo_ns(name, create_dirs),
@@ -52,11 +312,43 @@ fn name_to_addr(name: Name<'_>, create_dirs: bool) -> io::Result<SocketAddr> {
 	}
 }
 
-const SUN_LEN: usize = {
+pub(crate) fn probe(name: Name<'_>) -> io::Result<Liveness> {
+	probe_addr(&name_to_addr(name, false)?)
+}
+
+/// Probes the address with a nonblocking connection attempt, so that a server with a full listen
+/// queue doesn't make the probe hang.
+fn probe_addr(addr: &SocketAddr) -> io::Result<Liveness> {
+	let sock = c_wrappers::create_socket(libc::SOCK_STREAM, true)?;
+	match c_wrappers::connect(sock.as_fd(), addr) {
+		Ok(..) => Ok(Liveness::Listening),
+		// A full listen queue still means that there is a listener
+		Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(Liveness::Listening),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Liveness::Absent),
+		Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+			// Only socket files can outlive their listeners
+			let Some(path) = addr.as_pathname() else {
+				return Ok(Liveness::Absent);
+			};
+			// Connecting to a file which isn't a socket is refused as well
+			match fs::symlink_metadata(path) {
+				Ok(meta) if meta.file_type().is_socket() => Ok(Liveness::Stale),
+				Ok(..) => Err(io::Error::new(io::ErrorKind::AlreadyExists, NOT_A_SOCKET)),
+				Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Liveness::Absent),
+				Err(e) => Err(e),
+			}
+		}
+		Err(e) => Err(e),
+	}
+}
+
+static NOT_A_SOCKET: &str = "local socket name refers to a file which is not a socket";
+
+pub(crate) const SUN_LEN: usize = {
 	let dummy = unsafe { mem::zeroed::<libc::sockaddr_un>() };
 	dummy.sun_path.len()
 };
-const NMCAP: usize = SUN_LEN - "/run/user/18446744073709551614/".len();
+pub(crate) const NMCAP: usize = SUN_LEN - "/run/user/18446744073709551614/".len();
 
 static TOOLONG: &str = "local socket name length exceeds capacity of sun_path of sockaddr_un";
 
@@ -107,3 +399,26 @@ fn construct_and_prepare_pseudo_ns(
 	}
 	SocketAddr::from_pathname(opath)
 }
+
+/// Ancillary data which arrived with the data at the front of the receive queue, as returned by
+/// the `peek_ancillary()` method of [`Stream`].
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct PeekedAncillary {
+	/// Duplicates of the file descriptors sent as `SCM_RIGHTS` ancillary data.
+	pub fds: Vec<std::os::fd::OwnedFd>,
+	/// The credentials attached to the data, which are only received if `set_passcred()` has been
+	/// enabled on the stream.
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	#[cfg_attr(
+		feature = "doc_cfg",
+		doc(cfg(any(target_os = "linux", target_os = "android")))
+	)]
+	pub credentials: Option<Credentials>,
+	/// Whether some of the ancillary data didn't fit, which happens when more descriptors are
+	/// attached than there was room for (`MSG_CTRUNC` in the flags returned by `recvmsg()`).
+	///
+	/// The kernel never truncates the data itself or marks the end of a record on stream sockets,
+	/// so there are no flags for those.
+	pub ancillary_truncated: bool,
+}
@@ -1,17 +1,19 @@
-use super::{name_to_addr, ReclaimGuard, Stream};
+use super::{name_to_addr, probe_addr, ReclaimGuard, Stream};
 use crate::{
 	local_socket::{
 		traits::{self, Stream as _},
-		ListenerNonblockingMode, ListenerOptions,
+		ListenerNonblockingMode, ListenerOptions, Liveness,
 	},
-	os::unix::c_wrappers,
+	os::unix::{c_wrappers, local_socket::OwnedSocketFile},
+	TryClone,
 };
 use std::{
-	io,
+	fs, io,
 	os::{
 		fd::{AsFd, BorrowedFd, OwnedFd},
 		unix::net::UnixListener,
 	},
+	path::Path,
 	sync::atomic::{AtomicBool, Ordering::SeqCst},
 };
 
@@ -31,33 +33,119 @@ impl Listener {
 		})
 	}
 }
+/// Handoff.
+impl Listener {
+	/// Returns the path of the socket file which the listener reclaims on drop, if any, and
+	/// whether accepted streams are put in nonblocking mode.
+	pub(crate) fn handoff_state(&self) -> (Option<&Path>, bool) {
+		(
+			self.reclaim.0.as_ref().map(OwnedSocketFile::path),
+			self.nonblocking_streams.load(SeqCst),
+		)
+	}
+	/// Reassembles a listener from a descriptor and the state returned by
+	/// [`handoff_state()`](Self::handoff_state).
+	pub(crate) fn from_handoff(
+		fd: OwnedFd,
+		reclaim: Option<OwnedSocketFile>,
+		nonblocking_streams: bool,
+	) -> Self {
+		Self {
+			listener: fd.into(),
+			reclaim: ReclaimGuard(reclaim),
+			nonblocking_streams: AtomicBool::new(nonblocking_streams),
+		}
+	}
+}
+/// Socket file.
+impl Listener {
+	/// Detaches the socket file from the listener, returning a guard which unlinks it when dropped
+	/// instead of the listener. This disables
+	/// [name reclamation](crate::local_socket::Listener#name-reclamation) on the listener, leaving
+	/// the lifetime of the socket file up to the guard.
+	///
+	/// Returns `None` if the listener doesn't reclaim its name, either because it was told not to
+	/// or because it isn't bound to a filesystem path.
+	#[inline]
+	pub fn take_socket_file(&mut self) -> Option<OwnedSocketFile> {
+		self.reclaim.0.take()
+	}
+}
+/// Listen queue.
+impl Listener {
+	/// Returns the effective maximum length of the queue of pending connections, as set by the
+	/// [`backlog`](crate::os::unix::local_socket::ListenerOptionsExt::backlog) option and clamped by
+	/// the system-wide limit.
+	#[cfg(target_os = "freebsd")]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(target_os = "freebsd")))]
+	#[inline]
+	pub fn backlog(&self) -> io::Result<libc::c_int> {
+		c_wrappers::listen_queue_limit(self.as_fd())
+	}
+}
+sockopt_methods!(Listener);
+
+/// The clone refers to the same listening socket, sharing its queue of pending connections and
+/// its nonblocking mode for accepting connections, and has the same nonblocking mode for accepted
+/// streams. Only the original listener performs
+/// [name reclamation](crate::local_socket::Listener#name-reclamation).
+impl TryClone for Listener {
+	fn try_clone(&self) -> io::Result<Self> {
+		Ok(Self {
+			listener: self.listener.try_clone()?,
+			reclaim: ReclaimGuard::default(),
+			nonblocking_streams: AtomicBool::new(self.nonblocking_streams.load(SeqCst)),
+		})
+	}
+}
 impl crate::Sealed for Listener {}
 impl traits::Listener for Listener {
 	type Stream = Stream;
 
 	fn from_options(options: ListenerOptions<'_>) -> io::Result<Self> {
 		let nonblocking = options.nonblocking.accept_nonblocking();
+		let addr = name_to_addr(options.name.borrow(), true)?;
 
-		let listener = c_wrappers::bind_and_listen_with_mode(
-			libc::SOCK_STREAM,
-			&name_to_addr(options.name.borrow(), true)?,
-			nonblocking,
-			options.mode,
-		)
-		.map(UnixListener::from)
-		.map_err(Self::decode_listen_error)?;
-
-		if !c_wrappers::CAN_CREATE_NONBLOCKING && nonblocking {
-			listener.set_nonblocking(true)?;
-		}
+		let bind = || {
+			c_wrappers::bind_and_listen_with_mode(
+				libc::SOCK_STREAM,
+				&addr,
+				nonblocking,
+				options.mode,
+				options.backlog,
+				|fd| {
+					options
+						.configure_socket
+						.as_ref()
+						.map_or(Ok(()), |hook| (hook.0)(fd))
+				},
+			)
+			.map(UnixListener::from)
+			.map_err(Self::decode_listen_error)
+		};
+		let listener = match bind() {
+			Err(e) if e.kind() == io::ErrorKind::AddrInUse && options.replace_dead_socket => {
+				match probe_addr(&addr) {
+					Ok(Liveness::Stale) => {}
+					// Files that aren't sockets are never touched
+					Err(pe) if pe.kind() != io::ErrorKind::AlreadyExists => return Err(pe),
+					_ => return Err(e),
+				}
+				if let Some(path) = addr.as_pathname() {
+					fs::remove_file(path)?;
+				}
+				bind()?
+			}
+			els => els?,
+		};
 
 		Ok(Self {
 			listener,
-			reclaim: options
-				.reclaim_name
-				.then(|| options.name.into_owned())
-				.map(ReclaimGuard::new)
-				.unwrap_or_default(),
+			reclaim: if options.reclaim_name {
+				ReclaimGuard::new(&options.name)
+			} else {
+				ReclaimGuard::default()
+			},
 			nonblocking_streams: AtomicBool::new(options.nonblocking.stream_nonblocking()),
 		})
 	}
@@ -65,6 +153,7 @@ impl traits::Listener for Listener {
 	fn accept(&self) -> io::Result<Stream> {
 		// TODO(2.1.0) make use of the second return value in some shape or form
 		let stream = self.listener.accept().map(|(s, _)| Stream::from(s))?;
+		c_wrappers::prepare_stream(stream.as_fd())?;
 		if self.nonblocking_streams.load(SeqCst) {
 			stream.set_nonblocking(true)?;
 		}
@@ -90,6 +179,18 @@ impl From<Listener> for UnixListener {
 		l.listener
 	}
 }
+/// Wraps a listener created by other means. Its name is not reclaimed on drop, and the nonblocking
+/// mode of accepted streams is left to the OS, as with [`ListenerNonblockingMode::Neither`] or
+/// [`ListenerNonblockingMode::Accept`].
+impl From<UnixListener> for Listener {
+	fn from(listener: UnixListener) -> Self {
+		Listener {
+			listener,
+			reclaim: ReclaimGuard::default(),
+			nonblocking_streams: AtomicBool::new(false),
+		}
+	}
+}
 
 impl AsFd for Listener {
 	#[inline]
@@ -105,10 +206,6 @@ impl From<Listener> for OwnedFd {
 }
 impl From<OwnedFd> for Listener {
 	fn from(fd: OwnedFd) -> Self {
-		Listener {
-			listener: fd.into(),
-			reclaim: ReclaimGuard::default(),
-			nonblocking_streams: AtomicBool::new(false),
-		}
+		UnixListener::from(fd).into()
 	}
 }
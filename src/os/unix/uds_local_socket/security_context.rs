@@ -0,0 +1,112 @@
+use std::{
+	fmt::{self, Debug, Formatter},
+	str,
+};
+
+/// Security label of the process on the other end of a Unix domain socket connection, as
+/// assigned by the Linux security module in use and returned by the `peer_security_context()`
+/// method of [`Stream`](super::Stream).
+///
+/// The label is kept in its raw form, which is what should be passed on to policy engines, and
+/// can additionally be [parsed](Self::parse) into its components for the common case of SELinux
+/// and AppArmor labels.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SecurityContext(Vec<u8>);
+impl SecurityContext {
+	/// Returns the raw label, without a terminating nul byte.
+	#[inline]
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.0
+	}
+	/// Returns the raw label, without a terminating nul byte.
+	#[inline]
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.0
+	}
+	/// Returns the label as a string, or `None` if it isn't valid UTF-8.
+	#[inline]
+	pub fn to_str(&self) -> Option<&str> {
+		str::from_utf8(&self.0).ok()
+	}
+	/// Parses the label, recognizing the formats used by SELinux and AppArmor.
+	///
+	/// Since the label doesn't say which security module it came from, its format is determined
+	/// heuristically: labels of the form `user:role:type[:level]` are taken to be SELinux contexts,
+	/// and labels of the form `profile (mode)`, as well as `unconfined`, are taken to be AppArmor
+	/// labels.
+	pub fn parse(&self) -> ParsedSecurityContext<'_> {
+		let Some(label) = self.to_str() else {
+			return ParsedSecurityContext::Other;
+		};
+		if let Some((profile, mode)) = label.strip_suffix(')').and_then(|l| l.rsplit_once(" (")) {
+			return ParsedSecurityContext::AppArmor {
+				profile,
+				mode: Some(mode),
+			};
+		}
+		if label == "unconfined" {
+			return ParsedSecurityContext::AppArmor {
+				profile: label,
+				mode: None,
+			};
+		}
+		let mut parts = label.splitn(4, ':');
+		match (parts.next(), parts.next(), parts.next(), parts.next()) {
+			(Some(user), Some(role), Some(r#type), level)
+				if !user.is_empty() && !role.is_empty() && !r#type.is_empty() =>
+			{
+				ParsedSecurityContext::SeLinux {
+					user,
+					role,
+					r#type,
+					level,
+				}
+			}
+			_ => ParsedSecurityContext::Other,
+		}
+	}
+}
+impl From<Vec<u8>> for SecurityContext {
+	/// Creates a security context from a raw label, stripping a terminating nul byte if there is
+	/// one.
+	#[inline]
+	fn from(mut label: Vec<u8>) -> Self {
+		if label.last() == Some(&0) {
+			label.pop();
+		}
+		Self(label)
+	}
+}
+impl Debug for SecurityContext {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self.to_str() {
+			Some(s) => f.debug_tuple("SecurityContext").field(&s).finish(),
+			None => f.debug_tuple("SecurityContext").field(&self.0).finish(),
+		}
+	}
+}
+
+/// The components of a [`SecurityContext`], as returned by [`SecurityContext::parse()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParsedSecurityContext<'a> {
+	/// An SELinux security context.
+	SeLinux {
+		/// The SELinux user.
+		user: &'a str,
+		/// The role.
+		role: &'a str,
+		/// The type, also known as the domain for processes.
+		r#type: &'a str,
+		/// The MLS/MCS level or range, if the policy uses one.
+		level: Option<&'a str>,
+	},
+	/// An AppArmor label.
+	AppArmor {
+		/// The name of the profile confining the process, or `unconfined`.
+		profile: &'a str,
+		/// The mode of the profile, such as `enforce` or `complain`, if present.
+		mode: Option<&'a str>,
+	},
+	/// A label in an unrecognized format, or one which isn't valid UTF-8.
+	Other,
+}
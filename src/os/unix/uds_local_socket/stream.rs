@@ -1,16 +1,28 @@
 use super::name_to_addr;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use super::Credentials;
+use super::PeekedAncillary;
 use crate::{
 	error::ReuniteError,
+	handle_transfer::HandleTransfer,
 	local_socket::{
 		traits::{self, ReuniteResult},
 		ConcurrencyDetector, LocalSocketSite, Name,
 	},
+	os::unix::c_wrappers,
 	Sealed, TryClone,
 };
 use std::{
+	fs::File,
 	io::{self, prelude::*, IoSlice, IoSliceMut},
-	os::{fd::OwnedFd, unix::net::UnixStream},
+	mem::MaybeUninit,
+	net::Shutdown,
+	os::{
+		fd::{AsFd, OwnedFd},
+		unix::net::UnixStream,
+	},
 	sync::Arc,
+	time::Duration,
 };
 
 /// Wrapper around [`UnixStream`] that implements
@@ -23,7 +35,9 @@ impl traits::Stream for Stream {
 	type SendHalf = SendHalf;
 
 	fn connect(name: Name<'_>) -> io::Result<Self> {
-		UnixStream::connect_addr(&name_to_addr(name, false)?).map(Self::from)
+		let stream = UnixStream::connect_addr(&name_to_addr(name, false)?)?;
+		c_wrappers::prepare_stream(stream.as_fd())?;
+		Ok(Self::from(stream))
 	}
 	#[inline]
 	fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
@@ -46,6 +60,167 @@ impl traits::Stream for Stream {
 	}
 }
 
+impl Stream {
+	/// Creates a pair of connected, unnamed streams using `socketpair(2)`.
+	#[inline]
+	pub fn pair() -> io::Result<(Self, Self)> {
+		let (a, b) = UnixStream::pair()?;
+		c_wrappers::prepare_stream(a.as_fd())?;
+		c_wrappers::prepare_stream(b.as_fd())?;
+		Ok((Self::from(a), Self::from(b)))
+	}
+	/// Receives data from the stream without removing it from the receive queue, returning how many
+	/// bytes were received.
+	///
+	/// Successive calls return the same data, and subsequent reads will receive it again. This is
+	/// useful for inspecting the beginning of a message, such as a header or a protocol signature,
+	/// before deciding how to handle it.
+	pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+		let _guard = self.1.lock();
+		c_wrappers::peek(self.0.as_fd(), buf)
+	}
+	/// Same as [`peek()`](Self::peek), but also returns the ancillary data which arrived with the
+	/// peeked data, accepting up to `max_fds` file descriptors.
+	///
+	/// This lets a dispatcher inspect the descriptors or credentials that come with the next
+	/// message before deciding who should actually receive it. Each call installs a fresh set of
+	/// duplicates of the descriptors in the process, which are independent of those obtained by
+	/// whoever receives the data afterwards, and can simply be dropped once inspected.
+	///
+	/// Since the kernel never merges data sent with different ancillary data into a single
+	/// receive, the returned amount of data is exactly what the ancillary data belongs to. If more
+	/// than `max_fds` descriptors are attached, only the first `max_fds` are returned and
+	/// [`ancillary_truncated`](PeekedAncillary::ancillary_truncated) is set; unlike with a regular
+	/// receive, all of them are left in the queue, so peeking again with more room returns them.
+	pub fn peek_ancillary(
+		&self,
+		buf: &mut [u8],
+		max_fds: usize,
+	) -> io::Result<(usize, PeekedAncillary)> {
+		let _guard = self.1.lock();
+		let recv = c_wrappers::recv_ancillary(self.0.as_fd(), buf, max_fds, true)?;
+		Ok((
+			recv.received,
+			PeekedAncillary {
+				fds: recv.fds,
+				#[cfg(any(target_os = "linux", target_os = "android"))]
+				credentials: recv.cred.map(Credentials::from),
+				ancillary_truncated: recv.ancillary_truncated,
+			},
+		))
+	}
+	/// Same as `.read()` from the [`Read`] trait, but accepts an uninitialized buffer, sparing the
+	/// cost of zeroing large buffers before receiving into them.
+	///
+	/// Upon success, the first as many bytes of `buf` as are returned are initialized.
+	pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+		let _guard = self.1.lock();
+		c_wrappers::recv_uninit(self.0.as_fd(), buf)
+	}
+	/// Shuts down the receive half, the send half, or both halves of the stream, using
+	/// `shutdown(2)`.
+	///
+	/// Shutting down the send half makes the peer receive end of file once it has received
+	/// everything sent before that.
+	#[inline]
+	pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+		self.0.shutdown(how)
+	}
+	/// Sets the timeout for receive operations, after which they fail with an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock). `None` lets them wait indefinitely, which is the
+	/// default.
+	///
+	/// Uses the `SO_RCVTIMEO` socket option. A zero duration is rejected with an error of kind
+	/// [`InvalidInput`](io::ErrorKind::InvalidInput).
+	#[inline]
+	pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+		self.0.set_read_timeout(timeout)
+	}
+	/// Sets the timeout for send operations, after which they fail with an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock). `None` lets them wait indefinitely, which is the
+	/// default.
+	///
+	/// Uses the `SO_SNDTIMEO` socket option. A zero duration is rejected with an error of kind
+	/// [`InvalidInput`](io::ErrorKind::InvalidInput).
+	#[inline]
+	pub fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+		self.0.set_write_timeout(timeout)
+	}
+	/// Sends up to `len` bytes from `file`, starting at `offset`, returning how many bytes were
+	/// sent.
+	///
+	/// On Linux, Android and FreeBSD, this uses `sendfile(2)`, avoiding a copy of the data through
+	/// a userspace buffer. On other platforms, the data is read from the file into a small
+	/// intermediate buffer and then sent. In both cases, the file position of `file` is neither
+	/// used nor changed.
+	///
+	/// Like [`write()`](Write::write), this may send fewer bytes than requested, in which case the
+	/// call should be repeated with the offset advanced by the returned amount. A return value of
+	/// 0 with a nonzero `len` indicates that the end of the file has been reached.
+	pub fn send_file(&self, file: &File, offset: u64, len: usize) -> io::Result<usize> {
+		let _guard = self.1.lock();
+		c_wrappers::sendfile(self.0.as_fd(), file.as_fd(), offset, len)
+	}
+	/// Sends data along with the given credentials, attached as `SCM_CREDENTIALS` ancillary
+	/// data, returning how many bytes were sent.
+	///
+	/// The peer receives the credentials with
+	/// [`read_with_credentials()`](Self::read_with_credentials), and the kernel vouches for them:
+	/// sending credentials which the process could not assume fails with an error of kind
+	/// [`PermissionDenied`](io::ErrorKind::PermissionDenied). See [`Credentials`] for what is
+	/// allowed. Since the credentials are attached to the bytes that carry them, they apply to
+	/// exactly the data written by this call, which is why it should be repeated if not all of
+	/// `buf` was sent.
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	#[cfg_attr(
+		feature = "doc_cfg",
+		doc(cfg(any(target_os = "linux", target_os = "android")))
+	)]
+	pub fn write_with_credentials(&self, buf: &[u8], creds: Credentials) -> io::Result<usize> {
+		let _guard = self.1.lock();
+		c_wrappers::send_with_credentials(self.0.as_fd(), buf, creds.into())
+	}
+	/// Receives data along with the credentials attached to it, returning how many bytes were
+	/// received.
+	///
+	/// Credentials are only received if [`set_passcred()`](Self::set_passcred) has been enabled on
+	/// this stream. The kernel then attaches credentials to everything the peer sends, using its
+	/// process ID and real user and group IDs if it doesn't specify any with
+	/// [`write_with_credentials()`](Self::write_with_credentials). A single call never returns
+	/// data sent with different credentials.
+	///
+	/// File descriptors sent along with the data are closed. Fails with an error of kind
+	/// [`InvalidData`](io::ErrorKind::InvalidData) if so many of them arrive that the ancillary
+	/// data doesn't fit into the buffer reserved for it, in which case the data is still consumed.
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	#[cfg_attr(
+		feature = "doc_cfg",
+		doc(cfg(any(target_os = "linux", target_os = "android")))
+	)]
+	pub fn read_with_credentials(
+		&self,
+		buf: &mut [u8],
+	) -> io::Result<(usize, Option<Credentials>)> {
+		let _guard = self.1.lock();
+		let (received, creds) = c_wrappers::recv_with_credentials(self.0.as_fd(), buf)?;
+		Ok((received, creds.map(Credentials::from)))
+	}
+}
+
+sockopt_methods!(Stream);
+peer_methods!(Stream);
+
+impl HandleTransfer for Stream {
+	fn send_handle(&self, handle: OwnedFd) -> io::Result<()> {
+		let _guard = self.1.lock();
+		c_wrappers::send_fd(self.0.as_fd(), handle.as_fd())
+	}
+	fn recv_handle(&self) -> io::Result<OwnedFd> {
+		let _guard = self.1.lock();
+		c_wrappers::recv_fd(self.0.as_fd())
+	}
+}
+
 impl Read for &Stream {
 	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
 		let _guard = self.1.lock();
@@ -60,11 +235,11 @@ impl Read for &Stream {
 impl Write for &Stream {
 	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
 		let _guard = self.1.lock();
-		(&mut &self.0).write(buf)
+		c_wrappers::send(self.0.as_fd(), buf)
 	}
 	fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
 		let _guard = self.1.lock();
-		(&mut &self.0).write_vectored(bufs)
+		c_wrappers::send_vectored(self.0.as_fd(), bufs)
 	}
 	#[inline]
 	fn flush(&mut self) -> io::Result<()> {
@@ -78,6 +253,12 @@ impl From<UnixStream> for Stream {
 		Self(s, ConcurrencyDetector::new())
 	}
 }
+impl From<Stream> for UnixStream {
+	#[inline]
+	fn from(s: Stream) -> Self {
+		s.0
+	}
+}
 
 impl From<OwnedFd> for Stream {
 	fn from(fd: OwnedFd) -> Self {
@@ -1,13 +1,17 @@
 use super::Stream;
 use crate::{
 	local_socket::{prelude::*, traits::tokio as traits, ListenerNonblockingMode, ListenerOptions},
-	os::unix::uds_local_socket::{listener::Listener as SyncListener, ReclaimGuard},
+	os::unix::{
+		c_wrappers,
+		local_socket::OwnedSocketFile,
+		uds_local_socket::{listener::Listener as SyncListener, ReclaimGuard},
+	},
 	Sealed,
 };
 use std::{
 	fmt::{self, Debug, Formatter},
 	io,
-	os::unix::prelude::*,
+	os::unix::{net::UnixListener as SyncUnixListener, prelude::*},
 };
 use tokio::net::UnixListener;
 
@@ -15,6 +19,18 @@ pub struct Listener {
 	listener: UnixListener,
 	reclaim: ReclaimGuard,
 }
+/// Socket file.
+impl Listener {
+	/// Detaches the socket file from the listener, returning a guard which unlinks it when dropped
+	/// instead of the listener. See the
+	/// [equivalent method on the synchronous listener](SyncListener::take_socket_file).
+	#[inline]
+	pub fn take_socket_file(&mut self) -> Option<OwnedSocketFile> {
+		self.reclaim.0.take()
+	}
+}
+sockopt_methods!(Listener);
+
 impl Sealed for Listener {}
 impl traits::Listener for Listener {
 	type Stream = Stream;
@@ -27,6 +43,7 @@ impl traits::Listener for Listener {
 	}
 	async fn accept(&self) -> io::Result<Stream> {
 		let inner = self.listener.accept().await?.0;
+		c_wrappers::prepare_stream(inner.as_fd())?;
 		Ok(Stream::from(inner))
 	}
 
@@ -61,15 +78,45 @@ impl AsFd for Listener {
 		self.listener.as_fd()
 	}
 }
-impl TryFrom<Listener> for OwnedFd {
+/// Wraps a listener created by other means. Its name is not reclaimed on drop.
+impl From<UnixListener> for Listener {
+	fn from(listener: UnixListener) -> Self {
+		Self {
+			listener,
+			reclaim: ReclaimGuard::default(),
+		}
+	}
+}
+impl From<Listener> for UnixListener {
+	fn from(mut slf: Listener) -> Self {
+		slf.reclaim.forget();
+		slf.listener
+	}
+}
+/// Puts the listener in nonblocking mode and registers it with the Tokio runtime, failing if called
+/// outside of one. Its name is not reclaimed on drop.
+impl TryFrom<SyncUnixListener> for Listener {
+	type Error = io::Error;
+	fn try_from(listener: SyncUnixListener) -> io::Result<Self> {
+		Self::try_from(SyncListener::from(listener))
+	}
+}
+/// The resulting listener is left in nonblocking mode.
+impl TryFrom<Listener> for SyncUnixListener {
 	type Error = io::Error;
 	fn try_from(mut slf: Listener) -> io::Result<Self> {
 		slf.listener.into_std().map(|s| {
 			slf.reclaim.forget();
-			s.into()
+			s
 		})
 	}
 }
+impl TryFrom<Listener> for OwnedFd {
+	type Error = io::Error;
+	fn try_from(slf: Listener) -> io::Result<Self> {
+		SyncUnixListener::try_from(slf).map(Self::from)
+	}
+}
 impl TryFrom<OwnedFd> for Listener {
 	type Error = io::Error;
 	fn try_from(fd: OwnedFd) -> io::Result<Self> {
@@ -6,6 +6,7 @@ use crate::{
 	Sealed,
 };
 use std::{
+	fs::File,
 	io::{self, ErrorKind::WouldBlock},
 	net::Shutdown,
 	os::{
@@ -19,7 +20,7 @@ use std::{
 	task::{ready, Context, Poll},
 };
 use tokio::{
-	io::{AsyncRead, AsyncWrite, ReadBuf},
+	io::{AsyncRead, AsyncWrite, Interest, ReadBuf, Ready},
 	net::{
 		unix::{OwnedReadHalf as RecvHalfImpl, OwnedWriteHalf as SendHalfImpl},
 		UnixStream,
@@ -52,16 +53,148 @@ impl Stream {
 		}
 		UnixStream::connect(addr.as_pathname().unwrap()).await
 	}
+
+	/// Asynchronous counterpart of
+	/// [`HandleTransfer::send_handle()`](crate::handle_transfer::HandleTransfer::send_handle),
+	/// using the same wire format.
+	pub async fn send_handle(&self, handle: OwnedFd) -> io::Result<()> {
+		self.0
+			.async_io(Interest::WRITABLE, || {
+				c_wrappers::send_fd(self.0.as_fd(), handle.as_fd())
+			})
+			.await
+	}
+	/// Asynchronous counterpart of
+	/// [`HandleTransfer::recv_handle()`](crate::handle_transfer::HandleTransfer::recv_handle),
+	/// using the same wire format.
+	pub async fn recv_handle(&self) -> io::Result<OwnedFd> {
+		self.0
+			.async_io(Interest::READABLE, || c_wrappers::recv_fd(self.0.as_fd()))
+			.await
+	}
+
+	/// Connects to the given name, letting `configure` set up the socket before the connection is
+	/// initiated.
+	///
+	/// See the documentation of the
+	/// [enum-level version](crate::local_socket::tokio::Stream::connect_with) for details.
+	pub async fn connect_with<F>(name: Name<'_>, configure: F) -> io::Result<Self>
+	where
+		F: FnOnce(BorrowedFd<'_>) -> io::Result<()>,
+	{
+		let addr = name_to_addr(name, false)?;
+		let fd = c_wrappers::create_socket(libc::SOCK_STREAM, true)?;
+		configure(fd.as_fd())?;
+		let connected = c_wrappers::connect(fd.as_fd(), &addr)?;
+		let stream = UnixStream::from_std(SyncUnixStream::from(fd))?;
+		if !connected {
+			stream.writable().await?;
+			if let Some(e) = stream.take_error()? {
+				return Err(e);
+			}
+		}
+		c_wrappers::prepare_stream(stream.as_fd())?;
+		Ok(Self::from(stream))
+	}
+
+	/// Asynchronously receives data from the stream without removing it from the receive queue,
+	/// returning how many bytes were received.
+	///
+	/// See the documentation of the
+	/// [synchronous version](crate::os::unix::uds_local_socket::Stream::peek) for details.
+	pub async fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+		self.0
+			.async_io(Interest::READABLE, || c_wrappers::peek(self.0.as_fd(), buf))
+			.await
+	}
+
+	/// Asynchronously sends up to `len` bytes from `file`, starting at `offset`, returning how many
+	/// bytes were sent.
+	///
+	/// See the documentation of the
+	/// [synchronous version](crate::os::unix::uds_local_socket::Stream::send_file) for details.
+	pub async fn send_file(&self, file: &File, offset: u64, len: usize) -> io::Result<usize> {
+		self.0
+			.async_io(Interest::WRITABLE, || {
+				c_wrappers::sendfile(self.0.as_fd(), file.as_fd(), offset, len)
+			})
+			.await
+	}
+
+	/// Waits for any of the requested ready states, returning the ready set that was observed.
+	///
+	/// This and the other readiness methods mirror those of Tokio's
+	/// [`UnixStream`](tokio::net::UnixStream), and allow the stream to be driven by hand, for
+	/// instance to fill a buffer with as much as can be received before processing it, or to
+	/// batch up writes until the socket stops accepting data. Like with Tokio's, readiness may be
+	/// spurious, in which case the `try_` methods fail with
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) and the wait should be repeated.
+	#[inline]
+	pub async fn ready(&self, interest: Interest) -> io::Result<Ready> {
+		self.0.ready(interest).await
+	}
+	/// Waits for the stream to become readable.
+	#[inline]
+	pub async fn readable(&self) -> io::Result<()> {
+		self.0.readable().await
+	}
+	/// Waits for the stream to become writable.
+	#[inline]
+	pub async fn writable(&self) -> io::Result<()> {
+		self.0.writable().await
+	}
+	/// Polls for read readiness, registering the current task for wakeup if the stream is not
+	/// ready yet.
+	#[inline]
+	pub fn poll_read_ready(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		self.0.poll_read_ready(cx)
+	}
+	/// Polls for write readiness, registering the current task for wakeup if the stream is not
+	/// ready yet.
+	#[inline]
+	pub fn poll_write_ready(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		self.0.poll_write_ready(cx)
+	}
+	/// Tries to receive data without waiting, returning how many bytes were received, or an error
+	/// of kind [`WouldBlock`](io::ErrorKind::WouldBlock) if none are available, in which case the
+	/// readiness of the stream is cleared.
+	#[inline]
+	pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+		self.0.try_read(buf)
+	}
+	/// Same as [`try_read()`](Self::try_read), but receives into multiple buffers.
+	#[inline]
+	pub fn try_read_vectored(&self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+		self.0.try_read_vectored(bufs)
+	}
+	/// Tries to send data without waiting, returning how many bytes were sent, or an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) if the send buffer is full, in which case the
+	/// readiness of the stream is cleared.
+	///
+	/// Like the other writes to the stream, this honors
+	/// [`SIGPIPE` suppression](crate::os::unix::set_sigpipe_suppression).
+	#[inline]
+	pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+		send(&self.0, || c_wrappers::send(self.0.as_fd(), buf))
+	}
+	/// Same as [`try_write()`](Self::try_write), but sends from multiple buffers.
+	#[inline]
+	pub fn try_write_vectored(&self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+		send(&self.0, || c_wrappers::send_vectored(self.0.as_fd(), bufs))
+	}
 }
 
+sockopt_methods!(Stream);
+peer_methods!(Stream);
+
 impl traits::Stream for Stream {
 	type RecvHalf = RecvHalf;
 	type SendHalf = SendHalf;
 
 	async fn connect(name: Name<'_>) -> io::Result<Self> {
-		Self::_connect(name_to_addr(name, false)?)
-			.await
-			.map(Self::from)
+		let stream = Self::_connect(name_to_addr(name, false)?).await?;
+		c_wrappers::prepare_stream(stream.as_fd())?;
+		Ok(Self::from(stream))
 	}
 	fn split(self) -> (RecvHalf, SendHalf) {
 		let (r, w) = self.0.into_split();
@@ -90,6 +223,11 @@ fn ioloop(
 	}
 }
 
+/// Performs a send via the readiness tracking of the stream, so that `WouldBlock` clears it.
+fn send(stream: &UnixStream, f: impl FnOnce() -> io::Result<usize>) -> io::Result<usize> {
+	stream.try_io(Interest::WRITABLE, f)
+}
+
 multimacro! {
 	Stream,
 	pinproj_for_unpin(UnixStream),
@@ -115,7 +253,10 @@ impl AsyncWrite for &Stream {
 		cx: &mut Context<'_>,
 		buf: &[u8],
 	) -> Poll<io::Result<usize>> {
-		ioloop(|| self.0.try_write(buf), || self.0.poll_write_ready(cx))
+		ioloop(
+			|| send(&self.0, || c_wrappers::send(self.0.as_fd(), buf)),
+			|| self.0.poll_write_ready(cx),
+		)
 	}
 	#[inline]
 	fn poll_write_vectored(
@@ -124,7 +265,7 @@ impl AsyncWrite for &Stream {
 		bufs: &[io::IoSlice<'_>],
 	) -> Poll<io::Result<usize>> {
 		ioloop(
-			|| self.0.try_write_vectored(bufs),
+			|| send(&self.0, || c_wrappers::send_vectored(self.0.as_fd(), bufs)),
 			|| self.0.poll_write_ready(cx),
 		)
 	}
@@ -141,18 +282,36 @@ impl AsyncWrite for &Stream {
 		shutdown(self.get_mut())
 	}
 }
+/// The resulting stream is left in nonblocking mode.
+impl TryFrom<Stream> for SyncUnixStream {
+	type Error = io::Error;
+	#[inline]
+	fn try_from(slf: Stream) -> io::Result<Self> {
+		slf.0.into_std()
+	}
+}
+/// Puts the stream in nonblocking mode and registers it with the Tokio runtime, failing if called
+/// outside of one.
+impl TryFrom<SyncUnixStream> for Stream {
+	type Error = io::Error;
+	#[inline]
+	fn try_from(stream: SyncUnixStream) -> io::Result<Self> {
+		stream.set_nonblocking(true)?;
+		Ok(UnixStream::from_std(stream)?.into())
+	}
+}
 impl TryFrom<Stream> for OwnedFd {
 	type Error = io::Error;
 	#[inline]
 	fn try_from(slf: Stream) -> io::Result<Self> {
-		Ok(slf.0.into_std()?.into())
+		Ok(SyncUnixStream::try_from(slf)?.into())
 	}
 }
 impl TryFrom<OwnedFd> for Stream {
 	type Error = io::Error;
 	#[inline]
 	fn try_from(fd: OwnedFd) -> io::Result<Self> {
-		Ok(UnixStream::from_std(SyncUnixStream::from(fd))?.into())
+		Self::try_from(SyncUnixStream::from(fd))
 	}
 }
 
@@ -207,9 +366,10 @@ impl AsyncWrite for &SendHalf {
 		cx: &mut Context<'_>,
 		buf: &[u8],
 	) -> Poll<io::Result<usize>> {
+		let stream = self.0.as_ref();
 		ioloop(
-			|| self.0.try_write(buf),
-			|| self.0.as_ref().poll_write_ready(cx),
+			|| send(stream, || c_wrappers::send(stream.as_fd(), buf)),
+			|| stream.poll_write_ready(cx),
 		)
 	}
 	#[inline]
@@ -218,9 +378,10 @@ impl AsyncWrite for &SendHalf {
 		cx: &mut Context<'_>,
 		bufs: &[io::IoSlice<'_>],
 	) -> Poll<io::Result<usize>> {
+		let stream = self.0.as_ref();
 		ioloop(
-			|| self.0.try_write_vectored(bufs),
-			|| self.0.as_ref().poll_write_ready(cx),
+			|| send(stream, || c_wrappers::send_vectored(stream.as_fd(), bufs)),
+			|| stream.poll_write_ready(cx),
 		)
 	}
 	#[inline]
@@ -1,42 +1,34 @@
-use super::FdOps;
+#[cfg(feature = "tokio")]
+pub(crate) mod tokio;
+
+use super::{c_wrappers, FdOps};
 use crate::{
 	unnamed_pipe::{Recver as PubRecver, Sender as PubSender},
 	Sealed,
 };
-use libc::c_int;
 use std::{
 	fmt::{self, Debug, Formatter},
 	io,
-	os::{
-		fd::OwnedFd,
-		unix::io::{AsRawFd, FromRawFd},
-	},
+	os::{fd::AsFd, unix::io::AsRawFd},
 };
 
 pub(crate) fn pipe() -> io::Result<(PubSender, PubRecver)> {
-	let (success, fds) = unsafe {
-		let mut fds: [c_int; 2] = [0; 2];
-		let result = libc::pipe(fds.as_mut_ptr());
-		(result == 0, fds)
-	};
-	if success {
-		let (w, r) = unsafe {
-			// SAFETY: we just created both of those file descriptors, which means that neither of
-			// them can be in use elsewhere.
-			let w = OwnedFd::from_raw_fd(fds[1]);
-			let r = OwnedFd::from_raw_fd(fds[0]);
-			(w, r)
-		};
-		let w = PubSender(Sender(FdOps(w)));
-		let r = PubRecver(Recver(FdOps(r)));
-		Ok((w, r))
-	} else {
-		Err(io::Error::last_os_error())
-	}
+	let (r, w) = c_wrappers::pipe(false)?;
+	let w = PubSender(Sender(FdOps(w)));
+	let r = PubRecver(Recver(FdOps(r)));
+	Ok((w, r))
 }
 
 pub(crate) struct Recver(FdOps);
 impl Sealed for Recver {}
+impl Recver {
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		c_wrappers::set_nonblocking(self.0 .0.as_fd(), nonblocking)
+	}
+	pub fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+		c_wrappers::set_inheritable(self.0 .0.as_fd(), inheritable)
+	}
+}
 impl Debug for Recver {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		f.debug_struct("Recver")
@@ -55,6 +47,14 @@ multimacro! {
 
 pub(crate) struct Sender(FdOps);
 impl Sealed for Sender {}
+impl Sender {
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		c_wrappers::set_nonblocking(self.0 .0.as_fd(), nonblocking)
+	}
+	pub fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+		c_wrappers::set_inheritable(self.0 .0.as_fd(), inheritable)
+	}
+}
 impl Debug for Sender {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		f.debug_struct("Sender")
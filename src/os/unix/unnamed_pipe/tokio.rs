@@ -0,0 +1,35 @@
+use super::super::c_wrappers;
+use crate::{
+	unnamed_pipe::tokio::{Recver as PubRecver, Sender as PubSender},
+	Sealed,
+};
+use std::io;
+use tokio::net::unix::pipe::{Receiver as TokioRecver, Sender as TokioSender};
+
+pub(crate) fn pipe() -> io::Result<(PubSender, PubRecver)> {
+	let (r, w) = c_wrappers::pipe(true)?;
+	let w = PubSender(Sender(TokioSender::from_owned_fd(w)?));
+	let r = PubRecver(Recver(TokioRecver::from_owned_fd(r)?));
+	Ok((w, r))
+}
+
+pub(crate) struct Recver(TokioRecver);
+impl Sealed for Recver {}
+multimacro! {
+	Recver,
+	pinproj_for_unpin(TokioRecver),
+	forward_tokio_read,
+	forward_as_handle(unix),
+	forward_debug("Recver"),
+}
+
+pub(crate) struct Sender(TokioSender);
+impl Sealed for Sender {}
+multimacro! {
+	Sender,
+	pinproj_for_unpin(TokioSender),
+	forward_rbv(TokioSender, &),
+	forward_tokio_write,
+	forward_as_handle(unix),
+	forward_debug("Sender"),
+}
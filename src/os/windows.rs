@@ -2,10 +2,14 @@
 //! Windows-specific ones.
 
 pub mod local_socket;
+pub mod mailslot;
 pub mod named_pipe;
+pub mod process;
 pub mod security_descriptor;
 pub mod unnamed_pipe;
-//pub mod mailslot;
+
+pub(crate) mod notify;
+pub(crate) mod shmem;
 
 mod path_conversion;
 mod share_handle;
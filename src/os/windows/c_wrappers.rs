@@ -1,9 +1,17 @@
 use super::winprelude::*;
 use crate::OrErrno;
-use std::io;
+use std::{io, ptr};
 use windows_sys::Win32::{
-	Foundation::{DuplicateHandle, DUPLICATE_SAME_ACCESS},
-	System::Threading::GetCurrentProcess,
+	Foundation::{
+		DuplicateHandle, SetHandleInformation, DUPLICATE_CLOSE_SOURCE, DUPLICATE_SAME_ACCESS,
+		HANDLE_FLAG_INHERIT,
+	},
+	Storage::FileSystem::{GetFileType, FILE_TYPE_PIPE},
+	System::{
+		Pipes::{SetNamedPipeHandleState, PIPE_NOWAIT, PIPE_READMODE_BYTE, PIPE_WAIT},
+		RemoteDesktop::ProcessIdToSessionId,
+		Threading::{GetCurrentProcess, GetCurrentProcessId, OpenProcess, PROCESS_DUP_HANDLE},
+	},
 };
 
 pub fn duplicate_handle(handle: BorrowedHandle<'_>) -> io::Result<OwnedHandle> {
@@ -17,6 +25,31 @@ pub fn duplicate_handle_to_foreign(
 	duplicate_handle_inner(handle, Some(other_process))
 }
 
+/// Opens the process with the given ID with the right to duplicate handles into it.
+pub fn open_process_for_dup(pid: u32) -> io::Result<OwnedHandle> {
+	let handle = unsafe { OpenProcess(PROCESS_DUP_HANDLE, 0, pid) };
+	(handle != 0).true_val_or_errno(())?;
+	// SAFETY: we just opened this handle
+	Ok(unsafe { OwnedHandle::from_raw_handle(handle.to_std()) })
+}
+
+/// Closes a handle in another process, which must have been opened with the right to duplicate
+/// handles.
+pub fn close_foreign_handle(other_process: BorrowedHandle<'_>, handle: HANDLE) -> io::Result<()> {
+	unsafe {
+		DuplicateHandle(
+			other_process.as_int_handle(),
+			handle,
+			0,
+			ptr::null_mut(),
+			0,
+			0,
+			DUPLICATE_CLOSE_SOURCE,
+		)
+	}
+	.true_val_or_errno(())
+}
+
 fn duplicate_handle_inner(
 	handle: BorrowedHandle<'_>,
 	other_process: Option<BorrowedHandle<'_>>,
@@ -36,3 +69,33 @@ fn duplicate_handle_inner(
 	}
 	.true_val_or_errno(new_handle)
 }
+
+pub fn set_inheritable(handle: BorrowedHandle<'_>, inheritable: bool) -> io::Result<()> {
+	let flags = if inheritable { HANDLE_FLAG_INHERIT } else { 0 };
+	unsafe { SetHandleInformation(handle.as_int_handle(), HANDLE_FLAG_INHERIT, flags) }
+		.true_val_or_errno(())
+}
+
+/// Sets the wait mode of a byte-mode pipe, which includes anonymous pipes.
+pub fn set_nonblocking_bytes(handle: BorrowedHandle<'_>, nonblocking: bool) -> io::Result<()> {
+	let mode = PIPE_READMODE_BYTE | if nonblocking { PIPE_NOWAIT } else { PIPE_WAIT };
+	unsafe {
+		SetNamedPipeHandleState(
+			handle.as_int_handle(),
+			&mode,
+			ptr::null_mut(),
+			ptr::null_mut(),
+		)
+	}
+	.true_val_or_errno(())
+}
+
+pub fn is_pipe(handle: BorrowedHandle<'_>) -> bool {
+	unsafe { GetFileType(handle.as_int_handle()) == FILE_TYPE_PIPE }
+}
+
+/// Returns the ID of the Remote Desktop Services session the current process belongs to.
+pub fn session_id() -> io::Result<u32> {
+	let mut session = 0;
+	unsafe { ProcessIdToSessionId(GetCurrentProcessId(), &mut session) }.true_val_or_errno(session)
+}
@@ -7,15 +7,52 @@ pub(crate) mod name_type;
 
 pub use name_type::*;
 
-use super::security_descriptor::SecurityDescriptor;
-use crate::{local_socket::ListenerOptions, Sealed};
+use super::{named_pipe::WaitTimeout, security_descriptor::SecurityDescriptor};
+use crate::{
+	local_socket::{ListenerOptions, NameBuilder},
+	Sealed,
+};
+use std::ffi::OsStr;
 
 /// Windows-specific [listener options](ListenerOptions).
 #[allow(private_bounds)]
 pub trait ListenerOptionsExt: Sized + Sealed {
 	/// Sets the security descriptor that will control access to the underlying named pipe.
+	///
+	/// Security descriptors can be conveniently constructed from SDDL strings via
+	/// [`SecurityDescriptor::deserialize()`]. For instance, `D:P(A;;GA;;;SY)(A;;GA;;;OW)` only
+	/// admits the local system account and the owner of the pipe.
 	#[must_use = builder_must_use!()]
 	fn security_descriptor(self, sd: SecurityDescriptor) -> Self;
+
+	/// Sets whether clients on remote machines are allowed to connect to the underlying named pipe
+	/// over the network.
+	///
+	/// This is disabled by default, in which case the pipe is created with the
+	/// `PIPE_REJECT_REMOTE_CLIENTS` flag.
+	#[must_use = builder_must_use!()]
+	fn accept_remote(self, accept_remote: bool) -> Self;
+
+	/// Sets the size of the input buffer, which holds data sent by clients until the server
+	/// receives it. The system adjusts the value as it sees fit, so it only serves as a hint.
+	///
+	/// The default value is 512.
+	#[must_use = builder_must_use!()]
+	fn input_buffer_size_hint(self, size: u32) -> Self;
+
+	/// Sets the size of the output buffer, which holds data sent by the server until the client
+	/// receives it. The system adjusts the value as it sees fit, so it only serves as a hint.
+	///
+	/// The default value is 512.
+	#[must_use = builder_must_use!()]
+	fn output_buffer_size_hint(self, size: u32) -> Self;
+
+	/// Sets the default timeout that clients use when waiting for a free pipe instance to connect
+	/// to, which applies to clients which don't specify a timeout of their own.
+	///
+	/// The default value is [`WaitTimeout::DEFAULT`], which makes Windows use 50 milliseconds.
+	#[must_use = builder_must_use!()]
+	fn wait_timeout(self, timeout: WaitTimeout) -> Self;
 }
 
 impl ListenerOptionsExt for ListenerOptions<'_> {
@@ -24,4 +61,81 @@ impl ListenerOptionsExt for ListenerOptions<'_> {
 		self.security_descriptor = Some(sd);
 		self
 	}
+	#[inline(always)]
+	fn accept_remote(mut self, accept_remote: bool) -> Self {
+		self.accept_remote = accept_remote;
+		self
+	}
+	#[inline(always)]
+	fn input_buffer_size_hint(mut self, size: u32) -> Self {
+		self.input_buffer_size_hint = size;
+		self
+	}
+	#[inline(always)]
+	fn output_buffer_size_hint(mut self, size: u32) -> Self {
+		self.output_buffer_size_hint = size;
+		self
+	}
+	#[inline(always)]
+	fn wait_timeout(mut self, timeout: WaitTimeout) -> Self {
+		self.wait_timeout = timeout;
+		self
+	}
+}
+
+/// Windows-specific [name builder](NameBuilder) options, for building names of pipes on other
+/// machines.
+///
+/// Connecting to a pipe on another machine goes over SMB, which authenticates the current user to
+/// that machine. Since this can leak credentials to whoever controls the name, which is a concern
+/// for names that come from configuration or user input, [`.build()`](NameBuilder::build) rejects
+/// such names unless [`.allow_remote()`](Self::allow_remote) is used. Names of remote pipes which
+/// are produced by other means, such as [`ToFsName`](crate::local_socket::ToFsName) conversions or
+/// [`Name::interpret()`](crate::local_socket::Name::interpret), are always rejected.
+///
+/// ```no_run
+/// use interprocess::{
+/// 	local_socket::{prelude::*, Name, Stream},
+/// 	os::windows::local_socket::NameBuilderExt,
+/// };
+/// let name = Name::builder()
+/// 	.namespaced("Example")
+/// 	.host("FILESERVER")
+/// 	.allow_remote(true)
+/// 	.build()?;
+/// let conn = Stream::connect(name)?;
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[allow(private_bounds)]
+pub trait NameBuilderExt<'s>: Sized + Sealed {
+	/// Sets the host on which the pipe resides, making the name `\\<host>\pipe\<name>`. Only
+	/// applies to names specified with [`.namespaced()`](NameBuilder::namespaced) or
+	/// [`.auto()`](NameBuilder::auto); building fails with an error of kind
+	/// [`InvalidInput`](std::io::ErrorKind::InvalidInput) for paths.
+	///
+	/// The host `.` is the local machine. Any other host requires
+	/// [`.allow_remote()`](Self::allow_remote).
+	#[must_use = builder_must_use!()]
+	fn host(self, host: &'s (impl AsRef<OsStr> + ?Sized)) -> Self;
+
+	/// Sets whether the name may refer to a pipe on another machine, either via
+	/// [`.host()`](Self::host) or via a path of the form `\\<host>\pipe\<name>`.
+	///
+	/// This is disabled by default, in which case building such a name fails with an error of kind
+	/// [`PermissionDenied`](std::io::ErrorKind::PermissionDenied).
+	#[must_use = builder_must_use!()]
+	fn allow_remote(self, allow_remote: bool) -> Self;
+}
+
+impl<'s> NameBuilderExt<'s> for NameBuilder<'s> {
+	#[inline(always)]
+	fn host(mut self, host: &'s (impl AsRef<OsStr> + ?Sized)) -> Self {
+		self.host = Some(host.as_ref());
+		self
+	}
+	#[inline(always)]
+	fn allow_remote(mut self, allow_remote: bool) -> Self {
+		self.allow_remote = allow_remote;
+		self
+	}
 }
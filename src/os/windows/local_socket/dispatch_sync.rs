@@ -1,5 +1,5 @@
 use super::super::named_pipe::local_socket as np_impl;
-use crate::local_socket::{prelude::*, Listener, ListenerOptions, Name, Stream};
+use crate::local_socket::{prelude::*, Listener, ListenerOptions, Liveness, Name, Stream};
 use std::io;
 
 #[inline]
@@ -12,3 +12,14 @@ pub fn from_options(options: ListenerOptions<'_>) -> io::Result<Listener> {
 pub fn connect(name: Name<'_>) -> io::Result<Stream> {
 	np_impl::Stream::connect(name).map(Stream::from)
 }
+
+#[inline]
+pub fn pair() -> io::Result<(Stream, Stream)> {
+	let (a, b) = np_impl::Stream::pair()?;
+	Ok((Stream::from(a), Stream::from(b)))
+}
+
+#[inline]
+pub fn probe(name: Name<'_>) -> io::Result<Liveness> {
+	np_impl::probe(name)
+}
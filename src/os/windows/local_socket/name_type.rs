@@ -1,15 +1,24 @@
 use crate::{
-	local_socket::{Name, NameInner, NameType, PathNameType},
-	os::windows::{convert_and_encode_path, convert_osstr},
+	error::Error,
+	local_socket::{GenericNamespaced, Name, NameInner, NameType, PathNameType, ToNsName},
+	os::windows::{c_wrappers, convert_and_encode_path, convert_osstr},
 };
-use std::{borrow::Cow, ffi::OsStr, io};
+use std::{
+	borrow::Cow,
+	ffi::{OsStr, OsString},
+	io,
+};
+use widestring::U16CStr;
 
 tag_enum!(
 /// [Mapping](NameType) that produces
 /// [named pipe local socket](crate::os::windows::named_pipe::local_socket) names.
 ///
 /// Named pipe paths of the form `\\HOSTNAME\pipe\PIPENAME` are passed through verbatim. Other paths
-/// yield an error, as they do not point to NPFS.
+/// yield an error, as they do not point to NPFS. Paths with a host other than `.` refer to pipes on
+/// other machines and are rejected with an error of kind
+/// [`PermissionDenied`](io::ErrorKind::PermissionDenied) – see
+/// [`NameBuilderExt`](super::NameBuilderExt) for how to allow them.
 ///
 /// Namespaced strings have `\\.\pipe\` prepended to them – using
 /// [`ToNsName`](crate::local_socket::ToNsName) conversions implies the hostname `.`, which is the
@@ -22,16 +31,23 @@ impl NameType for NamedPipe {
 }
 impl PathNameType<OsStr> for NamedPipe {
 	fn map(path: Cow<'_, OsStr>) -> io::Result<Name<'_>> {
-		if !is_pipefs(&path) {
-			return Err(io::Error::new(
-				io::ErrorKind::Unsupported,
-				"not a named pipe path",
-			));
+		let name = map_path(path)?;
+		reject_remote(&name)?;
+		Ok(name)
+	}
+}
+
+/// Like [`NamedPipe::map()`], but lets paths of pipes on other machines through.
+pub(crate) fn map_path(path: Cow<'_, OsStr>) -> io::Result<Name<'_>> {
+	if !is_pipefs(&path) {
+		return Err(Error::UnsupportedNameType {
+			reason: "not a named pipe path",
 		}
-		Ok(Name(NameInner::NamedPipe(Cow::Owned(convert_osstr(
-			&path,
-		)?))))
+		.into());
 	}
+	Ok(Name(NameInner::NamedPipe(Cow::Owned(convert_osstr(
+		&path,
+	)?))))
 }
 
 pub(crate) fn map_generic_path_osstr(path: Cow<'_, OsStr>) -> io::Result<Name<'_>> {
@@ -47,6 +63,101 @@ pub(crate) fn map_generic_namespaced_osstr(name: Cow<'_, OsStr>) -> io::Result<N
 	))))
 }
 
+/// Builds the path of a pipe on the given host.
+pub(crate) fn on_host(host: &OsStr, name: &OsStr) -> io::Result<Name<'static>> {
+	let bytes = host.as_encoded_bytes();
+	if bytes.is_empty() || bytes.contains(&b'\\') || bytes.contains(&b'/') {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"invalid host name",
+		));
+	}
+	let mut path = OsString::from(r"\\");
+	path.push(host);
+	path.push(r"\pipe\");
+	path.push(name);
+	map_path(Cow::Owned(path))
+}
+
+/// Checks whether the path refers to a pipe on a host other than `.`, the local machine.
+fn is_remote(path: &U16CStr) -> bool {
+	let path = path.as_slice();
+	let backslash = u16::from(b'\\');
+	// Namespaced names have the local prefix prepended later.
+	let Some(rest) = path.strip_prefix(&[backslash, backslash]) else {
+		return false;
+	};
+	let host = rest.split(|&c| c == backslash).next().unwrap_or_default();
+	host != [u16::from(b'.')]
+}
+
+/// Fails with an error of kind [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the name
+/// refers to a pipe on another machine without having been [allowed to](allow_remote).
+pub(crate) fn reject_remote(name: &Name<'_>) -> io::Result<()> {
+	match &name.0 {
+		NameInner::NamedPipe(path) if is_remote(path) => Err(io::Error::new(
+			io::ErrorKind::PermissionDenied,
+			"name refers to a pipe on a remote machine, which must be allowed explicitly",
+		)),
+		_ => Ok(()),
+	}
+}
+
+/// Marks the name as allowed to refer to a pipe on another machine.
+pub(crate) fn allow_remote(name: Name<'_>) -> Name<'_> {
+	match name.0 {
+		NameInner::NamedPipe(path) if is_remote(&path) => Name(NameInner::RemoteNamedPipe(path)),
+		els => Name(els),
+	}
+}
+
+/// Extracts the path of the pipe from the name, failing as per [`reject_remote()`].
+pub(crate) fn pipe_path(name: Name<'_>) -> io::Result<Cow<'_, U16CStr>> {
+	reject_remote(&name)?;
+	let (NameInner::NamedPipe(path) | NameInner::RemoteNamedPipe(path)) = name.0;
+	Ok(path)
+}
+
+/// Puts the pipe in a directory of the pipe namespace named after the session of the current
+/// process, since named pipes are otherwise shared by all sessions on the system. This only
+/// prevents collisions; access is governed by the security descriptor of the pipe.
+pub(crate) fn user_scoped(file: &str) -> io::Result<Name<'static>> {
+	let session = c_wrappers::session_id()?;
+	format!(r"Session-{session}\{file}")
+		.to_ns_name::<GenericNamespaced>()
+		.map(Name::into_owned)
+}
+
+/// The maximum length of a full pipe path, in UTF-16 code units.
+const MAX_PIPE_PATH_LEN: usize = 256;
+
+/// Checks the name against the naming rules of named pipes, so that invalid names are reported
+/// before they get to `CreateNamedPipeW` or `CreateFileW`.
+pub(crate) fn validate(name: &Name<'_>) -> io::Result<()> {
+	let (NameInner::NamedPipe(path) | NameInner::RemoteNamedPipe(path)) = &name.0;
+	let path = path.as_slice();
+	// Namespaced names have the prefix prepended later.
+	let full_len = if path.starts_with(&[u16::from(b'\\'), u16::from(b'\\')]) {
+		path.len()
+	} else {
+		path.len().saturating_add(r"\\.\pipe\".len())
+	};
+	if path.is_empty() || path.last() == Some(&u16::from(b'\\')) {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"pipe name is empty",
+		));
+	}
+	if full_len > MAX_PIPE_PATH_LEN {
+		return Err(Error::NameTooLong {
+			len: full_len,
+			max: MAX_PIPE_PATH_LEN,
+		}
+		.into());
+	}
+	Ok(())
+}
+
 #[allow(clippy::indexing_slicing, clippy::arithmetic_side_effects)] // minlen check
 fn is_pipefs(slf: &OsStr) -> bool {
 	const PFX1: &[u8] = br"\\";
@@ -1,5 +1,202 @@
 //! Windows-specific IPC primitive designed for short multiple-producer-single-consumer message
 //! communication with UDP reliability guarantees, which works both on the local system and across
 //! the network.
+//!
+//! A mailslot is created by a [`MailslotServer`], which is the only party able to read from it.
+//! Any number of [`MailslotClient`]s can then connect to it and send messages, which are delivered
+//! as a whole and in order. Messages sent to remote computers (or to all computers in a domain,
+//! which is where mailslots come in handy) are sent as datagrams and may get lost, while local
+//! messages are always delivered.
+//!
+//! ## Names
+//! Mailslot names are given without the `\\.\mailslot\` prefix, which is added automatically. A
+//! name which starts with two backslashes is used as a full path, which allows clients to connect
+//! to mailslots on remote computers, such as `\\server\mailslot\name`, or to broadcast to all
+//! computers in a domain with `\\domain\mailslot\name` or `\\*\mailslot\name`.
 
-// TODO(2.4.0) this thing
+use super::{path_conversion::to_io_error, winprelude::*, FileHandle};
+use crate::{weaken_buf_init_mut, HandleOrErrno, OrErrno, RawOsErrorExt as _};
+use std::{io, ptr, time::Duration};
+use widestring::U16CString;
+use windows_sys::Win32::{
+	Foundation::{ERROR_SEM_TIMEOUT, GENERIC_WRITE},
+	Storage::FileSystem::{CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_SHARE_READ, OPEN_EXISTING},
+	System::{
+		Mailslots::{CreateMailslotW, GetMailslotInfo, SetMailslotInfo},
+		SystemServices::{MAILSLOT_NO_MESSAGE, MAILSLOT_WAIT_FOREVER},
+	},
+};
+
+fn mailslot_path(name: &str) -> io::Result<U16CString> {
+	let path = if name.starts_with(r"\\") {
+		name.to_owned()
+	} else {
+		format!(r"\\.\mailslot\{name}")
+	};
+	U16CString::from_str(path).map_err(to_io_error)
+}
+
+fn timeout_to_raw(timeout: Option<Duration>) -> u32 {
+	match timeout {
+		None => MAILSLOT_WAIT_FOREVER,
+		// MAILSLOT_WAIT_FOREVER is u32::MAX, so the largest finite value is one less
+		Some(t) => u32::try_from(t.as_millis())
+			.unwrap_or(u32::MAX)
+			.min(MAILSLOT_WAIT_FOREVER.saturating_sub(1)),
+	}
+}
+fn timeout_from_raw(raw: u32) -> Option<Duration> {
+	(raw != MAILSLOT_WAIT_FOREVER).then(|| Duration::from_millis(raw.into()))
+}
+
+/// Information about a mailslot, as returned by [`MailslotServer::info()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MailslotInfo {
+	/// The maximum size of a message, or `0` if any size is allowed.
+	pub max_message_size: u32,
+	/// The size of the next message, or `None` if there are no messages.
+	pub next_message_size: Option<u32>,
+	/// The number of messages waiting to be received.
+	pub message_count: u32,
+	/// How long [`recv()`](MailslotServer::recv) waits for a message before timing out, or `None`
+	/// if it waits indefinitely.
+	pub read_timeout: Option<Duration>,
+}
+
+/// The receiving end of a mailslot, which owns it.
+///
+/// See the [module-level documentation](self) for more.
+pub struct MailslotServer(FileHandle);
+impl MailslotServer {
+	/// Creates a mailslot with the given name which accepts messages of any size.
+	#[inline]
+	pub fn create(name: &str) -> io::Result<Self> {
+		Self::create_with_max_message_size(name, 0)
+	}
+	/// Creates a mailslot with the given name which only accepts messages up to the given size in
+	/// bytes. `0` means that messages of any size are accepted.
+	///
+	/// Messages sent to remote computers are limited to 424 bytes regardless of this setting.
+	pub fn create_with_max_message_size(name: &str, max_message_size: u32) -> io::Result<Self> {
+		let path = mailslot_path(name)?;
+		let handle = unsafe {
+			CreateMailslotW(
+				path.as_ptr(),
+				max_message_size,
+				MAILSLOT_WAIT_FOREVER,
+				ptr::null(),
+			)
+		}
+		.handle_or_errno()?;
+		// SAFETY: we just created this handle
+		Ok(Self(FileHandle::from(unsafe {
+			OwnedHandle::from_raw_handle(handle.to_std())
+		})))
+	}
+
+	/// Receives a message into the given buffer, returning its size.
+	///
+	/// Blocks until a message arrives or the [read timeout](Self::set_read_timeout) expires, in
+	/// which case an error of kind [`TimedOut`](io::ErrorKind::TimedOut) is returned. Fails if the
+	/// buffer is too small for the message, which can be checked for beforehand with
+	/// [`info()`](Self::info).
+	pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+		match self.0.read(weaken_buf_init_mut(buf)) {
+			Err(e) if e.raw_os_error().eeq(ERROR_SEM_TIMEOUT) => {
+				Err(io::ErrorKind::TimedOut.into())
+			}
+			els => els,
+		}
+	}
+
+	/// Queries the maximum message size, the number and size of pending messages and the read
+	/// timeout of the mailslot.
+	pub fn info(&self) -> io::Result<MailslotInfo> {
+		let (mut max_message_size, mut next_size, mut message_count, mut read_timeout) =
+			(0, 0, 0, 0);
+		unsafe {
+			GetMailslotInfo(
+				self.0.as_int_handle(),
+				&mut max_message_size,
+				&mut next_size,
+				&mut message_count,
+				&mut read_timeout,
+			)
+		}
+		.true_val_or_errno(())?;
+		Ok(MailslotInfo {
+			max_message_size,
+			next_message_size: (next_size != MAILSLOT_NO_MESSAGE).then_some(next_size),
+			message_count,
+			read_timeout: timeout_from_raw(read_timeout),
+		})
+	}
+	/// Returns the maximum message size, or `0` if any size is allowed.
+	#[inline]
+	pub fn max_message_size(&self) -> io::Result<u32> {
+		self.info().map(|i| i.max_message_size)
+	}
+	/// Sets how long [`recv()`](Self::recv) waits for a message before timing out. `None` waits
+	/// indefinitely, which is the default, and a zero duration makes it fail immediately if there
+	/// are no messages.
+	///
+	/// Durations are rounded down to whole milliseconds.
+	pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+		unsafe { SetMailslotInfo(self.0.as_int_handle(), timeout_to_raw(timeout)) }
+			.true_val_or_errno(())
+	}
+}
+multimacro! {
+	MailslotServer,
+	forward_handle,
+	forward_try_clone,
+	forward_debug,
+	derive_raw,
+}
+
+/// A sending end of a mailslot.
+///
+/// See the [module-level documentation](self) for more.
+pub struct MailslotClient(FileHandle);
+impl MailslotClient {
+	/// Connects to the mailslot with the given name.
+	///
+	/// This succeeds for remote and broadcast names even if there is no mailslot to deliver
+	/// messages to.
+	pub fn connect(name: &str) -> io::Result<Self> {
+		let path = mailslot_path(name)?;
+		let handle = unsafe {
+			CreateFileW(
+				path.as_ptr(),
+				GENERIC_WRITE,
+				FILE_SHARE_READ,
+				ptr::null(),
+				OPEN_EXISTING,
+				FILE_ATTRIBUTE_NORMAL,
+				0,
+			)
+		}
+		.handle_or_errno()?;
+		// SAFETY: we just created this handle
+		Ok(Self(FileHandle::from(unsafe {
+			OwnedHandle::from_raw_handle(handle.to_std())
+		})))
+	}
+	/// Sends a message, which is delivered as a whole.
+	///
+	/// Fails if the message is larger than the maximum message size of the mailslot.
+	pub fn send(&self, msg: &[u8]) -> io::Result<()> {
+		let written = self.0.write(msg)?;
+		if written != msg.len() {
+			return Err(io::ErrorKind::WriteZero.into());
+		}
+		Ok(())
+	}
+}
+multimacro! {
+	MailslotClient,
+	forward_handle,
+	forward_try_clone,
+	forward_debug,
+	derive_raw,
+}
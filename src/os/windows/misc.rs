@@ -4,7 +4,7 @@ pub(super) mod winprelude {
 	pub(crate) use windows_sys::Win32::Foundation::{HANDLE, INVALID_HANDLE_VALUE};
 }
 
-use std::io::{self, ErrorKind::BrokenPipe};
+use std::io::{self, ErrorKind::BrokenPipe, IoSlice};
 use winprelude::*;
 
 use crate::RawOsErrorExt as _;
@@ -44,3 +44,26 @@ pub(super) fn downgrade_eof<T: Default>(r: io::Result<T>) -> io::Result<T> {
 		els => els,
 	}
 }
+
+/// Returns the first nonempty buffer, which is what vectored writes to byte streams send, since
+/// Windows doesn't provide vectored I/O for pipes.
+pub(super) fn first_nonempty<'a>(bufs: &'a [IoSlice<'_>]) -> &'a [u8] {
+	bufs.iter().find(|b| !b.is_empty()).map_or(&[], |b| b)
+}
+
+/// Concatenates the contents of the given buffers into `scratch`, borrowing instead if there's at
+/// most one nonempty buffer. Used to emulate vectored writes of whole messages, which Windows
+/// doesn't provide for pipes; `scratch` is kept around by the caller so that its allocation is
+/// reused.
+pub(super) fn coalesce_bufs<'a>(bufs: &'a [IoSlice<'_>], scratch: &'a mut Vec<u8>) -> &'a [u8] {
+	let mut nonempty = bufs.iter().filter(|b| !b.is_empty());
+	match (nonempty.next(), nonempty.next()) {
+		(None, _) => &[],
+		(Some(only), None) => only,
+		_ => {
+			scratch.clear();
+			bufs.iter().for_each(|b| scratch.extend_from_slice(b));
+			scratch
+		}
+	}
+}
@@ -35,19 +35,20 @@
 //! documentation.
 
 // TODO(2.0.1) improve docs and add examples
-// TODO(2.1.0) client impersonation
 // TODO(2.2.0) raw instance functionality
 // TODO(2.2.0) transactions
 
 mod enums;
+mod impersonation;
 mod listener;
 mod stream;
 mod wait_timeout;
 
-pub use {enums::*, listener::*, stream::*, wait_timeout::*};
+pub use {enums::*, impersonation::*, listener::*, stream::*, wait_timeout::*};
 
 /// Local sockets implemented using Windows named pipes.
 pub mod local_socket {
+	mod handle_transfer;
 	mod listener;
 	mod stream;
 	pub use listener::*;
@@ -6,17 +6,23 @@ use crate::{
 	},
 	AsMutPtr, HandleOrErrno, OrErrno, RawOsErrorExt, SubUsizeExt,
 };
-use std::{io, mem::MaybeUninit, os::windows::prelude::*, ptr};
+use std::{io, mem::MaybeUninit, os::windows::prelude::*, ptr, time::Instant};
 use widestring::U16CStr;
 use windows_sys::Win32::{
-	Foundation::{ERROR_PIPE_BUSY, GENERIC_READ, GENERIC_WRITE},
+	Foundation::{
+		ERROR_NOT_FOUND, ERROR_PIPE_BUSY, ERROR_SEM_TIMEOUT, GENERIC_READ, GENERIC_WRITE,
+	},
+	Security::RevertToSelf,
 	Storage::FileSystem::{
 		CreateFileW, FILE_FLAG_OVERLAPPED, FILE_SHARE_READ, FILE_SHARE_WRITE,
 		FILE_WRITE_ATTRIBUTES, OPEN_EXISTING,
 	},
-	System::Pipes::{
-		GetNamedPipeHandleStateW, GetNamedPipeInfo, PeekNamedPipe, SetNamedPipeHandleState,
-		WaitNamedPipeW, PIPE_NOWAIT,
+	System::{
+		Pipes::{
+			GetNamedPipeHandleStateW, GetNamedPipeInfo, ImpersonateNamedPipeClient, PeekNamedPipe,
+			SetNamedPipeHandleState, WaitNamedPipeW, PIPE_NOWAIT,
+		},
+		IO::CancelIoEx,
 	},
 };
 
@@ -140,6 +146,43 @@ pub(crate) fn peek_msg_len(handle: BorrowedHandle<'_>) -> io::Result<usize> {
 	.true_val_or_errno(msglen.to_usize())
 }
 
+/// Cancels all pending I/O on the pipe, issued by any thread of the process. Finding no pending
+/// I/O to cancel is not an error.
+pub(crate) fn cancel_io(handle: BorrowedHandle<'_>) -> io::Result<()> {
+	match unsafe { CancelIoEx(handle.as_int_handle(), ptr::null()) }.true_val_or_errno(()) {
+		Err(e) if e.raw_os_error().eeq(ERROR_NOT_FOUND) => Ok(()),
+		els => els,
+	}
+}
+
+/// Copies data from the pipe into `buf` without removing it, returning the amount of bytes copied
+/// and the total amount of bytes available for receiving, in that order. With no buffer, only the
+/// latter is retrieved.
+pub(crate) fn peek(
+	handle: BorrowedHandle<'_>,
+	buf: Option<&mut [MaybeUninit<u8>]>,
+) -> io::Result<(usize, usize)> {
+	let (mut copied, mut available) = (0_u32, 0_u32);
+	let (buf_ptr, buf_len) = match buf {
+		Some(buf) => (
+			buf.as_mut_ptr().cast(),
+			u32::try_from(buf.len()).unwrap_or(u32::MAX),
+		),
+		None => (ptr::null_mut(), 0),
+	};
+	unsafe {
+		PeekNamedPipe(
+			handle.as_int_handle(),
+			buf_ptr,
+			buf_len,
+			copied.as_mut_ptr(),
+			available.as_mut_ptr(),
+			ptr::null_mut(),
+		)
+	}
+	.true_val_or_errno((copied.to_usize(), available.to_usize()))
+}
+
 fn modes_to_access_flags(recv: Option<PipeMode>, send: Option<PipeMode>) -> u32 {
 	let mut access_flags = 0;
 	if recv.is_some() {
@@ -198,3 +241,33 @@ pub(crate) fn set_nonblocking_given_readmode(
 pub(crate) fn block_for_server(path: &U16CStr, timeout: WaitTimeout) -> io::Result<()> {
 	unsafe { WaitNamedPipeW(path.as_ptr().cast_mut(), timeout.to_raw()) }.true_val_or_errno(())
 }
+
+/// Waits for a server instance to become available, giving up with an error of kind
+/// [`TimedOut`](io::ErrorKind::TimedOut) once the deadline has passed. With no deadline, waits
+/// indefinitely.
+pub(crate) fn block_for_server_until(path: &U16CStr, deadline: Option<Instant>) -> io::Result<()> {
+	let timeout = match deadline {
+		Some(deadline) => {
+			let remaining = deadline.saturating_duration_since(Instant::now());
+			if remaining.is_zero() {
+				return Err(io::ErrorKind::TimedOut.into());
+			}
+			// Both 0 and u32::MAX have special meanings, so round to whatever is in between
+			let ms = u32::try_from(remaining.as_millis()).unwrap_or(u32::MAX);
+			WaitTimeout::from_raw(ms.clamp(1, WaitTimeout::FOREVER.to_raw().saturating_sub(1)))
+		}
+		None => WaitTimeout::FOREVER,
+	};
+	match block_for_server(path, timeout) {
+		Err(e) if e.raw_os_error().eeq(ERROR_SEM_TIMEOUT) => Err(io::ErrorKind::TimedOut.into()),
+		els => els,
+	}
+}
+
+pub(crate) fn impersonate_client(handle: BorrowedHandle<'_>) -> io::Result<()> {
+	unsafe { ImpersonateNamedPipeClient(handle.as_int_handle()) }.true_val_or_errno(())
+}
+
+pub(crate) fn revert_to_self() -> io::Result<()> {
+	unsafe { RevertToSelf() }.true_val_or_errno(())
+}
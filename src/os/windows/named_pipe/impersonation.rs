@@ -0,0 +1,63 @@
+use super::c_wrappers;
+use std::{
+	fmt::{self, Debug, Formatter},
+	io,
+	marker::PhantomData,
+};
+
+/// Reverts the security context of the calling thread to that of the process, terminating
+/// impersonation of a named pipe client.
+///
+/// This is a thin wrapper around [`RevertToSelf`][rts]. [`ImpersonationGuard`] calls this function
+/// automatically when dropped.
+///
+/// [rts]: https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-reverttoself
+#[inline]
+pub fn revert_to_self() -> io::Result<()> {
+	c_wrappers::revert_to_self()
+}
+
+/// Scope guard for named pipe client impersonation, returned by
+/// [`PipeStream::impersonate_client()`](super::PipeStream::impersonate_client) and its Tokio
+/// counterpart.
+///
+/// For as long as the guard is alive, the thread which created it operates under the security
+/// context of the client. Dropping the guard [reverts](revert_to_self) to the security context of
+/// the process. Since impersonation is a property of a thread, the guard cannot be sent to other
+/// threads.
+///
+/// # Aborts
+/// Failing to revert to self is a fatal condition, since the thread would otherwise keep running
+/// with the privileges of the client without being aware of it. For this reason, the destructor
+/// aborts the process if reverting fails. A panic would not do, since it could be caught, or
+/// could happen while already unwinding, which aborts with a less helpful message. Use
+/// [`.revert()`](Self::revert) to handle the error instead.
+pub struct ImpersonationGuard<'s> {
+	_phantom: PhantomData<(&'s (), *const ())>,
+}
+impl ImpersonationGuard<'_> {
+	/// Creates the guard. Must only be called after impersonation has successfully begun.
+	pub(crate) fn new() -> Self {
+		Self {
+			_phantom: PhantomData,
+		}
+	}
+	/// Reverts to the security context of the process, returning the error if one occurs.
+	pub fn revert(self) -> io::Result<()> {
+		std::mem::forget(self);
+		revert_to_self()
+	}
+}
+impl Drop for ImpersonationGuard<'_> {
+	fn drop(&mut self) {
+		if let Err(e) = revert_to_self() {
+			eprintln!("fatal: failed to revert to self after client impersonation: {e}");
+			std::process::abort();
+		}
+	}
+}
+impl Debug for ImpersonationGuard<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str("ImpersonationGuard")
+	}
+}
@@ -4,10 +4,11 @@ mod options;
 
 pub use {incoming::*, options::*};
 
-use super::{c_wrappers, PipeModeTag, PipeStream, PipeStreamRole, RawPipeStream};
+use super::{c_wrappers, PipeMode, PipeModeTag, PipeStream, PipeStreamRole, RawPipeStream};
 use crate::{
+	error::FromHandleError,
 	os::windows::{winprelude::*, FileHandle},
-	poison_error, RawOsErrorExt, LOCK_POISON,
+	poison_error, RawOsErrorExt, TryClone, LOCK_POISON,
 };
 use std::{
 	fmt::{self, Debug, Formatter},
@@ -20,7 +21,10 @@ use std::{
 		Mutex,
 	},
 };
-use windows_sys::Win32::{Foundation::ERROR_PIPE_CONNECTED, System::Pipes::ConnectNamedPipe};
+use windows_sys::Win32::{
+	Foundation::ERROR_PIPE_CONNECTED,
+	System::Pipes::{ConnectNamedPipe, PIPE_SERVER_END, PIPE_TYPE_MESSAGE},
+};
 
 // TODO(2.3.0) finish collect_options and add conversion from handles after all
 
@@ -88,6 +92,31 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
 		Ok(())
 	}
 
+	/// Returns the number of currently existing instances of the named pipe, which includes the
+	/// instance held by the listener for the next client as well as those owned by server-side
+	/// streams that haven't been dropped yet.
+	///
+	/// The maximum is controlled by the
+	/// [`instance_limit` field](PipeListenerOptions::instance_limit) of the creation options. Once
+	/// it's reached, clients which connect wait for an instance to free up, and `.accept()` fails,
+	/// since it creates an instance for the next client before returning the connected one.
+	///
+	/// This locks the same internal mutex as `.accept()`, and will thus block until a concurrent
+	/// call to `.accept()` on another thread returns.
+	pub fn instance_count(&self) -> io::Result<u32> {
+		let instance = self.stored_instance.lock().map_err(poison_error)?;
+		let mut count = 0;
+		c_wrappers::get_np_handle_state(
+			instance.as_handle(),
+			None,
+			Some(&mut count),
+			None,
+			None,
+			None,
+		)?;
+		Ok(count)
+	}
+
 	/// Creates a listener from a handle and a [`PipeListenerOptions`] table with the assumption
 	/// that the handle was created with those options.
 	///
@@ -106,12 +135,68 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
 		}
 	}
 
+	/// Creates a listener from a handle to a named pipe server instance that was created outside of
+	/// the program, such as one inherited from a parent process or passed via the handle list of
+	/// `STARTUPINFOEX`, checking that it matches the given [`PipeListenerOptions`] table.
+	///
+	/// The handle must be the server end of a named pipe, and its pipe type must match the
+	/// [`mode` field](PipeListenerOptions::mode) of the options. If either of those conditions
+	/// doesn't hold, an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput) is returned
+	/// along with ownership of the handle. The options are otherwise trusted to describe the pipe
+	/// correctly, since they're used to create new instances in `.accept()`, which will fail if
+	/// they don't.
+	///
+	/// Once adopted, the handle is made non-inheritable.
+	pub fn try_from_handle_and_options(
+		handle: OwnedHandle,
+		options: PipeListenerOptions<'static>,
+	) -> Result<Self, FromHandleError> {
+		if let Err(e) = check_foreign_instance(handle.as_handle(), options.mode) {
+			return Err(FromHandleError {
+				details: Default::default(),
+				cause: Some(e),
+				source: Some(handle),
+			});
+		}
+		Ok(Self::from_handle_and_options(handle, options))
+	}
+
 	fn create_instance(&self, nonblocking: bool) -> io::Result<FileHandle> {
 		self.config
 			.create_instance(false, nonblocking, false, Self::STREAM_ROLE, Rm::MODE)
 			.map(FileHandle::from)
 	}
 }
+/// Validates a server instance handle for use in a listener with the given mode, and makes it
+/// non-inheritable.
+pub(crate) fn check_foreign_instance(handle: BorrowedHandle<'_>, mode: PipeMode) -> io::Result<()> {
+	let flags = c_wrappers::get_flags(handle)?;
+	let msg = if flags & PIPE_SERVER_END == 0 {
+		"handle is not the server end of a named pipe"
+	} else if (flags & PIPE_TYPE_MESSAGE != 0) != (mode == PipeMode::Messages) {
+		"pipe type of the handle does not match the mode in the listener options"
+	} else {
+		return crate::os::windows::c_wrappers::set_inheritable(handle, false);
+	};
+	Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+}
+
+/// Since a listener is not a Win32 object, the clone is a new listener for the same named pipe
+/// which creates its own server instance. Both listeners then accept clients independently, and
+/// each counts towards the [instance limit](PipeListenerOptions::instance_limit). The clone is in
+/// the same nonblocking mode as the original.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryClone for PipeListener<Rm, Sm> {
+	fn try_clone(&self) -> io::Result<Self> {
+		let nonblocking = self.nonblocking.load(Relaxed);
+		Ok(Self {
+			config: self.config.try_clone()?,
+			nonblocking: AtomicBool::new(nonblocking),
+			stored_instance: Mutex::new(self.create_instance(nonblocking)?),
+			_phantom: PhantomData,
+		})
+	}
+}
+
 impl<Rm: PipeModeTag, Sm: PipeModeTag> Debug for PipeListener<Rm, Sm> {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		f.debug_struct("PipeListener")
@@ -0,0 +1,72 @@
+//! The part of handle transfer which is shared between the blocking and Tokio local sockets, i.e.
+//! everything except for the I/O.
+//!
+//! The sender duplicates the handle into the process of the receiver and tells it the value of the
+//! new handle as a little-endian `i64`. Handle values always fit into 32 bits, which keeps this
+//! portable between 32-bit and 64-bit processes.
+
+use crate::os::windows::{c_wrappers, named_pipe::c_wrappers::hget, winprelude::*};
+use std::io;
+use windows_sys::Win32::System::Pipes;
+
+/// Length of the message which tells the receiver about a handle.
+pub(super) const MSG_LEN: usize = 8;
+
+/// Retrieves the process identifier of the other end of a named pipe connection.
+pub(super) fn peer_process_id(pipe: BorrowedHandle<'_>, is_server: bool) -> io::Result<u32> {
+	let f = if is_server {
+		Pipes::GetNamedPipeClientProcessId
+	} else {
+		Pipes::GetNamedPipeServerProcessId
+	};
+	unsafe { hget(pipe, f) }
+}
+
+/// A handle which has been duplicated into the process of the receiver, and which is closed there
+/// again if this is dropped before the receiver has been [told about it](Self::sent).
+pub(super) struct OutgoingHandle {
+	process: OwnedHandle,
+	/// `None` once the receiver owns the handle.
+	remote: Option<HANDLE>,
+	msg: [u8; MSG_LEN],
+}
+impl OutgoingHandle {
+	pub(super) fn new(handle: BorrowedHandle<'_>, peer_pid: u32) -> io::Result<Self> {
+		let process = c_wrappers::open_process_for_dup(peer_pid)?;
+		let remote = c_wrappers::duplicate_handle_to_foreign(handle, process.as_handle())?;
+		let mut slf = Self {
+			process,
+			remote: Some(remote),
+			msg: [0; MSG_LEN],
+		};
+		// Dropping `slf` on failure closes the handle in the receiver
+		slf.msg = i64::try_from(remote)
+			.map_err(io::Error::other)?
+			.to_le_bytes();
+		Ok(slf)
+	}
+	/// The message to send to the receiver.
+	#[inline]
+	pub(super) fn message(&self) -> &[u8; MSG_LEN] {
+		&self.msg
+	}
+	/// Leaves the handle to the receiver, which has been sent the message in full.
+	pub(super) fn sent(mut self) {
+		self.remote = None;
+	}
+}
+impl Drop for OutgoingHandle {
+	fn drop(&mut self) {
+		if let Some(remote) = self.remote {
+			let _ = c_wrappers::close_foreign_handle(self.process.as_handle(), remote);
+		}
+	}
+}
+
+/// Takes ownership of the handle which the given message tells about.
+pub(super) fn decode(msg: [u8; MSG_LEN]) -> io::Result<OwnedHandle> {
+	let handle = HANDLE::try_from(i64::from_le_bytes(msg))
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+	// SAFETY: the peer has duplicated this handle into our process for us to own
+	Ok(unsafe { OwnedHandle::from_raw_handle(handle.to_std()) })
+}
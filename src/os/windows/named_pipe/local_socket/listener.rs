@@ -2,10 +2,13 @@ use super::stream::Stream;
 use crate::{
 	local_socket::{
 		traits::{self, ListenerNonblockingMode, Stream as _},
-		ListenerOptions, NameInner,
+		ListenerOptions,
 	},
-	os::windows::named_pipe::{pipe_mode::Bytes, PipeListener, PipeListenerOptions},
-	AtomicEnum,
+	os::windows::{
+		local_socket::name_type::pipe_path,
+		named_pipe::{pipe_mode::Bytes, PipeListener, PipeListenerOptions},
+	},
+	AtomicEnum, TryClone,
 };
 use std::{io, os::windows::prelude::*, sync::atomic::Ordering::SeqCst};
 
@@ -25,10 +28,13 @@ impl traits::Listener for Listener {
 
 	fn from_options(options: ListenerOptions<'_>) -> io::Result<Self> {
 		let mut impl_options = PipeListenerOptions::new();
-		let NameInner::NamedPipe(path) = options.name.0;
-		impl_options.path = path;
+		impl_options.path = pipe_path(options.name)?;
 		impl_options.nonblocking = options.nonblocking.accept_nonblocking();
 		impl_options.security_descriptor = options.security_descriptor;
+		impl_options.accept_remote = options.accept_remote;
+		impl_options.input_buffer_size_hint = options.input_buffer_size_hint;
+		impl_options.output_buffer_size_hint = options.output_buffer_size_hint;
+		impl_options.wait_timeout = options.wait_timeout;
 
 		Ok(Self {
 			listener: impl_options.create()?,
@@ -56,6 +62,16 @@ impl traits::Listener for Listener {
 	fn do_not_reclaim_name_on_drop(&mut self) {}
 }
 
+/// See the implementation for [`PipeListener`] for details.
+impl TryClone for Listener {
+	fn try_clone(&self) -> io::Result<Self> {
+		Ok(Self {
+			listener: self.listener.try_clone()?,
+			nonblocking: AtomicEnum::new(self.nonblocking.load(SeqCst)),
+		})
+	}
+}
+
 impl From<Listener> for OwnedHandle {
 	#[inline]
 	fn from(l: Listener) -> Self {
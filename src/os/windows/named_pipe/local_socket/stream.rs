@@ -1,17 +1,32 @@
+use super::handle_transfer::{self, OutgoingHandle};
 use crate::{
 	error::{FromHandleError, ReuniteError},
+	handle_transfer::HandleTransfer,
 	local_socket::{
 		traits::{self, ReuniteResult},
-		Name, NameInner,
+		Liveness, Name,
 	},
-	os::windows::named_pipe::{pipe_mode::Bytes, DuplexPipeStream, RecvPipeStream, SendPipeStream},
-	Sealed,
+	os::windows::{
+		local_socket::name_type::pipe_path,
+		named_pipe::{
+			pipe_mode::Bytes, DuplexPipeStream, PipeListenerOptions, RecvPipeStream,
+			SendPipeStream, WaitTimeout,
+		},
+		path_conversion::{to_io_error, ToWtf16},
+		winprelude::*,
+	},
+	RawOsErrorExt, Sealed,
 };
 use std::{
-	io::{self, Write},
-	os::windows::io::OwnedHandle,
+	io::{self, Read, Write},
+	mem::MaybeUninit,
+	net::Shutdown,
+	process,
+	sync::atomic::{AtomicU32, Ordering::Relaxed},
 };
 
+use windows_sys::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SEM_TIMEOUT};
+
 type StreamImpl = DuplexPipeStream<Bytes>;
 type RecvHalfImpl = RecvPipeStream<Bytes>;
 type SendHalfImpl = SendPipeStream<Bytes>;
@@ -27,8 +42,7 @@ impl traits::Stream for Stream {
 	type SendHalf = SendHalf;
 
 	fn connect(name: Name<'_>) -> io::Result<Self> {
-		let NameInner::NamedPipe(path) = name.0;
-		StreamImpl::connect_by_path(path).map(Self)
+		StreamImpl::connect_by_path(pipe_path(name)?).map(Self)
 	}
 
 	#[inline]
@@ -50,6 +64,82 @@ impl traits::Stream for Stream {
 	}
 }
 
+impl Stream {
+	/// Creates a pair of connected streams using a named pipe with a unique name, returning the
+	/// server end first.
+	///
+	/// The pipe name is derived from the process ID and a counter. Should another process manage to
+	/// connect to it before the second end of the pair, the only instance of the pipe is busy and an
+	/// error of kind [`WouldBlock`](io::ErrorKind::WouldBlock) is returned.
+	pub fn pair() -> io::Result<(Self, Self)> {
+		static COUNTER: AtomicU32 = AtomicU32::new(0);
+		let pid = process::id();
+		let (path, listener) = loop {
+			let path = format!(
+				r"\\.\pipe\interprocess-pair-{pid:08x}-{:08x}",
+				COUNTER.fetch_add(1, Relaxed)
+			);
+			let rslt = PipeListenerOptions::new()
+				.path(path.as_str())
+				.create_duplex::<Bytes>();
+			match rslt {
+				// FILE_FLAG_FIRST_PIPE_INSTANCE reports name collisions as access denial
+				Err(e) if e.kind() == io::ErrorKind::PermissionDenied => continue,
+				els => break (path, els?),
+			}
+		};
+		let path = path.as_str().to_wtf_16().map_err(to_io_error)?;
+		let client = StreamImpl::connect_by_path_without_waiting(&path)?;
+		let server = listener.accept()?;
+		if server.client_process_id()? != pid {
+			return Err(io::Error::new(
+				io::ErrorKind::PermissionDenied,
+				"a different process connected to the named pipe of the stream pair",
+			));
+		}
+		Ok((Self(server), Self(client)))
+	}
+	/// Receives data from the stream without removing it. Never blocks – see
+	/// [`PipeStream::peek()`](crate::os::windows::named_pipe::PipeStream::peek) for details.
+	#[inline]
+	pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+		self.0.peek(buf)
+	}
+	/// Same as `.read()` from the [`Read`] trait, but accepts an uninitialized buffer – see
+	/// [`PipeStream::read_to_uninit()`](crate::os::windows::named_pipe::PipeStream::read_to_uninit)
+	/// for details.
+	#[inline]
+	pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+		self.0.read_to_uninit(buf)
+	}
+	/// Shuts down one or both halves of the stream. This is emulated, since named pipes cannot be
+	/// half-closed – see [`PipeStream::shutdown()`](crate::os::windows::named_pipe::PipeStream::shutdown)
+	/// for details.
+	#[inline]
+	pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+		self.0.shutdown(how)
+	}
+	/// Retrieves the process identifier of the other end of the connection.
+	#[inline]
+	pub(crate) fn peer_process_id(&self) -> io::Result<u32> {
+		handle_transfer::peer_process_id(self.0.as_handle(), self.0.is_server())
+	}
+}
+
+impl HandleTransfer for Stream {
+	fn send_handle(&self, handle: OwnedHandle) -> io::Result<()> {
+		let outgoing = OutgoingHandle::new(handle.as_handle(), self.peer_process_id()?)?;
+		(&self.0).write_all(outgoing.message())?;
+		outgoing.sent();
+		Ok(())
+	}
+	fn recv_handle(&self) -> io::Result<OwnedHandle> {
+		let mut msg = [0; handle_transfer::MSG_LEN];
+		(&self.0).read_exact(&mut msg)?;
+		handle_transfer::decode(msg)
+	}
+}
+
 /// Flushing fails with [`Unsupported`](io::ErrorKind::Unsupported).
 impl Write for &Stream {
 	#[inline]
@@ -153,3 +243,15 @@ impl Sealed for SendHalf {}
 impl traits::SendHalf for SendHalf {
 	type Stream = Stream;
 }
+
+pub(crate) fn probe(name: Name<'_>) -> io::Result<Liveness> {
+	let path = pipe_path(name)?;
+	// 0 means the default timeout of the pipe, so wait for as little as possible instead
+	match super::super::c_wrappers::block_for_server(&path, WaitTimeout::from_raw(1)) {
+		Ok(()) => Ok(Liveness::Listening),
+		// Every instance is busy, but the server is there
+		Err(e) if e.raw_os_error().eeq(ERROR_SEM_TIMEOUT) => Ok(Liveness::Listening),
+		Err(e) if e.raw_os_error().eeq(ERROR_FILE_NOT_FOUND) => Ok(Liveness::Absent),
+		Err(e) => Err(e),
+	}
+}
@@ -1,10 +1,13 @@
 use super::Stream;
 use crate::{
-	local_socket::{traits::tokio as traits, ListenerOptions, NameInner},
-	os::windows::named_pipe::{
-		pipe_mode,
-		tokio::{PipeListener as GenericPipeListener, PipeListenerOptionsExt as _},
-		PipeListenerOptions,
+	local_socket::{traits::tokio as traits, ListenerOptions},
+	os::windows::{
+		local_socket::name_type::pipe_path,
+		named_pipe::{
+			pipe_mode,
+			tokio::{PipeListener as GenericPipeListener, PipeListenerOptionsExt as _},
+			PipeListenerOptions,
+		},
 	},
 	Sealed,
 };
@@ -20,9 +23,12 @@ impl traits::Listener for Listener {
 
 	fn from_options(options: ListenerOptions<'_>) -> io::Result<Self> {
 		let mut impl_options = PipeListenerOptions::new();
-		let NameInner::NamedPipe(path) = options.name.0;
-		impl_options.path = path;
+		impl_options.path = pipe_path(options.name)?;
 		impl_options.security_descriptor = options.security_descriptor;
+		impl_options.accept_remote = options.accept_remote;
+		impl_options.input_buffer_size_hint = options.input_buffer_size_hint;
+		impl_options.output_buffer_size_hint = options.output_buffer_size_hint;
+		impl_options.wait_timeout = options.wait_timeout;
 		impl_options.create_tokio().map(Self)
 	}
 	async fn accept(&self) -> io::Result<Stream> {
@@ -1,22 +1,26 @@
+use super::super::handle_transfer::{self, OutgoingHandle};
 use crate::{
 	error::{FromHandleError, ReuniteError},
 	local_socket::{
 		traits::tokio::{self as traits, ReuniteResult},
-		Name, NameInner,
+		Name,
 	},
-	os::windows::named_pipe::{
-		pipe_mode::Bytes,
-		tokio::{DuplexPipeStream, RecvPipeStream, SendPipeStream},
+	os::windows::{
+		local_socket::name_type::pipe_path,
+		named_pipe::{
+			pipe_mode::Bytes,
+			tokio::{DuplexPipeStream, RecvPipeStream, SendPipeStream},
+		},
+		winprelude::*,
 	},
 	Sealed,
 };
 use std::{
 	io,
-	os::windows::prelude::*,
 	pin::Pin,
 	task::{Context, Poll},
 };
-use tokio::io::AsyncWrite;
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 type StreamImpl = DuplexPipeStream<Bytes>;
 type RecvHalfImpl = RecvPipeStream<Bytes>;
@@ -30,8 +34,9 @@ impl traits::Stream for Stream {
 	type SendHalf = SendHalf;
 
 	async fn connect(name: Name<'_>) -> io::Result<Self> {
-		let NameInner::NamedPipe(path) = name.0;
-		StreamImpl::connect_by_path(path).await.map(Self)
+		StreamImpl::connect_by_path(pipe_path(name)?)
+			.await
+			.map(Self)
 	}
 	#[inline]
 	fn split(self) -> (RecvHalf, SendHalf) {
@@ -49,6 +54,31 @@ impl traits::Stream for Stream {
 	}
 }
 
+impl Stream {
+	/// Asynchronous counterpart of
+	/// [`HandleTransfer::send_handle()`](crate::handle_transfer::HandleTransfer::send_handle),
+	/// using the same wire format.
+	pub async fn send_handle(&self, handle: OwnedHandle) -> io::Result<()> {
+		let outgoing = OutgoingHandle::new(handle.as_handle(), self.peer_process_id()?)?;
+		(&self.0).write_all(outgoing.message()).await?;
+		outgoing.sent();
+		Ok(())
+	}
+	/// Asynchronous counterpart of
+	/// [`HandleTransfer::recv_handle()`](crate::handle_transfer::HandleTransfer::recv_handle),
+	/// using the same wire format.
+	pub async fn recv_handle(&self) -> io::Result<OwnedHandle> {
+		let mut msg = [0; handle_transfer::MSG_LEN];
+		(&self.0).read_exact(&mut msg).await?;
+		handle_transfer::decode(msg)
+	}
+	/// Retrieves the process identifier of the other end of the connection.
+	#[inline]
+	pub(crate) fn peer_process_id(&self) -> io::Result<u32> {
+		handle_transfer::peer_process_id(self.0.as_handle(), self.0.is_server())
+	}
+}
+
 impl AsyncWrite for &Stream {
 	#[inline]
 	fn poll_write(
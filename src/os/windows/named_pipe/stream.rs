@@ -1,6 +1,8 @@
+mod cancel;
 mod enums;
 mod error;
-pub use {enums::*, error::*};
+mod info;
+pub use {cancel::*, enums::*, error::*, info::*};
 
 mod r#impl;
 mod limbo;
@@ -12,7 +14,14 @@ use crate::{
 	local_socket::{ConcurrencyDetectionSite, ConcurrencyDetector},
 	os::windows::FileHandle,
 };
-use std::{marker::PhantomData, os::windows::prelude::*};
+use std::{
+	marker::PhantomData,
+	os::windows::prelude::*,
+	sync::{
+		atomic::{AtomicU8, Ordering::Relaxed},
+		Mutex,
+	},
+};
 
 /// Named pipe stream, created by a server-side listener or by connecting to a server.
 ///
@@ -147,8 +156,15 @@ pub(crate) struct RawPipeStream {
 	is_server: bool,
 	needs_flush: NeedsFlush,
 	concurrency_detector: ConcurrencyDetector<NamedPipeSite>,
+	// Emulated shutdown state, a combination of the SHUT_* flags
+	shut: AtomicU8,
+	// Reused for concatenating the buffers of vectored message sends
+	send_scratch: Mutex<Vec<u8>>,
 }
 
+const SHUT_RECV: u8 = 0b01;
+const SHUT_SEND: u8 = 0b10;
+
 #[derive(Default)]
 struct NamedPipeSite;
 impl ConcurrencyDetectionSite for NamedPipeSite {
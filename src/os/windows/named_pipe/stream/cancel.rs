@@ -0,0 +1,32 @@
+use crate::os::windows::named_pipe::c_wrappers;
+use std::{io, os::windows::prelude::*};
+
+/// Handle for aborting blocking operations on a [`PipeStream`](super::PipeStream) from another
+/// thread, obtained via [`.cancel_handle()`](super::PipeStream::cancel_handle).
+///
+/// Cancelling makes every receive or send currently blocked on the pipe fail with the
+/// `ERROR_OPERATION_ABORTED` OS error, which the standard library reports with the
+/// [`TimedOut`](io::ErrorKind::TimedOut) error kind. The pipe itself stays open and can be used
+/// again after that, which makes this suitable for implementing timeouts and graceful shutdown
+/// without tearing down connections. Note, however, that a cancelled receive of a message might
+/// already have consumed part of it.
+///
+/// The handle refers to the pipe independently of the stream it was created from, and can be used
+/// any number of times. Only operations pending at the time of cancellation are affected – those
+/// that start afterwards block as usual.
+///
+/// The Tokio pipe stream does not need this: pending operations on it are cancelled by dropping
+/// their futures, e.g. via `tokio::time::timeout()` or `tokio::select!`.
+#[derive(Debug)]
+pub struct CancelHandle(OwnedHandle);
+impl CancelHandle {
+	pub(super) fn new(handle: OwnedHandle) -> Self {
+		Self(handle)
+	}
+	/// Cancels all operations pending on the pipe at the time of the call. Succeeds if there are
+	/// none.
+	#[inline]
+	pub fn cancel(&self) -> io::Result<()> {
+		c_wrappers::cancel_io(self.0.as_handle())
+	}
+}
@@ -13,11 +13,12 @@ pub(crate) use send_off::{LIMBO_ERR, REBURY_ERR};
 
 use super::*;
 use crate::os::windows::{
+	c_wrappers::duplicate_handle,
 	decode_eof,
 	named_pipe::{
 		c_wrappers::{self as c_wrappers, hget},
 		needs_flush::NeedsFlushVal,
-		PipeMode,
+		ImpersonationGuard, PipeMode,
 	},
 	FileHandle,
 };
@@ -25,6 +26,7 @@ use std::{
 	io::{self, prelude::*},
 	marker::PhantomData,
 	mem::MaybeUninit,
+	net::Shutdown,
 };
 use windows_sys::Win32::System::Pipes;
 
@@ -82,6 +84,55 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
 		unsafe { hget(self.as_handle(), Pipes::GetNamedPipeServerSessionId) }
 	}
 
+	/// Impersonates the client of the named pipe connection, making the calling thread operate
+	/// under the client's security context until the returned guard is dropped.
+	///
+	/// This can only be done on the server side, and only after at least one message has been
+	/// received from the client. The level of impersonation available is limited by the security
+	/// quality of service requested by the client when connecting.
+	///
+	/// See [`ImpersonationGuard`] for more on how impersonation is terminated.
+	#[inline]
+	pub fn impersonate_client(&self) -> io::Result<ImpersonationGuard<'_>> {
+		c_wrappers::impersonate_client(self.as_handle())?;
+		Ok(ImpersonationGuard::new())
+	}
+
+	/// Retrieves the properties which the pipe was created with, such as the sizes of its buffers.
+	#[inline]
+	pub fn pipe_info(&self) -> io::Result<PipeInfo> {
+		PipeInfo::query(self.as_handle())
+	}
+	/// Retrieves the current state of the pipe handle, such as its read mode.
+	#[inline]
+	pub fn handle_state(&self) -> io::Result<PipeHandleState> {
+		PipeHandleState::query(self.as_handle())
+	}
+	/// Returns the amount of bytes that can currently be received from the pipe without blocking.
+	///
+	/// For message pipes, this is the total size of all messages in the buffer.
+	///
+	/// Interacts with [concurrency prevention](#concurrency-prevention).
+	#[inline]
+	pub fn bytes_available(&self) -> io::Result<usize> {
+		self.raw.peek(None).map(|(_, available)| available)
+	}
+
+	/// Shuts down the receive half, the send half, or both halves of the stream.
+	///
+	/// Named pipes cannot be half-closed, so this is emulated on the local end of the pipe:
+	/// -	After the send half is shut down, sends fail with
+	/// 	[`BrokenPipe`](io::ErrorKind::BrokenPipe). Shutting it down flushes the stream, blocking
+	/// 	until the peer has received everything sent so far.
+	/// -	After the receive half is shut down, receives report end of file.
+	///
+	/// The peer is not notified and only observes the end of the connection once the stream is
+	/// dropped. Shutting down a half more than once has no additional effect.
+	#[inline]
+	pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+		self.raw.shutdown(how)
+	}
+
 	/// Returns `true` if the stream was created by a listener (server-side), `false` if it was
 	/// created by connecting to a server (server-side).
 	#[inline]
@@ -95,6 +146,17 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
 		!self.raw.is_server
 	}
 
+	/// Creates a [`CancelHandle`] which can be used to abort receives and sends blocked on the
+	/// stream from another thread.
+	///
+	/// The cancel handle holds a duplicate of the pipe handle, which keeps the pipe open for as
+	/// long as it exists: the peer does not observe the end of the connection until both the
+	/// stream and the cancel handle are dropped.
+	#[inline]
+	pub fn cancel_handle(&self) -> io::Result<CancelHandle> {
+		duplicate_handle(self.as_handle()).map(CancelHandle::new)
+	}
+
 	/// Sets whether the nonblocking mode for the pipe stream is enabled. By default, it is
 	/// disabled.
 	///
@@ -1,5 +1,6 @@
 use super::*;
 use crate::os::windows::{named_pipe::WaitTimeout, path_conversion::*};
+use std::time::{Duration, Instant};
 use widestring::U16CStr;
 use windows_sys::Win32::System::Pipes::PIPE_READMODE_MESSAGE;
 
@@ -10,6 +11,8 @@ impl RawPipeStream {
 			is_server,
 			needs_flush: NeedsFlush::from(NeedsFlushVal::No),
 			concurrency_detector: ConcurrencyDetector::new(),
+			shut: AtomicU8::new(0),
+			send_scratch: Mutex::new(Vec::new()),
 		}
 	}
 	pub(crate) fn new_server(handle: FileHandle) -> Self {
@@ -18,17 +21,41 @@ impl RawPipeStream {
 	fn new_client(handle: FileHandle) -> Self {
 		Self::new(handle, false)
 	}
-	fn connect(path: &U16CStr, recv: Option<PipeMode>, send: Option<PipeMode>) -> io::Result<Self> {
+	/// Connects to the pipe, waiting for a server instance for the default wait timeout if
+	/// `timeout` is `None` or for the given time otherwise.
+	fn connect(
+		path: &U16CStr,
+		recv: Option<PipeMode>,
+		send: Option<PipeMode>,
+		timeout: Option<Duration>,
+	) -> io::Result<Self> {
+		// If the timeout is too large to represent as a deadline, it's as good as infinite
+		let deadline = timeout.map(|t| Instant::now().checked_add(t));
 		let handle = loop {
 			match c_wrappers::connect_without_waiting(path, recv, send, false) {
 				Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
-					c_wrappers::block_for_server(path, WaitTimeout::DEFAULT)?;
+					match deadline {
+						Some(deadline) => c_wrappers::block_for_server_until(path, deadline)?,
+						None => c_wrappers::block_for_server(path, WaitTimeout::DEFAULT)?,
+					}
 					continue;
 				}
 				els => break els,
 			}
 		}?;
-
+		Self::finish_connect(handle, recv)
+	}
+	/// Connects to the pipe, failing with an error of kind [`WouldBlock`](io::ErrorKind::WouldBlock)
+	/// instead of waiting if all server instances are busy.
+	fn connect_without_waiting(
+		path: &U16CStr,
+		recv: Option<PipeMode>,
+		send: Option<PipeMode>,
+	) -> io::Result<Self> {
+		let handle = c_wrappers::connect_without_waiting(path, recv, send, false)?;
+		Self::finish_connect(handle, recv)
+	}
+	fn finish_connect(handle: FileHandle, recv: Option<PipeMode>) -> io::Result<Self> {
 		if recv == Some(PipeMode::Messages) {
 			c_wrappers::set_np_handle_state(
 				handle.as_handle(),
@@ -44,10 +71,47 @@ impl RawPipeStream {
 impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
 	/// Connects to the specified named pipe at the specified path (the `\\<hostname>\pipe\` prefix
 	/// is not added automatically), blocking until a server instance is dispatched.
+	///
+	/// Paths of pipes on other machines are connected to over the network as is. For paths which
+	/// come from untrusted sources, use
+	/// [`NameBuilderExt`](crate::os::windows::local_socket::NameBuilderExt) and local sockets,
+	/// which reject such paths by default.
 	#[inline]
 	pub fn connect_by_path<'p>(path: impl ToWtf16<'p>) -> io::Result<Self> {
-		RawPipeStream::connect(&path.to_wtf_16().map_err(to_io_error)?, Rm::MODE, Sm::MODE)
-			.map(Self::new)
+		RawPipeStream::connect(
+			&path.to_wtf_16().map_err(to_io_error)?,
+			Rm::MODE,
+			Sm::MODE,
+			None,
+		)
+		.map(Self::new)
+	}
+	/// Connects to the specified named pipe at the specified path (the `\\<hostname>\pipe\` prefix
+	/// is not added automatically), waiting for at most `timeout` for a server instance to become
+	/// available.
+	///
+	/// When all instances of the server are busy, this waits for one to free up via
+	/// `WaitNamedPipe` and retries, failing with an error of kind
+	/// [`TimedOut`](io::ErrorKind::TimedOut) if the timeout runs out first. Fails immediately if
+	/// the pipe does not exist at all.
+	#[inline]
+	pub fn connect_by_path_timeout<'p>(
+		path: impl ToWtf16<'p>,
+		timeout: Duration,
+	) -> io::Result<Self> {
+		RawPipeStream::connect(
+			&path.to_wtf_16().map_err(to_io_error)?,
+			Rm::MODE,
+			Sm::MODE,
+			Some(timeout),
+		)
+		.map(Self::new)
+	}
+	/// Like [`connect_by_path()`](Self::connect_by_path), but fails with an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) instead of waiting if all server instances are
+	/// busy.
+	pub(crate) fn connect_by_path_without_waiting(path: &U16CStr) -> io::Result<Self> {
+		RawPipeStream::connect_without_waiting(path, Rm::MODE, Sm::MODE).map(Self::new)
 	}
 
 	/// Internal constructor used by the listener. It's a logic error, but not UB, to create the
@@ -2,7 +2,7 @@ use windows_sys::Win32::System::Pipes::{PIPE_SERVER_END, PIPE_TYPE_MESSAGE};
 
 use super::*;
 use crate::{os::windows::c_wrappers::duplicate_handle, TryClone};
-use std::mem::ManuallyDrop;
+use std::{fs::File, mem::ManuallyDrop};
 
 impl AsHandle for RawPipeStream {
 	#[inline]
@@ -88,6 +88,28 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<OwnedHandle> for PipeStream<Rm, S
 	}
 }
 
+/// Attempts to unwrap the given stream into a [`File`], returning itself back if no ownership over
+/// the handle is available, as is the case when the stream is split.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<PipeStream<Rm, Sm>> for File {
+	type Error = PipeStream<Rm, Sm>;
+	#[inline]
+	fn try_from(s: PipeStream<Rm, Sm>) -> Result<Self, Self::Error> {
+		OwnedHandle::try_from(s).map(File::from)
+	}
+}
+
+/// Attempts to wrap the handle of the given file into the high-level pipe stream type, as with the
+/// [`OwnedHandle`] conversion. Files which aren't named pipes are rejected with
+/// [`IsServerCheckFailed`](FromHandleErrorKind::IsServerCheckFailed), the handle being returned
+/// in the error.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<File> for PipeStream<Rm, Sm> {
+	type Error = FromHandleError;
+	#[inline]
+	fn try_from(file: File) -> Result<Self, Self::Error> {
+		OwnedHandle::from(file).try_into()
+	}
+}
+
 impl<Rm: PipeModeTag, Sm: PipeModeTag> TryClone for PipeStream<Rm, Sm> {
 	fn try_clone(&self) -> io::Result<Self> {
 		let handle = duplicate_handle(self.as_handle())?;
@@ -8,9 +8,22 @@ impl RawPipeStream {
 	}
 	#[track_caller]
 	fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+		if self.is_shut(SHUT_RECV) {
+			return Ok(0);
+		}
 		let _guard = self.concurrency_detector.lock();
 		self.file_handle().read(buf)
 	}
+	/// Returns the amount of bytes copied into `buf` and the total amount of bytes available, in
+	/// that order.
+	#[track_caller]
+	pub(super) fn peek(&self, buf: Option<&mut [MaybeUninit<u8>]>) -> io::Result<(usize, usize)> {
+		if self.is_shut(SHUT_RECV) {
+			return Err(io::ErrorKind::BrokenPipe.into());
+		}
+		let _guard = self.concurrency_detector.lock();
+		c_wrappers::peek(self.as_handle(), buf)
+	}
 }
 
 impl<Sm: PipeModeTag> PipeStream<pipe_mode::Bytes, Sm> {
@@ -21,6 +34,20 @@ impl<Sm: PipeModeTag> PipeStream<pipe_mode::Bytes, Sm> {
 	pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
 		downgrade_eof(self.raw.read_to_uninit(buf))
 	}
+	/// Receives data from the pipe without removing it, returning how many bytes were received.
+	///
+	/// Successive calls return the same data, and subsequent reads will receive it again. Unlike
+	/// receiving, peeking never blocks: if no data is available, an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) is returned. The end of the connection is
+	/// reported as `Ok(0)`, like it is for receiving.
+	///
+	/// Interacts with [concurrency prevention](#concurrency-prevention).
+	pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+		match self.raw.peek(Some(weaken_buf_init_mut(buf))) {
+			Ok((0, 0)) if !buf.is_empty() => Err(io::ErrorKind::WouldBlock.into()),
+			els => downgrade_eof(els.map(|(copied, _)| copied)),
+		}
+	}
 }
 
 /// Interacts with [concurrency prevention](#concurrency-prevention).
@@ -34,6 +34,9 @@ impl RawPipeStream {
 
 		buf.set_fill(0);
 		buf.has_msg = false;
+		if self.is_shut(SHUT_RECV) {
+			return Ok(RecvResult::EndOfStream);
+		}
 		let mut more_data = true;
 		let mut partial = false;
 		let mut spilled = false;
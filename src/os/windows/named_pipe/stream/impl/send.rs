@@ -1,8 +1,16 @@
 use super::*;
+use crate::{
+	os::windows::{coalesce_bufs, first_nonempty},
+	LOCK_POISON,
+};
+use std::{io::IoSlice, net::Shutdown};
 
 impl RawPipeStream {
 	#[track_caller]
 	fn send(&self, buf: &[u8]) -> io::Result<usize> {
+		if self.is_shut(SHUT_SEND) {
+			return Err(io::ErrorKind::BrokenPipe.into());
+		}
 		let r = {
 			let _guard = self.concurrency_detector.lock();
 			self.file_handle().write(buf)
@@ -25,6 +33,22 @@ impl RawPipeStream {
 			Ok(())
 		}
 	}
+
+	pub(super) fn is_shut(&self, half: u8) -> bool {
+		self.shut.load(Relaxed) & half != 0
+	}
+	pub(super) fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+		let halves = match how {
+			Shutdown::Read => SHUT_RECV,
+			Shutdown::Write => SHUT_SEND,
+			Shutdown::Both => SHUT_RECV | SHUT_SEND,
+		};
+		self.shut.fetch_or(halves, Relaxed);
+		if halves & SHUT_SEND != 0 {
+			self.flush()?;
+		}
+		Ok(())
+	}
 }
 
 impl<Rm: PipeModeTag, Sm: PipeModeTag + PmtNotNone> PipeStream<Rm, Sm> {
@@ -66,6 +90,19 @@ impl<Rm: PipeModeTag> PipeStream<Rm, pipe_mode::Messages> {
 	pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
 		self.raw.send(buf)
 	}
+	/// Sends a message assembled from the given buffers into the pipe, returning how many bytes
+	/// were successfully sent.
+	///
+	/// The buffers are concatenated into a single message. Since Windows does not provide vectored
+	/// I/O for pipes, this entails copying the buffers into a buffer owned by the stream if more
+	/// than one of them is nonempty. That buffer is reused by subsequent calls, and grows to the
+	/// size of the largest message sent this way.
+	///
+	/// Interacts with [concurrency prevention](#concurrency-prevention).
+	pub fn send_vectored(&self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+		let mut scratch = self.raw.send_scratch.lock().expect(LOCK_POISON);
+		self.raw.send(coalesce_bufs(bufs, &mut scratch))
+	}
 }
 
 /// Interacts with [concurrency prevention](#concurrency-prevention).
@@ -74,6 +111,12 @@ impl<Rm: PipeModeTag> Write for &PipeStream<Rm, pipe_mode::Bytes> {
 	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
 		self.raw.send(buf)
 	}
+	/// Writes only the first nonempty buffer, since Windows does not provide vectored I/O for
+	/// pipes.
+	#[inline]
+	fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+		self.raw.send(first_nonempty(bufs))
+	}
 	#[inline]
 	fn flush(&mut self) -> io::Result<()> {
 		self.raw.flush()
@@ -86,6 +129,10 @@ impl<Rm: PipeModeTag> Write for PipeStream<Rm, pipe_mode::Bytes> {
 		(&*self).write(buf)
 	}
 	#[inline(always)]
+	fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+		(&*self).write_vectored(bufs)
+	}
+	#[inline(always)]
 	fn flush(&mut self) -> io::Result<()> {
 		(&mut &*self).flush()
 	}
@@ -0,0 +1,86 @@
+use crate::os::windows::named_pipe::{c_wrappers, PipeMode};
+use std::{io, num::NonZeroU8, os::windows::prelude::*};
+use windows_sys::Win32::System::Pipes::{PIPE_NOWAIT, PIPE_READMODE_MESSAGE, PIPE_TYPE_MESSAGE};
+
+/// Properties of a named pipe which are fixed when its server creates it, as returned by the
+/// `.pipe_info()` method of pipe streams.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct PipeInfo {
+	/// The mode in which data is written into the pipe.
+	pub mode: PipeMode,
+	/// The size of the buffer for data flowing from clients to the server, in bytes. Zero means
+	/// that buffers are allocated as needed.
+	pub input_buffer_size: u32,
+	/// The size of the buffer for data flowing from the server to clients, in bytes. Zero means
+	/// that buffers are allocated as needed.
+	pub output_buffer_size: u32,
+	/// The maximum amount of instances of the pipe which can exist at once, or `None` if there is no
+	/// limit.
+	pub instance_limit: Option<NonZeroU8>,
+}
+impl PipeInfo {
+	pub(crate) fn query(handle: BorrowedHandle<'_>) -> io::Result<Self> {
+		let [mut flags, mut input_buffer_size, mut output_buffer_size, mut max_instances] =
+			[0_u32; 4];
+		c_wrappers::get_np_info(
+			handle,
+			Some(&mut flags),
+			Some(&mut input_buffer_size),
+			Some(&mut output_buffer_size),
+			Some(&mut max_instances),
+		)?;
+		let mode = if flags & PIPE_TYPE_MESSAGE != 0 {
+			PipeMode::Messages
+		} else {
+			PipeMode::Bytes
+		};
+		// 255 is PIPE_UNLIMITED_INSTANCES
+		let instance_limit = match u8::try_from(max_instances) {
+			Ok(255) | Err(..) => None,
+			Ok(n) => NonZeroU8::new(n),
+		};
+		Ok(Self {
+			mode,
+			input_buffer_size,
+			output_buffer_size,
+			instance_limit,
+		})
+	}
+}
+
+/// State of a named pipe handle which can change over its lifetime, as returned by the
+/// `.handle_state()` method of pipe streams.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct PipeHandleState {
+	/// The mode in which data is read from the pipe through this handle.
+	pub read_mode: PipeMode,
+	/// Whether the handle is in nonblocking mode.
+	pub nonblocking: bool,
+	/// The amount of instances of the pipe which currently exist.
+	pub current_instances: u32,
+}
+impl PipeHandleState {
+	pub(crate) fn query(handle: BorrowedHandle<'_>) -> io::Result<Self> {
+		let [mut mode, mut current_instances] = [0_u32; 2];
+		c_wrappers::get_np_handle_state(
+			handle,
+			Some(&mut mode),
+			Some(&mut current_instances),
+			None,
+			None,
+			None,
+		)?;
+		let read_mode = if mode & PIPE_READMODE_MESSAGE != 0 {
+			PipeMode::Messages
+		} else {
+			PipeMode::Bytes
+		};
+		Ok(Self {
+			read_mode,
+			nonblocking: mode & PIPE_NOWAIT != 0,
+			current_instances,
+		})
+	}
+}
@@ -1,6 +1,8 @@
 use crate::{
+	error::FromHandleError,
 	os::windows::{
 		named_pipe::{
+			c_wrappers, check_foreign_instance,
 			enums::{PipeMode, PipeStreamRole},
 			pipe_mode,
 			tokio::{PipeStream, RawPipeStream},
@@ -127,6 +129,31 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
 		Ok(PipeStream::new(raw))
 	}
 
+	/// Returns the number of currently existing instances of the named pipe, which includes the
+	/// instance held by the listener for the next client as well as those owned by server-side
+	/// streams that haven't been dropped yet.
+	///
+	/// The maximum is controlled by the
+	/// [`instance_limit` field](PipeListenerOptions::instance_limit) of the creation options. Once
+	/// it's reached, clients which connect wait for an instance to free up, and `.accept()` fails,
+	/// since it creates an instance for the next client before returning the connected one.
+	///
+	/// This locks the same internal mutex as `.accept()`, and will thus wait until a concurrent
+	/// call to `.accept()` completes.
+	pub async fn instance_count(&self) -> io::Result<u32> {
+		let instance = self.stored_instance.lock().await;
+		let mut count = 0;
+		c_wrappers::get_np_handle_state(
+			instance.as_handle(),
+			None,
+			Some(&mut count),
+			None,
+			None,
+			None,
+		)?;
+		Ok(count)
+	}
+
 	/// Creates a listener from a [corresponding Tokio object](TokioNPServer) and a
 	/// [`PipeListenerOptions`] table with the assumption that the handle was created with those
 	/// options.
@@ -164,6 +191,35 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
 		))
 	}
 
+	/// Creates a listener from a handle to a named pipe server instance that was created outside of
+	/// the program, such as one inherited from a parent process or passed via the handle list of
+	/// `STARTUPINFOEX`, checking that it matches the given [`PipeListenerOptions`] table.
+	///
+	/// The handle must be the server end of a named pipe, and its pipe type must match the
+	/// [`mode` field](PipeListenerOptions::mode) of the options. If either of those conditions
+	/// doesn't hold, an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput) is returned
+	/// along with ownership of the handle. Additionally, the handle must have been created with
+	/// `FILE_FLAG_OVERLAPPED`, which cannot be checked.
+	///
+	/// Once adopted, the handle is made non-inheritable.
+	///
+	/// # Errors
+	/// In addition to the above, returns an error if called outside a Tokio runtime, in which case
+	/// ownership of the handle is not returned.
+	pub fn try_from_handle_and_options(
+		handle: OwnedHandle,
+		options: PipeListenerOptions<'static>,
+	) -> Result<Self, FromHandleError> {
+		if let Err(e) = check_foreign_instance(handle.as_handle(), options.mode) {
+			return Err(FromHandleError {
+				details: Default::default(),
+				cause: Some(e),
+				source: Some(handle),
+			});
+		}
+		Self::from_handle_and_options(handle, options).map_err(FromHandleError::from_cause)
+	}
+
 	fn create_instance(&self) -> io::Result<TokioNPServer> {
 		self.config
 			.create_instance(false, false, true, Self::STREAM_ROLE, Rm::MODE)
@@ -94,6 +94,8 @@ pub(crate) struct RawPipeStream {
 	// TODO(2.0.1) crackhead specialization
 	// Cleared by the generic pipes rather than by the raw pipe stream, unlike in sync land.
 	needs_flush: NeedsFlush,
+	// Reused for concatenating the buffers of vectored message sends
+	send_scratch: Mutex<Vec<u8>>,
 	// MESSAGE READING DISABLED
 	//recv_msg_state: Mutex<RecvMsgState>,
 }
@@ -48,6 +48,10 @@ impl Display for FromHandleErrorKind {
 /// [non-Tokio version](crate::os::windows::named_pipe::stream::FromHandleError).
 pub type FromHandleError = ConversionError<OwnedHandle, FromHandleErrorKind>;
 
+/// Error type for conversions from Tokio's own named pipe types, such as
+/// [`TryFrom<NamedPipeClient>`](TryFrom). The Tokio object is returned in the `source` field.
+pub type FromTokioError<T> = ConversionError<T, FromHandleErrorKind>;
+
 /// [`ReuniteError`](crate::error::ReuniteError) for Tokio named pipe streams.
 pub type ReuniteError<Rm, Sm> = crate::error::ReuniteError<RecvPipeStream<Rm>, SendPipeStream<Sm>>;
 
@@ -20,7 +20,7 @@ use super::*;
 use crate::os::windows::{
 	named_pipe::{
 		c_wrappers::{self, hget},
-		PipeMode,
+		ImpersonationGuard, PipeHandleState, PipeInfo, PipeMode,
 	},
 	winprelude::*,
 };
@@ -89,6 +89,41 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
 	pub fn server_session_id(&self) -> io::Result<u32> {
 		unsafe { hget(self.as_handle(), Pipes::GetNamedPipeServerSessionId) }
 	}
+	/// Impersonates the client of the named pipe connection, making the calling thread operate
+	/// under the client's security context until the returned guard is dropped.
+	///
+	/// This can only be done on the server side, and only after at least one message has been
+	/// received from the client. The level of impersonation available is limited by the security
+	/// quality of service requested by the client when connecting.
+	///
+	/// Impersonation applies to the thread rather than the task. The guard should thus not be held
+	/// across `.await` points, since the task may be resumed on a different thread.
+	///
+	/// See [`ImpersonationGuard`] for more on how impersonation is terminated.
+	#[inline]
+	pub fn impersonate_client(&self) -> io::Result<ImpersonationGuard<'_>> {
+		c_wrappers::impersonate_client(self.as_handle())?;
+		Ok(ImpersonationGuard::new())
+	}
+
+	/// Retrieves the properties which the pipe was created with, such as the sizes of its buffers.
+	#[inline]
+	pub fn pipe_info(&self) -> io::Result<PipeInfo> {
+		PipeInfo::query(self.as_handle())
+	}
+	/// Retrieves the current state of the pipe handle, such as its read mode.
+	#[inline]
+	pub fn handle_state(&self) -> io::Result<PipeHandleState> {
+		PipeHandleState::query(self.as_handle())
+	}
+	/// Returns the amount of bytes that can currently be received from the pipe without blocking.
+	///
+	/// For message pipes, this is the total size of all messages in the buffer.
+	#[inline]
+	pub fn bytes_available(&self) -> io::Result<usize> {
+		c_wrappers::peek(self.as_handle(), None).map(|(_, available)| available)
+	}
+
 	/// Returns `true` if the stream was created by a listener (server-side), `false` if it was
 	/// created by connecting to a server (server-side).
 	#[inline]
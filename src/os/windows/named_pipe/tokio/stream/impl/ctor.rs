@@ -5,13 +5,18 @@ use crate::os::windows::{
 	named_pipe::{NeedsFlushVal, WaitTimeout},
 	path_conversion::*,
 };
-use std::{borrow::Cow, mem::take};
+use std::{
+	borrow::Cow,
+	mem::take,
+	time::{Duration, Instant},
+};
 
 impl RawPipeStream {
 	pub(super) fn new(inner: InnerTokio) -> Self {
 		Self {
 			inner: Some(inner),
 			needs_flush: NeedsFlush::from(NeedsFlushVal::No),
+			send_scratch: Mutex::new(Vec::new()),
 			//recv_msg_state: Mutex::new(RecvMsgState::NotRecving),
 		}
 	}
@@ -22,9 +27,15 @@ impl RawPipeStream {
 		Self::new(InnerTokio::Client(client))
 	}
 
-	async fn wait_for_server(path: U16CString) -> io::Result<U16CString> {
+	async fn wait_for_server(
+		path: U16CString,
+		deadline: Option<Option<Instant>>,
+	) -> io::Result<U16CString> {
 		tokio::task::spawn_blocking(move || {
-			c_wrappers::block_for_server(&path, WaitTimeout::DEFAULT)?;
+			match deadline {
+				Some(deadline) => c_wrappers::block_for_server_until(&path, deadline)?,
+				None => c_wrappers::block_for_server(&path, WaitTimeout::DEFAULT)?,
+			}
 			Ok(path)
 		})
 		.await
@@ -35,11 +46,13 @@ impl RawPipeStream {
 		mut path: U16CString,
 		recv: Option<PipeMode>,
 		send: Option<PipeMode>,
+		timeout: Option<Duration>,
 	) -> io::Result<Self> {
+		let deadline = timeout.map(|t| Instant::now().checked_add(t));
 		let client = loop {
 			match c_wrappers::connect_without_waiting(&path, recv, send, true) {
 				Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
-					let path_take = Self::wait_for_server(take(&mut path)).await?;
+					let path_take = Self::wait_for_server(take(&mut path), deadline).await?;
 					path = path_take;
 				}
 				not_waiting => break not_waiting?,
@@ -65,6 +78,28 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
 			path.to_wtf_16().map(Cow::into_owned).map_err(to_io_error)?,
 			Rm::MODE,
 			Sm::MODE,
+			None,
+		)
+		.await
+		.map(Self::new)
+	}
+	/// Connects to the specified named pipe at the specified path (the `\\<hostname>\pipe\` prefix
+	/// is not added automatically), waiting for at most `timeout` for a server instance to become
+	/// available.
+	///
+	/// See the documentation of the
+	/// [synchronous version](crate::os::windows::named_pipe::PipeStream::connect_by_path_timeout)
+	/// for details.
+	#[inline]
+	pub async fn connect_by_path_timeout<'s>(
+		path: impl ToWtf16<'s>,
+		timeout: Duration,
+	) -> io::Result<Self> {
+		RawPipeStream::connect(
+			path.to_wtf_16().map(Cow::into_owned).map_err(to_io_error)?,
+			Rm::MODE,
+			Sm::MODE,
+			Some(timeout),
 		)
 		.await
 		.map(Self::new)
@@ -1,6 +1,7 @@
 use windows_sys::Win32::System::Pipes::{PIPE_SERVER_END, PIPE_TYPE_MESSAGE};
 
 use super::*;
+use crate::os::windows::named_pipe::LIMBO_ERR;
 use std::mem::ManuallyDrop;
 
 impl AsHandle for InnerTokio {
@@ -99,3 +100,106 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<OwnedHandle> for PipeStream<Rm, S
 }
 
 derive_asraw!({Rm: PipeModeTag, Sm: PipeModeTag} PipeStream<Rm, Sm>, windows);
+
+impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
+	fn try_from_tokio<T: AsHandle>(
+		obj: T,
+		wrap: impl FnOnce(T) -> InnerTokio,
+	) -> Result<Self, FromTokioError<T>> {
+		let flags = match c_wrappers::get_flags(obj.as_handle()) {
+			Ok(f) => f,
+			Err(e) => {
+				return Err(FromTokioError {
+					details: FromHandleErrorKind::IsServerCheckFailed,
+					cause: Some(e),
+					source: Some(obj),
+				})
+			}
+		};
+		if Rm::MODE == Some(PipeMode::Messages) && flags & PIPE_TYPE_MESSAGE == 0 {
+			return Err(FromTokioError {
+				details: FromHandleErrorKind::NoMessageBoundaries,
+				cause: None,
+				source: Some(obj),
+			});
+		}
+		Ok(Self::new(RawPipeStream::new(wrap(obj))))
+	}
+	fn try_into_tokio<T>(
+		self,
+		extract: impl FnOnce(InnerTokio) -> Result<T, InnerTokio>,
+	) -> Result<T, Self> {
+		let PipeStream {
+			raw,
+			flush,
+			_phantom,
+		} = self;
+		let raw = match raw {
+			MaybeArc::Inline(raw) => raw,
+			shared => {
+				return Err(PipeStream {
+					raw: shared,
+					flush,
+					_phantom,
+				})
+			}
+		};
+		// The destructor of the raw stream expects the Tokio object to still be there
+		let mut raw = ManuallyDrop::new(raw);
+		match extract(raw.inner.take().expect(LIMBO_ERR)) {
+			Ok(obj) => Ok(obj),
+			Err(inner) => {
+				raw.inner = Some(inner);
+				Err(PipeStream {
+					raw: MaybeArc::Inline(ManuallyDrop::into_inner(raw)),
+					flush,
+					_phantom,
+				})
+			}
+		}
+	}
+}
+
+/// Wraps the given Tokio pipe client into the high-level pipe stream type, checking that the pipe
+/// preserves message boundaries if the stream receives messages.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<TokioNPClient> for PipeStream<Rm, Sm> {
+	type Error = FromTokioError<TokioNPClient>;
+	#[inline]
+	fn try_from(client: TokioNPClient) -> Result<Self, Self::Error> {
+		Self::try_from_tokio(client, InnerTokio::Client)
+	}
+}
+/// Wraps the given connected Tokio pipe server into the high-level pipe stream type, checking that
+/// the pipe preserves message boundaries if the stream receives messages.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<TokioNPServer> for PipeStream<Rm, Sm> {
+	type Error = FromTokioError<TokioNPServer>;
+	#[inline]
+	fn try_from(server: TokioNPServer) -> Result<Self, Self::Error> {
+		Self::try_from_tokio(server, InnerTokio::Server)
+	}
+}
+
+/// Attempts to unwrap the given stream into the Tokio pipe client, returning itself back if it is
+/// split or is a server-side stream. Data that has been sent but not yet flushed is not waited for.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<PipeStream<Rm, Sm>> for TokioNPClient {
+	type Error = PipeStream<Rm, Sm>;
+	#[inline]
+	fn try_from(s: PipeStream<Rm, Sm>) -> Result<Self, Self::Error> {
+		s.try_into_tokio(|inner| match inner {
+			InnerTokio::Client(client) => Ok(client),
+			els => Err(els),
+		})
+	}
+}
+/// Attempts to unwrap the given stream into the Tokio pipe server, returning itself back if it is
+/// split or is a client-side stream. Data that has been sent but not yet flushed is not waited for.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<PipeStream<Rm, Sm>> for TokioNPServer {
+	type Error = PipeStream<Rm, Sm>;
+	#[inline]
+	fn try_from(s: PipeStream<Rm, Sm>) -> Result<Self, Self::Error> {
+		s.try_into_tokio(|inner| match inner {
+			InnerTokio::Server(server) => Ok(server),
+			els => Err(els),
+		})
+	}
+}
@@ -1,9 +1,9 @@
 use super::*;
 use crate::{
-	os::windows::{named_pipe::PmtNotNone, winprelude::*, FileHandle},
+	os::windows::{coalesce_bufs, named_pipe::PmtNotNone, winprelude::*, FileHandle},
 	UnpinExt, LOCK_POISON,
 };
-use std::sync::MutexGuard;
+use std::{io::IoSlice, mem::take, sync::MutexGuard};
 use tokio::io::AsyncWrite;
 
 impl RawPipeStream {
@@ -103,6 +103,20 @@ impl<Rm: PipeModeTag> PipeStream<Rm, pipe_mode::Messages> {
 		}
 		Write(&self.raw, buf).await
 	}
+	/// Sends a message assembled from the given buffers into the pipe, returning how many bytes
+	/// were successfully sent.
+	///
+	/// The buffers are concatenated into a single message. Since Windows does not provide vectored
+	/// I/O for pipes, this entails copying the buffers into a buffer owned by the stream if more
+	/// than one of them is nonempty. That buffer is reused by subsequent calls, and grows to the
+	/// size of the largest message sent this way.
+	pub async fn send_vectored(&self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+		// Taken out for the duration of the send so that the lock isn't held across an await
+		let mut scratch = take(&mut *self.raw.send_scratch.lock().expect(LOCK_POISON));
+		let rslt = self.send(coalesce_bufs(bufs, &mut scratch)).await;
+		*self.raw.send_scratch.lock().expect(LOCK_POISON) = scratch;
+		rslt
+	}
 }
 
 impl<Rm: PipeModeTag> AsyncWrite for &PipeStream<Rm, pipe_mode::Bytes> {
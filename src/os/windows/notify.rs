@@ -0,0 +1,68 @@
+//! Wakeup notifications between processes, split into a signalling half and a waiting half.
+//!
+//! Both halves are handles to the same auto-reset event object. A signal sent before the waiter
+//! starts waiting is not lost, and several signals may be coalesced into a single wakeup.
+
+use super::{c_wrappers, winprelude::*};
+use crate::{OrErrno, TryClone};
+use std::{io, ptr};
+use windows_sys::Win32::{
+	Foundation::{WAIT_FAILED, WAIT_OBJECT_0},
+	System::Threading::{CreateEventW, SetEvent, WaitForSingleObject, INFINITE},
+};
+
+pub(crate) fn notify_pair() -> io::Result<(Notifier, Waiter)> {
+	let handle = unsafe { CreateEventW(ptr::null(), 0, 0, ptr::null()) };
+	// CreateEventW() returns null rather than INVALID_HANDLE_VALUE on failure.
+	let handle =
+		(handle != 0).true_or_errno(|| unsafe { OwnedHandle::from_raw_handle(handle.to_std()) })?;
+	let dup = c_wrappers::duplicate_handle(handle.as_handle())?;
+	Ok((Notifier(dup), Waiter(handle)))
+}
+
+fn wait(handle: BorrowedHandle<'_>, timeout: u32) -> io::Result<bool> {
+	match unsafe { WaitForSingleObject(handle.as_int_handle(), timeout) } {
+		WAIT_OBJECT_0 => Ok(true),
+		WAIT_FAILED => Err(io::Error::last_os_error()),
+		_ => Ok(false),
+	}
+}
+
+pub(crate) struct Notifier(OwnedHandle);
+impl Notifier {
+	pub fn signal(&self) -> io::Result<()> {
+		unsafe { SetEvent(self.0.as_int_handle()) }.true_val_or_errno(())
+	}
+}
+impl TryClone for Notifier {
+	fn try_clone(&self) -> io::Result<Self> {
+		c_wrappers::duplicate_handle(self.0.as_handle()).map(Self)
+	}
+}
+multimacro! {
+	Notifier,
+	forward_handle(windows),
+	forward_debug("Notifier"),
+}
+
+pub(crate) struct Waiter(OwnedHandle);
+impl Waiter {
+	/// Consumes the pending signal, returning whether there was one.
+	pub fn try_wait(&self) -> io::Result<bool> {
+		wait(self.0.as_handle(), 0)
+	}
+	/// Blocks until a signal arrives, consuming it.
+	pub fn wait(&self) -> io::Result<()> {
+		wait(self.0.as_handle(), INFINITE).map(drop)
+	}
+}
+impl TryClone for Waiter {
+	fn try_clone(&self) -> io::Result<Self> {
+		c_wrappers::duplicate_handle(self.0.as_handle()).map(Self)
+	}
+}
+multimacro! {
+	Waiter,
+	forward_handle(windows),
+	forward_debug("Waiter"),
+}
@@ -0,0 +1,61 @@
+//! Passing local sockets to child processes.
+//!
+//! On the parent side, [`duplicate_inheritable()`] produces an inheritable copy of a handle, such
+//! as that of a [local socket stream](crate::local_socket::Stream), which is then inherited by
+//! every child spawned via [`Command`](std::process::Command) while the copy is alive. On the
+//! child side, [`adopt_stream()`] turns such a handle back into a local socket stream, after
+//! checking that it really is one. Named pipe listeners can likewise be adopted with
+//! [`PipeListener::try_from_handle_and_options()`](super::named_pipe::PipeListener::try_from_handle_and_options).
+//!
+//! Handle values are valid in the child process as-is, but the child has no way of finding out
+//! which handles it has inherited. The numeric value of the handle thus has to be communicated
+//! out-of-band, e.g. via a command-line argument or an environment variable.
+
+use super::{c_wrappers, named_pipe::local_socket::Stream as NpStream, winprelude::*};
+use crate::local_socket::Stream;
+use std::io;
+
+/// Duplicates the given handle, making the copy inheritable by child processes.
+///
+/// The original handle is left as-is, so that it isn't unintentionally leaked into processes
+/// spawned later on. The copy should be dropped right after the child process is spawned.
+pub fn duplicate_inheritable(handle: BorrowedHandle<'_>) -> io::Result<OwnedHandle> {
+	let copy = c_wrappers::duplicate_handle(handle)?;
+	c_wrappers::set_inheritable(copy.as_handle(), true)?;
+	Ok(copy)
+}
+
+/// Takes ownership of a local socket stream inherited from the parent process.
+///
+/// Before taking ownership, the handle is checked to actually be a named pipe, failing with an
+/// error if it isn't; in that case, the handle is left open. An error of kind
+/// [`InvalidInput`](io::ErrorKind::InvalidInput) is returned if the handle isn't a pipe at all.
+/// Upon success, the handle is made non-inheritable so that it doesn't leak further into
+/// grandchildren.
+///
+/// # Safety
+/// `handle` must be an open handle which isn't owned by anything else in the process, as per
+/// [`FromRawHandle::from_raw_handle()`].
+pub unsafe fn adopt_stream(handle: RawHandle) -> io::Result<Stream> {
+	// SAFETY: as per safety contract, the handle is open
+	let borrowed = unsafe { BorrowedHandle::borrow_raw(handle) };
+	if !c_wrappers::is_pipe(borrowed) {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"inherited handle is not a pipe",
+		));
+	}
+	// SAFETY: as per safety contract, nothing else owns the handle
+	let owned = unsafe { OwnedHandle::from_raw_handle(handle) };
+	match NpStream::try_from(owned) {
+		Ok(s) => {
+			c_wrappers::set_inheritable(s.as_handle(), false)?;
+			Ok(s.into())
+		}
+		Err(mut e) => {
+			// Give up ownership without closing the handle, as promised
+			let _ = e.source.take().map(IntoRawHandle::into_raw_handle);
+			Err(e.to_io_error())
+		}
+	}
+}
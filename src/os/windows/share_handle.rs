@@ -30,5 +30,10 @@ pub trait ShareHandle: AsHandle {
 		c_wrappers::duplicate_handle_to_foreign(self.as_handle(), receiver).map(HANDLE::to_std)
 	}
 }
+impl ShareHandle for crate::event::Notifier {}
+impl ShareHandle for crate::event::Waiter {}
+impl ShareHandle for crate::shmem::SharedMemObject {}
+impl ShareHandle for super::mailslot::MailslotServer {}
+impl ShareHandle for super::mailslot::MailslotClient {}
 impl ShareHandle for crate::unnamed_pipe::Recver {}
 impl ShareHandle for crate::unnamed_pipe::Sender {}
@@ -0,0 +1,213 @@
+use super::{c_wrappers, path_conversion::to_io_error, winprelude::*};
+use crate::{error::FromHandleError, OrErrno, TryClone};
+use std::{
+	ffi::c_void,
+	fmt::{self, Debug, Formatter},
+	io,
+	mem::{size_of, zeroed},
+	ptr::{self, NonNull},
+	slice,
+};
+use widestring::U16CString;
+use windows_sys::Win32::{
+	Foundation::{GetLastError, ERROR_ALREADY_EXISTS},
+	System::Memory::{
+		CreateFileMappingW, MapViewOfFile, OpenFileMappingW, UnmapViewOfFile, VirtualQuery,
+		FILE_MAP_ALL_ACCESS, MEMORY_BASIC_INFORMATION, MEMORY_MAPPED_VIEW_ADDRESS, PAGE_READWRITE,
+	},
+};
+
+fn invalid_input(msg: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+fn check_size(size: usize) -> io::Result<(u32, u32)> {
+	if size == 0 {
+		return Err(invalid_input("shared memory object cannot be empty"));
+	}
+	let size = u64::try_from(size).map_err(|_| invalid_input("shared memory size out of range"))?;
+	let high = u32::try_from(size.checked_shr(32).unwrap_or(0)).unwrap_or(u32::MAX);
+	let low = u32::try_from(size & u64::from(u32::MAX)).unwrap_or(u32::MAX);
+	Ok((high, low))
+}
+fn mapping_name(name: &str) -> io::Result<U16CString> {
+	U16CString::from_str(name).map_err(to_io_error)
+}
+/// File mapping functions return null rather than `INVALID_HANDLE_VALUE` on failure.
+unsafe fn own_mapping_handle(handle: HANDLE) -> io::Result<OwnedHandle> {
+	(handle != 0).true_or_errno(|| unsafe { OwnedHandle::from_raw_handle(handle.to_std()) })
+}
+
+pub(crate) struct SharedMemObject {
+	handle: OwnedHandle,
+	size: usize,
+}
+impl SharedMemObject {
+	fn create_impl(name: Option<&str>, size: usize) -> io::Result<Self> {
+		let (high, low) = check_size(size)?;
+		let name = name.map(mapping_name).transpose()?;
+		let handle = unsafe {
+			let handle = CreateFileMappingW(
+				INVALID_HANDLE_VALUE,
+				ptr::null(),
+				PAGE_READWRITE,
+				high,
+				low,
+				name.as_ref().map_or(ptr::null(), |n| n.as_ptr()),
+			);
+			own_mapping_handle(handle)?
+		};
+		// CreateFileMappingW() succeeds and opens the existing object if the name is taken
+		if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+			return Err(io::Error::from_raw_os_error(
+				i32::try_from(ERROR_ALREADY_EXISTS).unwrap_or(i32::MAX),
+			));
+		}
+		Ok(Self { handle, size })
+	}
+	pub fn create(name: &str, size: usize) -> io::Result<Self> {
+		Self::create_impl(Some(name), size)
+	}
+	pub fn open(name: &str, size: usize) -> io::Result<Self> {
+		check_size(size)?;
+		let name = mapping_name(name)?;
+		let handle =
+			unsafe { own_mapping_handle(OpenFileMappingW(FILE_MAP_ALL_ACCESS, 0, name.as_ptr()))? };
+		Ok(Self { handle, size })
+	}
+	pub fn anonymous(size: usize) -> io::Result<Self> {
+		Self::create_impl(None, size)
+	}
+	/// Named file mappings are reference-counted and disappear together with their last handle.
+	pub fn unlink(_name: &str) -> io::Result<()> {
+		Ok(())
+	}
+
+	#[inline]
+	pub fn size(&self) -> usize {
+		self.size
+	}
+	/// Maps the whole section, which is never smaller than what was requested when creating it,
+	/// but may be smaller than the size passed to `open()`.
+	pub unsafe fn map(&self) -> io::Result<Mapping> {
+		let ptr = map_view(self.handle.as_handle())?;
+		let mapping = Mapping {
+			ptr,
+			len: self.size,
+		};
+		if view_size(ptr)? < self.size {
+			return Err(invalid_input(
+				"shared memory object is smaller than the requested size",
+			));
+		}
+		Ok(mapping)
+	}
+}
+
+fn map_view(handle: BorrowedHandle<'_>) -> io::Result<NonNull<u8>> {
+	let addr = unsafe { MapViewOfFile(handle.as_int_handle(), FILE_MAP_ALL_ACCESS, 0, 0, 0) };
+	NonNull::new(addr.Value.cast::<u8>()).ok_or_else(io::Error::last_os_error)
+}
+fn unmap_view(ptr: NonNull<u8>) {
+	unsafe {
+		UnmapViewOfFile(MEMORY_MAPPED_VIEW_ADDRESS {
+			Value: ptr.as_ptr().cast::<c_void>(),
+		})
+	};
+}
+/// The size of a section cannot be queried directly, so this measures a view of the whole of it,
+/// which is rounded up to the page size.
+fn view_size(ptr: NonNull<u8>) -> io::Result<usize> {
+	let mut info = unsafe { zeroed::<MEMORY_BASIC_INFORMATION>() };
+	let rslt = unsafe {
+		VirtualQuery(
+			ptr.as_ptr().cast::<c_void>(),
+			&mut info,
+			size_of::<MEMORY_BASIC_INFORMATION>(),
+		)
+	};
+	(rslt != 0).true_or_errno(|| info.RegionSize)
+}
+fn handle_size(handle: BorrowedHandle<'_>) -> io::Result<usize> {
+	let ptr = map_view(handle)?;
+	let rslt = view_size(ptr);
+	unmap_view(ptr);
+	rslt
+}
+
+impl Debug for SharedMemObject {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SharedMemObject")
+			.field("handle", &self.handle.as_raw_handle())
+			.field("size", &self.size)
+			.finish()
+	}
+}
+impl TryClone for SharedMemObject {
+	fn try_clone(&self) -> io::Result<Self> {
+		Ok(Self {
+			handle: c_wrappers::duplicate_handle(self.handle.as_handle())?,
+			size: self.size,
+		})
+	}
+}
+impl AsHandle for SharedMemObject {
+	#[inline]
+	fn as_handle(&self) -> BorrowedHandle<'_> {
+		self.handle.as_handle()
+	}
+}
+impl From<SharedMemObject> for OwnedHandle {
+	#[inline]
+	fn from(obj: SharedMemObject) -> Self {
+		obj.handle
+	}
+}
+impl TryFrom<OwnedHandle> for SharedMemObject {
+	type Error = FromHandleError;
+	fn try_from(handle: OwnedHandle) -> Result<Self, Self::Error> {
+		match handle_size(handle.as_handle()) {
+			Ok(size) => Ok(Self { handle, size }),
+			Err(e) => Err(FromHandleError::from_source_and_cause(handle, e)),
+		}
+	}
+}
+
+pub(crate) struct Mapping {
+	ptr: NonNull<u8>,
+	len: usize,
+}
+impl Mapping {
+	#[inline]
+	pub fn as_ptr(&self) -> *mut u8 {
+		self.ptr.as_ptr()
+	}
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.len
+	}
+	#[inline]
+	pub fn as_slice(&self) -> &[u8] {
+		unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+	}
+	#[inline]
+	pub fn as_mut_slice(&mut self) -> &mut [u8] {
+		unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+	}
+}
+impl Drop for Mapping {
+	fn drop(&mut self) {
+		unmap_view(self.ptr);
+	}
+}
+impl Debug for Mapping {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Mapping")
+			.field("ptr", &self.ptr)
+			.field("len", &self.len)
+			.finish()
+	}
+}
+// SAFETY: the mapping is just memory; synchronizing access to it is up to the user, as promised
+// when mapping it.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
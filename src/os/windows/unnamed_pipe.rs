@@ -5,17 +5,20 @@
 
 // TODO(2.0.1) add examples and tests
 
-use super::{security_descriptor::*, winprelude::*, FileHandle};
+#[cfg(feature = "tokio")]
+pub(crate) mod tokio;
+
+use super::{c_wrappers, first_nonempty, security_descriptor::*, winprelude::*, FileHandle};
 use crate::{
 	unnamed_pipe::{Recver as PubRecver, Sender as PubSender},
-	weaken_buf_init_mut, AsPtr,
+	weaken_buf_init_mut, AsPtr, RawOsErrorExt,
 };
 use std::{
 	fmt::{self, Debug, Formatter},
-	io::{self, Read, Write},
+	io::{self, IoSlice, Read, Write},
 	num::NonZeroUsize,
 };
-use windows_sys::Win32::System::Pipes::CreatePipe;
+use windows_sys::Win32::{Foundation::ERROR_NO_DATA, System::Pipes::CreatePipe};
 
 /// Builder used to create unnamed pipes while supplying additional options.
 ///
@@ -28,7 +31,7 @@ pub struct CreationOptions<'sd> {
 	pub security_descriptor: Option<BorrowedSecurityDescriptor<'sd>>,
 	/// Specifies whether the resulting pipe can be inherited by child processes.
 	///
-	/// The default value is `true`.
+	/// The default value is `false`.
 	pub inheritable: bool,
 	/// Hint on the buffer size for the pipe. There is no way to ensure or check that the system
 	/// actually uses this exact size, since it's only a hint. Set to `None` to disable the hint and
@@ -117,9 +120,21 @@ pub(crate) fn pipe() -> io::Result<(PubSender, PubRecver)> {
 }
 
 pub(crate) struct Recver(FileHandle);
+impl Recver {
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		c_wrappers::set_nonblocking_bytes(self.as_handle(), nonblocking)
+	}
+	pub fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+		c_wrappers::set_inheritable(self.as_handle(), inheritable)
+	}
+}
 impl Read for Recver {
 	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-		self.0.read(weaken_buf_init_mut(buf))
+		match self.0.read(weaken_buf_init_mut(buf)) {
+			// This is what an empty pipe in PIPE_NOWAIT mode reports
+			Err(e) if e.raw_os_error().eeq(ERROR_NO_DATA) => Err(io::ErrorKind::WouldBlock.into()),
+			els => els,
+		}
 	}
 }
 impl Debug for Recver {
@@ -136,10 +151,21 @@ multimacro! {
 }
 
 pub(crate) struct Sender(FileHandle);
+impl Sender {
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		c_wrappers::set_nonblocking_bytes(self.as_handle(), nonblocking)
+	}
+	pub fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+		c_wrappers::set_inheritable(self.as_handle(), inheritable)
+	}
+}
 impl Write for Sender {
 	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
 		self.0.write(buf)
 	}
+	fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+		self.0.write(first_nonempty(bufs))
+	}
 	fn flush(&mut self) -> io::Result<()> {
 		self.0.flush()
 	}
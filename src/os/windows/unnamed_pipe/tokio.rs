@@ -0,0 +1,59 @@
+use crate::{
+	unnamed_pipe::tokio::{Recver as PubRecver, Sender as PubSender},
+	Sealed,
+};
+use std::{
+	io, process,
+	sync::atomic::{AtomicU32, Ordering::Relaxed},
+};
+use tokio::net::windows::named_pipe::{
+	ClientOptions, NamedPipeClient, NamedPipeServer, PipeMode, ServerOptions,
+};
+
+pub(crate) fn pipe() -> io::Result<(PubSender, PubRecver)> {
+	static COUNTER: AtomicU32 = AtomicU32::new(0);
+	let pid = process::id();
+	let (path, server) = loop {
+		let path = format!(
+			r"\\.\pipe\interprocess-unnamed-{pid:08x}-{:08x}",
+			COUNTER.fetch_add(1, Relaxed)
+		);
+		let rslt = ServerOptions::new()
+			.pipe_mode(PipeMode::Byte)
+			.access_outbound(false)
+			.first_pipe_instance(true)
+			.reject_remote_clients(true)
+			.max_instances(1)
+			.create(&path);
+		match rslt {
+			// FILE_FLAG_FIRST_PIPE_INSTANCE reports name collisions as access denial
+			Err(e) if e.kind() == io::ErrorKind::PermissionDenied => continue,
+			els => break (path, els?),
+		}
+	};
+	// The pipe only allows one instance, so if someone else manages to connect to it before us,
+	// this fails with ERROR_PIPE_BUSY instead of silently giving away the receiving end.
+	let client = ClientOptions::new().read(false).write(true).open(&path)?;
+	Ok((PubSender(Sender(client)), PubRecver(Recver(server))))
+}
+
+pub(crate) struct Recver(NamedPipeServer);
+impl Sealed for Recver {}
+multimacro! {
+	Recver,
+	pinproj_for_unpin(NamedPipeServer),
+	forward_tokio_read,
+	forward_as_handle(windows),
+	forward_debug("Recver"),
+}
+
+pub(crate) struct Sender(NamedPipeClient);
+impl Sealed for Sender {}
+multimacro! {
+	Sender,
+	pinproj_for_unpin(NamedPipeClient),
+	forward_rbv(NamedPipeClient, &),
+	forward_tokio_write,
+	forward_as_handle(windows),
+	forward_debug("Sender"),
+}
@@ -0,0 +1,401 @@
+//! A local service registry, through which processes find each other's sockets by service name.
+//!
+//! The registry is a [`Broker`] listening on a well-known name – [`broker_name()`], which is
+//! [`BROKER_NAME`] made [user-scoped](Name::user_scoped) – at which servers register the names of
+//! their sockets under service names, such as `com.example.service`, and clients look them up,
+//! much like DNS does for hosts. This lets servers pick their socket names at runtime, e.g. to run
+//! several instances side by side, without clients having to be configured with those names.
+//!
+//! Whoever listens on the name of the broker first gets to answer all lookups, and could thus
+//! direct clients to sockets of its choosing. That is why the default name is per user: on Unix,
+//! it is placed in a directory which only the current user can access, so that each user has a
+//! registry of their own which other users cannot take over. On Windows, however, named pipes
+//! share one namespace, and so a local user who starts listening on the name before the broker
+//! does can still impersonate it. Clients which must not be misdirected should authenticate the
+//! servers they connect to, e.g. with the encrypted sessions of the `secure` module, rather than
+//! trust the registry.
+//!
+//! A service stays registered for as long as the [`Client`] which registered it stays connected
+//! to the broker, so that services whose server has exited don't linger in the registry. Only that
+//! client can change or remove the registration, and other clients trying to register the same
+//! service get an error of kind [`AddrInUse`](io::ErrorKind::AddrInUse). Service names are limited
+//! to 1 KiB, and socket names are exchanged in the syntax of [`Name::interpret()`] and limited to
+//! 4 KiB.
+//!
+//! The broker runs on the [Tokio server](crate::local_socket::tokio::server), and so do the
+//! clients. This module is only available with the `registry` feature, which enables the `tokio`
+//! feature.
+//!
+//! # Example
+//! ```no_run
+//! # #[tokio::main(flavor = "current_thread")] async fn main() -> std::io::Result<()> {
+//! use interprocess::{
+//! 	local_socket::server::ServerConfig,
+//! 	registry::{self, broker_name, Broker, Client},
+//! };
+//!
+//! // Typically a process of its own
+//! let broker = Broker::serve(broker_name()?, ServerConfig::new())?;
+//!
+//! // In the server, which listens on `@example-1234.sock`
+//! let mut client = Client::connect(broker_name()?).await?;
+//! client.register("com.example.service", "@example-1234.sock").await?;
+//!
+//! // In a client of the server
+//! let name = registry::resolve("com.example.service").await?.expect("service not running");
+//! # let _ = name;
+//! # broker.shutdown().await?;
+//! # Ok(()) }
+//! ```
+
+use crate::{
+	local_socket::{
+		broker_util::{frame_kind, invalid, lock, race, read_frame_tokio, FrameHeader},
+		server::ServerConfig,
+		tokio::{
+			prelude::*,
+			server::{serve, Server},
+			Stream, TimeoutStream,
+		},
+		Name,
+	},
+	SubUsizeExt,
+};
+use std::{
+	collections::{btree_map::Entry, BTreeMap},
+	io,
+	sync::{
+		atomic::{AtomicU64, Ordering::SeqCst},
+		Arc, Mutex,
+	},
+};
+use tokio::{
+	io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+	sync::watch,
+};
+
+/// The file name from which [`broker_name()`] makes the name which the broker listens on by
+/// default.
+pub const BROKER_NAME: &str = "interprocess-registry.sock";
+
+const MAX_SERVICE_LEN: usize = 1 << 10;
+const MAX_ENDPOINT_LEN: usize = 1 << 12;
+const HEADER_LEN: usize = 5;
+
+/// Returns the [user-scoped](Name::user_scoped) name of the default broker of the current user,
+/// made from [`BROKER_NAME`].
+///
+/// See the [module-level documentation](self) for the extent to which other users are kept from
+/// listening on it.
+#[inline]
+pub fn broker_name() -> io::Result<Name<'static>> {
+	Name::user_scoped(BROKER_NAME)
+}
+
+/// Looks up the socket name of the given service with the broker listening on [`broker_name()`],
+/// returning `None` if the service isn't registered.
+///
+/// This connects to the broker anew every time. Use a [`Client`] to make several lookups over
+/// the same connection.
+pub async fn resolve(service: &str) -> io::Result<Option<Name<'static>>> {
+	Client::connect(broker_name()?)
+		.await?
+		.resolve(service)
+		.await
+}
+
+frame_kind! {
+	enum Kind {
+		Register = 1,
+		Unregister = 2,
+		Resolve = 3,
+		/// Sent by the broker if the request succeeded, with the socket name if it was a lookup.
+		Done = 4,
+		/// Sent by the broker if the service isn't registered, or, in response to
+		/// [`Unregister`](Self::Unregister), isn't registered by the client.
+		NotFound = 5,
+		/// Sent by the broker if the service is registered by another client.
+		Taken = 6,
+	}
+}
+
+/// A frame of the protocol spoken between the broker and its clients, which consists of the kind,
+/// the length of the service name and the length of the socket name as little-endian `u16`s, the
+/// service name and the socket name.
+#[derive(Debug)]
+struct Frame {
+	kind: Kind,
+	service: String,
+	endpoint: String,
+}
+
+fn encode(kind: Kind, service: &str, endpoint: &str) -> io::Result<Vec<u8>> {
+	if service.len() > MAX_SERVICE_LEN {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"service name too long",
+		));
+	}
+	if endpoint.len() > MAX_ENDPOINT_LEN {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"socket name too long",
+		));
+	}
+	// Both fit by the checks above
+	let service_len = u16::try_from(service.len()).unwrap_or(u16::MAX);
+	let endpoint_len = u16::try_from(endpoint.len()).unwrap_or(u16::MAX);
+
+	let mut buf = Vec::with_capacity(HEADER_LEN);
+	buf.push(kind.to_byte());
+	buf.extend(service_len.to_le_bytes());
+	buf.extend(endpoint_len.to_le_bytes());
+	buf.extend(service.as_bytes());
+	buf.extend(endpoint.as_bytes());
+	Ok(buf)
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Header {
+	kind: Kind,
+	service_len: usize,
+	endpoint_len: usize,
+}
+impl FrameHeader for Header {
+	type Bytes = [u8; HEADER_LEN];
+	type Frame = Frame;
+	fn parse(bytes: [u8; HEADER_LEN]) -> io::Result<Self> {
+		let [kind, s0, s1, e0, e1] = bytes;
+		let kind = Kind::from_byte(kind).ok_or_else(|| invalid("unknown frame kind"))?;
+		let service_len = u16::from_le_bytes([s0, s1]).to_usize();
+		let endpoint_len = u16::from_le_bytes([e0, e1]).to_usize();
+		if service_len > MAX_SERVICE_LEN || endpoint_len > MAX_ENDPOINT_LEN {
+			return Err(invalid("frame too large"));
+		}
+		Ok(Self {
+			kind,
+			service_len,
+			endpoint_len,
+		})
+	}
+	fn body_len(&self) -> usize {
+		self.service_len.saturating_add(self.endpoint_len)
+	}
+	fn finish(self, mut body: Vec<u8>) -> io::Result<Frame> {
+		if body.len() != self.body_len() {
+			return Err(invalid("frame truncated"));
+		}
+		let endpoint = body.split_off(self.service_len);
+		let utf8 = |bytes| String::from_utf8(bytes).map_err(|_| invalid("name is not valid UTF-8"));
+		Ok(Frame {
+			kind: self.kind,
+			service: utf8(body)?,
+			endpoint: utf8(endpoint)?,
+		})
+	}
+}
+
+/// The registry itself, running on the [Tokio server](crate::local_socket::tokio::server).
+///
+/// Every client occupies one of the [`max_connections`](ServerConfig::max_connections) of the
+/// configuration for as long as it is connected. The [`timeout`](ServerConfig::timeout) of the
+/// configuration bounds how long a client may go without sending anything, which includes servers
+/// keeping their services registered, and should thus usually be left unset.
+///
+/// Dropping the broker disconnects all clients and stops accepting new ones, without waiting for
+/// the tasks which handle the clients to finish.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct Broker {
+	stop: watch::Sender<bool>,
+	server: Server,
+}
+impl Broker {
+	/// Creates a listener on the given name, which is usually [`broker_name()`], and starts serving
+	/// the registry to the clients which connect to it.
+	///
+	/// This function must be called within the context of a Tokio runtime.
+	pub fn serve(name: Name<'_>, config: ServerConfig) -> io::Result<Self> {
+		let state = Arc::new(State::default());
+		let (stop, stop_rx) = watch::channel(false);
+		let server = serve(
+			name,
+			move |conn| Arc::clone(&state).handle(conn, stop_rx.clone()),
+			config,
+		)?;
+		Ok(Self { stop, server })
+	}
+	/// Disconnects all clients, which unregisters all services, stops accepting new ones and waits
+	/// for the tasks which handle the clients to finish.
+	///
+	/// Returns the error which made the underlying server stop beforehand, if any.
+	pub async fn shutdown(self) -> io::Result<()> {
+		let Self { stop, server } = self;
+		// Dropping the sender counts as a stop request as well
+		let _ = stop.send(true);
+		server.shutdown().await
+	}
+}
+
+#[derive(Debug)]
+struct Registration {
+	owner: u64,
+	endpoint: String,
+}
+
+#[derive(Debug, Default)]
+struct State {
+	next_id: AtomicU64,
+	services: Mutex<BTreeMap<String, Registration>>,
+}
+
+impl State {
+	async fn handle(
+		self: Arc<Self>,
+		mut conn: TimeoutStream<Stream>,
+		mut stop: watch::Receiver<bool>,
+	) {
+		let id = self.next_id.fetch_add(1, SeqCst);
+		race(
+			stop.wait_for(|&stop| stop),
+			self.serve_client(id, &mut conn),
+		)
+		.await;
+		lock(&self.services).retain(|_, registration| registration.owner != id);
+	}
+	async fn serve_client(
+		&self,
+		id: u64,
+		conn: &mut (impl AsyncRead + AsyncWrite + Unpin),
+	) -> io::Result<()> {
+		while let Some(frame) = read_frame_tokio::<Header>(conn).await? {
+			let (kind, endpoint) = match frame.kind {
+				Kind::Register => (self.register(id, frame.service, frame.endpoint), None),
+				Kind::Unregister => (self.unregister(id, &frame.service), None),
+				Kind::Resolve => match lock(&self.services).get(&frame.service) {
+					Some(registration) => (Kind::Done, Some(registration.endpoint.clone())),
+					None => (Kind::NotFound, None),
+				},
+				Kind::Done | Kind::NotFound | Kind::Taken => {
+					return Err(invalid("unexpected response from client"))
+				}
+			};
+			let endpoint = endpoint.as_deref().unwrap_or_default();
+			conn.write_all(&encode(kind, "", endpoint)?).await?;
+		}
+		Ok(())
+	}
+	fn register(&self, id: u64, service: String, endpoint: String) -> Kind {
+		match lock(&self.services).entry(service) {
+			Entry::Vacant(entry) => {
+				entry.insert(Registration {
+					owner: id,
+					endpoint,
+				});
+			}
+			Entry::Occupied(mut entry) if entry.get().owner == id => {
+				entry.get_mut().endpoint = endpoint;
+			}
+			Entry::Occupied(..) => return Kind::Taken,
+		}
+		Kind::Done
+	}
+	fn unregister(&self, id: u64, service: &str) -> Kind {
+		let mut services = lock(&self.services);
+		match services.get(service) {
+			Some(registration) if registration.owner == id => {
+				services.remove(service);
+				Kind::Done
+			}
+			_ => Kind::NotFound,
+		}
+	}
+}
+
+/// A connection to the [broker](Broker), through which services are registered and looked up.
+///
+/// Services registered by a client remain registered until they are
+/// [unregistered](Self::unregister) or the client is dropped.
+///
+/// Cancelling any of the methods leaves the client in an unspecified state, in which it should be
+/// dropped.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct Client(Stream);
+impl Client {
+	/// Connects to the broker with the given name, which is usually [`broker_name()`].
+	#[inline]
+	pub async fn connect(name: Name<'_>) -> io::Result<Self> {
+		Stream::connect(name).await.map(Self)
+	}
+	/// Registers the service under the given socket name, which is written in the syntax of
+	/// [`Name::interpret()`], or changes the socket name of a service already registered by this
+	/// client.
+	///
+	/// Fails with [`AddrInUse`](io::ErrorKind::AddrInUse) if the service is registered by another
+	/// client, and with [`InvalidInput`](io::ErrorKind::InvalidInput) if the service name is empty,
+	/// the socket name is not valid on this platform, or either exceeds the limits given in the
+	/// [module-level documentation](self).
+	pub async fn register(&mut self, service: &str, endpoint: &str) -> io::Result<()> {
+		check_service(service)?;
+		Name::interpret(endpoint)?;
+		match self.request(Kind::Register, service, endpoint).await? {
+			(Kind::Done, ..) => Ok(()),
+			(Kind::Taken, ..) => Err(io::Error::new(
+				io::ErrorKind::AddrInUse,
+				"service is registered by another client",
+			)),
+			_ => Err(invalid("unexpected response from broker")),
+		}
+	}
+	/// Unregisters a service registered by this client.
+	///
+	/// Fails with [`NotFound`](io::ErrorKind::NotFound) if the service is not registered by this
+	/// client.
+	pub async fn unregister(&mut self, service: &str) -> io::Result<()> {
+		check_service(service)?;
+		match self.request(Kind::Unregister, service, "").await? {
+			(Kind::Done, ..) => Ok(()),
+			(Kind::NotFound, ..) => Err(io::Error::new(
+				io::ErrorKind::NotFound,
+				"service is not registered by this client",
+			)),
+			_ => Err(invalid("unexpected response from broker")),
+		}
+	}
+	/// Looks up the socket name of the given service, returning `None` if the service isn't
+	/// registered.
+	pub async fn resolve(&mut self, service: &str) -> io::Result<Option<Name<'static>>> {
+		check_service(service)?;
+		match self.request(Kind::Resolve, service, "").await? {
+			(Kind::Done, endpoint) => endpoint.parse().map(Some),
+			(Kind::NotFound, ..) => Ok(None),
+			_ => Err(invalid("unexpected response from broker")),
+		}
+	}
+
+	async fn request(
+		&mut self,
+		kind: Kind,
+		service: &str,
+		endpoint: &str,
+	) -> io::Result<(Kind, String)> {
+		self.0.write_all(&encode(kind, service, endpoint)?).await?;
+		match read_frame_tokio::<Header>(&mut self.0).await? {
+			Some(frame) => Ok((frame.kind, frame.endpoint)),
+			None => Err(io::ErrorKind::UnexpectedEof.into()),
+		}
+	}
+}
+
+fn check_service(service: &str) -> io::Result<()> {
+	if service.is_empty() {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"service name is empty",
+		));
+	}
+	Ok(())
+}
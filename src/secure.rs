@@ -0,0 +1,499 @@
+//! Encryption of streams using the [Noise protocol framework](https://noiseprotocol.org/).
+//!
+//! Local sockets are normally only reachable from within the machine, but that stops being a
+//! guarantee of privacy once they are shared across a trust boundary, such as a socket file
+//! mounted into a container or a directory which several users can access. [`SecureStream`] wraps
+//! any stream in an encrypted and authenticated session, so that the data sent over it cannot be
+//! read or tampered with by anyone but the two peers, and its Tokio counterpart
+//! `secure::tokio::SecureStream` does the same for Tokio streams with the `tokio` feature.
+//!
+//! Sessions are established with the `Noise_XX_25519_ChaChaPoly_BLAKE2s` handshake, in which both
+//! peers prove possession of a static [`Keypair`]. Key material is entirely up to the application:
+//! each side is [configured](Config) with its own private key and, optionally, with the public key
+//! which it expects the other side to have, which is how a peer is authenticated. A side which
+//! doesn't set an expected key accepts any peer, and can inspect its key with
+//! [`.remote_public_key()`](SecureStream::remote_public_key) after the handshake.
+//!
+//! Once the handshake is complete, data is sent in frames of up to 65535 bytes, each consisting of
+//! its length as a little-endian `u16` and the ciphertext. A peer which sends a frame that fails to
+//! decrypt makes the receiving side fail with [`InvalidData`](io::ErrorKind::InvalidData).
+//!
+//! # Closing
+//! A session is ended with a close message, which is an encrypted frame without any data in it,
+//! sent by [`.close()`](SecureStream::close) or, for Tokio streams, by shutting the stream down.
+//! Only once it arrives does the other side read end of file. If the wrapped stream ends without
+//! one, reading fails with [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) instead, since the end
+//! of the wrapped stream could just as well have been caused by someone cutting it short in
+//! between two frames.
+//!
+//! # Failed I/O
+//! Every frame is encrypted with the next nonce of the session, so the two sides stay in sync only
+//! as long as every frame is sent and received in full. If the wrapped stream fails partway
+//! through a frame – for example, because a write to it fails after its frame has been encrypted,
+//! or because a read times out in the middle of a frame – the session is *poisoned*, and every
+//! subsequent read and write fails with an error of kind [`Other`](io::ErrorKind::Other).
+//! Failures which happen before any of a frame is read, such as a
+//! [`WouldBlock`](io::ErrorKind::WouldBlock) error on a nonblocking stream, leave the session
+//! intact.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::{
+//! 	local_socket::{prelude::*, GenericNamespaced, ListenerOptions, Stream},
+//! 	secure::{Config, Keypair, SecureStream},
+//! };
+//! use std::io::prelude::*;
+//!
+//! // Generated once and distributed to the peers ahead of time
+//! let (server_keys, client_keys) = (Keypair::generate()?, Keypair::generate()?);
+//! let server_config =
+//! 	Config::new(server_keys.private_key()).remote_public_key(client_keys.public_key());
+//! let client_config =
+//! 	Config::new(client_keys.private_key()).remote_public_key(server_keys.public_key());
+//!
+//! let name = "example-secure.sock".to_ns_name::<GenericNamespaced>()?;
+//! let listener = ListenerOptions::new().name(name.borrow()).create_sync()?;
+//! let client = std::thread::spawn(move || -> std::io::Result<()> {
+//! 	let mut conn = SecureStream::connect(Stream::connect(name)?, &client_config)?;
+//! 	conn.write_all(b"Hello from the client!")?;
+//! 	conn.close()
+//! });
+//!
+//! let mut conn = SecureStream::accept(listener.accept()?, &server_config)?;
+//! let mut buf = [0; 22];
+//! conn.read_exact(&mut buf)?;
+//! # client.join().unwrap()?;
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+use snow::{params::NoiseParams, Builder, HandshakeState, TransportState};
+use std::{
+	collections::VecDeque,
+	fmt::{self, Debug, Formatter},
+	io::{self, prelude::*},
+};
+use zeroize::Zeroize;
+
+const PARAMS: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+/// The largest message Noise allows, which is also the largest frame.
+const MAX_MESSAGE_LEN: usize = 65535;
+/// The most plaintext which fits into a single message along with its 16-byte authentication tag.
+const MAX_CHUNK_LEN: usize = 65519;
+
+fn params() -> io::Result<NoiseParams> {
+	PARAMS.parse().map_err(crypto_error)
+}
+
+fn crypto_error(e: snow::Error) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, e)
+}
+fn poisoned_error() -> io::Error {
+	io::Error::other("session is out of sync after a read or write failed partway through a frame")
+}
+fn truncated_error() -> io::Error {
+	io::Error::new(
+		io::ErrorKind::UnexpectedEof,
+		"stream ended without the peer closing the session",
+	)
+}
+fn closed_error() -> io::Error {
+	io::Error::new(io::ErrorKind::BrokenPipe, "session has been closed")
+}
+
+/// A static Curve25519 key pair, which identifies a peer.
+///
+/// The private key is overwritten with zeroes when the key pair is dropped. The [`Debug`]
+/// implementation omits it.
+#[derive(Clone)]
+pub struct Keypair {
+	private: Vec<u8>,
+	public: Vec<u8>,
+}
+impl Keypair {
+	/// Generates a new key pair using the random number generator of the OS.
+	pub fn generate() -> io::Result<Self> {
+		let snow::Keypair { private, public } = Builder::new(params()?)
+			.generate_keypair()
+			.map_err(crypto_error)?;
+		Ok(Self { private, public })
+	}
+	/// Returns the private key, which should be kept secret.
+	#[inline]
+	pub fn private_key(&self) -> &[u8] {
+		&self.private
+	}
+	/// Returns the public key, which is given to the peers which need to authenticate its owner.
+	#[inline]
+	pub fn public_key(&self) -> &[u8] {
+		&self.public
+	}
+}
+impl Drop for Keypair {
+	fn drop(&mut self) {
+		self.private.zeroize();
+	}
+}
+impl Debug for Keypair {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Keypair")
+			.field("public", &self.public)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Key material for one side of a session.
+///
+/// The private key is overwritten with zeroes when the configuration is dropped. The [`Debug`]
+/// implementation omits it.
+#[derive(Clone)]
+pub struct Config {
+	private_key: Vec<u8>,
+	remote_public_key: Option<Vec<u8>>,
+}
+impl Config {
+	/// Creates a configuration with the given private key, accepting peers with any public key.
+	#[inline]
+	pub fn new(private_key: impl Into<Vec<u8>>) -> Self {
+		Self {
+			private_key: private_key.into(),
+			remote_public_key: None,
+		}
+	}
+	/// Requires the peer to have the given public key. The handshake fails with
+	/// [`PermissionDenied`](io::ErrorKind::PermissionDenied) if it has a different one.
+	#[must_use = builder_must_use!()]
+	pub fn remote_public_key(mut self, key: impl Into<Vec<u8>>) -> Self {
+		self.remote_public_key = Some(key.into());
+		self
+	}
+}
+impl Drop for Config {
+	fn drop(&mut self) {
+		self.private_key.zeroize();
+	}
+}
+impl Debug for Config {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Config")
+			.field("remote_public_key", &self.remote_public_key)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Appends a frame with the given message to `out`.
+fn push_frame(msg: &[u8], out: &mut Vec<u8>) {
+	// Messages never exceed the limit, which is enforced by Snow
+	let len = u16::try_from(msg.len()).unwrap_or(u16::MAX);
+	out.extend(len.to_le_bytes());
+	out.extend(msg);
+}
+
+/// Handshake in progress, independent of how its messages are transferred.
+struct Handshake<'c> {
+	state: HandshakeState,
+	expected: Option<&'c [u8]>,
+	buf: Vec<u8>,
+}
+impl<'c> Handshake<'c> {
+	fn new(config: &'c Config, initiator: bool) -> io::Result<Self> {
+		let builder = Builder::new(params()?).local_private_key(&config.private_key);
+		let state = if initiator {
+			builder.build_initiator()
+		} else {
+			builder.build_responder()
+		}
+		.map_err(crypto_error)?;
+		Ok(Self {
+			state,
+			expected: config.remote_public_key.as_deref(),
+			buf: vec![0; MAX_MESSAGE_LEN],
+		})
+	}
+	fn is_finished(&self) -> bool {
+		self.state.is_handshake_finished()
+	}
+	fn is_my_turn(&self) -> bool {
+		self.state.is_my_turn()
+	}
+	/// Appends the next handshake frame to `out`.
+	fn write(&mut self, out: &mut Vec<u8>) -> io::Result<()> {
+		let len = self
+			.state
+			.write_message(&[], &mut self.buf)
+			.map_err(crypto_error)?;
+		push_frame(self.buf.get(..len).unwrap_or_default(), out);
+		Ok(())
+	}
+	/// Processes a handshake message received from the peer.
+	fn read(&mut self, msg: &[u8]) -> io::Result<()> {
+		self.state
+			.read_message(msg, &mut self.buf)
+			.map_err(crypto_error)?;
+		// Checked as soon as the key arrives, so that the initiator doesn't reveal its own key to
+		// an impostor
+		if let (Some(expected), Some(actual)) = (self.expected, self.state.get_remote_static()) {
+			if expected != actual {
+				return Err(io::Error::new(
+					io::ErrorKind::PermissionDenied,
+					"public key of peer does not match",
+				));
+			}
+		}
+		Ok(())
+	}
+	fn finish(self) -> io::Result<Session> {
+		let transport = self.state.into_transport_mode().map_err(crypto_error)?;
+		let remote_public_key = transport.get_remote_static().unwrap_or_default().to_vec();
+		Ok(Session {
+			transport,
+			remote_public_key,
+			buf: self.buf,
+			poisoned: false,
+			local_closed: false,
+			remote_closed: false,
+		})
+	}
+}
+
+/// Established session, independent of how its frames are transferred.
+struct Session {
+	transport: TransportState,
+	remote_public_key: Vec<u8>,
+	buf: Vec<u8>,
+	/// Set once a frame has been sent or received only in part.
+	poisoned: bool,
+	/// Set once the close message has been sent.
+	local_closed: bool,
+	/// Set once the close message of the peer has been received.
+	remote_closed: bool,
+}
+impl Session {
+	fn check(&self) -> io::Result<()> {
+		if self.poisoned {
+			return Err(poisoned_error());
+		}
+		Ok(())
+	}
+	fn check_writable(&self) -> io::Result<()> {
+		self.check()?;
+		if self.local_closed {
+			return Err(closed_error());
+		}
+		Ok(())
+	}
+	/// Encrypts as much of `plaintext` as fits into one frame and appends the frame to `out`,
+	/// returning the amount of plaintext consumed. Data frames are never empty, since an empty
+	/// frame is the close message.
+	fn seal(&mut self, plaintext: &[u8], out: &mut Vec<u8>) -> io::Result<usize> {
+		let chunk = plaintext.get(..MAX_CHUNK_LEN).unwrap_or(plaintext);
+		let len = self
+			.transport
+			.write_message(chunk, &mut self.buf)
+			.map_err(crypto_error)?;
+		push_frame(self.buf.get(..len).unwrap_or_default(), out);
+		Ok(chunk.len())
+	}
+	/// Appends the close message to `out`.
+	fn seal_close(&mut self, out: &mut Vec<u8>) -> io::Result<()> {
+		self.seal(&[], out)?;
+		self.local_closed = true;
+		Ok(())
+	}
+	/// Decrypts the message of a frame, appending the plaintext to `out`.
+	fn open(&mut self, msg: &[u8], out: &mut VecDeque<u8>) -> io::Result<()> {
+		if self.remote_closed {
+			self.poisoned = true;
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"peer sent data after closing the session",
+			));
+		}
+		let len = match self.transport.read_message(msg, &mut self.buf) {
+			Ok(len) => len,
+			Err(e) => {
+				self.poisoned = true;
+				return Err(crypto_error(e));
+			}
+		};
+		if len == 0 {
+			self.remote_closed = true;
+		}
+		out.extend(self.buf.get(..len).unwrap_or_default());
+		Ok(())
+	}
+}
+
+/// Reads the message of a frame into `msg`, returning `false` if the stream ends in between
+/// frames. Failures after part of the frame has been read set `poisoned`.
+fn read_frame(rdr: &mut impl Read, msg: &mut Vec<u8>, poisoned: &mut bool) -> io::Result<bool> {
+	let mut len = [0; 2];
+	let mut filled = 0;
+	while let Some(rest) = len.get_mut(filled..).filter(|r| !r.is_empty()) {
+		match rdr.read(rest) {
+			Ok(0) if filled == 0 => return Ok(false),
+			Ok(0) => {
+				*poisoned = true;
+				return Err(io::ErrorKind::UnexpectedEof.into());
+			}
+			Ok(n) => filled = filled.saturating_add(n),
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+			Err(e) => {
+				*poisoned |= filled != 0;
+				return Err(e);
+			}
+		}
+	}
+	msg.resize(usize::from(u16::from_le_bytes(len)), 0);
+	if let Err(e) = rdr.read_exact(msg) {
+		*poisoned = true;
+		return Err(e);
+	}
+	Ok(true)
+}
+
+/// Encrypted session over a blocking stream.
+///
+/// Every call to [`.write()`](Write::write) sends at least one frame, so small writes are best
+/// combined beforehand, e.g. with a [`BufWriter`](io::BufWriter). Reads return data from at most
+/// one frame at a time.
+///
+/// See the [module-level documentation](self) for more.
+pub struct SecureStream<S> {
+	inner: S,
+	session: Session,
+	plaintext: VecDeque<u8>,
+	frame: Vec<u8>,
+}
+impl<S: Read + Write> SecureStream<S> {
+	/// Performs the handshake as the initiator, which is conventionally the client.
+	#[inline]
+	pub fn connect(inner: S, config: &Config) -> io::Result<Self> {
+		Self::handshake(inner, config, true)
+	}
+	/// Performs the handshake as the responder, which is conventionally the server.
+	#[inline]
+	pub fn accept(inner: S, config: &Config) -> io::Result<Self> {
+		Self::handshake(inner, config, false)
+	}
+	fn handshake(mut inner: S, config: &Config, initiator: bool) -> io::Result<Self> {
+		let mut handshake = Handshake::new(config, initiator)?;
+		let mut frame = Vec::new();
+		while !handshake.is_finished() {
+			if handshake.is_my_turn() {
+				frame.clear();
+				handshake.write(&mut frame)?;
+				inner.write_all(&frame)?;
+				inner.flush()?;
+			} else {
+				if !read_frame(&mut inner, &mut frame, &mut false)? {
+					return Err(io::ErrorKind::UnexpectedEof.into());
+				}
+				handshake.read(&frame)?;
+			}
+		}
+		Ok(Self {
+			inner,
+			session: handshake.finish()?,
+			plaintext: VecDeque::new(),
+			frame,
+		})
+	}
+}
+impl<S> SecureStream<S> {
+	/// Returns the static public key of the peer.
+	#[inline]
+	pub fn remote_public_key(&self) -> &[u8] {
+		&self.session.remote_public_key
+	}
+	/// Returns `true` if the session is out of sync because of a failed read or write, in which
+	/// case it cannot be used any further.
+	///
+	/// See the [module-level documentation](self#failed-io) for more.
+	#[inline]
+	pub fn is_poisoned(&self) -> bool {
+		self.session.poisoned
+	}
+	/// Borrows the wrapped stream.
+	#[inline]
+	pub fn get_ref(&self) -> &S {
+		&self.inner
+	}
+	/// Mutably borrows the wrapped stream.
+	///
+	/// Reading from or writing to the stream directly desynchronizes the session.
+	#[inline]
+	pub fn get_mut(&mut self) -> &mut S {
+		&mut self.inner
+	}
+	/// Unwraps the stream, discarding the session along with any data which has been received but
+	/// not read yet.
+	#[inline]
+	pub fn into_inner(self) -> S {
+		self.inner
+	}
+}
+impl<S: Write> SecureStream<S> {
+	/// Sends the close message, after which the peer reads end of file, and flushes the wrapped
+	/// stream. Subsequent writes fail with [`BrokenPipe`](io::ErrorKind::BrokenPipe), while
+	/// reading remains possible until the peer closes its side as well.
+	///
+	/// Calling this again only flushes the wrapped stream. See the
+	/// [module-level documentation](self#closing) for why this is necessary.
+	pub fn close(&mut self) -> io::Result<()> {
+		self.session.check()?;
+		if !self.session.local_closed {
+			self.frame.clear();
+			self.session.seal_close(&mut self.frame)?;
+			self.send_frame()?;
+		}
+		self.inner.flush()
+	}
+	fn send_frame(&mut self) -> io::Result<()> {
+		// The frame has used up a nonce, so the peer falls out of sync unless it arrives in full
+		if let Err(e) = self.inner.write_all(&self.frame) {
+			self.session.poisoned = true;
+			return Err(e);
+		}
+		Ok(())
+	}
+}
+impl<S: Read> Read for SecureStream<S> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.session.check()?;
+		while self.plaintext.is_empty() && !buf.is_empty() && !self.session.remote_closed {
+			if !read_frame(&mut self.inner, &mut self.frame, &mut self.session.poisoned)? {
+				return Err(truncated_error());
+			}
+			self.session.open(&self.frame, &mut self.plaintext)?;
+		}
+		self.plaintext.read(buf)
+	}
+}
+impl<S: Write> Write for SecureStream<S> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.session.check_writable()?;
+		if buf.is_empty() {
+			return Ok(0);
+		}
+		self.frame.clear();
+		let consumed = self.session.seal(buf, &mut self.frame)?;
+		self.send_frame()?;
+		Ok(consumed)
+	}
+	#[inline]
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+impl<S: Debug> Debug for SecureStream<S> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SecureStream")
+			.field("inner", &self.inner)
+			.field("remote_public_key", &self.session.remote_public_key)
+			.finish_non_exhaustive()
+	}
+}
@@ -0,0 +1,252 @@
+//! Tokio counterpart of [`SecureStream`](super::SecureStream).
+
+use super::{truncated_error, Config, Handshake, Session};
+use std::{
+	collections::VecDeque,
+	fmt::{self, Debug, Formatter},
+	io,
+	pin::Pin,
+	task::{ready, Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+/// Length of the length prefix of a frame.
+const PREFIX_LEN: usize = 2;
+
+/// Reads the message of a frame into `msg`, returning `false` if the peer hangs up in between
+/// frames.
+async fn read_frame(rdr: &mut (impl AsyncRead + Unpin), msg: &mut Vec<u8>) -> io::Result<bool> {
+	let mut len = [0; PREFIX_LEN];
+	match rdr.read_exact(&mut len).await {
+		Ok(..) => {}
+		Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+		Err(e) => return Err(e),
+	}
+	msg.resize(usize::from(u16::from_le_bytes(len)), 0);
+	rdr.read_exact(msg).await?;
+	Ok(true)
+}
+
+/// Encrypted session over a Tokio stream.
+///
+/// Each write encrypts data into a frame which is sent as far as the wrapped stream accepts it
+/// without waiting, and finished by the next write or by flushing. As with other buffered writers,
+/// the stream must therefore be [flushed](AsyncWriteExt::flush) or
+/// [shut down](AsyncWriteExt::shutdown) to make sure that everything written reaches the peer.
+/// Shutting down also sends the close message, without which the peer fails to read end of file.
+///
+/// See the [module-level documentation](super) for more.
+pub struct SecureStream<S> {
+	inner: S,
+	session: Session,
+	plaintext: VecDeque<u8>,
+	/// The frame being received, including its length prefix.
+	incoming: Vec<u8>,
+	received: usize,
+	/// The frame being sent, including its length prefix.
+	outgoing: Vec<u8>,
+	sent: usize,
+}
+impl<S: AsyncRead + AsyncWrite + Unpin> SecureStream<S> {
+	/// Performs the handshake as the initiator, which is conventionally the client.
+	#[inline]
+	pub async fn connect(inner: S, config: &Config) -> io::Result<Self> {
+		Self::handshake(inner, config, true).await
+	}
+	/// Performs the handshake as the responder, which is conventionally the server.
+	#[inline]
+	pub async fn accept(inner: S, config: &Config) -> io::Result<Self> {
+		Self::handshake(inner, config, false).await
+	}
+	async fn handshake(mut inner: S, config: &Config, initiator: bool) -> io::Result<Self> {
+		let mut handshake = Handshake::new(config, initiator)?;
+		let mut frame = Vec::new();
+		while !handshake.is_finished() {
+			if handshake.is_my_turn() {
+				frame.clear();
+				handshake.write(&mut frame)?;
+				inner.write_all(&frame).await?;
+				inner.flush().await?;
+			} else {
+				if !read_frame(&mut inner, &mut frame).await? {
+					return Err(io::ErrorKind::UnexpectedEof.into());
+				}
+				handshake.read(&frame)?;
+			}
+		}
+		Ok(Self {
+			inner,
+			session: handshake.finish()?,
+			plaintext: VecDeque::new(),
+			incoming: Vec::new(),
+			received: 0,
+			outgoing: Vec::new(),
+			sent: 0,
+		})
+	}
+}
+impl<S> SecureStream<S> {
+	/// Returns the static public key of the peer.
+	#[inline]
+	pub fn remote_public_key(&self) -> &[u8] {
+		&self.session.remote_public_key
+	}
+	/// Returns `true` if the session is out of sync because of a failed read or write, in which
+	/// case it cannot be used any further.
+	///
+	/// See the [module-level documentation](super#failed-io) for more.
+	#[inline]
+	pub fn is_poisoned(&self) -> bool {
+		self.session.poisoned
+	}
+	/// Borrows the wrapped stream.
+	#[inline]
+	pub fn get_ref(&self) -> &S {
+		&self.inner
+	}
+	/// Mutably borrows the wrapped stream.
+	///
+	/// Reading from or writing to the stream directly desynchronizes the session.
+	#[inline]
+	pub fn get_mut(&mut self) -> &mut S {
+		&mut self.inner
+	}
+	/// Unwraps the stream, discarding the session along with any data which has been received but
+	/// not read yet or written but not sent yet.
+	#[inline]
+	pub fn into_inner(self) -> S {
+		self.inner
+	}
+}
+impl<S: AsyncRead + Unpin> SecureStream<S> {
+	/// Receives a whole frame into `incoming`, returning `false` if the peer hangs up in between
+	/// frames.
+	fn poll_frame(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<bool>> {
+		loop {
+			let target = match *self.incoming.as_slice() {
+				[l0, l1, ..] if self.received >= PREFIX_LEN => {
+					usize::from(u16::from_le_bytes([l0, l1])).saturating_add(PREFIX_LEN)
+				}
+				_ => PREFIX_LEN,
+			};
+			if self.received >= PREFIX_LEN && self.received == target {
+				self.incoming.truncate(target);
+				self.received = 0;
+				return Poll::Ready(Ok(true));
+			}
+			if self.incoming.len() < target {
+				self.incoming.resize(target, 0);
+			}
+
+			let dst = self
+				.incoming
+				.get_mut(self.received..target)
+				.unwrap_or_default();
+			let mut dst = ReadBuf::new(dst);
+			if let Err(e) = ready!(Pin::new(&mut self.inner).poll_read(cx, &mut dst)) {
+				self.session.poisoned |= self.received != 0;
+				return Poll::Ready(Err(e));
+			}
+			let n = dst.filled().len();
+			if n == 0 {
+				if self.received == 0 {
+					return Poll::Ready(Ok(false));
+				}
+				self.session.poisoned = true;
+				return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+			}
+			self.received = self.received.saturating_add(n);
+		}
+	}
+}
+impl<S: AsyncWrite + Unpin> SecureStream<S> {
+	/// Sends the rest of the outgoing frame. Since the frame has used up a nonce, the session is
+	/// poisoned if this fails.
+	fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		while let Some(rest) = self.outgoing.get(self.sent..).filter(|r| !r.is_empty()) {
+			let n = match ready!(Pin::new(&mut self.inner).poll_write(cx, rest)) {
+				Ok(0) => Err(io::ErrorKind::WriteZero.into()),
+				rslt => rslt,
+			};
+			match n {
+				Ok(n) => self.sent = self.sent.saturating_add(n),
+				Err(e) => {
+					self.session.poisoned = true;
+					return Poll::Ready(Err(e));
+				}
+			}
+		}
+		Poll::Ready(Ok(()))
+	}
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for SecureStream<S> {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		this.session.check()?;
+		while this.plaintext.is_empty() && buf.remaining() > 0 && !this.session.remote_closed {
+			if !ready!(this.poll_frame(cx))? {
+				return Poll::Ready(Err(truncated_error()));
+			}
+			let msg = this.incoming.get(PREFIX_LEN..).unwrap_or_default();
+			this.session.open(msg, &mut this.plaintext)?;
+		}
+		let (front, _) = this.plaintext.as_slices();
+		let n = front.len().min(buf.remaining());
+		buf.put_slice(front.get(..n).unwrap_or_default());
+		this.plaintext.drain(..n);
+		Poll::Ready(Ok(()))
+	}
+}
+impl<S: AsyncWrite + Unpin> AsyncWrite for SecureStream<S> {
+	fn poll_write(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<io::Result<usize>> {
+		let this = self.get_mut();
+		this.session.check_writable()?;
+		ready!(this.poll_drain(cx))?;
+		if buf.is_empty() {
+			return Poll::Ready(Ok(0));
+		}
+		this.outgoing.clear();
+		this.sent = 0;
+		let consumed = this.session.seal(buf, &mut this.outgoing)?;
+		// The frame is ours now, so it only has to be sent eventually
+		if let Poll::Ready(Err(e)) = this.poll_drain(cx) {
+			return Poll::Ready(Err(e));
+		}
+		Poll::Ready(Ok(consumed))
+	}
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		this.session.check()?;
+		ready!(this.poll_drain(cx))?;
+		Pin::new(&mut this.inner).poll_flush(cx)
+	}
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		this.session.check()?;
+		ready!(this.poll_drain(cx))?;
+		if !this.session.local_closed {
+			this.outgoing.clear();
+			this.sent = 0;
+			this.session.seal_close(&mut this.outgoing)?;
+			ready!(this.poll_drain(cx))?;
+		}
+		Pin::new(&mut this.inner).poll_shutdown(cx)
+	}
+}
+impl<S: Debug> Debug for SecureStream<S> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SecureStream")
+			.field("inner", &self.inner)
+			.field("remote_public_key", &self.session.remote_public_key)
+			.finish_non_exhaustive()
+	}
+}
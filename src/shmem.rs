@@ -0,0 +1,163 @@
+//! Shared memory objects.
+//!
+//! Sockets and pipes are a good fit for messages, but transferring large amounts of data through
+//! them means copying it through the kernel twice. Shared memory allows several processes to map
+//! the same pages into their address spaces instead, typically with a local socket or pipe used
+//! alongside it for signalling.
+//!
+//! A [`SharedMemObject`] is a handle to a region of shared memory, which can be named – in which
+//! case unrelated processes can open it by that name – or anonymous, in which case it can only be
+//! shared by passing its handle to other processes. To access the memory, the object has to be
+//! [mapped](SharedMemObject::map) into the address space of the process.
+//!
+//! ## Platform-specific behavior
+//! On Unix, named objects are created with `shm_open()` and anonymous ones with `memfd_create()`
+//! where available. A leading slash is prepended to names which don't have one. Named objects
+//! persist until [unlinked](SharedMemObject::unlink), even if no process has them open.
+//!
+//! On Windows, objects are pagefile-backed file mappings created with `CreateFileMappingW()`.
+//! Names may be prefixed with `Local\` or `Global\` to pick the namespace. Objects are destroyed
+//! when the last handle to them is closed, and unlinking is a no-op.
+//!
+//! ## Sharing handles
+//! [`SharedMemObject`] can be converted to and from the platform's owned handle type. On Windows,
+//! it implements `ShareHandle`, which can be used to send it to another process over a local
+//! socket. On Unix, it can be passed to child processes with
+//! `os::unix::process::CommandExt::inherit_fd()`.
+//!
+//! ## Ring buffers
+//! The [`ring_buffer`] module provides a byte channel on top of shared memory, for when sockets
+//! and pipes are too slow.
+
+pub mod ring_buffer;
+pub use ring_buffer::RingBuffer;
+
+impmod! {shmem,
+	SharedMemObject as SharedMemObjectImpl,
+	Mapping as MappingImpl,
+}
+use std::io;
+
+/// A region of memory which can be shared between processes.
+///
+/// See the [module-level documentation](self) for more.
+pub struct SharedMemObject(SharedMemObjectImpl);
+impl SharedMemObject {
+	/// Creates a new named shared memory object of the given size, filled with zeroes.
+	///
+	/// Fails with an error of kind [`AlreadyExists`](io::ErrorKind::AlreadyExists) if an object
+	/// with the given name already exists, and with one of kind
+	/// [`InvalidInput`](io::ErrorKind::InvalidInput) if `size` is zero.
+	#[inline]
+	pub fn create(name: &str, size: usize) -> io::Result<Self> {
+		SharedMemObjectImpl::create(name, size).map(Self)
+	}
+	/// Opens an existing named shared memory object, of which the first `size` bytes will be
+	/// mapped.
+	///
+	/// On Unix, fails with an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput) if the
+	/// object is smaller than `size`. On Windows, this is only detected when mapping the object.
+	#[inline]
+	pub fn open(name: &str, size: usize) -> io::Result<Self> {
+		SharedMemObjectImpl::open(name, size).map(Self)
+	}
+	/// Creates a new shared memory object which has no name and can only be shared by passing its
+	/// handle to other processes.
+	#[inline]
+	pub fn anonymous(size: usize) -> io::Result<Self> {
+		SharedMemObjectImpl::anonymous(size).map(Self)
+	}
+	/// Removes the name of a shared memory object, so that it's destroyed as soon as all handles to
+	/// it and mappings of it are gone.
+	///
+	/// Does nothing on Windows, where this happens automatically.
+	#[inline]
+	pub fn unlink(name: &str) -> io::Result<()> {
+		SharedMemObjectImpl::unlink(name)
+	}
+
+	/// Returns the size of the object in bytes, which is also the length of its mappings.
+	///
+	/// For objects created from a handle on Windows, this is rounded up to the page size.
+	#[inline]
+	pub fn size(&self) -> usize {
+		self.0.size()
+	}
+
+	/// Maps the object into the address space of the process.
+	///
+	/// The mapping stays valid after the object itself is dropped.
+	///
+	/// # Safety
+	/// Other processes, as well as other mappings of the same object within this process, must not
+	/// modify the memory while a slice obtained from the mapping is alive, unless it's done in a
+	/// way that synchronizes with the accesses made through that slice (which cannot be expressed
+	/// with `&[u8]` and `&mut [u8]` alone). The raw pointer returned by
+	/// [`as_ptr()`](SharedMemMapping::as_ptr) can be used with atomics for that purpose.
+	///
+	/// On Unix, the object must also not be shrunk while it's mapped. The size of the mapping is
+	/// fixed when it's created, and if another process truncates the object (with `ftruncate()`,
+	/// for example), accessing the pages past its new end raises `SIGBUS`, which kills the process
+	/// unless a handler for it is installed. Only map objects shared with trusted processes.
+	#[inline]
+	pub unsafe fn map(&self) -> io::Result<SharedMemMapping> {
+		unsafe { self.0.map() }.map(SharedMemMapping)
+	}
+}
+multimacro! {
+	SharedMemObject,
+	forward_asinto_handle,
+	forward_try_clone,
+	forward_debug,
+	derive_asintoraw,
+}
+#[cfg(unix)]
+impl TryFrom<std::os::unix::io::OwnedFd> for SharedMemObject {
+	type Error = crate::error::FromFdError;
+	#[inline]
+	fn try_from(fd: std::os::unix::io::OwnedFd) -> Result<Self, Self::Error> {
+		SharedMemObjectImpl::try_from(fd).map(Self)
+	}
+}
+#[cfg(windows)]
+impl TryFrom<std::os::windows::io::OwnedHandle> for SharedMemObject {
+	type Error = crate::error::FromHandleError;
+	#[inline]
+	fn try_from(handle: std::os::windows::io::OwnedHandle) -> Result<Self, Self::Error> {
+		SharedMemObjectImpl::try_from(handle).map(Self)
+	}
+}
+
+/// A [shared memory object](SharedMemObject) mapped into the address space of the process.
+///
+/// The memory is unmapped when this is dropped.
+pub struct SharedMemMapping(MappingImpl);
+impl SharedMemMapping {
+	/// Returns a pointer to the start of the mapped memory.
+	#[inline]
+	pub fn as_ptr(&self) -> *mut u8 {
+		self.0.as_ptr()
+	}
+	/// Returns the length of the mapping in bytes.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+	/// Returns `true` if the mapping is empty, which never happens, since empty objects cannot be
+	/// created.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.0.len() == 0
+	}
+	/// Borrows the mapped memory as a slice.
+	#[inline]
+	pub fn as_slice(&self) -> &[u8] {
+		self.0.as_slice()
+	}
+	/// Borrows the mapped memory as a mutable slice.
+	#[inline]
+	pub fn as_mut_slice(&mut self) -> &mut [u8] {
+		self.0.as_mut_slice()
+	}
+}
+forward_debug!(SharedMemMapping, "SharedMemMapping");
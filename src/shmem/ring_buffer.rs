@@ -0,0 +1,464 @@
+//! A single-producer single-consumer byte channel in shared memory.
+//!
+//! A [`RingBuffer`] is a [shared memory object](SharedMemObject) holding a fixed-size circular
+//! buffer, along with a pair of OS notification objects which the two ends use to wake each other
+//! up when the buffer stops being empty or full. Data is transferred without any system calls as
+//! long as neither end has to wait, which makes this considerably faster than sockets and pipes
+//! for high-volume or latency-sensitive traffic.
+//!
+//! Exactly one [`Sender`] and one [`Receiver`] exist for every ring buffer. Either of them can be
+//! moved to another process by disassembling it into its [parts](Parts), passing the handles in
+//! those parts to that process, and reassembling it on the other side.
+//!
+//! Both ends only ever access the shared memory with atomic operations, and the positions in the
+//! buffer are validated on every access. A misbehaving peer can thus make reads and writes fail or
+//! deliver garbage data, but can't make either end access memory outside of the buffer.
+//!
+//! ## Platform-specific behavior
+//! Wakeups are delivered with an eventfd on Linux and Android, a pipe on other Unix-like systems,
+//! and an auto-reset event on Windows.
+
+use super::{SharedMemMapping, SharedMemObject};
+use crate::TryClone;
+use std::{
+	fmt::{self, Debug, Formatter},
+	io::{self, prelude::*},
+	mem::ManuallyDrop,
+	ptr, slice,
+	sync::atomic::{
+		AtomicBool, AtomicU32, AtomicU8, AtomicUsize,
+		Ordering::{Acquire, Relaxed, Release, SeqCst},
+	},
+};
+
+impmod! {notify,
+	notify_pair,
+	Notifier,
+	Waiter,
+}
+
+const MAGIC: u32 = u32::from_be_bytes(*b"IPRB");
+// Fields written by different ends are kept on separate cache lines.
+const MAGIC_OFFSET: usize = 0;
+const CAPACITY_OFFSET: usize = 8;
+const WRITE_POS_OFFSET: usize = 64;
+const READ_POS_OFFSET: usize = 128;
+const FLAGS_OFFSET: usize = 192;
+const RECV_WAITING_OFFSET: usize = 196;
+const SEND_WAITING_OFFSET: usize = 200;
+const HEADER_SIZE: usize = 256;
+
+const SENDER_CLOSED: u32 = 1;
+const RECEIVER_CLOSED: u32 = 2;
+
+fn invalid_data(msg: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// A ring buffer which has been created, but not yet split into its two ends.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct RingBuffer {
+	shmem: SharedMemObject,
+	data: (Notifier, Waiter),
+	space: (Notifier, Waiter),
+}
+impl RingBuffer {
+	/// Creates a ring buffer which can hold `capacity` bytes at a time in an anonymous shared
+	/// memory object.
+	///
+	/// Fails with an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput) if `capacity` is
+	/// not a power of two or more than half of the address space. The positions in the buffer are
+	/// counters which wrap around at the end of the address space, and a power of two capacity
+	/// keeps the offsets they map to contiguous across that wraparound.
+	pub fn new(capacity: usize) -> io::Result<Self> {
+		let size = capacity
+			.checked_add(HEADER_SIZE)
+			.filter(|_| capacity.is_power_of_two() && capacity <= usize::MAX / 2)
+			.ok_or_else(|| {
+				io::Error::new(io::ErrorKind::InvalidInput, "invalid ring buffer capacity")
+			})?;
+		let shmem = SharedMemObject::anonymous(size)?;
+		{
+			// SAFETY: nothing else has access to the object yet
+			let ring = Ring {
+				map: unsafe { shmem.map()? },
+				capacity,
+			};
+			ring.atomic::<AtomicUsize>(CAPACITY_OFFSET)
+				.store(capacity, Relaxed);
+			ring.atomic::<AtomicU32>(MAGIC_OFFSET).store(MAGIC, Release);
+		}
+		Ok(Self {
+			shmem,
+			data: notify_pair()?,
+			space: notify_pair()?,
+		})
+	}
+	/// Moves both positions in the empty buffer to `pos`, so that tests can exercise the
+	/// wraparound of the counters without transferring that much data first.
+	#[cfg(test)]
+	pub(crate) fn set_positions(&self, pos: usize) -> io::Result<()> {
+		let ring = Ring::attach(&self.shmem)?;
+		ring.write_pos().store(pos, Relaxed);
+		ring.read_pos().store(pos, Relaxed);
+		Ok(())
+	}
+	/// Splits the ring buffer into its sending and receiving ends.
+	pub fn split(self) -> io::Result<(Sender, Receiver)> {
+		let Self {
+			shmem,
+			data: (data_notifier, data_waiter),
+			space: (space_notifier, space_waiter),
+		} = self;
+		let sender = Endpoint::new(
+			shmem.try_clone()?,
+			data_notifier,
+			space_waiter,
+			SENDER_CLOSED,
+		)?;
+		let receiver = Endpoint::new(shmem, space_notifier, data_waiter, RECEIVER_CLOSED)?;
+		Ok((Sender(sender), Receiver(receiver)))
+	}
+}
+
+/// The OS objects which make up one end of a [ring buffer](RingBuffer).
+///
+/// All of these have to be transferred to move an end to another process. On Windows, the shared
+/// memory object implements `ShareHandle`, while the other two handles can be duplicated into the
+/// receiving process with `DuplicateHandle`. On Unix, they can be inherited by a child process.
+#[derive(Debug)]
+pub struct Parts {
+	/// The shared memory object holding the buffer.
+	pub shmem: SharedMemObject,
+	/// The notification object with which this end wakes up the other one.
+	#[cfg(unix)]
+	pub notifier: std::os::unix::io::OwnedFd,
+	/// The notification object with which this end wakes up the other one.
+	#[cfg(windows)]
+	pub notifier: std::os::windows::io::OwnedHandle,
+	/// The notification object with which the other end wakes up this one.
+	#[cfg(unix)]
+	pub waiter: std::os::unix::io::OwnedFd,
+	/// The notification object with which the other end wakes up this one.
+	#[cfg(windows)]
+	pub waiter: std::os::windows::io::OwnedHandle,
+}
+
+/// The sending end of a [ring buffer](RingBuffer).
+///
+/// The core functionality is exposed in a [`Write`] interface. Writes block while the buffer is
+/// full, unless [nonblocking mode](Self::set_nonblocking) is enabled, and fail with an error of
+/// kind [`BrokenPipe`](io::ErrorKind::BrokenPipe) once the receiving end is dropped.
+pub struct Sender(Endpoint);
+impl Sender {
+	/// Reassembles a sending end from its parts.
+	///
+	/// Fails with an error of kind [`InvalidData`](io::ErrorKind::InvalidData) if the shared
+	/// memory object does not hold a ring buffer.
+	#[inline]
+	pub fn from_parts(parts: Parts) -> io::Result<Self> {
+		Endpoint::from_parts(parts, SENDER_CLOSED).map(Self)
+	}
+	/// Disassembles the sending end into its parts without notifying the receiving end.
+	#[inline]
+	pub fn into_parts(self) -> Parts {
+		self.0.into_parts()
+	}
+	/// Returns the number of bytes the buffer can hold at a time.
+	#[inline]
+	pub fn capacity(&self) -> usize {
+		self.0.ring.capacity
+	}
+	/// Enables or disables nonblocking mode, in which writes fail with an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) instead of waiting for space in the buffer.
+	#[inline]
+	pub fn set_nonblocking(&self, nonblocking: bool) {
+		self.0.nonblocking.store(nonblocking, Relaxed);
+	}
+}
+impl Write for Sender {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let ep = &self.0;
+		let ring = &ep.ring;
+		if buf.is_empty() {
+			return Ok(0);
+		}
+		loop {
+			if ring.flags().load(Acquire) & RECEIVER_CLOSED != 0 {
+				return Err(io::ErrorKind::BrokenPipe.into());
+			}
+			let w = ring.write_pos().load(Relaxed);
+			let r = ring.read_pos().load(Acquire);
+			let free = ring.capacity.saturating_sub(ring.used(w, r)?);
+			if free != 0 {
+				let (chunk, _) = buf.split_at(free.min(buf.len()));
+				ring.copy_in(w, chunk);
+				ring.write_pos().store(w.wrapping_add(chunk.len()), SeqCst);
+				if ring.recv_waiting().swap(0, SeqCst) != 0 {
+					ep.notifier.signal()?;
+				}
+				return Ok(chunk.len());
+			}
+			if ep.nonblocking.load(Relaxed) {
+				return Err(io::ErrorKind::WouldBlock.into());
+			}
+			ring.send_waiting().store(1, SeqCst);
+			// Checked again in case the receiver made space before seeing the flag
+			if ring.read_pos().load(SeqCst) == r && ring.flags().load(SeqCst) & RECEIVER_CLOSED == 0
+			{
+				ep.waiter.wait()?;
+			}
+		}
+	}
+	#[inline]
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+forward_debug!(Sender, "Sender");
+
+/// The receiving end of a [ring buffer](RingBuffer).
+///
+/// The core functionality is exposed in a [`Read`] interface. Reads block while the buffer is
+/// empty, unless [nonblocking mode](Self::set_nonblocking) is enabled, and return end-of-file once
+/// the sending end is dropped and the buffer is drained.
+pub struct Receiver(Endpoint);
+impl Receiver {
+	/// Reassembles a receiving end from its parts.
+	///
+	/// Fails with an error of kind [`InvalidData`](io::ErrorKind::InvalidData) if the shared
+	/// memory object does not hold a ring buffer.
+	#[inline]
+	pub fn from_parts(parts: Parts) -> io::Result<Self> {
+		Endpoint::from_parts(parts, RECEIVER_CLOSED).map(Self)
+	}
+	/// Disassembles the receiving end into its parts without notifying the sending end.
+	#[inline]
+	pub fn into_parts(self) -> Parts {
+		self.0.into_parts()
+	}
+	/// Returns the number of bytes the buffer can hold at a time.
+	#[inline]
+	pub fn capacity(&self) -> usize {
+		self.0.ring.capacity
+	}
+	/// Enables or disables nonblocking mode, in which reads fail with an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) instead of waiting for data to arrive.
+	#[inline]
+	pub fn set_nonblocking(&self, nonblocking: bool) {
+		self.0.nonblocking.store(nonblocking, Relaxed);
+	}
+}
+impl Read for Receiver {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let ep = &self.0;
+		let ring = &ep.ring;
+		if buf.is_empty() {
+			return Ok(0);
+		}
+		loop {
+			let r = ring.read_pos().load(Relaxed);
+			let w = ring.write_pos().load(Acquire);
+			let used = ring.used(w, r)?;
+			if used != 0 {
+				let (chunk, _) = buf.split_at_mut(used.min(buf.len()));
+				ring.copy_out(r, chunk);
+				ring.read_pos().store(r.wrapping_add(chunk.len()), SeqCst);
+				if ring.send_waiting().swap(0, SeqCst) != 0 {
+					ep.notifier.signal()?;
+				}
+				return Ok(chunk.len());
+			}
+			if ring.flags().load(Acquire) & SENDER_CLOSED != 0 {
+				// The final write happens before the flag is set
+				if ring.write_pos().load(Acquire) == w {
+					return Ok(0);
+				}
+				continue;
+			}
+			if ep.nonblocking.load(Relaxed) {
+				return Err(io::ErrorKind::WouldBlock.into());
+			}
+			ring.recv_waiting().store(1, SeqCst);
+			// Checked again in case the sender wrote something before seeing the flag
+			if ring.write_pos().load(SeqCst) == w && ring.flags().load(SeqCst) & SENDER_CLOSED == 0
+			{
+				ep.waiter.wait()?;
+			}
+		}
+	}
+}
+forward_debug!(Receiver, "Receiver");
+
+/// A mapped ring buffer with a validated capacity.
+struct Ring {
+	map: SharedMemMapping,
+	capacity: usize,
+}
+impl Ring {
+	fn attach(shmem: &SharedMemObject) -> io::Result<Self> {
+		if shmem.size() <= HEADER_SIZE {
+			return Err(invalid_data(
+				"shared memory object is too small for a ring buffer",
+			));
+		}
+		// SAFETY: the memory is only ever accessed through atomics
+		let map = unsafe { shmem.map()? };
+		let mut ring = Self { map, capacity: 0 };
+		if ring.atomic::<AtomicU32>(MAGIC_OFFSET).load(Acquire) != MAGIC {
+			return Err(invalid_data(
+				"shared memory object does not hold a ring buffer",
+			));
+		}
+		let capacity = ring.atomic::<AtomicUsize>(CAPACITY_OFFSET).load(Relaxed);
+		if !capacity.is_power_of_two() || capacity > ring.map.len().saturating_sub(HEADER_SIZE) {
+			return Err(invalid_data("ring buffer capacity out of range"));
+		}
+		ring.capacity = capacity;
+		Ok(ring)
+	}
+
+	/// Only used with the atomic types, at offsets aligned for them.
+	fn atomic<T>(&self, offset: usize) -> &T {
+		// SAFETY: the header lies within the mapping, which is page-aligned
+		unsafe { &*self.map.as_ptr().add(offset).cast::<T>() }
+	}
+	fn write_pos(&self) -> &AtomicUsize {
+		self.atomic(WRITE_POS_OFFSET)
+	}
+	fn read_pos(&self) -> &AtomicUsize {
+		self.atomic(READ_POS_OFFSET)
+	}
+	fn flags(&self) -> &AtomicU32 {
+		self.atomic(FLAGS_OFFSET)
+	}
+	fn recv_waiting(&self) -> &AtomicU32 {
+		self.atomic(RECV_WAITING_OFFSET)
+	}
+	fn send_waiting(&self) -> &AtomicU32 {
+		self.atomic(SEND_WAITING_OFFSET)
+	}
+
+	fn used(&self, w: usize, r: usize) -> io::Result<usize> {
+		let used = w.wrapping_sub(r);
+		if used > self.capacity {
+			return Err(invalid_data("ring buffer state is corrupted"));
+		}
+		Ok(used)
+	}
+	/// Splits a region of at most `capacity` bytes starting at `pos` into the part before the end
+	/// of the buffer and the part that wraps around to its start.
+	fn segments(&self, pos: usize, len: usize) -> (usize, usize, usize) {
+		// The capacity is a power of two, so this stays contiguous when `pos` wraps around
+		let start = pos & self.capacity.saturating_sub(1);
+		let first = len.min(self.capacity.saturating_sub(start));
+		(start, first, len.saturating_sub(first))
+	}
+	/// The buffer is accessed byte by byte with relaxed atomics rather than with plain copies,
+	/// since a misbehaving peer may access the same bytes concurrently, and a plain copy racing
+	/// with that would be undefined behavior. The stores of the positions order the bytes between
+	/// the two ends.
+	fn cells(&self) -> &[AtomicU8] {
+		// SAFETY: the mapping is larger than the header by at least the capacity, and `AtomicU8`
+		// has the same layout as `u8`
+		unsafe {
+			let data = self.map.as_ptr().add(HEADER_SIZE);
+			slice::from_raw_parts(data.cast::<AtomicU8>(), self.capacity)
+		}
+	}
+	fn copy_in(&self, pos: usize, src: &[u8]) {
+		let (start, first, _) = self.segments(pos, src.len());
+		let (head, tail) = src.split_at(first);
+		let cells = self.cells();
+		let wrapped = cells
+			.iter()
+			.skip(start)
+			.zip(head)
+			.chain(cells.iter().zip(tail));
+		for (cell, &byte) in wrapped {
+			cell.store(byte, Relaxed);
+		}
+	}
+	fn copy_out(&self, pos: usize, dst: &mut [u8]) {
+		let (start, first, _) = self.segments(pos, dst.len());
+		let (head, tail) = dst.split_at_mut(first);
+		let cells = self.cells();
+		let wrapped = cells
+			.iter()
+			.skip(start)
+			.zip(head)
+			.chain(cells.iter().zip(tail));
+		for (cell, byte) in wrapped {
+			*byte = cell.load(Relaxed);
+		}
+	}
+}
+impl Debug for Ring {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Ring")
+			.field("map", &self.map)
+			.field("capacity", &self.capacity)
+			.finish()
+	}
+}
+
+#[derive(Debug)]
+struct Endpoint {
+	shmem: SharedMemObject,
+	ring: Ring,
+	notifier: Notifier,
+	waiter: Waiter,
+	nonblocking: AtomicBool,
+	close_flag: u32,
+}
+impl Endpoint {
+	fn new(
+		shmem: SharedMemObject,
+		notifier: Notifier,
+		waiter: Waiter,
+		close_flag: u32,
+	) -> io::Result<Self> {
+		Ok(Self {
+			ring: Ring::attach(&shmem)?,
+			shmem,
+			notifier,
+			waiter,
+			nonblocking: AtomicBool::new(false),
+			close_flag,
+		})
+	}
+	fn from_parts(parts: Parts, close_flag: u32) -> io::Result<Self> {
+		let Parts {
+			shmem,
+			notifier,
+			waiter,
+		} = parts;
+		Self::new(shmem, notifier.into(), waiter.into(), close_flag)
+	}
+	fn into_parts(self) -> Parts {
+		let slf = ManuallyDrop::new(self);
+		// SAFETY: every field is moved out exactly once, and `slf` is not dropped
+		let (shmem, ring, notifier, waiter) = unsafe {
+			(
+				ptr::read(&slf.shmem),
+				ptr::read(&slf.ring),
+				ptr::read(&slf.notifier),
+				ptr::read(&slf.waiter),
+			)
+		};
+		drop(ring);
+		Parts {
+			shmem,
+			notifier: notifier.into(),
+			waiter: waiter.into(),
+		}
+	}
+}
+impl Drop for Endpoint {
+	fn drop(&mut self) {
+		self.ring.flags().fetch_or(self.close_flag, SeqCst);
+		let _ = self.notifier.signal();
+	}
+}
@@ -0,0 +1,286 @@
+//! Guards which make sure that only one instance of an application runs at a time.
+//!
+//! Desktop applications commonly want a second launch to hand its command line over to the
+//! instance which is already running, for example so that the file the user double-clicked opens in
+//! the existing window, and then exit. [`Guard::acquire()`] implements this on top of a local
+//! socket listener: binding to a name is exclusive, so the first process to get to it becomes the
+//! primary instance, and every process after it connects to the primary instance to
+//! [forward](Forwarder::forward) its arguments.
+//!
+//! Namespaced names, which are backed by abstract Unix domain sockets on Linux and named pipes on
+//! Windows, are the best fit for this, since they disappear along with the process which holds
+//! them. A socket file left behind by a primary instance which crashed is removed by the next
+//! instance to start, but if several instances start at the same time, more than one of them may
+//! end up believing to be the primary instance: the check for whether the socket is stale and the
+//! removal of its file are not atomic, so an instance which removes a stale socket file may remove
+//! the live one of a concurrent instance instead. Files at the path of the name which aren't
+//! sockets are never removed – acquisition fails with
+//! [`AlreadyExists`](io::ErrorKind::AlreadyExists) instead.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::{
+//! 	local_socket::{prelude::*, GenericNamespaced},
+//! 	single_instance::{Acquired, Guard},
+//! };
+//! use std::{env, process};
+//!
+//! let name = "com.example.app.sock".to_ns_name::<GenericNamespaced>()?;
+//! let guard = match Guard::acquire(name)? {
+//! 	Acquired::Primary(guard) => guard,
+//! 	Acquired::Secondary(forwarder) => {
+//! 		forwarder.forward(env::args_os().skip(1))?;
+//! 		process::exit(0);
+//! 	}
+//! };
+//! // In the event loop of the application:
+//! while let Ok(args) = guard.forwarded().try_recv() {
+//! 	println!("Launched again with {args:?}");
+//! }
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use crate::{
+	local_socket::{
+		prelude::*,
+		server::{serve, Server, ServerConfig},
+		Liveness, Name, Stream,
+	},
+	SubUsizeExt,
+};
+use std::{
+	ffi::{OsStr, OsString},
+	io::{self, prelude::*},
+	num::NonZeroUsize,
+	sync::mpsc::{self, Receiver, Sender},
+	time::Duration,
+};
+
+/// The largest forwarded message the primary instance accepts, in bytes.
+const MAX_MESSAGE_LEN: usize = 1 << 20;
+/// How long the primary instance waits for a secondary instance to send its arguments.
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(5);
+/// How many times acquisition is retried if the primary instance disappears midway through.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// The outcome of [`Guard::acquire()`].
+#[derive(Debug)]
+pub enum Acquired {
+	/// This process is the primary instance.
+	Primary(Guard),
+	/// Another process is the primary instance.
+	Secondary(Forwarder),
+}
+
+/// Proof of being the primary instance of an application, held for as long as the instance runs.
+///
+/// The guard serves the name it was acquired with on a background thread, over which it receives
+/// the arguments forwarded by secondary instances. Dropping it releases the name, allowing another
+/// instance to become the primary one.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct Guard {
+	forwarded: Receiver<Vec<OsString>>,
+	_server: Server,
+}
+impl Guard {
+	/// Attempts to become the primary instance under the given name, or connects to the primary
+	/// instance if another process already is one.
+	pub fn acquire(name: Name<'_>) -> io::Result<Acquired> {
+		let mut attempts = 1;
+		loop {
+			match Self::listen(name.borrow()) {
+				Ok(guard) => return Ok(Acquired::Primary(guard)),
+				Err(e) if e.kind() == io::ErrorKind::AddrInUse => {}
+				Err(e) => return Err(e),
+			}
+			match Stream::connect(name.borrow()) {
+				Ok(conn) => return Ok(Acquired::Secondary(Forwarder(conn))),
+				// The primary instance has either exited in the meantime or crashed without
+				// cleaning up its socket file
+				Err(e)
+					if attempts < MAX_ATTEMPTS
+						&& matches!(
+							e.kind(),
+							io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+						) =>
+				{
+					if name.probe()? == Liveness::Stale {
+						remove_stale(&name)?;
+					}
+				}
+				Err(e) => return Err(e),
+			}
+			attempts = attempts.saturating_add(1);
+		}
+	}
+	fn listen(name: Name<'_>) -> io::Result<Self> {
+		let (tx, rx) = mpsc::channel();
+		let config = ServerConfig::new()
+			.max_connections(NonZeroUsize::MIN)
+			.timeout(Some(FORWARD_TIMEOUT));
+		let server = serve(name, move |conn| receive(conn, &tx), config)?;
+		Ok(Self {
+			forwarded: rx,
+			_server: server,
+		})
+	}
+
+	/// Returns the receiving end of the channel which the arguments forwarded by secondary
+	/// instances are delivered to, one vector per secondary instance.
+	///
+	/// The channel is never closed while the guard exists, so its blocking receive methods only
+	/// return once a secondary instance forwards its arguments.
+	#[inline]
+	pub fn forwarded(&self) -> &Receiver<Vec<OsString>> {
+		&self.forwarded
+	}
+}
+
+/// Removes a socket file which was found to be stale.
+///
+/// Unix has no way of unlinking a file only if it is still the same file, so the type of the file
+/// and the liveness of the socket are checked once more right before the removal. This narrows,
+/// but doesn't close, the window in which another instance could replace the stale socket with
+/// its own and then have it removed from under it.
+#[cfg(unix)]
+fn remove_stale(name: &Name<'_>) -> io::Result<()> {
+	use crate::local_socket::NameInner;
+	use std::{fs, os::unix::fs::FileTypeExt};
+	let NameInner::UdSocketPath(path) = &name.0 else {
+		return Ok(());
+	};
+	// Files which aren't sockets belong to someone else and are never removed
+	if !fs::symlink_metadata(path)?.file_type().is_socket() {
+		return Err(io::Error::new(
+			io::ErrorKind::AlreadyExists,
+			"local socket name refers to a file which is not a socket",
+		));
+	}
+	if name.probe()? != Liveness::Stale {
+		return Ok(());
+	}
+	match fs::remove_file(path) {
+		Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+		_ => Ok(()),
+	}
+}
+#[cfg(windows)]
+fn remove_stale(_: &Name<'_>) -> io::Result<()> {
+	Ok(())
+}
+
+/// Connection to the primary instance, over which a secondary instance forwards its arguments.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct Forwarder(Stream);
+impl Forwarder {
+	/// Sends the given arguments to the primary instance and waits for it to confirm their
+	/// receipt.
+	///
+	/// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the arguments are too large to
+	/// be forwarded, which is the case if they add up to more than a mebibyte.
+	pub fn forward<I>(mut self, args: I) -> io::Result<()>
+	where
+		I: IntoIterator,
+		I::Item: AsRef<OsStr>,
+	{
+		let (mut body, mut arg_buf) = (Vec::new(), Vec::new());
+		for arg in args {
+			arg_buf.clear();
+			encode(arg.as_ref(), &mut arg_buf);
+			let len = u32::try_from(arg_buf.len()).map_err(|_| too_long())?;
+			body.extend(len.to_le_bytes());
+			body.extend(&arg_buf);
+		}
+		if body.len() > MAX_MESSAGE_LEN {
+			return Err(too_long());
+		}
+		let len = u32::try_from(body.len()).map_err(|_| too_long())?;
+		self.0.write_all(&len.to_le_bytes())?;
+		self.0.write_all(&body)?;
+		let mut ack = [0];
+		self.0.read_exact(&mut ack)
+	}
+}
+
+fn too_long() -> io::Error {
+	io::Error::new(
+		io::ErrorKind::InvalidInput,
+		"arguments too large to be forwarded",
+	)
+}
+
+/// Handles a connection from a secondary instance on the primary instance.
+fn receive(mut conn: Stream, tx: &Sender<Vec<OsString>>) {
+	let Ok(args) = read_args(&mut conn) else {
+		return;
+	};
+	if tx.send(args).is_ok() {
+		let _ = conn.write_all(&[0]);
+	}
+}
+
+fn read_args(conn: &mut Stream) -> io::Result<Vec<OsString>> {
+	let len = read_u32(conn)?.to_usize();
+	if len > MAX_MESSAGE_LEN {
+		return Err(invalid());
+	}
+	let mut body = vec![0; len];
+	conn.read_exact(&mut body)?;
+
+	let mut args = Vec::new();
+	let mut rest = &body[..];
+	while !rest.is_empty() {
+		let len = read_u32(&mut rest)?.to_usize();
+		let (arg, tail) = (rest.get(..len), rest.get(len..));
+		let (Some(arg), Some(tail)) = (arg, tail) else {
+			return Err(invalid());
+		};
+		args.push(decode(arg)?);
+		rest = tail;
+	}
+	Ok(args)
+}
+
+fn read_u32(rdr: &mut impl Read) -> io::Result<u32> {
+	let mut buf = [0; 4];
+	rdr.read_exact(&mut buf)?;
+	Ok(u32::from_le_bytes(buf))
+}
+
+fn invalid() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, "malformed forwarded arguments")
+}
+
+#[cfg(unix)]
+fn encode(arg: &OsStr, buf: &mut Vec<u8>) {
+	use std::os::unix::ffi::OsStrExt;
+	buf.extend(arg.as_bytes());
+}
+#[cfg(unix)]
+fn decode(bytes: &[u8]) -> io::Result<OsString> {
+	use std::os::unix::ffi::OsStrExt;
+	Ok(OsStr::from_bytes(bytes).to_owned())
+}
+
+#[cfg(windows)]
+fn encode(arg: &OsStr, buf: &mut Vec<u8>) {
+	use std::os::windows::ffi::OsStrExt;
+	buf.extend(arg.encode_wide().flat_map(u16::to_le_bytes));
+}
+#[cfg(windows)]
+fn decode(bytes: &[u8]) -> io::Result<OsString> {
+	use std::os::windows::ffi::OsStringExt;
+	let chunks = bytes.chunks_exact(2);
+	if !chunks.remainder().is_empty() {
+		return Err(invalid());
+	}
+	let wide = chunks
+		.map(|c| <[u8; 2]>::try_from(c).map(u16::from_le_bytes))
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|_| invalid())?;
+	Ok(OsString::from_wide(&wide))
+}
@@ -0,0 +1,145 @@
+//! Helpers for tests of programs which use local sockets.
+//!
+//! Tests which run in parallel, or several runs of the same test suite at once, need socket names
+//! that don't collide, and a test which fails halfway through shouldn't leave socket files behind
+//! to trip up the next run. [`TempSocket`] takes care of both.
+
+#[cfg(windows)]
+use crate::local_socket::GenericNamespaced;
+use crate::local_socket::{prelude::*, Listener, ListenerOptions, Name, Stream};
+use std::{
+	collections::hash_map::RandomState,
+	hash::{BuildHasher, Hasher},
+	io, process,
+	sync::atomic::{AtomicU32, Ordering::Relaxed},
+};
+#[cfg(unix)]
+use {
+	crate::local_socket::GenericFilePath,
+	std::{
+		env, fs,
+		path::{Path, PathBuf},
+	},
+};
+
+/// How many times a fresh name is tried if the previous one turns out to be taken.
+const ATTEMPTS: u32 = 16;
+/// The longest socket path that fits in `sockaddr_un` on every supported platform, excluding the
+/// terminating nul.
+#[cfg(unix)]
+const MAX_PATH_LEN: usize = 103;
+
+/// A local socket listener bound to a freshly generated, unique name, which is cleaned up when the
+/// `TempSocket` is dropped.
+///
+/// On Unix, the socket file is created in the temporary directory of the system (or `/tmp`, if the
+/// path to that one is too long for a socket address), and removed on drop. On Windows, a named
+/// pipe with a unique name is created, which the system disposes of once all handles to it are
+/// closed.
+///
+/// Since cleanup is done by the destructor, it also happens when the test panics, as long as the
+/// panic unwinds. Processes which are killed or abort leave the file behind, but the names are
+/// never reused, so that doesn't affect later runs.
+///
+/// # Example
+/// ```
+/// use interprocess::{local_socket::prelude::*, testing::TempSocket};
+/// use std::io::prelude::*;
+///
+/// let socket = TempSocket::new()?;
+/// let mut client = socket.connect()?;
+/// let mut server = socket.listener().accept()?;
+/// client.write_all(b"ping\n")?;
+/// let mut buf = [0; 5];
+/// server.read_exact(&mut buf)?;
+/// assert_eq!(&buf, b"ping\n");
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Debug)]
+pub struct TempSocket {
+	listener: Listener,
+	name: Name<'static>,
+	#[cfg(unix)]
+	path: PathBuf,
+}
+impl TempSocket {
+	/// Creates a listener with a unique name.
+	pub fn new() -> io::Result<Self> {
+		let mut last_err = None;
+		for _ in 0..ATTEMPTS {
+			#[cfg(unix)]
+			let (name, path) = unique_name()?;
+			#[cfg(windows)]
+			let name = unique_name()?;
+			match ListenerOptions::new().name(name.borrow()).create_sync() {
+				Ok(listener) => {
+					return Ok(Self {
+						listener,
+						name,
+						#[cfg(unix)]
+						path,
+					})
+				}
+				Err(e) if e.kind() == io::ErrorKind::AddrInUse => last_err = Some(e),
+				Err(e) => return Err(e),
+			}
+		}
+		Err(last_err.unwrap_or_else(|| io::ErrorKind::AddrInUse.into()))
+	}
+
+	/// Returns the name that the listener is bound to.
+	#[inline]
+	pub fn name(&self) -> Name<'_> {
+		self.name.borrow()
+	}
+	/// Returns the path of the socket file.
+	#[cfg(unix)]
+	#[cfg_attr(feature = "doc_cfg", doc(cfg(unix)))]
+	#[inline]
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+	/// Returns a reference to the listener.
+	#[inline]
+	pub fn listener(&self) -> &Listener {
+		&self.listener
+	}
+	/// Connects to the listener.
+	#[inline]
+	pub fn connect(&self) -> io::Result<Stream> {
+		Stream::connect(self.name())
+	}
+}
+#[cfg(unix)]
+impl Drop for TempSocket {
+	fn drop(&mut self) {
+		// The listener normally takes care of this, unless told not to reclaim the name
+		let _ = fs::remove_file(&self.path);
+	}
+}
+
+fn unique_stem() -> String {
+	static COUNTER: AtomicU32 = AtomicU32::new(0);
+	let random = RandomState::new().build_hasher().finish();
+	let seq = COUNTER.fetch_add(1, Relaxed);
+	format!(
+		"interprocess-{:x}-{seq:x}-{:08x}",
+		process::id(),
+		random & 0xffff_ffff,
+	)
+}
+
+#[cfg(unix)]
+fn unique_name() -> io::Result<(Name<'static>, PathBuf)> {
+	let file = format!("{}.sock", unique_stem());
+	let mut path = env::temp_dir().join(&file);
+	if path.as_os_str().len() > MAX_PATH_LEN {
+		path = Path::new("/tmp").join(file);
+	}
+	let name = path.clone().to_fs_name::<GenericFilePath>()?;
+	Ok((name, path))
+}
+#[cfg(windows)]
+fn unique_name() -> io::Result<Name<'static>> {
+	unique_stem().to_ns_name::<GenericNamespaced>()
+}
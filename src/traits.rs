@@ -0,0 +1,145 @@
+//! Traits which unify connection-based IPC transports.
+//!
+//! Each transport in this crate has its own API, shaped after the capabilities of the underlying
+//! OS primitive. The traits in this module capture the small subset that all of them share –
+//! connecting to a server by name and accepting connections – so that code which only needs that
+//! subset can be generic over the transport. This is useful for libraries which want to leave the
+//! choice of transport to their users, and for tests which want to swap one transport for another.
+//!
+//! The following types implement these traits:
+//! -	The [local socket](crate::local_socket) [`Stream`](crate::local_socket::Stream) and
+//! 	[`Listener`](crate::local_socket::Listener) enums, along with the implementation types they
+//! 	dispatch to;
+//! -	On Windows, byte-mode named pipe streams and listeners;
+//! -	The [in-memory](crate::mem) [`MemoryStream`](crate::mem::MemoryStream) and
+//! 	[`MemoryListener`](crate::mem::MemoryListener), which make for a handy stand-in in tests.
+//!
+//! [`Shutdown`] is implemented by the same stream types, as well as by named pipe streams of all
+//! modes.
+//!
+//! Transport-specific functionality remains available through the concrete types.
+//!
+//! ## Example
+//! ```no_run
+//! use interprocess::{local_socket, traits::{Listener, Stream}};
+//! use std::io::{self, prelude::*};
+//!
+//! fn serve_one<L: Listener>(listener: &L) -> io::Result<()> {
+//! 	let mut conn = listener.accept()?;
+//! 	conn.write_all(b"Hello!\n")
+//! }
+//! fn greet<S: Stream>(name: S::Name<'_>) -> io::Result<String> {
+//! 	let mut conn = S::connect(name)?;
+//! 	let mut buf = [0; 7];
+//! 	conn.read_exact(&mut buf)?;
+//! 	Ok(String::from_utf8_lossy(&buf).into_owned())
+//! }
+//!
+//! let name = local_socket::Name::interpret("@example.sock")?;
+//! let greeting = greet::<local_socket::Stream>(name)?;
+//! # io::Result::<()>::Ok(())
+//! ```
+
+use std::{
+	io::{self, prelude::*},
+	net,
+};
+
+/// Connection-based byte streams which can connect to a server by name.
+///
+/// See the [module-level documentation](self) for more.
+pub trait Stream: Read + Write + Sized {
+	/// The type of names of servers which this stream type can connect to.
+	type Name<'n>;
+	/// Connects to the server with the given name.
+	fn connect(name: Self::Name<'_>) -> io::Result<Self>;
+}
+
+/// Servers which accept connections from [`Stream`]s.
+///
+/// See the [module-level documentation](self) for more.
+pub trait Listener {
+	/// The type of streams produced by accepting connections.
+	type Stream: Stream;
+	/// Blocks until a client connects, returning the server end of the connection.
+	fn accept(&self) -> io::Result<Self::Stream>;
+}
+
+/// Streams which can be shut down through a shared reference, and thus from a thread other than
+/// the one using them.
+pub trait Shutdown {
+	/// Shuts down the receive half, the send half or both halves of the connection.
+	///
+	/// See the `.shutdown()` method of the implementing type for the exact semantics.
+	fn shutdown(&self, how: net::Shutdown) -> io::Result<()>;
+}
+
+macro_rules! local_socket_impls {
+	($($listener:ty => $stream:ty),+ $(,)?) => {$(
+		impl Stream for $stream {
+			type Name<'n> = crate::local_socket::Name<'n>;
+			#[inline]
+			fn connect(name: Self::Name<'_>) -> io::Result<Self> {
+				crate::local_socket::traits::Stream::connect(name)
+			}
+		}
+		impl Listener for $listener {
+			type Stream = $stream;
+			#[inline]
+			fn accept(&self) -> io::Result<Self::Stream> {
+				crate::local_socket::traits::Listener::accept(self)
+			}
+		}
+		impl Shutdown for $stream {
+			#[inline]
+			fn shutdown(&self, how: net::Shutdown) -> io::Result<()> {
+				<$stream>::shutdown(self, how)
+			}
+		}
+	)+};
+}
+
+local_socket_impls! {
+	crate::local_socket::Listener => crate::local_socket::Stream,
+}
+#[cfg(unix)]
+local_socket_impls! {
+	crate::os::unix::uds_local_socket::Listener => crate::os::unix::uds_local_socket::Stream,
+}
+#[cfg(windows)]
+local_socket_impls! {
+	crate::os::windows::named_pipe::local_socket::Listener
+		=> crate::os::windows::named_pipe::local_socket::Stream,
+}
+
+#[cfg(windows)]
+mod named_pipe {
+	use super::*;
+	use crate::os::windows::named_pipe::{
+		pipe_mode::{Bytes, PipeModeTag},
+		PipeListener, PipeStream,
+	};
+	use std::ffi::OsStr;
+
+	impl Stream for PipeStream<Bytes, Bytes> {
+		/// The full path of the pipe, including the `\\<hostname>\pipe\` prefix.
+		type Name<'n> = &'n OsStr;
+		#[inline]
+		fn connect(name: Self::Name<'_>) -> io::Result<Self> {
+			Self::connect_by_path(name)
+		}
+	}
+	impl Listener for PipeListener<Bytes, Bytes> {
+		type Stream = PipeStream<Bytes, Bytes>;
+		#[inline]
+		fn accept(&self) -> io::Result<Self::Stream> {
+			PipeListener::accept(self)
+		}
+	}
+	impl<Rm: PipeModeTag, Sm: PipeModeTag> Shutdown for PipeStream<Rm, Sm> {
+		#[inline]
+		fn shutdown(&self, how: net::Shutdown) -> io::Result<()> {
+			PipeStream::shutdown(self, how)
+		}
+	}
+}
@@ -4,12 +4,17 @@
 //! have names in their special named pipe filesystem, while unnamed pipes only have handles. This
 //! can both be useful or problematic, depending on the use case. Unnamed pipes work best when a
 //! child process is used. With the fork model on Unix-like systems, the handle can be transferred
-//! to the child process thanks to the cloned address space; on Windows, inheritable handles (the
-//! default for unnamed pipes in this crate) can be used.
+//! to the child process thanks to the cloned address space; on Windows, inheritable handles can be
+//! used. The inheritability of either end can be changed after creation with the
+//! `set_inheritable()` methods on [`Sender`] and [`Recver`].
 //!
 //! Another way to use unnamed pipes is to use a named pipe or a Unix domain socket to establish an
 //! unnamed pipe connection. It just so happens that this crate supports all three.
 
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
 impmod! {unnamed_pipe,
 	Recver as RecverImpl,
 	Sender as SenderImpl,
@@ -42,6 +47,27 @@ pub fn pipe() -> io::Result<(Sender, Recver)> {
 /// [`FromRawFd`]: https://doc.rust-lang.org/std/os/unix/io/trait.FromRawFd.html
 // field is pub(crate) to allow platform builders to create the public-facing pipe types
 pub struct Recver(pub(crate) RecverImpl);
+impl Recver {
+	/// Enables or disables nonblocking mode on the receiving end of the pipe.
+	///
+	/// In nonblocking mode, reads that would otherwise block fail with an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) instead.
+	///
+	/// On Windows, this sets the `PIPE_NOWAIT` mode via `SetNamedPipeHandleState`; on Unix,
+	/// `O_NONBLOCK` is toggled via `fcntl`.
+	#[inline]
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		self.0.set_nonblocking(nonblocking)
+	}
+	/// Specifies whether the receiving end of the pipe is to be inherited by child processes.
+	///
+	/// On Unix, this clears or sets the `FD_CLOEXEC` flag, with inheritable descriptors surviving
+	/// `exec`. On Windows, this toggles `HANDLE_FLAG_INHERIT`.
+	#[inline]
+	pub fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+		self.0.set_inheritable(inheritable)
+	}
+}
 multimacro! {
 	Recver,
 	forward_sync_read,
@@ -65,6 +91,27 @@ multimacro! {
 /// [IRF]: https://doc.rust-lang.org/std/os/unix/io/trait.IntoRawFd.html
 /// [`FromRawFd`]: https://doc.rust-lang.org/std/os/unix/io/trait.FromRawFd.html
 pub struct Sender(pub(crate) SenderImpl);
+impl Sender {
+	/// Enables or disables nonblocking mode on the sending end of the pipe.
+	///
+	/// In nonblocking mode, writes that would otherwise block fail with an error of kind
+	/// [`WouldBlock`](io::ErrorKind::WouldBlock) instead.
+	///
+	/// On Windows, this sets the `PIPE_NOWAIT` mode via `SetNamedPipeHandleState`; on Unix,
+	/// `O_NONBLOCK` is toggled via `fcntl`.
+	#[inline]
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		self.0.set_nonblocking(nonblocking)
+	}
+	/// Specifies whether the sending end of the pipe is to be inherited by child processes.
+	///
+	/// On Unix, this clears or sets the `FD_CLOEXEC` flag, with inheritable descriptors surviving
+	/// `exec`. On Windows, this toggles `HANDLE_FLAG_INHERIT`.
+	#[inline]
+	pub fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+		self.0.set_inheritable(inheritable)
+	}
+}
 multimacro! {
 	Sender,
 	forward_sync_write,
@@ -0,0 +1,61 @@
+//! Asynchronous unnamed pipes for Tokio.
+//!
+//! The types in this module implement Tokio's [`AsyncRead`](tokio::io::AsyncRead) and
+//! [`AsyncWrite`](tokio::io::AsyncWrite) traits and are otherwise analogous to their
+//! [synchronous counterparts](super).
+//!
+//! ## Platform-specific behavior
+//! ### Unix
+//! A regular unnamed pipe is created and both of its ends are registered with the Tokio reactor.
+//! Both ends are non-inheritable; use [`set_inheritable()`](super::Sender::set_inheritable) on a
+//! synchronous pipe instead if the pipe is to be passed to a child process.
+//!
+//! ### Windows
+//! Anonymous pipes created by `CreatePipe` cannot be used with overlapped I/O, which Tokio
+//! requires. Instead, a unidirectional named pipe with a unique name is created, connected to and
+//! restricted to a single instance, which makes it behave like an unnamed pipe in every way that
+//! matters.
+
+impmod! {unnamed_pipe::tokio,
+	Recver as RecverImpl,
+	Sender as SenderImpl,
+	pipe as pipe_impl,
+}
+use std::io;
+
+/// Creates a new asynchronous pipe and returns the handles to its sending end and receiving end.
+///
+/// # Panics
+/// Panics if called outside of a Tokio runtime context with I/O enabled.
+#[inline]
+pub fn pipe() -> io::Result<(Sender, Recver)> {
+	pipe_impl()
+}
+
+/// Handle to the receiving end of an asynchronous unnamed pipe, created by the [`pipe()`] function
+/// together with the [sending end](Sender).
+///
+/// The core functionality is exposed via the [`AsyncRead`](tokio::io::AsyncRead) trait.
+// field is pub(crate) to allow platform builders to create the public-facing pipe types
+pub struct Recver(pub(crate) RecverImpl);
+multimacro! {
+	Recver,
+	pinproj_for_unpin(RecverImpl),
+	forward_tokio_read,
+	forward_as_handle,
+	forward_debug,
+}
+
+/// Handle to the sending end of an asynchronous unnamed pipe, created by the [`pipe()`] function
+/// together with the [receiving end](Recver).
+///
+/// The core functionality is exposed via the [`AsyncWrite`](tokio::io::AsyncWrite) trait.
+pub struct Sender(pub(crate) SenderImpl);
+multimacro! {
+	Sender,
+	pinproj_for_unpin(SenderImpl),
+	forward_rbv(SenderImpl, &),
+	forward_tokio_write,
+	forward_as_handle,
+	forward_debug,
+}
@@ -0,0 +1,57 @@
+use crate::{
+	event,
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::thread;
+
+fn test_inner() -> TestResult {
+	let (notifier, waiter) = event::pair().opname("create")?;
+	ensure_eq!(waiter.try_wait().opname("try_wait")?, false);
+
+	// Signals sent before waiting are coalesced into one
+	notifier.signal().opname("signal")?;
+	notifier.signal().opname("signal")?;
+	ensure_eq!(waiter.try_wait().opname("try_wait")?, true);
+	ensure_eq!(waiter.try_wait().opname("try_wait")?, false);
+
+	let jh = thread::spawn(move || notifier.signal());
+	waiter.wait().opname("wait")?;
+	jh.join().unwrap().opname("signal")?;
+	Ok(())
+}
+
+#[test]
+fn event() -> TestResult {
+	test_wrapper(test_inner)
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+	use crate::{
+		event::{self, tokio::Waiter},
+		tests::util::{tokio::test_wrapper, TestResult, WrapErrExt},
+	};
+
+	async fn test_inner() -> TestResult {
+		let (notifier, waiter) = event::pair().opname("create")?;
+		let waiter = Waiter::try_from(waiter).opname("register")?;
+		ensure_eq!(waiter.try_wait().opname("try_wait")?, false);
+
+		let jh = ::tokio::task::spawn_blocking(move || {
+			notifier.signal()?;
+			Ok::<_, std::io::Error>(notifier)
+		});
+		waiter.wait().await.opname("wait")?;
+		let notifier = jh.await.unwrap().opname("signal")?;
+
+		notifier.signal().opname("signal")?;
+		waiter.wait().await.opname("wait")?;
+		ensure_eq!(waiter.try_wait().opname("try_wait")?, false);
+		Ok(())
+	}
+
+	#[test]
+	fn tokio_event() -> TestResult {
+		test_wrapper(test_inner())
+	}
+}
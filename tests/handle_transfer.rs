@@ -0,0 +1,30 @@
+use crate::{
+	handle_transfer::HandleTransfer,
+	local_socket::Stream,
+	tests::util::*,
+	unnamed_pipe::{pipe, Sender},
+};
+use std::io::{prelude::*, BufReader};
+
+fn test_inner() -> TestResult {
+	let (a, b) = Stream::pair().opname("pair")?;
+	let (tx, rx) = pipe().opname("pipe")?;
+
+	a.send_handle(tx.into()).opname("send handle")?;
+	let mut tx = Sender::from(b.recv_handle().opname("receive handle")?);
+
+	tx.write_all(b"ping\n")
+		.opname("write to transferred sender")?;
+	drop(tx);
+	let mut line = String::new();
+	BufReader::new(rx)
+		.read_line(&mut line)
+		.opname("read from pipe")?;
+	ensure_eq!(line, "ping\n");
+	Ok(())
+}
+
+#[test]
+fn handle_transfer() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,85 @@
+use crate::{
+	error::IdleTimeoutError,
+	idle_timeout::IdleTimeout,
+	local_socket::Stream,
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::{
+	io::{self, prelude::*},
+	time::Duration,
+};
+
+fn is_idle_timeout(e: &io::Error) -> bool {
+	e.get_ref().is_some_and(|e| e.is::<IdleTimeoutError>())
+}
+
+fn test_inner() -> TestResult {
+	let (a, mut b) = Stream::pair().opname("pair")?;
+	let mut a = IdleTimeout::new(a, Duration::from_millis(200)).opname("wrap")?;
+
+	// Activity keeps the connection alive
+	let mut buf = [0; 4];
+	for _ in 0..3 {
+		std::thread::sleep(Duration::from_millis(100));
+		b.write_all(b"ping").opname("send")?;
+		a.read_exact(&mut buf).opname("receive")?;
+		ensure_eq!(&buf, b"ping");
+	}
+	ensure_eq!(a.is_expired(), false);
+
+	// A blocked read is cut short by the watchdog
+	let e = a.read(&mut buf).err();
+	ensure_eq!(e.as_ref().map(is_idle_timeout), Some(true));
+	ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::TimedOut));
+	ensure_eq!(a.is_expired(), true);
+	let e = a.write(b"pong").err();
+	ensure_eq!(e.as_ref().map(is_idle_timeout), Some(true));
+	ensure_eq!(b.read(&mut buf).opname("peer receive")?, 0);
+	Ok(())
+}
+
+#[test]
+fn idle_timeout() -> TestResult {
+	test_wrapper(test_inner)
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+	use super::is_idle_timeout;
+	use crate::{
+		idle_timeout::tokio::IdleTimeout,
+		tests::util::{tokio::test_wrapper, TestResult, WrapErrExt},
+	};
+	use ::tokio::{
+		io::{duplex, AsyncReadExt, AsyncWriteExt},
+		time::sleep,
+	};
+	use std::time::Duration;
+
+	async fn test_inner() -> TestResult {
+		let (a, mut b) = duplex(64);
+		let mut a = IdleTimeout::new(a, Duration::from_millis(200));
+
+		let mut buf = [0; 4];
+		for _ in 0..3 {
+			sleep(Duration::from_millis(100)).await;
+			b.write_all(b"ping").await.opname("send")?;
+			a.read_exact(&mut buf).await.opname("receive")?;
+			ensure_eq!(&buf, b"ping");
+		}
+		ensure_eq!(a.is_expired(), false);
+
+		let e = a.read(&mut buf).await.err();
+		ensure_eq!(e.as_ref().map(is_idle_timeout), Some(true));
+		ensure_eq!(a.is_expired(), true);
+
+		// The wrapped stream was dropped, which the peer observes as end of file
+		ensure_eq!(b.read(&mut buf).await.opname("peer receive")?, 0);
+		Ok(())
+	}
+
+	#[test]
+	fn tokio_idle_timeout() -> TestResult {
+		test_wrapper(test_inner())
+	}
+}
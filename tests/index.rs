@@ -5,16 +5,71 @@ mod util;
 mod os {
 	#[cfg(unix)]
 	mod unix {
+		mod eintr_policy;
+		mod fifo;
+		mod local_socket_backlog;
+		mod local_socket_configure_socket;
+		#[cfg(feature = "tokio")]
+		mod local_socket_connect_with;
+		mod local_socket_copy_ancillary;
+		#[cfg(any(target_os = "linux", target_os = "android"))]
+		mod local_socket_credentials;
+		mod local_socket_excess_fds;
 		mod local_socket_fake_ns;
+		mod local_socket_handoff;
+		mod local_socket_listener_set;
 		mod local_socket_mode;
+		mod local_socket_owned_socket_file;
+		mod local_socket_peek;
+		mod local_socket_peek_ancillary;
+		mod local_socket_peer_creds;
+		#[cfg(any(target_os = "linux", target_os = "android"))]
+		mod local_socket_peer_security;
+		mod local_socket_probe;
+		mod local_socket_replace_dead;
+		mod local_socket_send_file;
+		mod local_socket_shared_listener;
+		mod local_socket_sigpipe;
+		mod local_socket_sockopts;
+		mod local_socket_splice;
+		mod local_socket_std_conv;
+		mod local_socket_take_error;
+		#[cfg(feature = "tokio")]
+		mod local_socket_tokio_readiness;
+		#[cfg(target_os = "linux")]
+		mod posix_mqueue;
+		mod process;
+		#[cfg(feature = "systemd")]
+		mod sd_listen_fds;
 	}
 	#[cfg(windows)]
 	mod windows {
+		mod local_socket_pipe_options;
+		mod local_socket_remote_name;
 		mod local_socket_security_descriptor;
+		mod mailslot;
+		mod named_pipe_adopt;
+		mod named_pipe_conv;
 	}
 }
 
+mod event;
+mod handle_transfer;
+mod idle_timeout;
 mod local_socket;
+mod mem;
+mod metrics;
 mod named_pipe;
+#[cfg(feature = "registry")]
+mod registry;
+#[cfg(feature = "noise")]
+mod secure;
+mod shmem;
+mod single_instance;
+#[cfg(feature = "testing")]
+mod testing;
 mod tokio_local_socket;
 mod tokio_named_pipe;
+mod tokio_unnamed_pipe;
+mod traits;
+mod unnamed_pipe;
@@ -1,7 +1,22 @@
 // TODO(2.0.1) test various error conditions
 
+mod authenticated;
+mod batch_writer;
+mod interpret_name;
+mod listener_clone;
+mod name_builder;
+mod name_resolver;
 mod no_server;
+mod pair;
+mod peek;
+mod probe;
+mod read_uninit;
+mod retry;
+mod server;
+mod shutdown;
 mod stream;
+mod typed_error;
+mod user_scoped;
 
 use crate::tests::util::*;
 
@@ -33,7 +48,149 @@ tests! {test_stream
 	stream_namespaced	false
 }
 
+fn test_retry_late_server(id: &'static str, path: bool) -> TestResult {
+	test_wrapper(move || retry::late_server(id, path))
+}
+
+fn test_retry_no_server(id: &'static str, path: bool) -> TestResult {
+	test_wrapper(move || retry::no_server(id, path))
+}
+
 tests! {test_no_server
 	no_server_file			true
 	no_server_namespaced	false
 }
+
+fn test_listener_clone(id: &'static str, path: bool) -> TestResult {
+	test_wrapper(move || listener_clone::run(id, path))
+}
+
+tests! {test_listener_clone
+	listener_clone_file			true
+	listener_clone_namespaced	false
+}
+
+tests! {test_retry_late_server
+	retry_late_server_file			true
+	retry_late_server_namespaced	false
+}
+
+tests! {test_retry_no_server
+	retry_no_server_file			true
+	retry_no_server_namespaced		false
+}
+
+fn test_authenticated(id: &'static str, path: bool) -> TestResult {
+	test_wrapper(move || authenticated::run(id, path))
+}
+
+tests! {test_authenticated
+	authenticated_file			true
+	authenticated_namespaced	false
+}
+
+fn test_authenticated_serve(id: &'static str, path: bool) -> TestResult {
+	test_wrapper(move || authenticated::serve(id, path))
+}
+
+tests! {test_authenticated_serve
+	authenticated_serve_file		true
+	authenticated_serve_namespaced	false
+}
+
+fn test_probe(id: &'static str, path: bool) -> TestResult {
+	test_wrapper(move || probe::run(id, path))
+}
+
+tests! {test_probe
+	probe_file			true
+	probe_namespaced	false
+}
+
+fn test_server(id: &'static str, path: bool) -> TestResult {
+	test_wrapper(move || server::run(id, path))
+}
+
+tests! {test_server
+	server_file			true
+	server_namespaced	false
+}
+
+fn test_server_refuse(id: &'static str, path: bool) -> TestResult {
+	test_wrapper(move || server::refuse(id, path))
+}
+
+tests! {test_server_refuse
+	server_refuse_file			true
+	server_refuse_namespaced	false
+}
+
+fn test_typed_error_addr_in_use(id: &'static str, path: bool) -> TestResult {
+	test_wrapper(move || typed_error::addr_in_use(id, path))
+}
+
+tests! {test_typed_error_addr_in_use
+	typed_error_addr_in_use_file		true
+	typed_error_addr_in_use_namespaced	false
+}
+
+#[cfg(unix)]
+#[test]
+fn typed_error_connection_refused() -> TestResult {
+	test_wrapper(|| typed_error::connection_refused(make_id!()))
+}
+
+#[test]
+fn typed_error_name_too_long() -> TestResult {
+	test_wrapper(typed_error::name_too_long)
+}
+
+#[test]
+fn typed_error_round_trip() -> TestResult {
+	test_wrapper(typed_error::round_trip)
+}
+
+#[test]
+fn stream_batch_writer() -> TestResult {
+	test_wrapper(batch_writer::run)
+}
+
+#[test]
+fn stream_pair() -> TestResult {
+	test_wrapper(pair::run)
+}
+
+#[test]
+fn stream_peek() -> TestResult {
+	test_wrapper(peek::run)
+}
+
+#[test]
+fn stream_read_uninit() -> TestResult {
+	test_wrapper(read_uninit::run)
+}
+
+#[test]
+fn stream_shutdown() -> TestResult {
+	test_wrapper(shutdown::run)
+}
+
+#[test]
+fn interpret_name() -> TestResult {
+	test_wrapper(interpret_name::run)
+}
+
+#[test]
+fn name_builder() -> TestResult {
+	test_wrapper(name_builder::run)
+}
+
+#[test]
+fn name_resolver() -> TestResult {
+	test_wrapper(name_resolver::run)
+}
+
+#[test]
+fn user_scoped() -> TestResult {
+	test_wrapper(|| user_scoped::run(make_id!()))
+}
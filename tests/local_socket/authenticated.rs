@@ -0,0 +1,109 @@
+//! Tests the authentication handshake with various combinations of checks and client tokens.
+
+use crate::{
+	local_socket::{
+		authenticated::{self, ServerBuilder},
+		prelude::*,
+		server::ServerConfig,
+		ListenerOptions,
+	},
+	tests::util::*,
+};
+use color_eyre::eyre::bail;
+use std::{
+	io::{self, prelude::*},
+	process, thread,
+	time::Duration,
+};
+
+/// Performs the handshake with the given checks and token, returning the error kinds observed by
+/// the server and the client.
+fn handshake(
+	id: &str,
+	path: bool,
+	builder: ServerBuilder,
+	token: &'static [u8],
+) -> TestResult<(Option<io::ErrorKind>, Option<io::ErrorKind>)> {
+	let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ListenerOptions::new().name(nm.borrow()).create_sync()
+	})?;
+	let server = thread::spawn(move || -> io::Result<()> {
+		let conn = builder
+			.handshake_timeout(Some(Duration::from_secs(5)))
+			.authenticate(listener.accept()?)?;
+		(&conn).write_all(b"ok")
+	});
+
+	let client = authenticated::connect(name.borrow(), token).and_then(|mut conn| {
+		let mut buf = [0; 2];
+		conn.read_exact(&mut buf)?;
+		Ok(buf)
+	});
+	if let Ok(buf) = &client {
+		ensure_eq!(buf, b"ok");
+	}
+	let server = match server.join() {
+		Ok(rslt) => rslt,
+		Err(..) => bail!("server thread panicked"),
+	};
+	Ok((
+		server.err().map(|e| e.kind()),
+		client.err().map(|e| e.kind()),
+	))
+}
+
+pub fn run(id: &str, path: bool) -> TestResult {
+	use io::ErrorKind::PermissionDenied;
+	let denied = (Some(PermissionDenied), Some(PermissionDenied));
+
+	let token = || ServerBuilder::new().token("secret");
+	ensure_eq!(handshake(id, path, token(), b"secret")?, (None, None));
+	ensure_eq!(handshake(id, path, token(), b"wrong")?, denied);
+	ensure_eq!(handshake(id, path, token(), b"")?, denied);
+	// Servers which don't require a token ignore it
+	ensure_eq!(
+		handshake(id, path, ServerBuilder::new(), b"extra")?,
+		(None, None)
+	);
+
+	#[cfg(unix)]
+	{
+		let uid = unsafe { libc::getuid() };
+		let allowed = ServerBuilder::new().allow_uid(uid);
+		ensure_eq!(handshake(id, path, allowed, b"")?, (None, None));
+		let other = ServerBuilder::new().allow_uid(uid.wrapping_add(1));
+		ensure_eq!(handshake(id, path, other, b"")?, denied);
+	}
+
+	if cfg!(any(target_os = "linux", target_os = "android", windows)) {
+		let allowed = ServerBuilder::new().allow_pid(process::id());
+		ensure_eq!(handshake(id, path, allowed, b"")?, (None, None));
+	}
+	let other = ServerBuilder::new().allow_pid(process::id().wrapping_add(1));
+	ensure_eq!(handshake(id, path, other, b"")?, denied);
+	Ok(())
+}
+
+pub fn serve(id: &str, path: bool) -> TestResult {
+	let (name, server) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ServerBuilder::new().token("secret").serve(
+			nm.borrow(),
+			|conn| {
+				let _ = (&conn).write_all(b"welcome");
+			},
+			ServerConfig::new(),
+		)
+	})?;
+
+	let e = authenticated::connect(name.borrow(), b"wrong")
+		.err()
+		.map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::PermissionDenied));
+
+	let mut conn = authenticated::connect(name.borrow(), b"secret").opname("connect")?;
+	let mut buf = [0; 7];
+	conn.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"welcome");
+	server.shutdown().opname("shutdown")?;
+	Ok(())
+}
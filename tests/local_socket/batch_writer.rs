@@ -0,0 +1,45 @@
+//! Tests for `local_socket::BatchWriter`.
+
+use crate::{
+	local_socket::{BatchWriter, Stream},
+	tests::util::*,
+};
+use std::io::prelude::*;
+
+pub fn run() -> TestResult {
+	let (a, mut b) = Stream::pair().opname("pair")?;
+	let mut writer = BatchWriter::with_capacity(64, &a);
+
+	for i in 0..10u8 {
+		writer.write_all(&[i; 4]).opname("small write")?;
+	}
+	ensure_eq!(writer.buffer().len(), 40);
+	ensure_eq!(writer.stats().syscalls, 0);
+
+	// Doesn't fit, so the buffered data goes out along with it in one vectored send
+	writer.write_all(&[0xff; 32]).opname("overflowing write")?;
+	ensure_eq!(writer.stats().syscalls, 1);
+	ensure_eq!(writer.buffer().len(), 0);
+
+	// Larger than the whole buffer, so it bypasses it
+	writer.write_all(&[0xee; 100]).opname("large write")?;
+	ensure_eq!(writer.stats().syscalls, 2);
+
+	writer.write_all(b"tail").opname("tail write")?;
+	writer.flush().opname("flush")?;
+	let stats = writer.stats();
+	ensure_eq!((stats.writes, stats.syscalls), (13, 3));
+
+	let (_, unsent) = writer.into_parts();
+	ensure_eq!(unsent, b"");
+	drop(a);
+
+	let mut received = Vec::new();
+	b.read_to_end(&mut received).opname("receive")?;
+	let mut expected = (0..10u8).flat_map(|i| [i; 4]).collect::<Vec<_>>();
+	expected.extend_from_slice(&[0xff; 32]);
+	expected.extend_from_slice(&[0xee; 100]);
+	expected.extend_from_slice(b"tail");
+	ensure_eq!(received, expected);
+	Ok(())
+}
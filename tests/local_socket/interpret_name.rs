@@ -0,0 +1,34 @@
+//! Tests `Name::interpret()` by connecting to a server through a parsed name.
+
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, Name, Stream},
+	tests::util::*,
+};
+use std::{
+	io::{Read, Write},
+	process,
+};
+
+pub fn run() -> TestResult {
+	let name = Name::interpret("@interprocess-test.sock").opname("interpret")?;
+	ensure_eq!(name.is_namespaced(), true);
+	if cfg!(unix) {
+		let name = Name::interpret("./@interprocess-test.sock").opname("interpret")?;
+		ensure_eq!((name.is_path(), name.is_namespaced()), (true, false));
+	}
+
+	let string = format!("@interprocess-test-interpret-{:08x}.sock", process::id());
+	let listener = ListenerOptions::new()
+		.name(Name::interpret(&string).opname("interpret")?)
+		.create_sync()
+		.opname("listen")?;
+	let name: Name<'static> = string.parse().opname("parse")?;
+	let mut client = Stream::connect(name).opname("connect")?;
+	let mut server = listener.accept().opname("accept")?;
+
+	client.write_all(b"ping").opname("send")?;
+	let mut buf = [0; 4];
+	server.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"ping");
+	Ok(())
+}
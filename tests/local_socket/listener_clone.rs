@@ -0,0 +1,28 @@
+//! Tests that cloned listeners accept connections and leave name reclamation to the original.
+
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, Stream},
+	tests::util::*,
+	TryClone,
+};
+use std::io::{Read, Write};
+
+pub fn run(id: &'static str, path: bool) -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ListenerOptions::new().name(nm.borrow()).create_sync()
+	})?;
+	let clone = listener.try_clone().opname("clone")?;
+
+	let mut client = Stream::connect(name.borrow()).opname("connect")?;
+	let mut conn = clone.accept().opname("accept on clone")?;
+	client.write_all(b"ping").opname("send")?;
+	let mut buf = [0; 4];
+	conn.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"ping");
+	drop(clone);
+
+	// Dropping the clone must not have deleted the socket file
+	let _client = Stream::connect(name.borrow()).opname("connect after dropping clone")?;
+	listener.accept().opname("accept on original")?;
+	Ok(())
+}
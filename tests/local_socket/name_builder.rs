@@ -0,0 +1,43 @@
+//! Tests the validation performed by `NameBuilder`.
+
+use crate::{local_socket::Name, tests::util::*};
+use std::io;
+
+fn kind(rslt: io::Result<Name<'_>>) -> Option<io::ErrorKind> {
+	rslt.err().map(|e| e.kind())
+}
+
+pub fn run() -> TestResult {
+	let invalid = Some(io::ErrorKind::InvalidInput);
+	ensure_eq!(kind(Name::builder().build()), invalid);
+	ensure_eq!(kind(Name::builder().auto("").build()), invalid);
+
+	let long = "a".repeat(1000);
+	ensure_eq!(kind(Name::builder().auto(&long).build()), invalid);
+	if cfg!(unix) {
+		ensure_eq!(kind(Name::builder().path(&long).build()), invalid);
+		let name = Name::builder()
+			.path("/tmp/example.sock")
+			.build()
+			.opname("build path")?;
+		ensure_eq!(name.is_path(), true);
+	}
+
+	let name = Name::builder()
+		.auto("example.sock")
+		.build()
+		.opname("build auto")?;
+	ensure_eq!(name.is_path() && !name.is_namespaced(), false);
+	if cfg!(any(target_os = "linux", target_os = "android", windows)) {
+		let name = Name::builder()
+			.namespaced("example.sock")
+			.build()
+			.opname("build namespaced")?;
+		ensure_eq!(name.is_namespaced(), true);
+		ensure_eq!(kind(Name::builder().namespaced(&long).build()), invalid);
+	} else {
+		let e = kind(Name::builder().namespaced("example.sock").build());
+		ensure_eq!(e, Some(io::ErrorKind::Unsupported));
+	}
+	Ok(())
+}
@@ -0,0 +1,28 @@
+//! Tests service name resolution.
+//!
+//! The process-wide resolver is never installed here, since all tests share the process.
+
+use crate::{
+	local_socket::{GenericNamespaced, Name, NameResolver, ToNsName},
+	tests::util::*,
+};
+use std::io;
+
+pub fn run() -> TestResult {
+	if cfg!(any(target_os = "linux", target_os = "android", windows)) {
+		let name = Name::service("example").opname("resolve")?;
+		let expected = "example".to_ns_name::<GenericNamespaced>()?;
+		ensure_eq!(name, expected);
+	}
+	let e = Name::service(&"a".repeat(1000)).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::InvalidInput));
+
+	let resolver = |service: &str| {
+		format!("resolved-{service}")
+			.to_ns_name::<GenericNamespaced>()
+			.map(Name::into_owned)
+	};
+	let name = NameResolver::resolve(&resolver, "example").opname("custom resolve")?;
+	ensure_eq!(name, "resolved-example".to_ns_name::<GenericNamespaced>()?);
+	Ok(())
+}
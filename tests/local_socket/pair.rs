@@ -0,0 +1,18 @@
+//! Tests `Stream::pair()` by sending a message in each direction.
+
+use crate::{local_socket::Stream, tests::util::*};
+use std::io::{Read, Write};
+
+pub fn run() -> TestResult {
+	let (mut a, mut b) = Stream::pair().opname("pair")?;
+	let mut buf = [0; 4];
+
+	a.write_all(b"ping").opname("send ping")?;
+	b.read_exact(&mut buf).opname("receive ping")?;
+	ensure_eq!(&buf, b"ping");
+
+	b.write_all(b"pong").opname("send pong")?;
+	a.read_exact(&mut buf).opname("receive pong")?;
+	ensure_eq!(&buf, b"pong");
+	Ok(())
+}
@@ -0,0 +1,27 @@
+//! Tests non-destructive peeking on `local_socket::Stream`.
+
+use crate::{local_socket::Stream, tests::util::*};
+use std::{
+	io::{self, prelude::*},
+	thread,
+};
+
+pub fn run() -> TestResult {
+	let (a, mut b) = Stream::pair().opname("pair")?;
+	(&a).write_all(b"GET / HTTP/1.1\r\n").opname("send")?;
+	// Named pipes don't block for peeking, so give the data time to arrive if needed
+	let mut sig = [0; 4];
+	let peeked = loop {
+		match b.peek(&mut sig) {
+			Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::yield_now(),
+			els => break els.opname("peek")?,
+		}
+	};
+	ensure_eq!(&sig[..peeked], &b"GET "[..peeked]);
+	ensure_eq!(b.peek(&mut sig).opname("second peek")?, peeked);
+
+	let mut line = [0; 16];
+	b.read_exact(&mut line).opname("receive")?;
+	ensure_eq!(&line, b"GET / HTTP/1.1\r\n");
+	Ok(())
+}
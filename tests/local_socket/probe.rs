@@ -0,0 +1,38 @@
+//! Tests `Name::probe()` on a name before, during and after the lifetime of a listener.
+
+use crate::{
+	local_socket::{ListenerOptions, Liveness},
+	tests::util::*,
+};
+
+pub fn run(id: &str, path: bool) -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ListenerOptions::new()
+			.name(nm.borrow())
+			.reclaim_name(false)
+			.create_sync()
+	})?;
+	ensure_eq!(
+		name.probe().opname("probe with listener")?,
+		Liveness::Listening
+	);
+	drop(listener);
+
+	let stale = cfg!(unix) && path;
+	let expected = if stale {
+		Liveness::Stale
+	} else {
+		Liveness::Absent
+	};
+	ensure_eq!(name.probe().opname("probe after listener")?, expected);
+
+	#[cfg(unix)]
+	if let crate::local_socket::NameInner::UdSocketPath(path) = &name.0 {
+		std::fs::remove_file(path).opname("remove socket file")?;
+	}
+	ensure_eq!(
+		name.probe().opname("probe without listener")?,
+		Liveness::Absent
+	);
+	Ok(())
+}
@@ -0,0 +1,29 @@
+//! Tests receiving into uninitialized buffers on `local_socket::Stream`.
+
+use crate::{local_socket::Stream, tests::util::*};
+use std::{
+	io::{self, prelude::*},
+	mem::MaybeUninit,
+};
+
+pub fn run() -> TestResult {
+	let (a, b) = Stream::pair().opname("pair")?;
+	(&a).write_all(b"uninit").opname("send")?;
+	drop(a);
+
+	let mut buf = [MaybeUninit::<u8>::uninit(); 64];
+	let mut received = Vec::new();
+	loop {
+		let n = match b.read_to_uninit(&mut buf) {
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			els => els.opname("receive")?,
+		};
+		if n == 0 {
+			break;
+		}
+		// SAFETY: the first n bytes were initialized by the receive
+		received.extend(buf.iter().take(n).map(|b| unsafe { b.assume_init() }));
+	}
+	ensure_eq!(received, b"uninit");
+	Ok(())
+}
@@ -0,0 +1,40 @@
+//! Tests `connect_with_retries()` both with a server that starts late and with no server at all.
+
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, RetryConfig, Stream},
+	tests::util::*,
+};
+use color_eyre::eyre::bail;
+use std::{num::NonZeroU32, thread, time::Duration};
+
+pub fn late_server(id: &str, path: bool) -> TestResult {
+	let name = namegen_local_socket(id, path).next().unwrap()?;
+	let server_name = name.clone();
+	let server = thread::spawn(move || {
+		thread::sleep(Duration::from_millis(100));
+		let listener = ListenerOptions::new()
+			.name(server_name.borrow())
+			.create_sync()?;
+		listener.accept().map(drop)
+	});
+	let config = RetryConfig::new().deadline(Some(Duration::from_secs(5)));
+	Stream::connect_with_retries(name.borrow(), config).opname("connect with retries")?;
+	match server.join() {
+		Ok(r) => r.opname("server")?,
+		Err(..) => bail!("server thread panicked"),
+	}
+	Ok(())
+}
+
+pub fn no_server(id: &str, path: bool) -> TestResult {
+	let name = namegen_local_socket(id, path).next().unwrap()?;
+	let config = RetryConfig::new()
+		.max_attempts(NonZeroU32::new(3))
+		.initial_delay(Duration::from_millis(1));
+	let err = match Stream::connect_with_retries(name.borrow(), config) {
+		Err(e) => e,
+		Ok(..) => bail!("client successfully connected to nonexistent server"),
+	};
+	ensure_eq!(err.attempts, 3);
+	Ok(())
+}
@@ -0,0 +1,102 @@
+//! Tests the thread-based server runner.
+
+use crate::{
+	local_socket::{
+		prelude::*,
+		server::{serve, OverloadPolicy, RateLimit, ServerConfig},
+		Stream,
+	},
+	tests::util::*,
+};
+use std::{
+	io::{prelude::*, BufReader},
+	num::{NonZeroU32, NonZeroUsize},
+	thread,
+	time::Duration,
+};
+
+fn echo(conn: Stream) {
+	let mut conn = BufReader::new(conn);
+	let mut line = String::new();
+	if conn.read_line(&mut line).is_ok() {
+		let _ = conn.get_mut().write_all(line.as_bytes());
+	}
+}
+
+pub fn run(id: &'static str, path: bool) -> TestResult {
+	let config = ServerConfig::new().max_connections(NonZeroUsize::new(2).unwrap());
+	let (name, server) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		serve(nm.borrow(), echo, config)
+	})?;
+
+	// More clients than workers, so that some of them have to wait for a free worker
+	let clients = (0..6)
+		.map(|i| {
+			let name = name.clone();
+			thread::spawn(move || -> TestResult {
+				let conn = Stream::connect(name.borrow()).opname("connect")?;
+				let mut conn = BufReader::new(conn);
+				let msg = format!("Hello from client {i}\n");
+				conn.get_mut().write_all(msg.as_bytes()).opname("send")?;
+				let mut line = String::new();
+				conn.read_line(&mut line).opname("receive")?;
+				ensure_eq!(line, msg);
+				Ok(())
+			})
+		})
+		.collect::<Vec<_>>();
+	for client in clients {
+		client.join().expect("client thread panicked")?;
+	}
+
+	server.shutdown().opname("shutdown")?;
+	ensure_eq!(Stream::connect(name.borrow()).is_err(), true);
+	Ok(())
+}
+
+fn refused(conn: &mut BufReader<Stream>) -> TestResult<bool> {
+	// Refused connections are closed without anything being sent to them
+	Ok(conn.read_line(&mut String::new()).opname("receive")? == 0)
+}
+
+pub fn refuse(id: &'static str, path: bool) -> TestResult {
+	let config = ServerConfig::new()
+		.max_connections(NonZeroUsize::new(1).unwrap())
+		.accept_rate(Some(RateLimit::new(
+			NonZeroU32::new(2).unwrap(),
+			Duration::from_secs(3600),
+		)))
+		.overload(OverloadPolicy::Refuse);
+	let (name, server) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		serve(nm.borrow(), echo, config)
+	})?;
+	let connect = || {
+		Stream::connect(name.borrow())
+			.opname("connect")
+			.map(BufReader::new)
+	};
+
+	// Occupies the only worker until it sends its line
+	let mut first = connect()?;
+	let mut second = connect()?;
+	ensure_eq!(
+		refused(&mut second)?,
+		true,
+		"connection past the connection limit was accepted"
+	);
+
+	first.get_mut().write_all(b"Hello\n").opname("send")?;
+	let mut line = String::new();
+	first.read_line(&mut line).opname("receive")?;
+	ensure_eq!(line, "Hello\n");
+
+	let mut third = connect()?;
+	ensure_eq!(
+		refused(&mut third)?,
+		true,
+		"connection past the rate limit was accepted"
+	);
+
+	server.shutdown().opname("shutdown")?;
+	Ok(())
+}
@@ -0,0 +1,30 @@
+//! Tests half-closing `local_socket::Stream`.
+
+use crate::{local_socket::Stream, tests::util::*};
+use std::{
+	io::{self, prelude::*},
+	net::Shutdown,
+};
+
+pub fn run() -> TestResult {
+	let (a, b) = Stream::pair().opname("pair")?;
+	(&a).write_all(b"hello").opname("send")?;
+	// On Windows, shutting down the send half waits for the peer to receive everything
+	let mut buf = [0; 5];
+	(&b).read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"hello");
+
+	a.shutdown(Shutdown::Write).opname("shutdown send half")?;
+	let e = (&a).write_all(b"again").err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::BrokenPipe));
+	if cfg!(unix) {
+		// Only Unix delivers the shutdown to the peer
+		ensure_eq!((&b).read(&mut buf).opname("receive EOF")?, 0);
+	}
+
+	(&b).write_all(b"reply").opname("send reply")?;
+	a.shutdown(Shutdown::Read).opname("shutdown receive half")?;
+	#[cfg(windows)]
+	ensure_eq!((&a).read(&mut buf).opname("receive after shutdown")?, 0);
+	Ok(())
+}
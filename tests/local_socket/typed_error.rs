@@ -0,0 +1,99 @@
+//! Tests the structured errors attached to the `io::Error`s of local socket operations.
+
+use crate::{
+	local_socket::{ListenerOptions, Name},
+	tests::util::*,
+	Error,
+};
+use color_eyre::eyre::bail;
+use std::io;
+
+pub fn addr_in_use(id: &str, path: bool) -> TestResult {
+	let (name, _listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ListenerOptions::new().name(nm.borrow()).create_sync()
+	})?;
+	let e = match ListenerOptions::new().name(name.borrow()).create_sync() {
+		Ok(..) => bail!("second listener bound to a name in use"),
+		Err(e) => e,
+	};
+	ensure_eq!(e.kind(), io::ErrorKind::AddrInUse);
+	let e = Error::from(e);
+	// The OS error stays reachable through the error chain
+	let source = std::error::Error::source(&e).and_then(|s| s.downcast_ref::<io::Error>());
+	ensure_eq!(source.map(io::Error::kind), Some(io::ErrorKind::AddrInUse));
+	#[cfg(unix)]
+	ensure_eq!(
+		source.and_then(io::Error::raw_os_error),
+		Some(libc::EADDRINUSE)
+	);
+	match e {
+		Error::AddrInUse { name: in_use, .. } => ensure_eq!(in_use, *name),
+		e => bail!("unexpected error: {e:?}"),
+	}
+	Ok(())
+}
+
+pub fn name_too_long() -> TestResult {
+	let e = Name::builder().auto(&"a".repeat(1000)).build().err();
+	ensure_eq!(
+		e.as_ref().map(io::Error::kind),
+		Some(io::ErrorKind::InvalidInput)
+	);
+	match e.map(Error::from) {
+		Some(Error::NameTooLong { len, max }) => ensure_eq!(len > max, true),
+		e => bail!("unexpected result: {e:?}"),
+	}
+	Ok(())
+}
+
+/// A socket file which no one listens on refuses connections.
+#[cfg(unix)]
+pub fn connection_refused(id: &str) -> TestResult {
+	use crate::local_socket::{prelude::*, Stream};
+	let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, true), |nm| {
+		ListenerOptions::new()
+			.name(nm.borrow())
+			.reclaim_name(false)
+			.create_sync()
+	})?;
+	drop(listener);
+	let rslt = Stream::connect(name.borrow());
+	if let crate::local_socket::NameInner::UdSocketPath(path) = &name.0 {
+		let _ = std::fs::remove_file(path);
+	}
+	let e = match rslt {
+		Ok(..) => bail!("connected to a socket no one listens on"),
+		Err(e) => e,
+	};
+	ensure_eq!(e.kind(), io::ErrorKind::ConnectionRefused);
+	match Error::from(e) {
+		Error::ConnectionRefused {
+			name: refused,
+			source,
+		} => {
+			ensure_eq!(refused, *name);
+			ensure_eq!(source.raw_os_error(), Some(libc::ECONNREFUSED));
+		}
+		e => bail!("unexpected error: {e:?}"),
+	}
+	Ok(())
+}
+
+pub fn round_trip() -> TestResult {
+	// Plain OS errors pass through untouched
+	let e = io::Error::from_raw_os_error(2);
+	let back = io::Error::from(Error::from(e));
+	ensure_eq!(back.raw_os_error(), Some(2));
+
+	let e = io::Error::from(Error::UnsupportedNameType { reason: "test" });
+	ensure_eq!(e.kind(), io::ErrorKind::Unsupported);
+	ensure_eq!(e.to_string(), "test");
+	ensure_eq!(
+		matches!(
+			Error::from(e),
+			Error::UnsupportedNameType { reason: "test" }
+		),
+		true
+	);
+	Ok(())
+}
@@ -0,0 +1,36 @@
+//! Tests user-scoped names.
+
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, Name, Stream},
+	tests::util::*,
+};
+use std::{io, sync::Arc};
+
+pub fn run(id: &str) -> TestResult {
+	for file in ["", ".", "..", "a/b", r"a\b"] {
+		let e = Name::user_scoped(file).err().map(|e| e.kind());
+		ensure_eq!(e, Some(io::ErrorKind::InvalidInput));
+	}
+
+	let mut namegen = NameGen::new(id, |rn| {
+		Name::user_scoped(&format!("interprocess-test-{rn:08x}.sock")).map(Arc::new)
+	});
+	let (name, _listener) = listen_and_pick_name(&mut namegen, |nm| {
+		ListenerOptions::new().name(nm.borrow()).create_sync()
+	})?;
+	#[cfg(unix)]
+	{
+		use std::{env, os::unix::fs::PermissionsExt, path::Path};
+		ensure_eq!(name.is_path(), true);
+		let crate::local_socket::NameInner::UdSocketPath(path) = &name.0 else {
+			color_eyre::eyre::bail!("user-scoped name is not a path");
+		};
+		let dir = Path::new(&**path).parent().unwrap_or(Path::new("/"));
+		if env::var_os("XDG_RUNTIME_DIR").is_none() {
+			let mode = std::fs::metadata(dir)?.permissions().mode();
+			ensure_eq!(mode & 0o077, 0);
+		}
+	}
+	Stream::connect(name.borrow()).opname("connect")?;
+	Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::{
+	mem::{MemoryListener, MemoryStream},
+	tests::util::*,
+};
+use std::{
+	io::{self, prelude::*},
+	net::Shutdown,
+	thread,
+};
+
+fn test_listener() -> TestResult {
+	let name = make_id!();
+	let listener = MemoryListener::bind(name).opname("bind")?;
+	let e = MemoryListener::bind(name).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::AddrInUse));
+	ensure_eq!(listener.try_accept().is_none(), true);
+
+	let jh = thread::spawn(move || -> io::Result<()> {
+		let mut conn = listener.accept()?;
+		let mut buf = [0; 4];
+		conn.read_exact(&mut buf)?;
+		conn.write_all(&buf)
+	});
+	let mut conn = MemoryStream::connect(name).opname("connect")?;
+	conn.write_all(b"ping").opname("send")?;
+	let mut buf = [0; 4];
+	conn.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"ping");
+	jh.join().unwrap().opname("server")?;
+
+	// The listener was dropped by the server thread, which unregisters the name
+	let e = MemoryStream::connect(name).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::NotFound));
+	Ok(())
+}
+
+fn test_hangup() -> TestResult {
+	let (mut a, mut b) = MemoryStream::pair();
+	a.write_all(b"bye").opname("send")?;
+	a.shutdown(Shutdown::Write).opname("shutdown")?;
+	let e = a.write(b"!").err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::BrokenPipe));
+
+	// Data sent before the shutdown is still received, followed by end of file
+	let mut buf = Vec::new();
+	b.read_to_end(&mut buf).opname("receive")?;
+	ensure_eq!(buf, b"bye");
+
+	drop(a);
+	let e = b.write(b"?").err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::BrokenPipe));
+	Ok(())
+}
+
+#[test]
+fn mem_listener() -> TestResult {
+	test_wrapper(test_listener)
+}
+#[test]
+fn mem_hangup() -> TestResult {
+	test_wrapper(test_hangup)
+}
@@ -0,0 +1,59 @@
+use crate::{
+	local_socket::{prelude::*, Stream},
+	metrics::{self, Event, MetricsSink, Operation},
+	tests::util::*,
+};
+use color_eyre::eyre::bail;
+use std::{
+	cell::RefCell,
+	io::{self, prelude::*},
+};
+
+type Record = (Operation, Result<usize, io::ErrorKind>);
+
+thread_local! {
+	static EVENTS: RefCell<Vec<Record>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Collects events per thread, so that tests running concurrently don't see each other's.
+struct ThreadLocalSink;
+impl MetricsSink for ThreadLocalSink {
+	fn record(&self, event: &Event<'_>) {
+		let rec = match *event {
+			Event::Completed { op, bytes } => (op, Ok(bytes)),
+			Event::Failed { op, error } => (op, Err(error.kind())),
+		};
+		EVENTS.with(|events| events.borrow_mut().push(rec));
+	}
+}
+
+fn test_inner() -> TestResult {
+	let _ = metrics::set_sink(ThreadLocalSink);
+
+	let (mut a, mut b) = Stream::pair().opname("pair")?;
+	a.write_all(b"hello").opname("send")?;
+	let mut buf = [0; 5];
+	b.read_exact(&mut buf).opname("receive")?;
+
+	let name = namegen_local_socket(make_id!(), false).next().unwrap()?;
+	let err = match Stream::connect(name.borrow()) {
+		Err(e) => e.kind(),
+		Ok(..) => bail!("client successfully connected to nonexistent server"),
+	};
+
+	let events = EVENTS.with(|events| events.take());
+	ensure_eq!(
+		events,
+		[
+			(Operation::Send, Ok(5)),
+			(Operation::Receive, Ok(5)),
+			(Operation::Connect, Err(err)),
+		]
+	);
+	Ok(())
+}
+
+#[test]
+fn metrics() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -1,7 +1,13 @@
 #![cfg(windows)]
 
 mod bytes;
+mod cancel;
+mod connect_timeout;
+mod impersonation;
+mod info;
+mod instance_limit;
 mod msg;
+mod send_vectored;
 
 use crate::{os::windows::named_pipe::PipeListenerOptions, tests::util::*};
 use std::{
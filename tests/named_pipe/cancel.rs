@@ -0,0 +1,46 @@
+use crate::{
+	os::windows::named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions},
+	tests::util::*,
+};
+use std::{io::prelude::*, path::Path, thread, time::Duration};
+
+type Stream = DuplexPipeStream<pipe_mode::Bytes>;
+
+const ERROR_OPERATION_ABORTED: i32 = 995;
+
+fn test_inner() -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+		PipeListenerOptions::new()
+			.path(Path::new(nm))
+			.create_duplex::<pipe_mode::Bytes>()
+	})?;
+	let mut client = Stream::connect_by_path(&*name).opname("connect")?;
+	let mut server = listener.accept().opname("accept")?;
+	let cancel = client.cancel_handle().opname("cancel_handle")?;
+
+	let reader = thread::spawn(move || {
+		let mut buf = [0; 4];
+		let rslt = client.read(&mut buf);
+		(client, rslt)
+	});
+	// Cancelling before the reader blocks is a no-op, so keep at it until it wakes up
+	while !reader.is_finished() {
+		cancel.cancel().opname("cancel")?;
+		thread::sleep(Duration::from_millis(50));
+	}
+	let (mut client, rslt) = reader.join().unwrap();
+	let e = rslt.err().and_then(|e| e.raw_os_error());
+	ensure_eq!(e, Some(ERROR_OPERATION_ABORTED));
+
+	// The connection survives cancellation
+	server.write_all(b"ping").opname("server send")?;
+	let mut buf = [0; 4];
+	client.read_exact(&mut buf).opname("client receive")?;
+	ensure_eq!(&buf, b"ping");
+	Ok(())
+}
+
+#[test]
+fn cancel() -> TestResult {
+	test_wrapper(test_inner)
+}
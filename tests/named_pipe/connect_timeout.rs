@@ -0,0 +1,29 @@
+use crate::{
+	os::windows::named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions},
+	tests::util::*,
+};
+use std::{io, path::Path, time::Duration};
+
+type Stream = DuplexPipeStream<pipe_mode::Bytes>;
+
+fn test_inner() -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+		PipeListenerOptions::new()
+			.path(Path::new(nm))
+			.create_duplex::<pipe_mode::Bytes>()
+	})?;
+	let _client =
+		Stream::connect_by_path_timeout(&*name, Duration::from_secs(1)).opname("connect")?;
+	// The only server instance is taken until the listener accepts the first client
+	let e = Stream::connect_by_path_timeout(&*name, Duration::from_millis(100))
+		.err()
+		.map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::TimedOut));
+	drop(listener);
+	Ok(())
+}
+
+#[test]
+fn connect_timeout() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,61 @@
+use crate::{
+	misc::RawOsErrorExt,
+	os::windows::{
+		named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions},
+		HANDLEExt,
+	},
+	tests::util::*,
+};
+use std::{
+	io::{self, prelude::*},
+	os::windows::prelude::*,
+	path::Path,
+};
+use windows_sys::Win32::{
+	Foundation::{ERROR_NO_TOKEN, HANDLE},
+	Security::TOKEN_QUERY,
+	System::Threading::{GetCurrentThread, OpenThreadToken},
+};
+
+type Stream = DuplexPipeStream<pipe_mode::Bytes>;
+
+/// Returns whether the calling thread has a token of its own, which is only the case while it's
+/// impersonating someone.
+fn thread_has_token() -> io::Result<bool> {
+	let mut token: HANDLE = 0;
+	let success = unsafe { OpenThreadToken(GetCurrentThread(), TOKEN_QUERY, 1, &mut token) } != 0;
+	if success {
+		drop(unsafe { OwnedHandle::from_raw_handle(token.to_std()) });
+		return Ok(true);
+	}
+	let e = io::Error::last_os_error();
+	if e.raw_os_error().eeq(ERROR_NO_TOKEN) {
+		return Ok(false);
+	}
+	Err(e)
+}
+
+fn test_inner() -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+		PipeListenerOptions::new()
+			.path(Path::new(nm))
+			.create_duplex::<pipe_mode::Bytes>()
+	})?;
+	let mut client = Stream::connect_by_path(&*name).opname("connect")?;
+	let mut server = listener.accept().opname("accept")?;
+	// Clients can only be impersonated once something has been read from them
+	client.write_all(b"x").opname("send")?;
+	server.read_exact(&mut [0]).opname("receive")?;
+
+	ensure_eq!(thread_has_token().opname("token check")?, false);
+	let guard = server.impersonate_client().opname("impersonate")?;
+	ensure_eq!(thread_has_token().opname("token check")?, true);
+	drop(guard);
+	ensure_eq!(thread_has_token().opname("token check")?, false);
+	Ok(())
+}
+
+#[test]
+fn impersonation() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,35 @@
+use crate::{
+	os::windows::named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions, PipeMode},
+	tests::util::*,
+};
+use std::{io::prelude::*, num::NonZeroU8, path::Path};
+
+type Stream = DuplexPipeStream<pipe_mode::Bytes>;
+
+fn test_inner() -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+		PipeListenerOptions::new()
+			.path(Path::new(nm))
+			.instance_limit(NonZeroU8::new(4))
+			.create_duplex::<pipe_mode::Bytes>()
+	})?;
+	let client = Stream::connect_by_path(&*name).opname("connect")?;
+	let mut server = listener.accept().opname("accept")?;
+
+	let info = client.pipe_info().opname("pipe_info")?;
+	ensure_eq!(info.mode, PipeMode::Bytes);
+	ensure_eq!(info.instance_limit, NonZeroU8::new(4));
+	let state = server.handle_state().opname("handle_state")?;
+	ensure_eq!(state.read_mode, PipeMode::Bytes);
+	ensure_eq!(state.nonblocking, false);
+
+	ensure_eq!(client.bytes_available().opname("bytes_available")?, 0);
+	server.write_all(b"ping").opname("send")?;
+	ensure_eq!(client.bytes_available().opname("bytes_available")?, 4);
+	Ok(())
+}
+
+#[test]
+fn info() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,38 @@
+use crate::{
+	os::windows::named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions},
+	tests::util::*,
+};
+use std::{io, num::NonZeroU8, path::Path, time::Duration};
+
+type Stream = DuplexPipeStream<pipe_mode::Bytes>;
+
+fn test_inner() -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+		PipeListenerOptions::new()
+			.path(Path::new(nm))
+			.instance_limit(NonZeroU8::new(2))
+			.create_duplex::<pipe_mode::Bytes>()
+	})?;
+	ensure_eq!(listener.instance_count().opname("instance_count")?, 1);
+
+	let _first = Stream::connect_by_path(&*name).opname("first connect")?;
+	let _first_server = listener.accept().opname("first accept")?;
+	ensure_eq!(listener.instance_count().opname("instance_count")?, 2);
+	// Takes the instance which the listener created for the next client
+	let _second = Stream::connect_by_path(&*name).opname("second connect")?;
+	ensure_eq!(listener.instance_count().opname("instance_count")?, 2);
+
+	// With every instance taken and none left to create, further clients wait until they time out
+	let e = Stream::connect_by_path_timeout(&*name, Duration::from_millis(100))
+		.err()
+		.map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::TimedOut));
+	// Accepting the second client fails, since it entails creating an instance for the next one
+	ensure_eq!(listener.accept().is_err(), true);
+	Ok(())
+}
+
+#[test]
+fn instance_limit() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,42 @@
+use crate::{
+	os::windows::named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions},
+	tests::util::*,
+};
+use recvmsg::{MsgBuf, RecvMsg};
+use std::{io::IoSlice, path::Path};
+
+type Stream = DuplexPipeStream<pipe_mode::Messages>;
+
+fn test_inner() -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+		PipeListenerOptions::new()
+			.path(Path::new(nm))
+			.create_duplex::<pipe_mode::Messages>()
+	})?;
+	let client = Stream::connect_by_path(&*name).opname("connect")?;
+	let mut server = listener.accept().opname("accept")?;
+
+	// The second send reuses the buffer which the first one concatenated into
+	let first = [
+		IoSlice::new(b"Hello"),
+		IoSlice::new(b""),
+		IoSlice::new(b", "),
+		IoSlice::new(b"world!"),
+	];
+	let second = [IoSlice::new(b"ab"), IoSlice::new(b"cd")];
+	ensure_eq!(client.send_vectored(&first).opname("first send")?, 13);
+	ensure_eq!(client.send_vectored(&second).opname("second send")?, 4);
+
+	let mut buf = MsgBuf::from(Vec::with_capacity(64));
+	server.recv_msg(&mut buf, None).opname("first receive")?;
+	ensure_eq!(buf.filled_part(), b"Hello, world!");
+	let mut buf = MsgBuf::from(Vec::with_capacity(64));
+	server.recv_msg(&mut buf, None).opname("second receive")?;
+	ensure_eq!(buf.filled_part(), b"abcd");
+	Ok(())
+}
+
+#[test]
+fn send_vectored() -> TestResult {
+	test_wrapper(test_inner)
+}
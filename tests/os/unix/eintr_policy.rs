@@ -0,0 +1,81 @@
+use crate::{
+	os::unix::{eintr_policy, fifo_file::Fifo, set_eintr_policy, EintrPolicy},
+	tests::util::{is_child, run_in_child, test_wrapper, TestResult, WrapErrExt},
+};
+use color_eyre::eyre::bail;
+use std::{env, io, process, sync::mpsc, thread, time::Duration};
+
+extern "C" fn ignore_signal(_: libc::c_int) {}
+
+/// Installs a handler for `SIGUSR2` without `SA_RESTART`, so that blocking calls interrupted by it
+/// fail with `EINTR`.
+#[allow(clippy::as_conversions)]
+fn install_handler() -> io::Result<()> {
+	unsafe {
+		let mut act = std::mem::zeroed::<libc::sigaction>();
+		act.sa_sigaction = ignore_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+		act.sa_flags = 0;
+		if libc::sigaction(libc::SIGUSR2, &act, std::ptr::null_mut()) == -1 {
+			return Err(io::Error::last_os_error());
+		}
+	}
+	Ok(())
+}
+
+/// Runs in a child process, since the policy is process-wide and would otherwise affect the other
+/// tests which run in parallel.
+fn child_inner() -> TestResult {
+	ensure_eq!(eintr_policy(), EintrPolicy::RetryForever);
+	set_eintr_policy(EintrPolicy::Retry(3));
+	ensure_eq!(eintr_policy(), EintrPolicy::Retry(3));
+	set_eintr_policy(EintrPolicy::Retry(0));
+	ensure_eq!(eintr_policy(), EintrPolicy::Surface);
+	set_eintr_policy(EintrPolicy::Retry(u32::MAX));
+	ensure_eq!(eintr_policy(), EintrPolicy::RetryForever);
+
+	install_handler().opname("sigaction")?;
+	let path = env::temp_dir().join(format!(
+		"interprocess-test-eintr-{:08x}.fifo",
+		process::id()
+	));
+	let _ = std::fs::remove_file(&path);
+	let fifo = Fifo::create(path, 0o600).opname("create")?;
+
+	set_eintr_policy(EintrPolicy::Surface);
+	let (tid_tx, tid_rx) = mpsc::channel();
+	let (done_tx, done_rx) = mpsc::channel();
+	let thread_fifo = fifo.clone();
+	let jh = thread::spawn(move || {
+		let _ = tid_tx.send(unsafe { libc::pthread_self() });
+		// Blocks until a sender appears, which never happens
+		let rslt = thread_fifo.open_recver().map(drop);
+		let _ = done_tx.send(());
+		rslt
+	});
+	let tid = tid_rx.recv().opname("receive thread ID")?;
+	// The signal may arrive before the thread starts blocking, so keep sending it
+	while done_rx.recv_timeout(Duration::from_millis(20)).is_err() {
+		unsafe { libc::pthread_kill(tid, libc::SIGUSR2) };
+	}
+	let rslt = jh.join();
+	set_eintr_policy(EintrPolicy::RetryForever);
+	let _ = fifo.remove();
+	match rslt {
+		Ok(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {}
+		Ok(rslt) => bail!("unexpected result of interrupted open: {rslt:?}"),
+		Err(..) => bail!("opening thread panicked"),
+	}
+	Ok(())
+}
+
+#[test]
+fn eintr_policy_surface() -> TestResult {
+	test_wrapper(|| run_in_child(module_path!(), "eintr_policy_surface_child"))
+}
+#[test]
+fn eintr_policy_surface_child() -> TestResult {
+	if !is_child("eintr_policy_surface_child") {
+		return Ok(());
+	}
+	test_wrapper(child_inner)
+}
@@ -0,0 +1,105 @@
+use crate::{
+	os::unix::fifo_file::Fifo,
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::{
+	env, io,
+	io::{prelude::*, BufReader},
+	process, thread,
+	time::Duration,
+};
+
+fn fifo(id: &str) -> Fifo {
+	Fifo::new(env::temp_dir().join(format!("interprocess-test-{id}-{:08x}.fifo", process::id())))
+}
+
+fn test_inner() -> TestResult {
+	let fifo = fifo("fifo");
+	let _ = std::fs::remove_file(fifo.path());
+	let fifo = Fifo::create(fifo.path(), 0o600).opname("create")?;
+	let rslt = (|| {
+		let e = fifo.open_sender_nonblocking().err();
+		ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::WouldBlock));
+		let e = fifo.open_sender_timeout(Duration::from_millis(20)).err();
+		ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::TimedOut));
+
+		let thread_fifo = fifo.clone();
+		let jh = thread::spawn(move || {
+			let mut tx = thread_fifo.open_sender()?;
+			tx.write_all(b"Hello from sender!\n")
+		});
+		let mut rx = BufReader::new(fifo.open_recver().opname("open receiver")?);
+		let mut buf = String::new();
+		rx.read_line(&mut buf).opname("receive")?;
+		ensure_eq!(buf, "Hello from sender!\n");
+		jh.join().unwrap().opname("send")?;
+
+		let rx = fifo
+			.open_recver_nonblocking()
+			.opname("open nonblocking receiver")?;
+		let mut tx = fifo
+			.open_sender_timeout(Duration::from_secs(1))
+			.opname("open sender with timeout")?;
+		tx.write_all(b"ping").opname("send")?;
+		let mut buf = [0; 4];
+		(&mut &rx).read_exact(&mut buf).opname("receive")?;
+		ensure_eq!(&buf, b"ping");
+		Ok(())
+	})();
+	fifo.remove().opname("remove")?;
+	rslt
+}
+
+#[test]
+fn fifo_file() -> TestResult {
+	test_wrapper(test_inner)
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+	use super::fifo;
+	use crate::{
+		os::unix::fifo_file::{
+			tokio::{Recver, Sender},
+			Fifo,
+		},
+		tests::util::{tokio::test_wrapper, TestResult, WrapErrExt},
+	};
+	use ::tokio::{
+		io::{AsyncReadExt, AsyncWriteExt},
+		try_join,
+	};
+
+	async fn test_inner() -> TestResult {
+		let fifo = fifo("tokio-fifo");
+		let _ = std::fs::remove_file(fifo.path());
+		let fifo = Fifo::create(fifo.path(), 0o600).opname("create")?;
+		let rslt = async {
+			let e = Sender::try_open(&fifo).err();
+			ensure_eq!(e.map(|e| e.kind()), Some(std::io::ErrorKind::WouldBlock));
+
+			// The sender has to wait for the receiver, which is opened afterwards
+			let open_tx = Sender::open(&fifo);
+			let open_rx = async {
+				::tokio::task::yield_now().await;
+				Recver::open(&fifo)
+			};
+			let (mut tx, mut rx) = try_join!(open_tx, open_rx).opname("open")?;
+
+			tx.write_all(b"Hello from sender!\n").await.opname("send")?;
+			drop(tx);
+			let mut buf = Vec::new();
+			rx.read_to_end(&mut buf).await.opname("receive")?;
+			ensure_eq!(buf, b"Hello from sender!\n");
+			Ok(())
+		}
+		.await;
+		fifo.remove().opname("remove")?;
+		rslt
+	}
+
+	#[test]
+	fn tokio_fifo_file() -> TestResult {
+		test_wrapper(test_inner())
+	}
+}
@@ -0,0 +1,37 @@
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, Stream},
+	os::unix::local_socket::ListenerOptionsExt,
+	tests::util::*,
+};
+use std::sync::Arc;
+
+fn test_inner(path: bool) -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
+			ListenerOptions::new()
+				.name(nm.borrow())
+				.backlog(1)
+				.create_sync()
+		})?;
+	let name = Arc::try_unwrap(name).unwrap();
+	let _client = Stream::connect(name.borrow()).opname("client connect")?;
+	let _server = listener.accept().opname("accept")?;
+
+	#[cfg(target_os = "freebsd")]
+	{
+		let crate::local_socket::Listener::UdSocket(l) = &listener;
+		ensure_eq!(l.backlog().opname("get backlog")?, 1);
+	}
+	Ok(())
+}
+
+#[test]
+fn local_socket_file_backlog() -> TestResult {
+	test_wrapper(|| test_inner(true))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn local_socket_namespaced_backlog() -> TestResult {
+	test_wrapper(|| test_inner(false))
+}
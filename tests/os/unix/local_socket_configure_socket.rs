@@ -0,0 +1,81 @@
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, Stream},
+	os::unix::local_socket::ListenerOptionsExt,
+	tests::util::*,
+};
+use std::{
+	io,
+	os::fd::{AsRawFd, BorrowedFd},
+	sync::{
+		atomic::{AtomicUsize, Ordering::SeqCst},
+		Arc,
+	},
+};
+
+/// Returns whether the socket is listening, via `SO_ACCEPTCONN`.
+fn is_listening(fd: BorrowedFd<'_>) -> io::Result<bool> {
+	let mut val: libc::c_int = 0;
+	let mut len = libc::socklen_t::try_from(std::mem::size_of_val(&val)).unwrap();
+	let rslt = unsafe {
+		libc::getsockopt(
+			fd.as_raw_fd(),
+			libc::SOL_SOCKET,
+			libc::SO_ACCEPTCONN,
+			std::ptr::addr_of_mut!(val).cast(),
+			&mut len,
+		)
+	};
+	if rslt == -1 {
+		return Err(io::Error::last_os_error());
+	}
+	Ok(val != 0)
+}
+
+fn test_inner(path: bool) -> TestResult {
+	let calls = Arc::new(AtomicUsize::new(0));
+	let hook_calls = Arc::clone(&calls);
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
+			let hook_calls = Arc::clone(&hook_calls);
+			ListenerOptions::new()
+				.name(nm.borrow())
+				.configure_socket(move |fd| {
+					hook_calls.fetch_add(1, SeqCst);
+					// The socket isn't listening yet
+					match is_listening(fd)? {
+						false => Ok(()),
+						true => Err(io::Error::other("hook called too late")),
+					}
+				})
+				.create_sync()
+		})?;
+	ensure_eq!(calls.load(SeqCst) >= 1, true);
+	let _client = Stream::connect(name.borrow()).opname("client connect")?;
+	let _server = listener.accept().opname("accept")?;
+	drop(listener);
+
+	// A failing hook aborts creation, leaving the name free
+	let e = ListenerOptions::new()
+		.name(name.borrow())
+		.configure_socket(|_| Err(io::ErrorKind::PermissionDenied.into()))
+		.create_sync()
+		.err()
+		.map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::PermissionDenied));
+	ListenerOptions::new()
+		.name(name.borrow())
+		.create_sync()
+		.opname("listen after failed hook")?;
+	Ok(())
+}
+
+#[test]
+fn local_socket_file_configure_socket() -> TestResult {
+	test_wrapper(|| test_inner(true))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn local_socket_namespaced_configure_socket() -> TestResult {
+	test_wrapper(|| test_inner(false))
+}
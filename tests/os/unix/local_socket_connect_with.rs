@@ -0,0 +1,68 @@
+use crate::{
+	local_socket::{
+		tokio::{prelude::*, Stream},
+		ListenerOptions,
+	},
+	tests::util::{tokio::test_wrapper, *},
+};
+use ::tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	try_join,
+};
+use std::{io, os::fd::AsRawFd};
+
+const BUFFER_SIZE: libc::c_int = 1 << 16;
+
+async fn test_inner(id: &str, path: bool) -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ListenerOptions::new().name(nm.borrow()).create_tokio()
+	})?;
+
+	// A failing hook aborts the connection attempt
+	let e = Stream::connect_with(name.borrow(), |_| {
+		Err(io::ErrorKind::PermissionDenied.into())
+	})
+	.await
+	.err()
+	.map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::PermissionDenied));
+
+	let connect = Stream::connect_with(name.borrow(), |fd| {
+		let size = BUFFER_SIZE;
+		let rslt = unsafe {
+			libc::setsockopt(
+				fd.as_raw_fd(),
+				libc::SOL_SOCKET,
+				libc::SO_RCVBUF,
+				std::ptr::addr_of!(size).cast(),
+				std::mem::size_of_val(&size).try_into().unwrap(),
+			)
+		};
+		if rslt == -1 {
+			return Err(io::Error::last_os_error());
+		}
+		Ok(())
+	});
+	let (mut client, mut server) = try_join!(connect, listener.accept()).opname("connect")?;
+	let Stream::UdSocket(inner) = &client;
+	let size = inner.recv_buffer_size().opname("get buffer size")?;
+	ensure_eq!(size >= BUFFER_SIZE.try_into()?, true);
+
+	client.write_all(b"ping").await.opname("send")?;
+	let mut buf = [0; 4];
+	server.read_exact(&mut buf).await.opname("receive")?;
+	ensure_eq!(&buf, b"ping");
+	server.write_all(b"pong").await.opname("reply")?;
+	client.read_exact(&mut buf).await.opname("receive reply")?;
+	ensure_eq!(&buf, b"pong");
+	Ok(())
+}
+
+#[test]
+fn local_socket_connect_with_file() -> TestResult {
+	test_wrapper(test_inner(make_id!(), true))
+}
+#[test]
+fn local_socket_connect_with_namespaced() -> TestResult {
+	test_wrapper(test_inner(make_id!(), false))
+}
@@ -0,0 +1,68 @@
+use crate::{
+	handle_transfer::HandleTransfer,
+	local_socket::Stream,
+	os::unix::local_socket::{copy_ancillary, Copied, CopyLimits},
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::{
+	fs::File,
+	io::{prelude::*, Seek, SeekFrom},
+	net::Shutdown,
+	thread,
+};
+
+fn test_inner() -> TestResult {
+	let (client, proxy_in) = Stream::pair().opname("pair")?;
+	let (proxy_out, server) = Stream::pair().opname("pair")?;
+	let proxy = thread::spawn(move || {
+		let rslt = copy_ancillary(&proxy_in, &proxy_out, CopyLimits::new().max_fds(4));
+		proxy_out.shutdown(Shutdown::Write)?;
+		rslt
+	});
+
+	let mut file = tempfile().opname("create file")?;
+	file.write_all(b"contents").opname("write file")?;
+	(&client).write_all(b"hello").opname("send")?;
+	client
+		.send_handle(file.try_clone()?.into())
+		.opname("send handle")?;
+	(&client).write_all(b" world").opname("send")?;
+	drop(client);
+
+	let mut buf = [0; 5];
+	(&server).read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"hello");
+	let mut received = File::from(server.recv_handle().opname("receive handle")?);
+	let mut rest = String::new();
+	(&server).read_to_string(&mut rest).opname("receive")?;
+	ensure_eq!(rest, " world");
+
+	let mut contents = String::new();
+	received.seek(SeekFrom::Start(0))?;
+	received.read_to_string(&mut contents).opname("read file")?;
+	ensure_eq!(contents, "contents");
+
+	let copied = proxy.join().unwrap().opname("copy")?;
+	ensure_eq!(copied, Copied { bytes: 12, fds: 1 });
+	Ok(())
+}
+
+fn tempfile() -> std::io::Result<File> {
+	let path = std::env::temp_dir().join(format!(
+		"interprocess-test-copy-ancillary-{}",
+		std::process::id()
+	));
+	let file = File::options()
+		.read(true)
+		.write(true)
+		.create(true)
+		.truncate(true)
+		.open(&path)?;
+	std::fs::remove_file(&path)?;
+	Ok(file)
+}
+
+#[test]
+fn local_socket_copy_ancillary() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,92 @@
+use crate::{
+	os::unix::uds_local_socket::{Credentials, Stream},
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use color_eyre::eyre::bail;
+use std::{
+	io::{self, prelude::*},
+	mem,
+	os::unix::prelude::*,
+};
+
+/// Sends a byte with `N` duplicates of `fd` in a single `SCM_RIGHTS` control message.
+#[allow(clippy::as_conversions)]
+fn send_fds<const N: usize>(sock: BorrowedFd<'_>, fd: BorrowedFd<'_>) -> io::Result<()> {
+	let mut byte = [0_u8];
+	let mut iov = libc::iovec {
+		iov_base: byte.as_mut_ptr().cast(),
+		iov_len: byte.len(),
+	};
+	let data_len = mem::size_of::<[libc::c_int; N]>() as u32;
+	let mut cmsg_buf = vec![unsafe { mem::zeroed::<libc::cmsghdr>() }; N.saturating_add(4)];
+	unsafe {
+		let mut hdr = mem::zeroed::<libc::msghdr>();
+		hdr.msg_iov = &mut iov;
+		hdr.msg_iovlen = 1;
+		hdr.msg_control = cmsg_buf.as_mut_ptr().cast();
+		hdr.msg_controllen = libc::CMSG_SPACE(data_len) as _;
+		let cmsg = libc::CMSG_FIRSTHDR(&hdr);
+		(*cmsg).cmsg_level = libc::SOL_SOCKET;
+		(*cmsg).cmsg_type = libc::SCM_RIGHTS;
+		(*cmsg).cmsg_len = libc::CMSG_LEN(data_len) as _;
+		std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast(), [fd.as_raw_fd(); N]);
+		if libc::sendmsg(sock.as_raw_fd(), &hdr, 0) == -1 {
+			return Err(io::Error::last_os_error());
+		}
+	}
+	Ok(())
+}
+
+fn test_inner() -> TestResult {
+	let (a, b) = Stream::pair().opname("pair")?;
+	b.set_passcred(true).opname("set_passcred")?;
+	let current = Credentials::current();
+	let mut buf = [0; 16];
+
+	let sent = a
+		.write_with_credentials(b"explicit", current)
+		.opname("write_with_credentials")?;
+	ensure_eq!(sent, 8);
+	let (received, creds) = b
+		.read_with_credentials(&mut buf)
+		.opname("read_with_credentials")?;
+	ensure_eq!(buf.get(..received), Some(&b"explicit"[..]));
+	ensure_eq!(creds, Some(current));
+
+	// Data sent without credentials gets the real IDs of the sender attached
+	(&a).write_all(b"implicit").opname("write")?;
+	let (received, creds) = b
+		.read_with_credentials(&mut buf)
+		.opname("read_with_credentials")?;
+	ensure_eq!(buf.get(..received), Some(&b"implicit"[..]));
+	let real = unsafe {
+		Credentials {
+			uid: libc::getuid(),
+			gid: libc::getgid(),
+			..current
+		}
+	};
+	ensure_eq!(creds, Some(real));
+
+	// Without the relevant capabilities, only a process's own credentials can be sent
+	if current.uid != 0 {
+		let forged = Credentials { pid: 1, ..current };
+		match a.write_with_credentials(b"forged", forged) {
+			Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {}
+			rslt => bail!("unexpected result of sending forged credentials: {rslt:?}"),
+		}
+	}
+
+	// Enough descriptors to overflow the control buffer truncate the ancillary data
+	send_fds::<16>(a.as_fd(), a.as_fd()).opname("sendmsg")?;
+	match b.read_with_credentials(&mut buf) {
+		Err(e) if e.kind() == io::ErrorKind::InvalidData => {}
+		rslt => bail!("unexpected result of receiving truncated ancillary data: {rslt:?}"),
+	}
+	Ok(())
+}
+
+#[test]
+fn local_socket_credentials() -> TestResult {
+	test_wrapper(test_inner)
+}
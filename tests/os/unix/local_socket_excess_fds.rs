@@ -0,0 +1,73 @@
+//! Tests that file descriptors received in excess of the one expected by `recv_handle()` are
+//! closed rather than leaked.
+
+use crate::{
+	handle_transfer::HandleTransfer,
+	os::unix::uds_local_socket::Stream,
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::{
+	io::{self, prelude::*},
+	mem,
+	os::unix::{net::UnixStream, prelude::*},
+	time::Duration,
+};
+
+/// Sends two descriptors in a single `SCM_RIGHTS` control message, which `send_handle()` never
+/// does.
+#[allow(clippy::as_conversions)]
+fn send_two_fds(sock: BorrowedFd<'_>, fds: [BorrowedFd<'_>; 2]) -> io::Result<()> {
+	#[repr(C)]
+	union CmsgBuf {
+		_align: libc::cmsghdr,
+		buf: [u8; 64],
+	}
+	let mut byte = [0_u8];
+	let mut iov = libc::iovec {
+		iov_base: byte.as_mut_ptr().cast(),
+		iov_len: byte.len(),
+	};
+	let mut cmsg_buf = CmsgBuf { buf: [0; 64] };
+	let data_len = mem::size_of::<[libc::c_int; 2]>() as u32;
+	unsafe {
+		let mut hdr = mem::zeroed::<libc::msghdr>();
+		hdr.msg_iov = &mut iov;
+		hdr.msg_iovlen = 1;
+		hdr.msg_control = cmsg_buf.buf.as_mut_ptr().cast();
+		hdr.msg_controllen = libc::CMSG_SPACE(data_len) as _;
+		let cmsg = libc::CMSG_FIRSTHDR(&hdr);
+		(*cmsg).cmsg_level = libc::SOL_SOCKET;
+		(*cmsg).cmsg_type = libc::SCM_RIGHTS;
+		(*cmsg).cmsg_len = libc::CMSG_LEN(data_len) as _;
+		let raw = [fds[0].as_raw_fd(), fds[1].as_raw_fd()];
+		std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast(), raw);
+		if libc::sendmsg(sock.as_raw_fd(), &hdr, 0) == -1 {
+			return Err(io::Error::last_os_error());
+		}
+	}
+	Ok(())
+}
+
+fn test_inner() -> TestResult {
+	let (a, b) = Stream::pair().opname("pair")?;
+	let (payload, mut witness) = UnixStream::pair().opname("payload pair")?;
+	witness
+		.set_read_timeout(Some(Duration::from_secs(5)))
+		.opname("set_read_timeout")?;
+
+	send_two_fds(a.as_fd(), [payload.as_fd(), payload.as_fd()]).opname("sendmsg")?;
+	drop(payload);
+	let e = b.recv_handle().err();
+	ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::InvalidData));
+
+	// If any of the received copies were leaked, this would time out instead of hitting EOF.
+	let mut buf = [0; 1];
+	let read = witness.read(&mut buf).opname("read from witness")?;
+	ensure_eq!(read, 0);
+	Ok(())
+}
+
+#[test]
+fn local_socket_excess_fds() -> TestResult {
+	test_wrapper(test_inner)
+}
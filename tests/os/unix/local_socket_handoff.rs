@@ -0,0 +1,107 @@
+use crate::{
+	handle_transfer::HandleTransfer,
+	local_socket::{prelude::*, ListenerOptions, NameInner, Stream},
+	os::unix::local_socket::ListenerToken,
+	tests::util::*,
+};
+use color_eyre::eyre::bail;
+use std::{
+	io::{self, prelude::*},
+	os::{
+		fd::AsFd,
+		unix::{ffi::OsStrExt, net::UnixStream},
+	},
+	path::Path,
+};
+
+fn test_inner() -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+	let NameInner::UdSocketPath(path) = &name.0 else {
+		bail!("path name expected");
+	};
+	let path = Path::new(&**path).to_owned();
+
+	// Stands in for the connection between the old and the new process
+	let (old, new) = UnixStream::pair().opname("socket pair")?;
+	let (old, new) = (Stream::from(old), Stream::from(new));
+
+	let mut token = ListenerToken::from(listener);
+	token.send(&old).opname("send token")?;
+	let received = ListenerToken::recv(&new)
+		.opname("receive token")?
+		.into_listener();
+
+	// Connections made while both copies exist can be accepted by either of them
+	let mut client = Stream::connect(name.borrow()).opname("connect")?;
+	drop(token);
+	ensure_eq!(path.exists(), true);
+	let mut server = received.accept().opname("accept")?;
+	client.write_all(b"ping").opname("send")?;
+	let mut buf = [0; 4];
+	server.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"ping");
+
+	// The receiving side has taken over name reclamation
+	drop(received);
+	ensure_eq!(path.exists(), false);
+	Ok(())
+}
+
+fn garbage_inner() -> TestResult {
+	let (old, new) = UnixStream::pair().opname("socket pair")?;
+	let (mut old, new) = (Stream::from(old), Stream::from(new));
+	old.write_all(&[0xff, 0]).opname("send")?;
+	let e = ListenerToken::recv(&new).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::InvalidData));
+	Ok(())
+}
+
+/// A token whose socket file path doesn't match the socket must not make the receiver delete
+/// whatever file the path refers to.
+fn forged_path_inner() -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+	let NameInner::UdSocketPath(path) = &name.0 else {
+		bail!("path name expected");
+	};
+	let victim = Path::new(&**path).with_extension("victim");
+	std::fs::write(&victim, b"user data").opname("create victim file")?;
+
+	let (old, new) = UnixStream::pair().opname("socket pair")?;
+	let (mut old, new) = (Stream::from(old), Stream::from(new));
+	let victim_bytes = victim.as_os_str().as_bytes();
+	let mut header = vec![1, 1 << 1];
+	header.extend_from_slice(&u16::try_from(victim_bytes.len())?.to_le_bytes());
+	header.extend_from_slice(victim_bytes);
+	old.write_all(&header).opname("send header")?;
+	let crate::local_socket::Listener::UdSocket(listener) = &listener;
+	let fd = listener.as_fd().try_clone_to_owned().opname("duplicate")?;
+	old.send_handle(fd).opname("send listener")?;
+
+	let e = ListenerToken::recv(&new).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::InvalidData));
+	ensure_eq!(
+		std::fs::read(&victim).opname("read victim file")?,
+		b"user data"
+	);
+	std::fs::remove_file(&victim).opname("remove victim file")?;
+	Ok(())
+}
+
+#[test]
+fn local_socket_handoff() -> TestResult {
+	test_wrapper(test_inner)
+}
+#[test]
+fn local_socket_handoff_garbage() -> TestResult {
+	test_wrapper(garbage_inner)
+}
+#[test]
+fn local_socket_handoff_forged_path() -> TestResult {
+	test_wrapper(forged_path_inner)
+}
@@ -0,0 +1,46 @@
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, Stream},
+	os::unix::local_socket::ListenerSet,
+	tests::util::*,
+};
+use std::{
+	io::{Read, Write},
+	thread,
+};
+
+fn test_inner(path: bool) -> TestResult {
+	let mut namegen = namegen_local_socket(make_id!(), path);
+	let mut set = ListenerSet::new();
+	let mut names = Vec::new();
+	for _ in 0..2 {
+		let (name, listener) = listen_and_pick_name(&mut namegen, |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+		names.push(name);
+		set.push(listener);
+	}
+
+	let second = names[1].clone();
+	let client = thread::spawn(move || {
+		let mut conn = Stream::connect(second.borrow())?;
+		conn.write_all(b"ping!\n")
+	});
+	let (idx, mut conn) = set.accept().opname("accept")?;
+	ensure_eq!(idx, 1);
+	let mut buf = [0; 6];
+	conn.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"ping!\n");
+	client.join().unwrap().opname("client")?;
+	Ok(())
+}
+
+#[test]
+fn local_socket_file_listener_set() -> TestResult {
+	test_wrapper(|| test_inner(true))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn local_socket_namespaced_listener_set() -> TestResult {
+	test_wrapper(|| test_inner(false))
+}
@@ -0,0 +1,57 @@
+use crate::{
+	local_socket::{Listener, ListenerOptions, Name, NameInner},
+	os::unix::local_socket::OwnedSocketFile,
+	tests::util::*,
+};
+use color_eyre::eyre::ensure;
+use std::{fs, io, os::unix::net::UnixListener, path::Path};
+
+fn test_inner() -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+	let Name(NameInner::UdSocketPath(path)) = &*name else {
+		unreachable!()
+	};
+	let path = Path::new(&**path);
+
+	// Detaching the file makes its lifetime independent from that of the listener
+	let Listener::UdSocket(mut listener) = listener;
+	let file = listener
+		.take_socket_file()
+		.ok_or_else(|| io::Error::other("listener has no socket file"))
+		.opname("take socket file")?;
+	ensure_eq!(file.path(), path);
+	drop(listener);
+	ensure!(path.exists(), "socket file removed along with the listener");
+	drop(file);
+	ensure!(!path.exists(), "socket file not removed by the guard");
+
+	// A socket file which has been replaced in the meantime is left alone. The old one is moved
+	// rather than deleted, so that its inode number can't be reused by the new one.
+	let moved = path.with_extension("moved");
+	let listener = UnixListener::bind(path).opname("bind first socket")?;
+	let file = OwnedSocketFile::new(path).opname("guard first socket")?;
+	drop(listener);
+	fs::rename(path, &moved).opname("move first socket")?;
+	let listener = UnixListener::bind(path).opname("bind second socket")?;
+	drop(file);
+	ensure!(path.exists(), "replacement socket file removed");
+	drop(listener);
+	let _ = OwnedSocketFile::new(path).opname("guard second socket")?;
+	ensure!(!path.exists(), "second socket file not removed");
+	fs::remove_file(&moved).opname("remove first socket")?;
+
+	// Files which aren't sockets are refused
+	fs::write(path, b"not a socket").opname("create regular file")?;
+	let err = OwnedSocketFile::new(path).err().map(|e| e.kind());
+	ensure_eq!(err, Some(io::ErrorKind::InvalidInput));
+	fs::remove_file(path).opname("remove regular file")?;
+	Ok(())
+}
+
+#[test]
+fn local_socket_owned_socket_file() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,66 @@
+//! Tests that peeking on Unix domain socket streams leaves the data in the receive queue.
+
+use crate::{
+	local_socket::traits::Stream as _,
+	os::unix::uds_local_socket::Stream,
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::io::prelude::*;
+
+fn test_inner() -> TestResult {
+	let (a, b) = Stream::pair().opname("pair")?;
+	(&a).write_all(b"HEADbody").opname("send")?;
+
+	// Repeated peeks see the same data, regardless of how much of it they ask for
+	let mut sig = [0; 4];
+	ensure_eq!(b.peek(&mut sig).opname("peek")?, 4);
+	ensure_eq!(&sig, b"HEAD");
+	let mut all = [0; 16];
+	let peeked = b.peek(&mut all).opname("second peek")?;
+	ensure_eq!(all.get(..peeked), Some(&b"HEADbody"[..]));
+
+	let mut received = [0; 8];
+	(&b).read_exact(&mut received).opname("receive")?;
+	ensure_eq!(&received, b"HEADbody");
+
+	// Nothing is left after the receive
+	b.set_nonblocking(true).opname("set_nonblocking")?;
+	let e = b.peek(&mut sig).err().map(|e| e.kind());
+	ensure_eq!(e, Some(std::io::ErrorKind::WouldBlock));
+	Ok(())
+}
+
+#[cfg(feature = "tokio")]
+async fn test_tokio() -> TestResult {
+	use crate::os::unix::uds_local_socket::tokio::Stream;
+	use ::tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use std::os::unix::net::UnixStream;
+
+	let (a, b) = UnixStream::pair().opname("pair")?;
+	let a = Stream::try_from(a).opname("convert")?;
+	let b = Stream::try_from(b).opname("convert")?;
+
+	// Peeking waits for data to arrive
+	let mut sig = [0; 4];
+	let mut writer = &a;
+	let (peeked, sent) = ::tokio::join!(b.peek(&mut sig), writer.write_all(b"HEADbody"));
+	sent.opname("send")?;
+	ensure_eq!(peeked.opname("peek")?, 4);
+	ensure_eq!(&sig, b"HEAD");
+	ensure_eq!(b.peek(&mut sig).await.opname("second peek")?, 4);
+
+	let mut received = [0; 8];
+	(&b).read_exact(&mut received).await.opname("receive")?;
+	ensure_eq!(&received, b"HEADbody");
+	Ok(())
+}
+
+#[test]
+fn local_socket_peek() -> TestResult {
+	test_wrapper(test_inner)
+}
+#[cfg(feature = "tokio")]
+#[test]
+fn local_socket_peek_tokio() -> TestResult {
+	crate::tests::util::tokio::test_wrapper(test_tokio())
+}
@@ -0,0 +1,90 @@
+use crate::{
+	handle_transfer::HandleTransfer,
+	os::unix::uds_local_socket::Stream,
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::{
+	io::prelude::*,
+	os::unix::{net::UnixStream, prelude::*},
+};
+
+fn test_inner() -> TestResult {
+	let (a, b) = Stream::pair().opname("pair")?;
+	let (payload, mut payload_peer) = UnixStream::pair().opname("payload pair")?;
+	a.send_handle(OwnedFd::from(payload))
+		.opname("send_handle")?;
+	(&a).write_all(b"after").opname("write")?;
+
+	// Peeking repeatedly yields fresh duplicates and leaves everything in place
+	let mut buf = [0; 16];
+	for _ in 0..2 {
+		let (peeked, anc) = b.peek_ancillary(&mut buf, 1).opname("peek_ancillary")?;
+		ensure_eq!(peeked, 1);
+		ensure_eq!(anc.fds.len(), 1);
+		ensure_eq!(anc.ancillary_truncated, false);
+		for fd in &anc.fds {
+			let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD) };
+			ensure_eq!(flags & libc::FD_CLOEXEC, libc::FD_CLOEXEC);
+		}
+		let mut dup = anc.fds.into_iter().next().map(UnixStream::from);
+		if let Some(dup) = &mut dup {
+			dup.write_all(b"y").opname("write through duplicate")?;
+		}
+	}
+	let mut echoed = [0; 2];
+	payload_peer
+		.read_exact(&mut echoed)
+		.opname("payload read")?;
+	ensure_eq!(&echoed, b"yy");
+
+	// Too little room for the descriptor is reported rather than treated as an error
+	let (peeked, anc) = b.peek_ancillary(&mut buf, 0).opname("peek_ancillary")?;
+	ensure_eq!(peeked, 1);
+	ensure_eq!(anc.fds.len(), 0);
+	ensure_eq!(anc.ancillary_truncated, true);
+
+	let received = b.recv_handle().opname("recv_handle")?;
+	let mut received = UnixStream::from(received);
+	received.write_all(b"z").opname("write through received")?;
+	payload_peer
+		.read_exact(&mut echoed[..1])
+		.opname("payload read")?;
+	ensure_eq!(&echoed[..1], b"z");
+
+	// Data without ancillary data peeks with none
+	let (peeked, anc) = b.peek_ancillary(&mut buf, 1).opname("peek_ancillary")?;
+	ensure_eq!(buf.get(..peeked), Some(&b"after"[..]));
+	ensure_eq!(anc.fds.len(), 0);
+	Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_credentials() -> TestResult {
+	use crate::os::unix::uds_local_socket::Credentials;
+	let (a, b) = Stream::pair().opname("pair")?;
+	b.set_passcred(true).opname("set_passcred")?;
+	let current = Credentials::current();
+	a.write_with_credentials(b"creds", current)
+		.opname("write_with_credentials")?;
+
+	let mut buf = [0; 16];
+	let (peeked, anc) = b.peek_ancillary(&mut buf, 0).opname("peek_ancillary")?;
+	ensure_eq!(buf.get(..peeked), Some(&b"creds"[..]));
+	ensure_eq!(anc.credentials, Some(current));
+	let (received, creds) = b
+		.read_with_credentials(&mut buf)
+		.opname("read_with_credentials")?;
+	ensure_eq!(received, 5);
+	ensure_eq!(creds, Some(current));
+	Ok(())
+}
+
+#[test]
+fn local_socket_peek_ancillary() -> TestResult {
+	test_wrapper(test_inner)
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn local_socket_peek_credentials() -> TestResult {
+	test_wrapper(test_credentials)
+}
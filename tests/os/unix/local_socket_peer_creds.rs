@@ -0,0 +1,31 @@
+use crate::{
+	os::unix::uds_local_socket::Stream,
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+
+fn test_inner() -> TestResult {
+	let (a, _b) = Stream::pair().opname("pair")?;
+	let creds = a.peer_credentials().opname("peer_credentials")?;
+	ensure_eq!(creds.uid, unsafe { libc::geteuid() });
+	ensure_eq!(creds.gid, unsafe { libc::getegid() });
+	if cfg!(any(target_os = "linux", target_os = "android")) {
+		ensure_eq!(creds.pid, Some(i32::try_from(std::process::id()).unwrap()));
+	}
+
+	#[cfg(target_os = "linux")]
+	{
+		let mut groups = a.peer_groups().opname("peer_groups")?;
+		let mut expected = vec![0; 256];
+		let n = unsafe { libc::getgroups(256, expected.as_mut_ptr()) };
+		expected.truncate(usize::try_from(n).unwrap());
+		groups.sort_unstable();
+		expected.sort_unstable();
+		ensure_eq!(groups, expected);
+	}
+	Ok(())
+}
+
+#[test]
+fn local_socket_peer_creds() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,52 @@
+use crate::{
+	os::unix::uds_local_socket::{ParsedSecurityContext, SecurityContext, Stream},
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+
+fn ctx(label: &str) -> SecurityContext {
+	SecurityContext::from(label.as_bytes().to_vec())
+}
+
+fn test_inner() -> TestResult {
+	ensure_eq!(
+		ctx("system_u:system_r:sshd_t:s0-s0:c0.c1023").parse(),
+		ParsedSecurityContext::SeLinux {
+			user: "system_u",
+			role: "system_r",
+			r#type: "sshd_t",
+			level: Some("s0-s0:c0.c1023"),
+		}
+	);
+	ensure_eq!(
+		ctx("/usr/sbin/cupsd (enforce)").parse(),
+		ParsedSecurityContext::AppArmor {
+			profile: "/usr/sbin/cupsd",
+			mode: Some("enforce"),
+		}
+	);
+	ensure_eq!(
+		ctx("unconfined").parse(),
+		ParsedSecurityContext::AppArmor {
+			profile: "unconfined",
+			mode: None,
+		}
+	);
+	ensure_eq!(ctx("_").parse(), ParsedSecurityContext::Other);
+	ensure_eq!(
+		SecurityContext::from(b"label\0".to_vec()).as_bytes(),
+		b"label"
+	);
+
+	let (a, _b) = Stream::pair().opname("pair")?;
+	match a.peer_security_context() {
+		Ok(ctx) => ensure_eq!(ctx.as_bytes().is_empty(), false),
+		Err(e) if e.raw_os_error() == Some(libc::ENOPROTOOPT) => {}
+		Err(e) => return Err(e).opname("peer_security_context"),
+	}
+	Ok(())
+}
+
+#[test]
+fn local_socket_peer_security() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,103 @@
+use crate::{
+	local_socket::{ListenerOptions, Liveness, Name, NameInner},
+	os::unix::local_socket::ListenerOptionsExt,
+	tests::util::*,
+};
+use color_eyre::eyre::ensure;
+use std::{
+	fs, io, mem,
+	os::{
+		fd::{FromRawFd, OwnedFd},
+		unix::ffi::OsStrExt,
+	},
+	path::Path,
+};
+
+/// Makes a nonblocking connection attempt with the raw system call, since the ones in the crate
+/// and the standard library block when the listen queue is full.
+fn connect_nonblocking(path: &Path) -> io::Result<OwnedFd> {
+	let fd = unsafe { libc::socket(libc::AF_UNIX, libc::SOCK_STREAM, 0) };
+	if fd == -1 {
+		return Err(io::Error::last_os_error());
+	}
+	let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+	let raw = std::os::fd::AsRawFd::as_raw_fd(&fd);
+	if unsafe { libc::fcntl(raw, libc::F_SETFL, libc::O_NONBLOCK) } == -1 {
+		return Err(io::Error::last_os_error());
+	}
+	let mut addr = unsafe { mem::zeroed::<libc::sockaddr_un>() };
+	addr.sun_family = libc::AF_UNIX.try_into().unwrap();
+	for (dst, src) in addr.sun_path.iter_mut().zip(path.as_os_str().as_bytes()) {
+		*dst = libc::c_char::from_ne_bytes([*src]);
+	}
+	let len = mem::size_of_val(&addr).try_into().unwrap();
+	if unsafe { libc::connect(raw, std::ptr::addr_of!(addr).cast(), len) } == -1 {
+		return Err(io::Error::last_os_error());
+	}
+	Ok(fd)
+}
+
+fn full_backlog() -> TestResult {
+	let (name, _listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+			ListenerOptions::new()
+				.name(nm.borrow())
+				.backlog(0)
+				.create_sync()
+		})?;
+	let Name(NameInner::UdSocketPath(path)) = &*name else {
+		unreachable!()
+	};
+	let mut pending = Vec::new();
+	loop {
+		match connect_nonblocking(Path::new(&**path)) {
+			Ok(fd) => pending.push(fd),
+			Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+			Err(e) => return Err(e).opname("fill listen queue"),
+		}
+		ensure!(pending.len() < 1024, "listen queue never filled up");
+	}
+	ensure_eq!(
+		name.probe().opname("probe with full queue")?,
+		Liveness::Listening
+	);
+	Ok(())
+}
+
+fn regular_file() -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+	drop(listener);
+	let Name(NameInner::UdSocketPath(path)) = &*name else {
+		unreachable!()
+	};
+	fs::write(&**path, b"not a socket").opname("create regular file")?;
+
+	let err = name.probe().err().map(|e| e.kind());
+	ensure_eq!(err, Some(io::ErrorKind::AlreadyExists));
+
+	let err = ListenerOptions::new()
+		.name(name.borrow())
+		.replace_dead_socket(true)
+		.create_sync()
+		.err()
+		.map(|e| e.kind());
+	ensure_eq!(err, Some(io::ErrorKind::AddrInUse));
+	ensure_eq!(
+		fs::read(&**path).opname("read regular file")?,
+		b"not a socket"
+	);
+	fs::remove_file(&**path).opname("remove regular file")?;
+	Ok(())
+}
+
+#[test]
+fn local_socket_probe_full_backlog() -> TestResult {
+	test_wrapper(full_backlog)
+}
+#[test]
+fn local_socket_probe_regular_file() -> TestResult {
+	test_wrapper(regular_file)
+}
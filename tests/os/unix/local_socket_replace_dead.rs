@@ -0,0 +1,50 @@
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, Name, NameInner, Stream},
+	os::unix::local_socket::ListenerOptionsExt,
+	tests::util::*,
+};
+use color_eyre::eyre::{bail, ensure};
+use std::{io, os::unix::net::UnixListener, sync::Arc};
+
+fn test_inner() -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+			let Name(NameInner::UdSocketPath(path)) = nm else {
+				unreachable!()
+			};
+			UnixListener::bind(path)
+		})?;
+	// The standard library does not unlink the socket file, leaving a dead socket behind.
+	drop(listener);
+
+	let name = Arc::try_unwrap(name).unwrap();
+	match ListenerOptions::new().name(name.borrow()).create_sync() {
+		Err(e) if e.kind() == io::ErrorKind::AddrInUse => {}
+		Err(e) => bail!("unexpected error without replacement: {e}"),
+		Ok(..) => bail!("bind over dead socket succeeded without replacement"),
+	}
+
+	let listener = ListenerOptions::new()
+		.name(name.borrow())
+		.replace_dead_socket(true)
+		.create_sync()
+		.opname("bind with replacement")?;
+	let _ = Stream::connect(name.borrow()).opname("client connect")?;
+
+	let err = ListenerOptions::new()
+		.name(name.borrow())
+		.replace_dead_socket(true)
+		.create_sync()
+		.err();
+	ensure!(
+		matches!(&err, Some(e) if e.kind() == io::ErrorKind::AddrInUse),
+		"live socket was replaced: {err:?}"
+	);
+	drop(listener);
+	Ok(())
+}
+
+#[test]
+fn local_socket_replace_dead() -> TestResult {
+	test_wrapper(test_inner)
+}
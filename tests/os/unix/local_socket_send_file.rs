@@ -0,0 +1,45 @@
+use crate::{os::unix::uds_local_socket::Stream, tests::util::*};
+use std::{
+	fs::{self, File},
+	io::Read,
+	os::unix::net::UnixStream,
+	thread,
+};
+
+const CONTENTS: &[u8] = b"The quick brown fox jumps over the lazy dog";
+const OFFSET: usize = 4;
+
+fn test_inner() -> TestResult {
+	let path = std::env::temp_dir().join(format!("interprocess-test-{}.txt", std::process::id()));
+	fs::write(&path, CONTENTS).opname("temporary file creation")?;
+	let file = File::open(&path).opname("temporary file open");
+	let _ = fs::remove_file(&path);
+	let file = file?;
+
+	let (sender, mut receiver) = UnixStream::pair().opname("socket pair creation")?;
+	let sender = Stream::from(sender);
+	let reader = thread::spawn(move || {
+		let mut buf = Vec::new();
+		receiver.read_to_end(&mut buf).map(|_| buf)
+	});
+
+	let mut offset = OFFSET;
+	while offset < CONTENTS.len() {
+		let len = CONTENTS.len() - offset;
+		let sent = sender
+			.send_file(&file, offset.try_into()?, len)
+			.opname("send_file")?;
+		ensure_eq!(sent == 0, false, "premature end of file");
+		offset += sent;
+	}
+	drop(sender);
+
+	let received = reader.join().unwrap().opname("receive")?;
+	ensure_eq!(received, &CONTENTS[OFFSET..]);
+	Ok(())
+}
+
+#[test]
+fn local_socket_send_file() -> TestResult {
+	test_wrapper(test_inner)
+}
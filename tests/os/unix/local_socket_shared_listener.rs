@@ -0,0 +1,72 @@
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, NameInner, Stream},
+	os::unix::local_socket::SharedListener,
+	tests::util::*,
+};
+use color_eyre::eyre::bail;
+use std::{
+	io::{self, prelude::*},
+	os::unix::net::UnixStream,
+	path::Path,
+	sync::Arc,
+	thread,
+};
+
+fn test_inner() -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+	let NameInner::UdSocketPath(path) = &name.0 else {
+		bail!("path name expected");
+	};
+	let path = Path::new(&**path).to_owned();
+	let old = Arc::new(SharedListener::new(listener).opname("share")?);
+
+	// Serves connections until told to drain
+	let acceptor = thread::spawn({
+		let old = Arc::clone(&old);
+		move || -> io::Result<usize> {
+			let mut served = 0_usize;
+			while let Some(mut conn) = old.accept()? {
+				conn.write_all(b"old")?;
+				served = served.saturating_add(1);
+			}
+			Ok(served)
+		}
+	});
+	let mut buf = [0; 3];
+	let mut client = Stream::connect(name.borrow()).opname("connect to old")?;
+	client.read_exact(&mut buf).opname("receive from old")?;
+	ensure_eq!(&buf, b"old");
+
+	let (ours, theirs) = UnixStream::pair().opname("socket pair")?;
+	let successor = thread::spawn(move || SharedListener::take_over(&Stream::from(theirs)));
+	old.hand_over(&Stream::from(ours)).opname("hand over")?;
+	let new = match successor.join() {
+		Ok(rslt) => rslt.opname("take over")?,
+		Err(..) => bail!("successor thread panicked"),
+	};
+	let served = match acceptor.join() {
+		Ok(rslt) => rslt.opname("old accept loop")?,
+		Err(..) => bail!("acceptor thread panicked"),
+	};
+	ensure_eq!(served, 1);
+	ensure_eq!(old.is_draining(), true);
+
+	let _client = Stream::connect(name.borrow()).opname("connect to new")?;
+	let accepted = new.accept().opname("accept by new")?;
+	ensure_eq!(accepted.is_some(), true);
+
+	// The old process leaves the name to the successor
+	drop(old);
+	ensure_eq!(path.exists(), true);
+	drop(new);
+	ensure_eq!(path.exists(), false);
+	Ok(())
+}
+
+#[test]
+fn local_socket_shared_listener() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,79 @@
+use crate::{local_socket::Stream, os::unix::sigpipe_suppression, tests::util::*};
+use std::{
+	env,
+	fs::File,
+	io::{self, prelude::*, IoSlice},
+};
+
+/// Writes to sockets whose peer has hung up. Run with the default disposition of `SIGPIPE`, which
+/// kills the process if any of the writes raises it.
+fn child_inner() -> TestResult {
+	ensure_eq!(sigpipe_suppression(), true);
+	let (mut a, b) = Stream::pair().opname("pair")?;
+	drop(b);
+	let e = a.write(b"ping").err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::BrokenPipe));
+	let e = a
+		.write_vectored(&[IoSlice::new(b"pi"), IoSlice::new(b"ng")])
+		.err()
+		.map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::BrokenPipe));
+
+	// Any file with some data in it will do
+	let file = File::open(env::current_exe()?).opname("open file")?;
+	let Stream::UdSocket(a) = a;
+	let e = a.send_file(&file, 0, 4).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::BrokenPipe));
+	Ok(())
+}
+
+/// Runs [`child_inner()`] in a child process and checks that it survives, which can't be
+/// observed from within the test process itself: Rust programs ignore `SIGPIPE` on startup, and
+/// changing that would affect all other tests that run in parallel.
+fn test_inner() -> TestResult {
+	run_in_child(module_path!(), "local_socket_sigpipe_child")
+}
+
+#[test]
+fn local_socket_sigpipe() -> TestResult {
+	test_wrapper(test_inner)
+}
+#[test]
+fn local_socket_sigpipe_child() -> TestResult {
+	if !is_child("local_socket_sigpipe_child") {
+		return Ok(());
+	}
+	unsafe { libc::signal(libc::SIGPIPE, libc::SIG_DFL) };
+	test_wrapper(child_inner)
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+	use crate::{
+		local_socket::{
+			tokio::{prelude::*, Stream},
+			ListenerOptions,
+		},
+		tests::util::{tokio::test_wrapper, *},
+	};
+	use ::tokio::{io::AsyncWriteExt, try_join};
+	use std::io;
+
+	async fn test_inner() -> TestResult {
+		let (name, listener) =
+			listen_and_pick_name(&mut namegen_local_socket(make_id!(), false), |nm| {
+				ListenerOptions::new().name(nm.borrow()).create_tokio()
+			})?;
+		let (mut client, server) =
+			try_join!(Stream::connect(name.borrow()), listener.accept()).opname("connect")?;
+		drop(server);
+		let e = client.write(b"ping").await.err().map(|e| e.kind());
+		ensure_eq!(e, Some(io::ErrorKind::BrokenPipe));
+		Ok(())
+	}
+
+	#[test]
+	fn tokio_local_socket_sigpipe() -> TestResult {
+		test_wrapper(test_inner())
+	}
+}
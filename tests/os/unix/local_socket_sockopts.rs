@@ -0,0 +1,89 @@
+use crate::{
+	local_socket::{prelude::*, Listener, ListenerOptions, Stream},
+	tests::util::*,
+};
+use std::{
+	io::{self, prelude::*},
+	time::Duration,
+};
+
+const BUFFER_SIZE: usize = 1 << 16;
+
+fn test_inner(path: bool) -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+	let Listener::UdSocket(listener) = &listener;
+	listener
+		.set_recv_buffer_size(BUFFER_SIZE)
+		.opname("set listener receive buffer size")?;
+	let size = listener
+		.recv_buffer_size()
+		.opname("get listener receive buffer size")?;
+	ensure_eq!(size >= BUFFER_SIZE, true);
+	ensure_eq!(listener.linger().opname("get listener linger")?, None);
+
+	let client = Stream::connect(name.borrow()).opname("connect")?;
+	let Stream::UdSocket(client) = &client;
+	client
+		.set_send_buffer_size(BUFFER_SIZE)
+		.opname("set stream send buffer size")?;
+	let size = client
+		.send_buffer_size()
+		.opname("get stream send buffer size")?;
+	ensure_eq!(size >= BUFFER_SIZE, true);
+	client
+		.set_linger(Some(Duration::from_secs(1)))
+		.opname("set stream linger")?;
+	ensure_eq!(
+		client.linger().opname("get stream linger")?,
+		Some(Duration::from_secs(1))
+	);
+
+	ensure_eq!(
+		client.recv_timeout().opname("get stream receive timeout")?,
+		None
+	);
+	client
+		.set_recv_timeout(Some(Duration::from_millis(1500)))
+		.opname("set stream receive timeout")?;
+	ensure_eq!(
+		client.recv_timeout().opname("get stream receive timeout")?,
+		Some(Duration::from_millis(1500))
+	);
+	client
+		.set_send_timeout(Some(Duration::from_secs(2)))
+		.opname("set stream send timeout")?;
+	ensure_eq!(
+		client.send_timeout().opname("get stream send timeout")?,
+		Some(Duration::from_secs(2))
+	);
+	client
+		.set_send_timeout(None)
+		.opname("clear stream send timeout")?;
+	ensure_eq!(
+		client.send_timeout().opname("get stream send timeout")?,
+		None
+	);
+	let e = client.set_recv_timeout(Some(Duration::ZERO)).err();
+	ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::InvalidInput));
+
+	// Nothing is ever sent to the client, so receiving times out
+	client
+		.set_recv_timeout(Some(Duration::from_millis(50)))
+		.opname("set stream receive timeout")?;
+	let mut reader = client;
+	let e = reader.read(&mut [0]).err();
+	ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::WouldBlock));
+	Ok(())
+}
+
+#[test]
+fn local_socket_sockopts_file() -> TestResult {
+	test_wrapper(|| test_inner(true))
+}
+#[test]
+fn local_socket_sockopts_namespaced() -> TestResult {
+	test_wrapper(|| test_inner(false))
+}
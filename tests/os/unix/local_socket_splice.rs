@@ -0,0 +1,35 @@
+#![cfg(feature = "tokio")]
+
+use crate::{
+	local_socket::tokio::splice,
+	os::unix::uds_local_socket::tokio::Stream,
+	tests::util::{tokio::test_wrapper, TestResult, WrapErrExt},
+};
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::UnixStream,
+	task,
+};
+
+async fn test_inner() -> TestResult {
+	let (mut client, a) = UnixStream::pair().opname("socket pair creation")?;
+	let (b, mut server) = UnixStream::pair().opname("socket pair creation")?;
+	let proxy = task::spawn(splice(Stream::from(a), Stream::from(b)));
+
+	let mut buf = [0; 6];
+	client.write_all(b"ping!\n").await.opname("client send")?;
+	server.read_exact(&mut buf).await.opname("server receive")?;
+	ensure_eq!(&buf, b"ping!\n");
+	server.write_all(b"pong!\n").await.opname("server send")?;
+	client.read_exact(&mut buf).await.opname("client receive")?;
+	ensure_eq!(&buf, b"pong!\n");
+	drop((client, server));
+
+	ensure_eq!(proxy.await?.opname("splice")?, (6, 6));
+	Ok(())
+}
+
+#[test]
+fn local_socket_splice() -> TestResult {
+	test_wrapper(test_inner())
+}
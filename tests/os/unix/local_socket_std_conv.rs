@@ -0,0 +1,87 @@
+use crate::{
+	local_socket::{prelude::*, Listener, ListenerOptions, NameInner, Stream},
+	tests::util::*,
+};
+use color_eyre::eyre::bail;
+use std::{
+	io::{prelude::*, BufReader},
+	os::unix::net::{UnixListener, UnixStream},
+	path::Path,
+	thread,
+};
+
+fn test_inner() -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+	let NameInner::UdSocketPath(path) = &name.0 else {
+		bail!("path name expected");
+	};
+	let path = Path::new(&**path).to_owned();
+
+	let listener = UnixListener::from(listener);
+	let client = thread::spawn({
+		let name = name.clone();
+		move || Stream::connect(name.borrow()).map(UnixStream::from)
+	});
+	let server = Stream::from(listener.accept().opname("accept")?.0);
+	let Ok(client) = client.join() else {
+		bail!("client thread panicked");
+	};
+	let mut client = client.opname("client connect")?;
+
+	client.write_all(b"ping\n").opname("send")?;
+	let mut buf = String::new();
+	BufReader::new(server)
+		.read_line(&mut buf)
+		.opname("receive")?;
+	ensure_eq!(buf, "ping\n");
+
+	// Neither the conversion nor wrapping a foreign listener takes over name reclamation
+	drop(Listener::from(listener));
+	ensure_eq!(path.exists(), true);
+	std::fs::remove_file(&path).opname("remove socket file")?;
+	Ok(())
+}
+
+#[test]
+fn local_socket_std_conv() -> TestResult {
+	test_wrapper(test_inner)
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+	use crate::{
+		local_socket::tokio::Stream,
+		tests::util::{tokio::test_wrapper, *},
+	};
+	use ::tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use std::os::unix::net::UnixStream;
+
+	async fn test_inner() -> TestResult {
+		// Blocking streams, as created by the standard library
+		let (a, b) = UnixStream::pair()?;
+		let mut a = Stream::try_from(a).opname("convert a")?;
+		let mut b = Stream::try_from(b).opname("convert b")?;
+
+		let mut buf = [0; 4];
+		let recv = b.read_exact(&mut buf);
+		let (sent, received) = ::tokio::join!(a.write_all(b"ping"), recv);
+		sent.opname("send")?;
+		received.opname("receive")?;
+		ensure_eq!(&buf, b"ping");
+
+		let mut a = UnixStream::try_from(a).opname("convert back")?;
+		a.set_nonblocking(false)?;
+		std::io::Write::write_all(&mut a, b"pong")?;
+		b.read_exact(&mut buf).await.opname("receive reply")?;
+		ensure_eq!(&buf, b"pong");
+		Ok(())
+	}
+
+	#[test]
+	fn tokio_local_socket_std_conv() -> TestResult {
+		test_wrapper(test_inner())
+	}
+}
@@ -0,0 +1,38 @@
+use crate::{
+	local_socket::{traits::Stream as _, ListenerOptions, Stream},
+	tests::util::*,
+};
+
+fn test_inner(path: bool) -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+	ensure_eq!(
+		listener
+			.take_error()
+			.opname("listener take_error")?
+			.is_none(),
+		true
+	);
+
+	let client = Stream::connect(name.borrow()).opname("connect")?;
+	ensure_eq!(
+		client.take_error().opname("stream take_error")?.is_none(),
+		true
+	);
+
+	let (a, b) = Stream::pair().opname("pair")?;
+	drop(b);
+	ensure_eq!(a.take_error().opname("pair take_error")?.is_none(), true);
+	Ok(())
+}
+
+#[test]
+fn local_socket_take_error_file() -> TestResult {
+	test_wrapper(|| test_inner(true))
+}
+#[test]
+fn local_socket_take_error_namespaced() -> TestResult {
+	test_wrapper(|| test_inner(false))
+}
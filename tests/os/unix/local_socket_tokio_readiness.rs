@@ -0,0 +1,69 @@
+use crate::{
+	os::unix::uds_local_socket::tokio::Stream,
+	tests::util::{tokio::test_wrapper, TestResult, WrapErrExt},
+};
+use ::tokio::io::Interest;
+use color_eyre::eyre::bail;
+use std::{
+	io::{self, IoSlice},
+	os::unix::net::UnixStream,
+};
+
+async fn test_inner() -> TestResult {
+	let (a, b) = UnixStream::pair().opname("pair")?;
+	let a = Stream::try_from(a).opname("convert")?;
+	let b = Stream::try_from(b).opname("convert")?;
+	let mut buf = [0; 64];
+
+	match b.try_read(&mut buf) {
+		Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+		rslt => bail!("unexpected result of reading with nothing to read: {rslt:?}"),
+	}
+
+	a.writable().await.opname("writable")?;
+	let sent = a.try_write(b"ping").opname("try_write")?;
+	ensure_eq!(sent, 4);
+	let bufs = [IoSlice::new(b"-"), IoSlice::new(b"pong")];
+	let sent = a.try_write_vectored(&bufs).opname("try_write_vectored")?;
+	ensure_eq!(sent, 5);
+
+	let ready = b.ready(Interest::READABLE).await.opname("ready")?;
+	ensure_eq!(ready.is_readable(), true);
+	let mut received = 0;
+	while received < 9 {
+		b.readable().await.opname("readable")?;
+		match b.try_read(buf.get_mut(received..).unwrap_or_default()) {
+			Ok(n) => received += n,
+			Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+			Err(e) => return Err(e).opname("try_read"),
+		}
+	}
+	ensure_eq!(buf.get(..received), Some(&b"ping-pong"[..]));
+
+	// Fill the send buffer to observe backpressure
+	let chunk = [0; 4096];
+	loop {
+		match a.try_write(&chunk) {
+			Ok(..) => {}
+			Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+			Err(e) => return Err(e).opname("try_write"),
+		}
+	}
+	// Draining the peer lifts it
+	let mut sink = [0; 4096];
+	loop {
+		match b.try_read(&mut sink) {
+			Ok(..) => {}
+			Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+			Err(e) => return Err(e).opname("try_read"),
+		}
+	}
+	a.writable().await.opname("writable after draining")?;
+	a.try_write(b"more").opname("try_write after draining")?;
+	Ok(())
+}
+
+#[test]
+fn local_socket_tokio_readiness() -> TestResult {
+	test_wrapper(test_inner())
+}
@@ -0,0 +1,85 @@
+use crate::{
+	os::unix::posix_mqueue::MessageQueue,
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::{io, process};
+
+fn name(id: &str) -> String {
+	format!("/interprocess-test-{id}-{:08x}", process::id())
+}
+
+fn test_inner() -> TestResult {
+	let name = name("mqueue");
+	let tx = MessageQueue::create(&name, 4, 64).opname("create")?;
+	let rslt = (|| {
+		let rx = MessageQueue::open(&name).opname("open")?;
+		let attr = rx.attributes().opname("attributes")?;
+		ensure_eq!((attr.max_messages, attr.max_message_size), (4, 64));
+
+		tx.send(b"low", 1).opname("send")?;
+		tx.send(b"high", 7).opname("send")?;
+		ensure_eq!(rx.attributes().opname("attributes")?.current_messages, 2);
+
+		let mut buf = [0; 64];
+		let (len, prio) = rx.recv(&mut buf).opname("receive")?;
+		ensure_eq!((&buf[..len], prio), (&b"high"[..], 7));
+		let (len, prio) = rx.recv(&mut buf).opname("receive")?;
+		ensure_eq!((&buf[..len], prio), (&b"low"[..], 1));
+
+		rx.set_nonblocking(true).opname("set_nonblocking")?;
+		ensure_eq!(rx.attributes().opname("attributes")?.nonblocking, true);
+		let e = rx.recv(&mut buf).err();
+		ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::WouldBlock));
+		Ok(())
+	})();
+	MessageQueue::unlink(&name).opname("unlink")?;
+	rslt
+}
+
+#[test]
+fn posix_mqueue() -> TestResult {
+	test_wrapper(test_inner)
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+	use super::name;
+	use crate::{
+		os::unix::posix_mqueue::tokio::MessageQueue,
+		tests::util::{tokio::test_wrapper, TestResult, WrapErrExt},
+	};
+	use ::tokio::try_join;
+
+	async fn test_inner() -> TestResult {
+		let name = name("tokio-mqueue");
+		let tx = MessageQueue::create(&name, 1, 16).opname("create")?;
+		let rslt = async {
+			let rx = MessageQueue::open(&name).opname("open")?;
+			// The queue only fits one message, so the second send has to wait for the receiver
+			let send = async {
+				tx.send(b"ping!\n", 0).await?;
+				tx.send(b"pong!\n", 0).await
+			};
+			let recv = async {
+				let mut buf = [0; 16];
+				let mut msgs = Vec::new();
+				for _ in 0..2 {
+					let (len, _) = rx.recv(&mut buf).await?;
+					msgs.push(buf[..len].to_vec());
+				}
+				Ok(msgs)
+			};
+			let ((), msgs) = try_join!(send, recv).opname("transfer")?;
+			ensure_eq!(msgs, [b"ping!\n".to_vec(), b"pong!\n".to_vec()]);
+			Ok(())
+		}
+		.await;
+		crate::os::unix::posix_mqueue::MessageQueue::unlink(&name).opname("unlink")?;
+		rslt
+	}
+
+	#[test]
+	fn tokio_posix_mqueue() -> TestResult {
+		test_wrapper(test_inner())
+	}
+}
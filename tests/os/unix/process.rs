@@ -0,0 +1,64 @@
+//! Tests passing a local socket to a child process and adopting inherited descriptors.
+
+use crate::{
+	os::unix::{
+		process::{adopt_stream, CommandExt},
+		uds_local_socket::Stream,
+	},
+	tests::util::*,
+	unnamed_pipe,
+};
+use color_eyre::eyre::bail;
+use std::{
+	io::{self, Read, Write},
+	os::unix::io::{FromRawFd, IntoRawFd, OwnedFd},
+	process::{Command, Stdio},
+};
+
+fn inherit_inner() -> TestResult {
+	let (mut ours, theirs) = Stream::pair().opname("pair")?;
+	let mut child = Command::new("sh")
+		.args(["-c", "printf 'ping!\\n' >&3"])
+		.stdin(Stdio::null())
+		.inherit_fd(OwnedFd::from(theirs), 3)
+		.spawn()
+		.opname("spawn")?;
+	let status = child.wait().opname("wait")?;
+	ensure_eq!(status.success(), true);
+
+	let mut buf = [0; 6];
+	ours.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"ping!\n");
+	Ok(())
+}
+
+fn adopt_inner() -> TestResult {
+	let (mut a, b) = Stream::pair().opname("pair")?;
+	let fd = OwnedFd::from(b).into_raw_fd();
+	let mut adopted = unsafe { adopt_stream(fd) }.opname("adopt")?;
+	a.write_all(b"ping!\n").opname("send")?;
+	let mut buf = [0; 6];
+	adopted.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"ping!\n");
+
+	let (_tx, rx) = unnamed_pipe::pipe().opname("pipe creation")?;
+	let fd = OwnedFd::from(rx).into_raw_fd();
+	let e = match unsafe { adopt_stream(fd) } {
+		Ok(..) => bail!("pipe adopted as a socket"),
+		Err(e) => e,
+	};
+	ensure_eq!(e.kind(), io::ErrorKind::InvalidInput);
+	// The descriptor is left open upon failure.
+	drop(unsafe { OwnedFd::from_raw_fd(fd) });
+	Ok(())
+}
+
+#[test]
+fn process_inherit_fd() -> TestResult {
+	test_wrapper(inherit_inner)
+}
+
+#[test]
+fn process_adopt_stream() -> TestResult {
+	test_wrapper(adopt_inner)
+}
@@ -0,0 +1,84 @@
+use crate::{os::unix::sd_listen_fds::parse_env, tests::util::*};
+use std::{ffi::OsString, io};
+
+const PID: u32 = 1234;
+
+/// Parses the given variables as if they were the environment of the process with ID [`PID`].
+#[allow(clippy::type_complexity)]
+fn parse(vars: &[(&str, &str)]) -> io::Result<Option<(usize, Option<Vec<String>>)>> {
+	parse_env(PID, |key| {
+		vars.iter()
+			.find(|(k, _)| *k == key)
+			.map(|(_, v)| OsString::from(v))
+	})
+}
+
+fn invalid(vars: &[(&str, &str)]) -> bool {
+	parse(vars).is_err_and(|e| e.kind() == io::ErrorKind::InvalidData)
+}
+
+fn test_inner() -> TestResult {
+	// Not socket-activated at all
+	ensure_eq!(parse(&[]).opname("parse without variables")?, None);
+	ensure_eq!(
+		parse(&[("LISTEN_FDS", "2")]).opname("parse without LISTEN_PID")?,
+		None
+	);
+
+	// Intended for a different process, such as the parent
+	ensure_eq!(
+		parse(&[("LISTEN_PID", "4321"), ("LISTEN_FDS", "2")]).opname("parse foreign PID")?,
+		None
+	);
+	ensure_eq!(invalid(&[("LISTEN_PID", "-1"), ("LISTEN_FDS", "2")]), true);
+
+	// Missing or malformed descriptor count
+	ensure_eq!(invalid(&[("LISTEN_PID", "1234")]), true);
+	for count in ["", "two", "-2", "99999999999999999999999"] {
+		ensure_eq!(
+			invalid(&[("LISTEN_PID", "1234"), ("LISTEN_FDS", count)]),
+			true
+		);
+	}
+	ensure_eq!(
+		parse(&[("LISTEN_PID", "1234"), ("LISTEN_FDS", "2")]).opname("parse without names")?,
+		Some((2, None))
+	);
+
+	// The number of names must match the number of descriptors
+	let names = parse(&[
+		("LISTEN_PID", "1234"),
+		("LISTEN_FDS", "2"),
+		("LISTEN_FDNAMES", "public:admin"),
+	])
+	.opname("parse with names")?;
+	ensure_eq!(
+		names,
+		Some((2, Some(vec!["public".to_owned(), "admin".to_owned()])))
+	);
+	for names in ["public", "public:admin:extra", ""] {
+		ensure_eq!(
+			invalid(&[
+				("LISTEN_PID", "1234"),
+				("LISTEN_FDS", "2"),
+				("LISTEN_FDNAMES", names),
+			]),
+			true
+		);
+	}
+	ensure_eq!(
+		parse(&[
+			("LISTEN_PID", "1234"),
+			("LISTEN_FDS", "0"),
+			("LISTEN_FDNAMES", ""),
+		])
+		.opname("parse with no descriptors")?,
+		Some((0, Some(Vec::new())))
+	);
+	Ok(())
+}
+
+#[test]
+fn sd_listen_fds_parse_env() -> TestResult {
+	test_wrapper(test_inner)
+}
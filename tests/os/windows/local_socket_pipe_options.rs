@@ -0,0 +1,45 @@
+use crate::{
+	local_socket::{prelude::*, ListenerOptions, Stream},
+	os::windows::{local_socket::ListenerOptionsExt, named_pipe::WaitTimeout},
+	tests::util::*,
+};
+use std::{
+	io::{prelude::*, BufReader},
+	sync::Arc,
+	thread,
+};
+
+fn test_inner() -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), false), |nm| {
+			ListenerOptions::new()
+				.name(nm.borrow())
+				.accept_remote(false)
+				.input_buffer_size_hint(8192)
+				.output_buffer_size_hint(16384)
+				.wait_timeout(WaitTimeout::from_raw(2000))
+				.create_sync()
+		})?;
+	let name = Arc::try_unwrap(name).unwrap();
+
+	let client = thread::spawn(move || -> TestResult {
+		let mut conn = BufReader::new(Stream::connect(name).opname("connect")?);
+		conn.get_mut().write_all(b"ping\n").opname("client send")?;
+		let mut line = String::new();
+		conn.read_line(&mut line).opname("client receive")?;
+		ensure_eq!(line, "pong\n");
+		Ok(())
+	});
+
+	let mut conn = BufReader::new(listener.accept().opname("accept")?);
+	let mut line = String::new();
+	conn.read_line(&mut line).opname("server receive")?;
+	ensure_eq!(line, "ping\n");
+	conn.get_mut().write_all(b"pong\n").opname("server send")?;
+	client.join().unwrap()
+}
+
+#[test]
+fn local_socket_pipe_options() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,72 @@
+//! Tests the handling of names of pipes on other machines.
+
+use crate::{
+	local_socket::{prelude::*, GenericFilePath, Name, NameInner, Stream},
+	os::windows::local_socket::{NameBuilderExt, NamedPipe},
+	tests::util::*,
+};
+use std::{borrow::Cow, io};
+use widestring::U16CString;
+
+fn kind(rslt: io::Result<Name<'_>>) -> Option<io::ErrorKind> {
+	rslt.err().map(|e| e.kind())
+}
+
+fn test_inner() -> TestResult {
+	let denied = Some(io::ErrorKind::PermissionDenied);
+	ensure_eq!(
+		kind(Name::builder().path(r"\\SERVER\pipe\Example").build()),
+		denied
+	);
+	ensure_eq!(
+		kind(Name::builder().namespaced("Example").host("SERVER").build()),
+		denied
+	);
+	ensure_eq!(
+		kind(Name::builder().path("Example").host(".").build()),
+		Some(io::ErrorKind::InvalidInput)
+	);
+	ensure_eq!(
+		kind(Name::builder().auto("Example").host(r"A\B").build()),
+		Some(io::ErrorKind::InvalidInput)
+	);
+
+	let local = Name::builder()
+		.namespaced("Example")
+		.host(".")
+		.build()
+		.opname("build local")?;
+	ensure_eq!(local, Name::builder().path(r"\\.\pipe\Example").build()?);
+
+	let remote = Name::builder()
+		.namespaced("Example")
+		.host("SERVER")
+		.allow_remote(true)
+		.build()
+		.opname("build remote")?;
+	let path = Name::builder()
+		.path(r"\\SERVER\pipe\Example")
+		.allow_remote(true)
+		.build()
+		.opname("build remote path")?;
+	ensure_eq!(remote, path);
+
+	// Other ways of making names don't allow remote pipes
+	let remote = r"\\SERVER\pipe\Example";
+	ensure_eq!(kind(remote.to_fs_name::<GenericFilePath>()), denied);
+	ensure_eq!(kind(remote.to_fs_name::<NamedPipe>()), denied);
+	ensure_eq!(kind(Name::interpret(remote)), denied);
+	let local = Name::interpret(r"\\.\pipe\Example").opname("interpret local")?;
+	ensure_eq!(local.is_path(), true);
+
+	// Names which weren't allowed to be remote are refused before anything goes over the network
+	let path = U16CString::from_str(remote).opname("encode remote path")?;
+	let name = Name(NameInner::NamedPipe(Cow::Owned(path)));
+	ensure_eq!(Stream::connect(name).err().map(|e| e.kind()), denied);
+	Ok(())
+}
+
+#[test]
+fn local_socket_remote_name() -> TestResult {
+	test_wrapper(test_inner)
+}
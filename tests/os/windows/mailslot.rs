@@ -0,0 +1,40 @@
+use crate::{
+	os::windows::mailslot::{MailslotClient, MailslotServer},
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::{io, process, time::Duration};
+
+fn test_inner() -> TestResult {
+	let name = format!("interprocess-test-mailslot-{:08x}", process::id());
+	let server = MailslotServer::create_with_max_message_size(&name, 64).opname("create")?;
+	ensure_eq!(server.max_message_size().opname("max_message_size")?, 64);
+
+	let client = MailslotClient::connect(&name).opname("connect")?;
+	client.send(b"first").opname("send")?;
+	client.send(b"second").opname("send")?;
+
+	let info = server.info().opname("info")?;
+	ensure_eq!((info.message_count, info.next_message_size), (2, Some(5)));
+
+	let mut buf = [0; 64];
+	let len = server.recv(&mut buf).opname("receive")?;
+	ensure_eq!(&buf[..len], b"first");
+	let len = server.recv(&mut buf).opname("receive")?;
+	ensure_eq!(&buf[..len], b"second");
+
+	server
+		.set_read_timeout(Some(Duration::from_millis(10)))
+		.opname("set_read_timeout")?;
+	ensure_eq!(
+		server.info().opname("info")?.read_timeout,
+		Some(Duration::from_millis(10))
+	);
+	let e = server.recv(&mut buf).err();
+	ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::TimedOut));
+	Ok(())
+}
+
+#[test]
+fn mailslot() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -0,0 +1,136 @@
+use crate::{
+	os::windows::{
+		named_pipe::{pipe_mode, DuplexPipeStream, PipeListener, PipeListenerOptions, PipeMode},
+		AsRawHandleExt as _,
+	},
+	tests::util::*,
+};
+use color_eyre::eyre::{bail, ensure};
+use std::{
+	env,
+	fs::File,
+	io::{self, prelude::*},
+	os::windows::io::OwnedHandle,
+	process, thread,
+};
+use windows_sys::Win32::Foundation::{GetHandleInformation, HANDLE_FLAG_INHERIT};
+
+type Listener = PipeListener<pipe_mode::Bytes, pipe_mode::Bytes>;
+type Stream = DuplexPipeStream<pipe_mode::Bytes>;
+
+fn path(which: &str) -> String {
+	format!(
+		r"\\.\pipe\interprocess-test-adopt-{which}-{}",
+		process::id()
+	)
+}
+
+fn is_inheritable(listener: &Listener) -> io::Result<bool> {
+	let mut flags = 0;
+	if unsafe { GetHandleInformation(listener.as_int_handle(), &mut flags) } == 0 {
+		return Err(io::Error::last_os_error());
+	}
+	Ok(flags & HANDLE_FLAG_INHERIT != 0)
+}
+
+/// Checks that the given handle is refused with the given error kind and returned to the caller.
+fn ensure_refused(handle: OwnedHandle, mode: PipeMode, kind: Option<io::ErrorKind>) -> TestResult {
+	let options = PipeListenerOptions::new().path(path("refused")).mode(mode);
+	let Err(e) = Listener::try_from_handle_and_options(handle, options) else {
+		bail!("handle was adopted");
+	};
+	ensure!(e.source.is_some(), "handle was not returned");
+	if let Some(kind) = kind {
+		ensure_eq!(e.cause.map(|e| e.kind()), Some(kind));
+	}
+	Ok(())
+}
+
+/// A server instance created elsewhere is picked up by the listener, which then goes on to create
+/// instances of its own.
+fn round_trip() -> TestResult {
+	let path = path("round-trip");
+	let options = PipeListenerOptions::new()
+		.path(path.as_str())
+		.inheritable(true);
+	let handle = OwnedHandle::from(
+		options
+			.create_duplex::<pipe_mode::Bytes>()
+			.opname("create instance")?,
+	);
+	let listener = Listener::try_from_handle_and_options(handle, options.to_owned()?)
+		.map_err(io::Error::from)
+		.opname("adopt instance")?;
+	ensure_eq!(
+		is_inheritable(&listener).opname("query inheritability")?,
+		false
+	);
+
+	let client = thread::spawn(move || -> io::Result<()> {
+		for _ in 0..2 {
+			let mut conn = Stream::connect_by_path(path.as_str())?;
+			conn.write_all(b"ping")?;
+		}
+		Ok(())
+	});
+	for i in 0..2 {
+		let mut conn = listener.accept().opname(if i == 0 {
+			"accept adopted"
+		} else {
+			"accept own"
+		})?;
+		let mut buf = [0; 4];
+		conn.read_exact(&mut buf).opname("receive")?;
+		ensure_eq!(&buf, b"ping");
+	}
+	client
+		.join()
+		.map_err(|_| io::Error::other("client thread panicked"))
+		.and_then(|r| r)
+		.opname("client")?;
+	Ok(())
+}
+
+/// Handles which aren't suitable server instances are refused, and ownership of them is returned.
+fn refused() -> TestResult {
+	// Not a named pipe at all
+	let file_path = env::temp_dir().join(format!("interprocess-test-adopt-{}", process::id()));
+	let file = File::create(&file_path).opname("create file")?;
+	let rslt = ensure_refused(file.into(), PipeMode::Bytes, None);
+	let _ = std::fs::remove_file(&file_path);
+	rslt?;
+
+	// Mismatched pipe type
+	let path = path("mismatch");
+	let listener = PipeListenerOptions::new()
+		.path(path.as_str())
+		.create_duplex::<pipe_mode::Bytes>()
+		.opname("create instance")?;
+	ensure_refused(
+		listener.into(),
+		PipeMode::Messages,
+		Some(io::ErrorKind::InvalidInput),
+	)?;
+
+	// The client end of a pipe
+	let listener = PipeListenerOptions::new()
+		.path(path.as_str())
+		.create_duplex::<pipe_mode::Bytes>()
+		.opname("create instance")?;
+	let client = Stream::connect_by_path(path.as_str()).opname("connect")?;
+	let client = OwnedHandle::try_from(client)
+		.map_err(|_| io::Error::other("could not unwrap client"))
+		.opname("unwrap client")?;
+	ensure_refused(client, PipeMode::Bytes, Some(io::ErrorKind::InvalidInput))?;
+	drop(listener);
+	Ok(())
+}
+
+#[test]
+fn named_pipe_adopt_round_trip() -> TestResult {
+	test_wrapper(round_trip)
+}
+#[test]
+fn named_pipe_adopt_refused() -> TestResult {
+	test_wrapper(refused)
+}
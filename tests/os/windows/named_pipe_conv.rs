@@ -0,0 +1,80 @@
+use crate::{
+	os::windows::named_pipe::{pipe_mode, DuplexPipeStream, FromHandleErrorKind},
+	tests::util::*,
+};
+use color_eyre::eyre::bail;
+use std::{env, fs::File, process};
+
+/// Files which aren't named pipes are rejected, and the handle is returned.
+fn not_a_pipe() -> TestResult {
+	let path = env::temp_dir().join(format!("interprocess-test-not-a-pipe-{}", process::id()));
+	let file = File::create(&path).opname("create file")?;
+	let rslt = DuplexPipeStream::<pipe_mode::Bytes>::try_from(file);
+	let _ = std::fs::remove_file(&path);
+	let Err(e) = rslt else {
+		bail!("a regular file was accepted as a named pipe");
+	};
+	ensure_eq!(e.details, FromHandleErrorKind::IsServerCheckFailed);
+	ensure_eq!(e.source.is_some(), true);
+	Ok(())
+}
+
+#[test]
+fn named_pipe_conv_not_a_pipe() -> TestResult {
+	test_wrapper(not_a_pipe)
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+	use crate::{
+		os::windows::named_pipe::{pipe_mode, tokio::DuplexPipeStream},
+		tests::util::{tokio::test_wrapper, *},
+	};
+	use ::tokio::{
+		io::{AsyncReadExt, AsyncWriteExt},
+		net::windows::named_pipe::{
+			ClientOptions, NamedPipeClient, NamedPipeServer, ServerOptions,
+		},
+	};
+	use color_eyre::eyre::bail;
+	use std::{io, process};
+
+	type Stream = DuplexPipeStream<pipe_mode::Bytes>;
+
+	async fn test_inner() -> TestResult {
+		let path = format!(r"\\.\pipe\interprocess-test-conv-{}", process::id());
+		let server = ServerOptions::new()
+			.first_pipe_instance(true)
+			.create(&path)
+			.opname("create server")?;
+		let client = ClientOptions::new().open(&path).opname("open client")?;
+		server.connect().await.opname("accept")?;
+
+		let mut server = Stream::try_from(server)
+			.map_err(io::Error::from)
+			.opname("wrap server")?;
+		let mut client = Stream::try_from(client)
+			.map_err(io::Error::from)
+			.opname("wrap client")?;
+
+		client.write_all(b"ping").await.opname("send")?;
+		client.flush().await.opname("flush")?;
+		let mut buf = [0; 4];
+		server.read_exact(&mut buf).await.opname("receive")?;
+		ensure_eq!(&buf, b"ping");
+
+		// The server side can't be unwrapped into a client
+		let Err(server) = NamedPipeClient::try_from(server) else {
+			bail!("server unwrapped into a client");
+		};
+		let server = NamedPipeServer::try_from(server).ok();
+		let client = NamedPipeClient::try_from(client).ok();
+		ensure_eq!((server.is_some(), client.is_some()), (true, true));
+		Ok(())
+	}
+
+	#[test]
+	fn tokio_named_pipe_conv() -> TestResult {
+		test_wrapper(test_inner())
+	}
+}
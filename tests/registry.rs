@@ -0,0 +1,85 @@
+use crate::{
+	local_socket::server::ServerConfig,
+	registry::{Broker, Client},
+	tests::util::{
+		listen_and_pick_name, namegen_local_socket, tokio::test_wrapper, TestResult, WrapErrExt,
+	},
+};
+use color_eyre::eyre::bail;
+use std::io;
+
+async fn test_inner(id: &'static str, path: bool) -> TestResult {
+	let (name, broker) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		Broker::serve(nm.borrow(), ServerConfig::new())
+	})?;
+
+	let mut server = Client::connect(name.borrow()).await.opname("connect")?;
+	server
+		.register("com.example.a", "@example-a.sock")
+		.await
+		.opname("register")?;
+	server
+		.register("com.example.b", "example-b.sock")
+		.await
+		.opname("register")?;
+
+	let mut other = Client::connect(name.borrow()).await.opname("connect")?;
+	let resolved = other.resolve("com.example.a").await.opname("resolve")?;
+	ensure_eq!(resolved, Some("@example-a.sock".parse()?));
+	ensure_eq!(
+		other.resolve("com.example.c").await.opname("resolve")?,
+		None
+	);
+	match other.register("com.example.a", "@other.sock").await {
+		Err(e) if e.kind() == io::ErrorKind::AddrInUse => {}
+		rslt => bail!("unexpected result of taking over a registration: {rslt:?}"),
+	}
+	match other.unregister("com.example.a").await {
+		Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+		rslt => bail!("unexpected result of removing someone else's registration: {rslt:?}"),
+	}
+
+	server
+		.register("com.example.a", "@example-a2.sock")
+		.await
+		.opname("reregister")?;
+	let resolved = other.resolve("com.example.a").await.opname("resolve")?;
+	ensure_eq!(resolved, Some("@example-a2.sock".parse()?));
+	server
+		.unregister("com.example.a")
+		.await
+		.opname("unregister")?;
+	ensure_eq!(
+		other.resolve("com.example.a").await.opname("resolve")?,
+		None
+	);
+
+	// Registrations go away with the client that made them
+	drop(server);
+	let mut gone = false;
+	for _ in 0..100 {
+		if other
+			.resolve("com.example.b")
+			.await
+			.opname("resolve")?
+			.is_none()
+		{
+			gone = true;
+			break;
+		}
+		::tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+	}
+	ensure_eq!(gone, true);
+
+	broker.shutdown().await.opname("shutdown")?;
+	Ok(())
+}
+
+#[test]
+fn registry_file() -> TestResult {
+	test_wrapper(test_inner(make_id!(), true))
+}
+#[test]
+fn registry_namespaced() -> TestResult {
+	test_wrapper(test_inner(make_id!(), false))
+}
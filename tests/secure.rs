@@ -0,0 +1,174 @@
+use crate::{
+	mem::MemoryStream,
+	secure::{Config, Keypair, SecureStream},
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use color_eyre::eyre::bail;
+use std::{
+	io::{self, prelude::*},
+	thread,
+};
+
+/// A message which spans several frames.
+fn long_message() -> Vec<u8> {
+	(0..200_000_u32).flat_map(u32::to_le_bytes).collect()
+}
+
+fn test_inner() -> TestResult {
+	let (server_keys, client_keys) = (Keypair::generate()?, Keypair::generate()?);
+	let (a, b) = MemoryStream::pair();
+
+	let server_config = Config::new(server_keys.private_key());
+	let server = thread::spawn(move || -> io::Result<_> {
+		let mut conn = SecureStream::accept(a, &server_config)?;
+		let mut buf = vec![0; long_message().len()];
+		conn.read_exact(&mut buf)?;
+		conn.write_all(b"pong")?;
+		conn.close()?;
+		Ok((conn.remote_public_key().to_vec(), buf))
+	});
+
+	let config = Config::new(client_keys.private_key()).remote_public_key(server_keys.public_key());
+	let mut conn = SecureStream::connect(b, &config).opname("handshake")?;
+	ensure_eq!(conn.remote_public_key(), server_keys.public_key());
+	conn.write_all(&long_message()).opname("send")?;
+	let mut buf = [0; 4];
+	conn.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"pong");
+	let (client_public, received) = match server.join() {
+		Ok(rslt) => rslt.opname("server")?,
+		Err(..) => bail!("server thread panicked"),
+	};
+	ensure_eq!(client_public, client_keys.public_key());
+	ensure_eq!(received == long_message(), true);
+
+	// Once the server closes the session, the client receives end of file
+	ensure_eq!(conn.read(&mut buf).opname("receive EOF")?, 0);
+	Ok(())
+}
+
+/// Sets up a session over an in-memory stream, returning both ends.
+fn session_pair() -> TestResult<(SecureStream<MemoryStream>, SecureStream<MemoryStream>)> {
+	let (server_keys, client_keys) = (Keypair::generate()?, Keypair::generate()?);
+	let (a, b) = MemoryStream::pair();
+	let server_config = Config::new(server_keys.private_key());
+	let server = thread::spawn(move || SecureStream::accept(a, &server_config));
+	let client =
+		SecureStream::connect(b, &Config::new(client_keys.private_key())).opname("handshake")?;
+	let Ok(server) = server.join() else {
+		bail!("server thread panicked");
+	};
+	Ok((server.opname("accept")?, client))
+}
+
+fn truncated_inner() -> TestResult {
+	let (server, mut client) = session_pair()?;
+	// Hanging up the wrapped stream without the close message must not look like end of file
+	drop(server);
+	let e = client.read(&mut [0; 4]).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::UnexpectedEof));
+	Ok(())
+}
+
+fn poisoned_inner() -> TestResult {
+	let (mut server, mut client) = session_pair()?;
+	// A frame which claims to be longer than what follows it, after which the peer hangs up
+	server
+		.get_mut()
+		.write_all(&[16, 0, 1, 2, 3])
+		.opname("send partial frame")?;
+	drop(server);
+	let e = client.read(&mut [0; 4]).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::UnexpectedEof));
+	ensure_eq!(client.is_poisoned(), true);
+	let e = client.read(&mut [0; 4]).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::Other));
+	let e = client.write(b"ping").err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::Other));
+	Ok(())
+}
+
+fn wrong_key_inner() -> TestResult {
+	let (server_keys, client_keys) = (Keypair::generate()?, Keypair::generate()?);
+	let (a, b) = MemoryStream::pair();
+	let server =
+		thread::spawn(move || SecureStream::accept(a, &Config::new(server_keys.private_key())));
+
+	let config =
+		Config::new(client_keys.private_key()).remote_public_key(Keypair::generate()?.public_key());
+	let e = SecureStream::connect(b, &config).err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::PermissionDenied));
+	// The client hangs up without completing the handshake
+	let Ok(server) = server.join() else {
+		bail!("server thread panicked");
+	};
+	ensure_eq!(server.is_err(), true);
+	Ok(())
+}
+
+#[test]
+fn secure() -> TestResult {
+	test_wrapper(test_inner)
+}
+#[test]
+fn secure_wrong_key() -> TestResult {
+	test_wrapper(wrong_key_inner)
+}
+#[test]
+fn secure_truncated() -> TestResult {
+	test_wrapper(truncated_inner)
+}
+#[test]
+fn secure_poisoned() -> TestResult {
+	test_wrapper(poisoned_inner)
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+	use super::long_message;
+	use crate::{
+		secure::{tokio::SecureStream, Config, Keypair},
+		tests::util::{tokio::test_wrapper, TestResult, WrapErrExt},
+	};
+	use ::tokio::{
+		io::{duplex, AsyncReadExt, AsyncWriteExt},
+		task,
+	};
+
+	async fn test_inner() -> TestResult {
+		let (server_keys, client_keys) = (Keypair::generate()?, Keypair::generate()?);
+		// Smaller than a frame, so that frames are sent and received in pieces
+		let (a, b) = duplex(1024);
+
+		let server_config =
+			Config::new(server_keys.private_key()).remote_public_key(client_keys.public_key());
+		let server = task::spawn(async move {
+			let mut conn = SecureStream::accept(a, &server_config).await?;
+			let mut buf = vec![0; long_message().len()];
+			conn.read_exact(&mut buf).await?;
+			conn.write_all(b"pong").await?;
+			conn.shutdown().await?;
+			std::io::Result::Ok(buf)
+		});
+
+		let config =
+			Config::new(client_keys.private_key()).remote_public_key(server_keys.public_key());
+		let mut conn = SecureStream::connect(b, &config)
+			.await
+			.opname("handshake")?;
+		conn.write_all(&long_message()).await.opname("send")?;
+		conn.flush().await.opname("flush")?;
+		let mut buf = [0; 4];
+		conn.read_exact(&mut buf).await.opname("receive")?;
+		ensure_eq!(&buf, b"pong");
+		let received = server.await?.opname("server")?;
+		ensure_eq!(received == long_message(), true);
+		ensure_eq!(conn.read(&mut buf).await.opname("receive EOF")?, 0);
+		Ok(())
+	}
+
+	#[test]
+	fn tokio_secure() -> TestResult {
+		test_wrapper(test_inner())
+	}
+}
@@ -0,0 +1,134 @@
+use crate::{
+	shmem::{
+		ring_buffer::{Receiver, Sender},
+		RingBuffer, SharedMemObject,
+	},
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+	TryClone,
+};
+use std::{
+	io::{self, prelude::*},
+	process, thread,
+};
+
+fn named_inner() -> TestResult {
+	let name = format!("interprocess-test-shmem-{:08x}", process::id());
+	let obj = SharedMemObject::create(&name, 4096).opname("create")?;
+	let rslt = (|| {
+		let e = SharedMemObject::create(&name, 4096).err();
+		ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::AlreadyExists));
+
+		let other = SharedMemObject::open(&name, 4096).opname("open")?;
+		let mut wmap = unsafe { obj.map() }.opname("map")?;
+		let rmap = unsafe { other.map() }.opname("map opened")?;
+		ensure_eq!(rmap.len(), 4096);
+		ensure_eq!(&rmap.as_slice()[..6], &[0; 6]);
+		wmap.as_mut_slice()[..6].copy_from_slice(b"ping!\n");
+		ensure_eq!(&rmap.as_slice()[..6], b"ping!\n");
+		Ok(())
+	})();
+	SharedMemObject::unlink(&name).opname("unlink")?;
+	rslt
+}
+
+fn anonymous_inner() -> TestResult {
+	let obj = SharedMemObject::anonymous(64).opname("create")?;
+	ensure_eq!(obj.size(), 64);
+	let clone = obj.try_clone().opname("clone")?;
+	let mut wmap = unsafe { obj.map() }.opname("map")?;
+	drop(obj);
+	wmap.as_mut_slice()[60..].copy_from_slice(b"pong");
+
+	#[cfg(unix)]
+	let clone = SharedMemObject::try_from(std::os::fd::OwnedFd::from(clone))
+		.map_err(|e| e.cause.unwrap())
+		.opname("conversion from descriptor")?;
+	let rmap = unsafe { clone.map() }.opname("map clone")?;
+	ensure_eq!(&rmap.as_slice()[60..], b"pong");
+	Ok(())
+}
+
+fn ring_buffer_inner() -> TestResult {
+	let (tx, mut rx) = RingBuffer::new(64)
+		.and_then(RingBuffer::split)
+		.opname("ring buffer creation")?;
+	ensure_eq!(tx.capacity(), 64);
+	// Moves both ends through their parts, as if they were sent to another process
+	let mut tx = Sender::from_parts(tx.into_parts()).opname("sender reassembly")?;
+	let msg = (0..100_000_u32)
+		.map(|i| i.to_le_bytes()[0] ^ 0x5a)
+		.collect::<Vec<_>>();
+	let expected = msg.clone();
+	let sender = thread::spawn(move || tx.write_all(&msg));
+
+	let mut received = Vec::with_capacity(expected.len());
+	rx.read_to_end(&mut received).opname("receive")?;
+	sender.join().unwrap().opname("send")?;
+	ensure_eq!(received.len(), expected.len());
+	ensure_eq!(received, expected);
+	Ok(())
+}
+
+/// Data written across the point where the positions wrap around arrives intact.
+fn ring_buffer_wraparound_inner() -> TestResult {
+	let ring = RingBuffer::new(16).opname("ring buffer creation")?;
+	ring.set_positions(usize::MAX - 20)
+		.opname("position adjustment")?;
+	let (mut tx, mut rx) = ring.split().opname("ring buffer split")?;
+	let msg = (0..200_u8).collect::<Vec<_>>();
+	let expected = msg.clone();
+	let sender = thread::spawn(move || tx.write_all(&msg));
+
+	let mut received = Vec::with_capacity(expected.len());
+	rx.read_to_end(&mut received).opname("receive")?;
+	sender.join().unwrap().opname("send")?;
+	ensure_eq!(received, expected);
+	Ok(())
+}
+
+fn ring_buffer_nonblocking_inner() -> TestResult {
+	let (mut tx, rx) = RingBuffer::new(4)
+		.and_then(RingBuffer::split)
+		.opname("ring buffer creation")?;
+	let mut rx = Receiver::from_parts(rx.into_parts()).opname("receiver reassembly")?;
+	tx.set_nonblocking(true);
+	rx.set_nonblocking(true);
+
+	let mut buf = [0; 8];
+	let e = rx.read(&mut buf).err();
+	ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::WouldBlock));
+	ensure_eq!(tx.write(b"ping!\n").opname("send")?, 4);
+	let e = tx.write(b"!\n").err();
+	ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::WouldBlock));
+	ensure_eq!(rx.read(&mut buf).opname("receive")?, 4);
+	ensure_eq!(&buf[..4], b"ping");
+
+	drop(rx);
+	let e = tx.write(b"!\n").err();
+	ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::BrokenPipe));
+	Ok(())
+}
+
+#[test]
+fn shmem_named() -> TestResult {
+	test_wrapper(named_inner)
+}
+
+#[test]
+fn shmem_anonymous() -> TestResult {
+	test_wrapper(anonymous_inner)
+}
+
+#[test]
+fn shmem_ring_buffer() -> TestResult {
+	test_wrapper(ring_buffer_inner)
+}
+
+#[test]
+fn shmem_ring_buffer_wraparound() -> TestResult {
+	test_wrapper(ring_buffer_wraparound_inner)
+}
+#[test]
+fn shmem_ring_buffer_nonblocking() -> TestResult {
+	test_wrapper(ring_buffer_nonblocking_inner)
+}
@@ -0,0 +1,86 @@
+use crate::{
+	local_socket::Name,
+	single_instance::{Acquired, Forwarder, Guard},
+	tests::util::*,
+};
+use color_eyre::eyre::bail;
+use std::{ffi::OsString, io, time::Duration};
+
+fn acquire_primary(name: &Name<'_>) -> io::Result<Guard> {
+	match Guard::acquire(name.borrow())? {
+		Acquired::Primary(guard) => Ok(guard),
+		Acquired::Secondary(..) => Err(io::ErrorKind::AddrInUse.into()),
+	}
+}
+fn acquire_secondary(name: &Name<'_>) -> TestResult<Forwarder> {
+	match Guard::acquire(name.borrow()).opname("acquire")? {
+		Acquired::Primary(..) => bail!("second instance became the primary instance"),
+		Acquired::Secondary(forwarder) => Ok(forwarder),
+	}
+}
+
+fn test_forward(id: &'static str, path: bool) -> TestResult {
+	let (name, guard) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		acquire_primary(nm)
+	})?;
+
+	let args = ["open", "file with spaces.txt", ""];
+	acquire_secondary(&name)?.forward(args).opname("forward")?;
+	acquire_secondary(&name)?
+		.forward(Vec::<OsString>::new())
+		.opname("forward nothing")?;
+
+	let timeout = Duration::from_secs(5);
+	let received = guard.forwarded().recv_timeout(timeout)?;
+	ensure_eq!(received, args.map(OsString::from));
+	ensure_eq!(
+		guard.forwarded().recv_timeout(timeout)?,
+		Vec::<OsString>::new()
+	);
+
+	// Releasing the name lets the next instance become the primary one
+	drop(guard);
+	acquire_primary(&name).opname("reacquire")?;
+	Ok(())
+}
+
+#[cfg(unix)]
+fn test_stale() -> TestResult {
+	use std::os::unix::net::UnixListener;
+	let (name, guard) = listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+		acquire_primary(nm)
+	})?;
+	drop(guard);
+	let crate::local_socket::NameInner::UdSocketPath(path) = &name.0 else {
+		bail!("file path name is not a path");
+	};
+	// A listener which goes away without removing its socket file, as if it crashed
+	drop(UnixListener::bind(path).opname("bind")?);
+	let guard = acquire_primary(&name).opname("acquire over stale file")?;
+	drop(guard);
+
+	// Files which aren't sockets are left alone
+	std::fs::write(path, b"user data").opname("create regular file")?;
+	let err = Guard::acquire(name.borrow()).err().map(|e| e.kind());
+	ensure_eq!(err, Some(io::ErrorKind::AlreadyExists));
+	ensure_eq!(
+		std::fs::read(path).opname("read regular file")?,
+		b"user data"
+	);
+	std::fs::remove_file(path).opname("remove regular file")?;
+	Ok(())
+}
+
+#[test]
+fn single_instance_forward_file() -> TestResult {
+	test_wrapper(|| test_forward(make_id!(), true))
+}
+#[test]
+fn single_instance_forward_namespaced() -> TestResult {
+	test_wrapper(|| test_forward(make_id!(), false))
+}
+#[cfg(unix)]
+#[test]
+fn single_instance_stale() -> TestResult {
+	test_wrapper(test_stale)
+}
@@ -0,0 +1,49 @@
+use crate::{
+	local_socket::prelude::*,
+	testing::TempSocket,
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+};
+use std::io::prelude::*;
+
+fn test_inner() -> TestResult {
+	let a = TempSocket::new().opname("create first")?;
+	let b = TempSocket::new().opname("create second")?;
+	ensure_eq!(a.name() == b.name(), false);
+
+	let mut client = a.connect().opname("connect")?;
+	let mut server = a.listener().accept().opname("accept")?;
+	client.write_all(b"hi").opname("send")?;
+	let mut buf = [0; 2];
+	server.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"hi");
+
+	#[cfg(unix)]
+	{
+		let path = a.path().to_owned();
+		ensure_eq!(path.exists(), true);
+		drop(a);
+		ensure_eq!(path.exists(), false);
+
+		// Cleanup happens when unwinding, too
+		let path = b.path().to_owned();
+		let rslt = std::panic::catch_unwind(move || {
+			let _b = b;
+			std::panic::resume_unwind(Box::new("test failure"));
+		});
+		ensure_eq!(rslt.is_err(), true);
+		ensure_eq!(path.exists(), false);
+	}
+	#[cfg(windows)]
+	{
+		let name = a.name().into_owned();
+		drop((a, client, server));
+		ensure_eq!(b.name() == name.borrow(), false);
+		ensure_eq!(crate::local_socket::Stream::connect(name).is_err(), true);
+	}
+	Ok(())
+}
+
+#[test]
+fn temp_socket() -> TestResult {
+	test_wrapper(test_inner)
+}
@@ -1,8 +1,15 @@
 // TODO(2.0.1) test various error conditions
 #![cfg(feature = "tokio")]
 
+mod handle_transfer;
+mod hub;
+mod listener_set;
 mod no_server;
+mod read_uninit;
+mod reunite;
+mod server;
 mod stream;
+mod timeout;
 
 use crate::{
 	local_socket::{tokio::Stream, Name},
@@ -60,3 +67,74 @@ fn no_server_file() -> TestResult {
 fn no_server_namespaced() -> TestResult {
 	test_wrapper(no_server::run_and_verify_error(false))
 }
+
+#[test]
+fn listener_set_file() -> TestResult {
+	test_wrapper(listener_set::run(make_id!(), true))
+}
+#[test]
+fn listener_set_namespaced() -> TestResult {
+	test_wrapper(listener_set::run(make_id!(), false))
+}
+
+#[test]
+fn timeout_file() -> TestResult {
+	test_wrapper(timeout::run(make_id!(), true))
+}
+#[test]
+fn timeout_namespaced() -> TestResult {
+	test_wrapper(timeout::run(make_id!(), false))
+}
+
+#[test]
+fn server_file() -> TestResult {
+	test_wrapper(server::run(make_id!(), true))
+}
+#[test]
+fn server_namespaced() -> TestResult {
+	test_wrapper(server::run(make_id!(), false))
+}
+
+#[test]
+fn handle_transfer_file() -> TestResult {
+	test_wrapper(handle_transfer::run(make_id!(), true))
+}
+#[test]
+fn handle_transfer_namespaced() -> TestResult {
+	test_wrapper(handle_transfer::run(make_id!(), false))
+}
+
+#[test]
+fn hub_file() -> TestResult {
+	test_wrapper(hub::run(make_id!(), true))
+}
+#[test]
+fn hub_namespaced() -> TestResult {
+	test_wrapper(hub::run(make_id!(), false))
+}
+#[test]
+fn hub_blocking_clients_file() -> TestResult {
+	test_wrapper(hub::blocking_clients(make_id!(), true))
+}
+#[test]
+fn hub_blocking_clients_namespaced() -> TestResult {
+	test_wrapper(hub::blocking_clients(make_id!(), false))
+}
+
+#[test]
+fn read_uninit_file() -> TestResult {
+	test_wrapper(read_uninit::run(make_id!(), true))
+}
+#[test]
+fn read_uninit_namespaced() -> TestResult {
+	test_wrapper(read_uninit::run(make_id!(), false))
+}
+
+#[test]
+fn reunite_file() -> TestResult {
+	test_wrapper(reunite::run(make_id!(), true))
+}
+#[test]
+fn reunite_namespaced() -> TestResult {
+	test_wrapper(reunite::run(make_id!(), false))
+}
@@ -0,0 +1,30 @@
+use crate::{
+	local_socket::{
+		tokio::{prelude::*, Stream},
+		ListenerOptions,
+	},
+	tests::util::*,
+	unnamed_pipe::{pipe, Sender},
+};
+use ::tokio::try_join;
+use std::io::{prelude::*, BufReader};
+
+pub async fn run(id: &'static str, path: bool) -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ListenerOptions::new().name(nm.borrow()).create_tokio()
+	})?;
+	let (server, client) = try_join!(listener.accept(), Stream::connect(name.borrow()))?;
+	let (tx, rx) = pipe().opname("pipe")?;
+
+	let ((), received) = try_join!(server.send_handle(tx.into()), client.recv_handle())?;
+	let mut tx = Sender::from(received);
+	tx.write_all(b"ping\n")
+		.opname("write to transferred sender")?;
+	drop(tx);
+	let mut line = String::new();
+	BufReader::new(rx)
+		.read_line(&mut line)
+		.opname("read from pipe")?;
+	ensure_eq!(line, "ping\n");
+	Ok(())
+}
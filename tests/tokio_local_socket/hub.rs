@@ -0,0 +1,75 @@
+use crate::{
+	local_socket::{
+		hub::{self, Message},
+		server::ServerConfig,
+		tokio::hub::{Hub, Publisher, Subscriber},
+	},
+	tests::util::{listen_and_pick_name, namegen_local_socket, TestResult, WrapErrExt},
+};
+use ::tokio::task;
+
+fn msg(topic: &str, payload: &[u8]) -> Option<Message> {
+	Some(Message {
+		topic: topic.to_owned(),
+		payload: payload.to_owned(),
+	})
+}
+
+pub async fn run(id: &'static str, path: bool) -> TestResult {
+	let (name, hub) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		Hub::serve(nm.borrow(), ServerConfig::new())
+	})?;
+
+	let mut both = Subscriber::connect(name.borrow()).await.opname("connect")?;
+	both.subscribe("a").await.opname("subscribe")?;
+	both.subscribe("b").await.opname("subscribe")?;
+	let mut only_b = Subscriber::connect(name.borrow()).await.opname("connect")?;
+	only_b.subscribe("b").await.opname("subscribe")?;
+
+	let mut publisher = Publisher::connect(name.borrow()).await.opname("connect")?;
+	publisher.publish("a", b"1").await.opname("publish")?;
+	publisher.publish("b", b"2").await.opname("publish")?;
+	publisher.publish("c", b"3").await.opname("publish")?;
+	ensure_eq!(both.recv().await.opname("receive")?, msg("a", b"1"));
+	ensure_eq!(both.recv().await.opname("receive")?, msg("b", b"2"));
+	ensure_eq!(only_b.recv().await.opname("receive")?, msg("b", b"2"));
+
+	only_b.unsubscribe("b").await.opname("unsubscribe")?;
+	only_b.subscribe("c").await.opname("subscribe")?;
+	publisher.publish("b", b"4").await.opname("publish")?;
+	publisher.publish("c", b"5").await.opname("publish")?;
+	// The message under the topic it unsubscribed from is skipped
+	ensure_eq!(only_b.recv().await.opname("receive")?, msg("c", b"5"));
+	ensure_eq!(both.recv().await.opname("receive")?, msg("b", b"4"));
+
+	hub.shutdown().await.opname("shutdown")?;
+	ensure_eq!(both.recv().await.opname("receive after shutdown")?, None);
+	Ok(())
+}
+
+pub async fn blocking_clients(id: &'static str, path: bool) -> TestResult {
+	let (name, hub) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		Hub::serve(nm.borrow(), ServerConfig::new())
+	})?;
+
+	let mut subscriber = Subscriber::connect(name.borrow()).await.opname("connect")?;
+	subscriber.subscribe("topic").await.opname("subscribe")?;
+
+	let sync_name = name.clone();
+	let sync_subscriber = task::spawn_blocking(move || {
+		let mut subscriber = hub::Subscriber::connect(sync_name.borrow())?;
+		subscriber.subscribe("topic")?;
+		let mut publisher = hub::Publisher::connect(sync_name.borrow())?;
+		publisher.publish("topic", b"blocking")?;
+		subscriber.recv()
+	});
+	ensure_eq!(
+		subscriber.recv().await.opname("receive")?,
+		msg("topic", b"blocking")
+	);
+	let received = sync_subscriber.await?.opname("blocking clients")?;
+	ensure_eq!(received, msg("topic", b"blocking"));
+
+	hub.shutdown().await.opname("shutdown")?;
+	Ok(())
+}
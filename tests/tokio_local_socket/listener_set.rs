@@ -0,0 +1,38 @@
+use crate::{
+	local_socket::{
+		tokio::{prelude::*, ListenerSet, Stream},
+		ListenerOptions,
+	},
+	tests::util::{listen_and_pick_name, namegen_local_socket, TestResult, WrapErrExt},
+};
+use ::tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	task,
+};
+
+pub async fn run(id: &'static str, path: bool) -> TestResult {
+	let mut namegen = namegen_local_socket(id, path);
+	let mut set = ListenerSet::new();
+	let mut names = Vec::new();
+	for _ in 0..2 {
+		let (name, listener) = listen_and_pick_name(&mut namegen, |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_tokio()
+		})?;
+		names.push(name);
+		set.push(listener);
+	}
+
+	let second = names[1].clone();
+	let client = task::spawn(async move {
+		let mut conn = Stream::connect(second.borrow()).await?;
+		conn.write_all(b"ping!\n").await?;
+		std::io::Result::Ok(conn)
+	});
+	let (idx, mut conn) = set.accept().await.opname("accept")?;
+	ensure_eq!(idx, 1);
+	let mut buf = [0; 6];
+	conn.read_exact(&mut buf).await.opname("receive")?;
+	ensure_eq!(&buf, b"ping!\n");
+	client.await?.opname("client")?;
+	Ok(())
+}
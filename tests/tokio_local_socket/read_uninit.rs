@@ -0,0 +1,31 @@
+use crate::{
+	local_socket::{
+		tokio::{prelude::*, Stream},
+		ListenerOptions,
+	},
+	tests::util::*,
+};
+use ::tokio::{io::AsyncWriteExt, try_join};
+use std::mem::MaybeUninit;
+
+pub async fn run(id: &'static str, path: bool) -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ListenerOptions::new().name(nm.borrow()).create_tokio()
+	})?;
+	let (server, mut client) = try_join!(listener.accept(), Stream::connect(name.borrow()))?;
+	client.write_all(b"uninit").await.opname("send")?;
+	drop(client);
+
+	let mut buf = [MaybeUninit::<u8>::uninit(); 64];
+	let mut received = Vec::new();
+	loop {
+		let n = server.read_to_uninit(&mut buf).await.opname("receive")?;
+		if n == 0 {
+			break;
+		}
+		// SAFETY: the first n bytes were initialized by the receive
+		received.extend(buf.iter().take(n).map(|b| unsafe { b.assume_init() }));
+	}
+	ensure_eq!(received, b"uninit");
+	Ok(())
+}
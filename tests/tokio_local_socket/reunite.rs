@@ -0,0 +1,30 @@
+use crate::{
+	error::ReuniteError,
+	local_socket::{
+		tokio::{prelude::*, Stream},
+		ListenerOptions,
+	},
+	tests::util::*,
+};
+use ::tokio::try_join;
+use color_eyre::eyre::bail;
+
+/// Halves of different connections must not be reunited, and must be handed back intact when that
+/// is attempted, regardless of what the connections are implemented with.
+pub async fn run(id: &'static str, path: bool) -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ListenerOptions::new().name(nm.borrow()).create_tokio()
+	})?;
+	let (_server1, client1) = try_join!(listener.accept(), Stream::connect(name.borrow()))?;
+	let (_server2, client2) = try_join!(listener.accept(), Stream::connect(name.borrow()))?;
+	let (rh1, sh1) = client1.split();
+	let (rh2, sh2) = client2.split();
+
+	let ReuniteError { rh: rh1, sh: sh2 } = match Stream::reunite(rh1, sh2) {
+		Err(e) => e,
+		Ok(..) => bail!("halves of different streams were reunited"),
+	};
+	Stream::reunite(rh1, sh1).opname("reunite first")?;
+	Stream::reunite(rh2, sh2).opname("reunite second")?;
+	Ok(())
+}
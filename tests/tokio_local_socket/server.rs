@@ -0,0 +1,56 @@
+use crate::{
+	local_socket::{
+		server::ServerConfig,
+		tokio::{prelude::*, server::serve, Stream, TimeoutStream},
+	},
+	tests::util::{listen_and_pick_name, namegen_local_socket, TestResult, WrapErrExt},
+};
+use ::tokio::{
+	io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+	task,
+};
+use std::{num::NonZeroUsize, time::Duration};
+
+async fn echo(conn: TimeoutStream<Stream>) {
+	let mut conn = BufReader::new(conn);
+	let mut line = String::new();
+	if conn.read_line(&mut line).await.is_ok() {
+		let _ = conn.get_mut().write_all(line.as_bytes()).await;
+	}
+}
+
+pub async fn run(id: &'static str, path: bool) -> TestResult {
+	let config = ServerConfig::new()
+		.max_connections(NonZeroUsize::new(2).unwrap())
+		.timeout(Some(Duration::from_secs(5)));
+	let (name, server) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		serve(nm.borrow(), echo, config)
+	})?;
+
+	// More clients than permits, so that some of them have to wait for a task to finish
+	let clients = (0..6)
+		.map(|i| {
+			let name = name.clone();
+			task::spawn(async move {
+				let conn = Stream::connect(name.borrow()).await.opname("connect")?;
+				let mut conn = BufReader::new(conn);
+				let msg = format!("Hello from client {i}\n");
+				conn.get_mut()
+					.write_all(msg.as_bytes())
+					.await
+					.opname("send")?;
+				let mut line = String::new();
+				conn.read_line(&mut line).await.opname("receive")?;
+				ensure_eq!(line, msg);
+				TestResult::Ok(())
+			})
+		})
+		.collect::<Vec<_>>();
+	for client in clients {
+		client.await.opname("client task")??;
+	}
+
+	server.shutdown().await.opname("shutdown")?;
+	ensure_eq!(Stream::connect(name.borrow()).await.is_err(), true);
+	Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::{
+	local_socket::{
+		tokio::{prelude::*, Stream, TimeoutStream},
+		ListenerOptions,
+	},
+	tests::util::{listen_and_pick_name, namegen_local_socket, TestResult, WrapErrExt},
+};
+use ::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::{io, time::Duration};
+
+pub async fn run(id: &'static str, path: bool) -> TestResult {
+	let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+		ListenerOptions::new().name(nm.borrow()).create_tokio()
+	})?;
+	let (client, server) =
+		::tokio::try_join!(Stream::connect(name.borrow()), listener.accept()).opname("connect")?;
+	let (mut client, mut server) = (TimeoutStream::new(client), server);
+	client.set_read_timeout(Some(Duration::from_millis(50)));
+	ensure_eq!(client.read_timeout(), Some(Duration::from_millis(50)));
+
+	let mut buf = [0; 4];
+	let e = client.read(&mut buf).await.err().map(|e| e.kind());
+	ensure_eq!(e, Some(io::ErrorKind::TimedOut));
+
+	// The stream remains usable after a timeout
+	server.write_all(b"ping").await.opname("send")?;
+	client.read_exact(&mut buf).await.opname("receive")?;
+	ensure_eq!(&buf, b"ping");
+	Ok(())
+}
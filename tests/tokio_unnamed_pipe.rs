@@ -0,0 +1,26 @@
+#![cfg(feature = "tokio")]
+
+use crate::{
+	tests::util::{tokio::test_wrapper, TestResult, WrapErrExt},
+	unnamed_pipe::tokio::pipe,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+async fn test_inner() -> TestResult {
+	let (mut tx, mut rx) = pipe().opname("pipe creation")?;
+	let mut buf = [0; 6];
+	tx.write_all(b"ping!\n").await.opname("send")?;
+	rx.read_exact(&mut buf).await.opname("receive")?;
+	ensure_eq!(&buf, b"ping!\n");
+
+	drop(tx);
+	let mut rest = Vec::new();
+	rx.read_to_end(&mut rest).await.opname("receive EOF")?;
+	ensure_eq!(rest.len(), 0);
+	Ok(())
+}
+
+#[test]
+fn tokio_unnamed_pipe() -> TestResult {
+	test_wrapper(test_inner())
+}
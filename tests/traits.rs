@@ -0,0 +1,68 @@
+use crate::{
+	local_socket::{self, ListenerOptions},
+	mem::MemoryListener,
+	tests::util::*,
+	traits::{Listener, Stream},
+};
+use std::{
+	io::{prelude::*, BufReader},
+	sync::Arc,
+	thread,
+};
+
+fn ping<L: Listener + Send + Sync + 'static>(
+	listener: L,
+	name: <L::Stream as Stream>::Name<'_>,
+) -> TestResult {
+	let listener = Arc::new(listener);
+	let jh = thread::spawn(move || {
+		let mut conn = BufReader::new(listener.accept()?);
+		let mut line = String::new();
+		conn.read_line(&mut line)?;
+		conn.get_mut().write_all(line.as_bytes())
+	});
+	let mut conn = BufReader::new(L::Stream::connect(name).opname("connect")?);
+	conn.get_mut().write_all(b"ping\n").opname("send")?;
+	let mut line = String::new();
+	conn.read_line(&mut line).opname("receive")?;
+	ensure_eq!(line, "ping\n");
+	jh.join().unwrap().opname("server")?;
+	Ok(())
+}
+
+fn test_inner(path: bool) -> TestResult {
+	let (name, listener) =
+		listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
+			ListenerOptions::new().name(nm.borrow()).create_sync()
+		})?;
+	ping::<local_socket::Listener>(listener, name.borrow())?;
+
+	#[cfg(unix)]
+	{
+		use crate::os::unix::uds_local_socket;
+		let (name, listener) =
+			listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
+				ListenerOptions::new()
+					.name(nm.borrow())
+					.create_sync_as::<uds_local_socket::Listener>()
+			})?;
+		ping::<uds_local_socket::Listener>(listener, name.borrow())?;
+	}
+	Ok(())
+}
+
+#[test]
+fn generic_traits_memory() -> TestResult {
+	test_wrapper(|| {
+		let name = make_id!();
+		ping(MemoryListener::bind(name).opname("bind")?, name)
+	})
+}
+#[test]
+fn generic_traits_file() -> TestResult {
+	test_wrapper(|| test_inner(true))
+}
+#[test]
+fn generic_traits_namespaced() -> TestResult {
+	test_wrapper(|| test_inner(false))
+}
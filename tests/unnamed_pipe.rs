@@ -0,0 +1,81 @@
+use crate::{
+	tests::util::{test_wrapper, TestResult, WrapErrExt},
+	unnamed_pipe::pipe,
+};
+use std::io::{self, IoSlice, Read, Write};
+
+fn vectored_inner() -> TestResult {
+	let (mut tx, mut rx) = pipe().opname("pipe creation")?;
+	let bufs = [
+		IoSlice::new(b"ping"),
+		IoSlice::new(b""),
+		IoSlice::new(b"!\n"),
+	];
+	let written = tx.write_vectored(&bufs).opname("vectored send")?;
+	ensure_eq!(written, 6);
+	let mut buf = [0; 6];
+	rx.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"ping!\n");
+	Ok(())
+}
+
+fn nonblocking_inner() -> TestResult {
+	let (mut tx, mut rx) = pipe().opname("pipe creation")?;
+	rx.set_nonblocking(true).opname("set_nonblocking")?;
+	let mut buf = [0; 6];
+	let e = rx.read(&mut buf).err();
+	ensure_eq!(e.map(|e| e.kind()), Some(io::ErrorKind::WouldBlock));
+	tx.write_all(b"ping!\n").opname("send")?;
+	rx.read_exact(&mut buf).opname("receive")?;
+	ensure_eq!(&buf, b"ping!\n");
+	Ok(())
+}
+
+#[cfg(unix)]
+fn is_inheritable(fd: impl std::os::unix::io::AsFd) -> io::Result<bool> {
+	use std::os::unix::io::AsRawFd;
+	let flags = unsafe { libc::fcntl(fd.as_fd().as_raw_fd(), libc::F_GETFD) };
+	if flags == -1 {
+		return Err(io::Error::last_os_error());
+	}
+	Ok(flags & libc::FD_CLOEXEC == 0)
+}
+#[cfg(windows)]
+fn is_inheritable(handle: impl std::os::windows::io::AsHandle) -> io::Result<bool> {
+	use crate::os::windows::AsRawHandleExt as _;
+	use windows_sys::Win32::Foundation::{GetHandleInformation, HANDLE_FLAG_INHERIT};
+	let mut flags = 0;
+	let handle = handle.as_handle();
+	if unsafe { GetHandleInformation(handle.as_int_handle(), &mut flags) } == 0 {
+		return Err(io::Error::last_os_error());
+	}
+	Ok(flags & HANDLE_FLAG_INHERIT != 0)
+}
+
+fn inheritable_inner() -> TestResult {
+	let (tx, rx) = pipe().opname("pipe creation")?;
+	tx.set_inheritable(false).opname("set_inheritable(false)")?;
+	rx.set_inheritable(true).opname("set_inheritable(true)")?;
+	ensure_eq!(is_inheritable(&tx).opname("query sender")?, false);
+	ensure_eq!(is_inheritable(&rx).opname("query receiver")?, true);
+	tx.set_inheritable(true).opname("set_inheritable(true)")?;
+	rx.set_inheritable(false).opname("set_inheritable(false)")?;
+	ensure_eq!(is_inheritable(&tx).opname("query sender")?, true);
+	ensure_eq!(is_inheritable(&rx).opname("query receiver")?, false);
+	Ok(())
+}
+
+#[test]
+fn unnamed_pipe_vectored() -> TestResult {
+	test_wrapper(vectored_inner)
+}
+
+#[test]
+fn unnamed_pipe_nonblocking() -> TestResult {
+	test_wrapper(nonblocking_inner)
+}
+
+#[test]
+fn unnamed_pipe_inheritable() -> TestResult {
+	test_wrapper(inheritable_inner)
+}
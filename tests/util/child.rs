@@ -0,0 +1,45 @@
+//! Running tests in a process of their own, for those which change process-wide state that other
+//! tests running in parallel would observe.
+
+use super::{TestResult, WrapErrExt};
+use color_eyre::eyre::ensure;
+use std::{
+	env,
+	process::{Command, Stdio},
+};
+
+/// Set in the environment of the child process to the name of the test it is supposed to run.
+const CHILD_VAR: &str = "INTERPROCESS_TEST_CHILD";
+
+/// Returns `true` if this is the child process spawned by [`run_in_child()`] for the given test.
+/// The test should return right away otherwise.
+pub fn is_child(test: &str) -> bool {
+	env::var_os(CHILD_VAR).is_some_and(|t| t == test)
+}
+
+/// Reruns the test binary with only the given test selected, and checks that the test passes.
+/// `module` is the `module_path!()` of the test.
+pub fn run_in_child(module: &str, test: &str) -> TestResult {
+	// The path printed by the harness doesn't include the crate name
+	let module = module.split_once("::").map_or("", |(_, rest)| rest);
+	let path = format!("{module}::{test}");
+	let output = Command::new(env::current_exe()?)
+		.args([&*path, "--exact", "--nocapture", "--test-threads=1"])
+		.env(CHILD_VAR, test)
+		.stdin(Stdio::null())
+		.output()
+		.opname("run child")?;
+	#[cfg(unix)]
+	{
+		use std::os::unix::process::ExitStatusExt;
+		let signal = output.status.signal();
+		ensure!(signal.is_none(), "child killed by signal {signal:?}");
+	}
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	ensure!(output.status.success(), "child failed: {stdout}");
+	ensure!(
+		stdout.contains("1 passed"),
+		"child didn't run the test: {stdout}"
+	);
+	Ok(())
+}
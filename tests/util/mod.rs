@@ -6,13 +6,14 @@
 mod eyre;
 #[macro_use]
 mod namegen;
+mod child;
 mod choke;
 mod drive;
 mod wdt;
 mod xorshift;
 
 #[allow(unused_imports)]
-pub use {drive::*, eyre::*, namegen::*, xorshift::*};
+pub use {child::*, drive::*, eyre::*, namegen::*, xorshift::*};
 
 #[cfg(feature = "tokio")]
 pub mod tokio;
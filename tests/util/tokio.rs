@@ -13,6 +13,7 @@ pub fn test_wrapper(f: impl Future<Output = TestResult> + Send + 'static) -> Tes
 	super::test_wrapper(|| {
 		let rt = tokio::runtime::Builder::new_current_thread()
 			.enable_io()
+			.enable_time()
 			.build()
 			.opname("Tokio runtime spawn")?;
 		rt.block_on(f)